@@ -9,6 +9,9 @@ pub enum Error<'gc> {
     #[error("A script in this movie has taken too long to execute and has been terminated.")]
     ExecutionTimeout,
 
+    #[error("A script in this movie has taken too long to execute and has been aborted.")]
+    ScriptAborted,
+
     #[error("{0} levels of function recursion were exceeded in one action list. This is probably an infinite loop.")]
     FunctionRecursionLimit(u16),
 
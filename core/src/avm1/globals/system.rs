@@ -6,6 +6,7 @@ use crate::avm1::property_decl::{define_properties_on, Declaration};
 use crate::avm1::runtime::Avm1;
 use crate::avm1::{ScriptObject, TObject, Value};
 use crate::avm1_stub;
+use crate::backend::ui::LanguageIdentifier;
 use crate::context::{GcContext, UpdateContext};
 use bitflags::bitflags;
 use core::fmt;
@@ -151,6 +152,44 @@ pub enum Language {
 }
 
 impl Language {
+    /// The language a locale such as `fr-CA` is reported as, or `Unknown` if Flash had no code for it.
+    pub fn from_language_identifier(locale: &LanguageIdentifier) -> Self {
+        match locale.language.as_str() {
+            "cs" => Language::Czech,
+            "da" => Language::Danish,
+            "nl" => Language::Dutch,
+            "en" => Language::English,
+            "fi" => Language::Finnish,
+            "fr" => Language::French,
+            "de" => Language::German,
+            "hu" => Language::Hungarian,
+            "it" => Language::Italian,
+            "ja" => Language::Japanese,
+            "ko" => Language::Korean,
+            "no" | "nb" | "nn" => Language::Norwegian,
+            "pl" => Language::Polish,
+            "pt" => Language::Portuguese,
+            "ru" => Language::Russian,
+            "zh" => {
+                let traditional = locale
+                    .script
+                    .is_some_and(|script| script.as_str() == "Hant")
+                    || locale
+                        .region
+                        .is_some_and(|region| matches!(region.as_str(), "TW" | "HK" | "MO"));
+                if traditional {
+                    Language::TraditionalChinese
+                } else {
+                    Language::SimplifiedChinese
+                }
+            }
+            "es" => Language::Spanish,
+            "sv" => Language::Swedish,
+            "tr" => Language::Turkish,
+            _ => Language::Unknown,
+        }
+    }
+
     pub fn get_language_code(&self, player_version: u8) -> &str {
         match self {
             Language::Czech => "cs",
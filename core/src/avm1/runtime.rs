@@ -592,6 +592,11 @@ pub fn root_error_handler<'gc>(activation: &mut Activation<'_, 'gc>, error: Erro
             // Continue execution without halting.
             return;
         }
+        Error::ScriptAborted => {
+            // Only the current script is stopped, the rest of the movie keeps running.
+            tracing::warn!("{}", error);
+            return;
+        }
         Error::InvalidSwf(swf_error) => {
             tracing::error!("{}: {}", error, swf_error);
         }
@@ -94,6 +94,9 @@ use num_traits::FromPrimitive;
 
 const BROADCAST_WHITELIST: [&str; 4] = ["enterFrame", "exitFrame", "frameConstructed", "render"];
 
+/// How many calls may be logged per frame when tracing calls, to keep the log readable.
+const MAX_CALL_TRACES_PER_FRAME: u32 = 500;
+
 /// The state of an AVM2 interpreter.
 #[derive(Collect)]
 #[collect(no_drop)]
@@ -114,6 +117,13 @@ pub struct Avm2<'gc> {
     /// The current call stack of the player.
     call_stack: GcCell<'gc, CallStack<'gc>>,
 
+    /// Whether the call stack should be sampled for the debug UI while scripts run.
+    is_sampling_call_stack: bool,
+
+    /// The call stack as it was when it was last sampled.
+    #[collect(require_static)]
+    sampled_call_stack: Option<String>,
+
     /// This domain is used exclusively for classes from playerglobals
     playerglobals_domain: Domain<'gc>,
 
@@ -191,6 +201,22 @@ pub struct Avm2<'gc> {
     pub debug_output: bool,
 
     pub optimizer_enabled: bool,
+
+    /// Patterns of methods that should never be optimized, such as `Class.method` or `Class.*`.
+    #[collect(require_static)]
+    pub optimizer_denylist: Vec<String>,
+
+    /// Patterns of functions whose calls are logged (all of them if empty),
+    /// or `None` if calls aren't logged at all.
+    #[collect(require_static)]
+    call_trace: Option<Vec<String>>,
+
+    /// How many calls were logged during the current frame.
+    call_trace_count: u32,
+
+    /// If a script has run for too long and the user has asked for it, the AVM may be halted.
+    /// Movie code is no longer run while halted, but playerglobals are.
+    halted: bool,
 }
 
 impl<'gc> Avm2<'gc> {
@@ -214,6 +240,8 @@ impl<'gc> Avm2<'gc> {
             stack: Vec::new(),
             scope_stack: Vec::new(),
             call_stack: GcCell::new(context.gc_context, CallStack::new()),
+            is_sampling_call_stack: false,
+            sampled_call_stack: None,
             playerglobals_domain,
             stage_domain,
             system_classes: None,
@@ -262,9 +290,29 @@ impl<'gc> Avm2<'gc> {
             debug_output: false,
 
             optimizer_enabled: true,
+            optimizer_denylist: Vec::new(),
+            call_trace: None,
+            call_trace_count: 0,
+            halted: false,
+        }
+    }
+
+    /// Halts the AVM, preventing execution of any further movie code.
+    ///
+    /// If the AVM is currently running a method, it will continue until it realizes that it has
+    /// been halted. If an immediate stop is required, an Error must be raised inside of the execution.
+    pub fn halt(&mut self) {
+        if !self.halted {
+            self.halted = true;
+            tracing::error!("No more movie code will be executed.")
         }
     }
 
+    /// Whether the AVM has been halted, see `Avm2::halt`.
+    pub fn is_halted(&self) -> bool {
+        self.halted
+    }
+
     pub fn load_player_globals(context: &mut UpdateContext<'_, 'gc>) -> Result<(), Error<'gc>> {
         let globals = context.avm2.playerglobals_domain;
         let mut activation = Activation::from_domain(context.reborrow(), globals);
@@ -616,6 +664,24 @@ impl<'gc> Avm2<'gc> {
         self.call_stack
     }
 
+    pub fn set_sample_call_stack(&mut self, value: bool) {
+        self.is_sampling_call_stack = value;
+        if !value {
+            self.sampled_call_stack = None;
+        }
+    }
+
+    /// Records the current call stack, if the debug UI asked for it.
+    pub fn sample_call_stack(&mut self) {
+        if self.is_sampling_call_stack {
+            self.sampled_call_stack = Some(self.call_stack.read().to_string());
+        }
+    }
+
+    pub fn sampled_call_stack(&self) -> Option<&str> {
+        self.sampled_call_stack.as_deref()
+    }
+
     #[cold]
     fn stack_overflow(&self) {
         tracing::warn!("Avm2::push: Stack overflow");
@@ -755,6 +821,72 @@ impl<'gc> Avm2<'gc> {
     pub fn set_optimizer_enabled(&mut self, value: bool) {
         self.optimizer_enabled = value;
     }
+
+    pub fn optimizer_denylist(&self) -> &[String] {
+        &self.optimizer_denylist
+    }
+
+    pub fn set_optimizer_denylist(&mut self, patterns: Vec<String>) {
+        self.optimizer_denylist = patterns;
+    }
+
+    /// Logs calls to functions matching any of the patterns (or all functions, if there are none),
+    /// which is useful for following what a movie is doing without a debugger.
+    pub fn set_call_trace(&mut self, patterns: Option<Vec<String>>) {
+        self.call_trace = patterns;
+    }
+
+    pub fn is_tracing_calls(&self) -> bool {
+        self.call_trace.is_some()
+    }
+
+    /// Checks whether a call to the given function should be logged,
+    /// counting it against the limit of calls logged per frame.
+    ///
+    /// The name is as given by `Executable::debug_full_name`, for example `pkg::Class/method()`.
+    pub fn should_trace_call(&mut self, name: &str) -> bool {
+        let Some(patterns) = &self.call_trace else {
+            return false;
+        };
+        if !call_trace_matches(patterns, name) {
+            return false;
+        }
+
+        self.call_trace_count += 1;
+        if self.call_trace_count == MAX_CALL_TRACES_PER_FRAME {
+            tracing::warn!(
+                "Logged {MAX_CALL_TRACES_PER_FRAME} calls this frame, skipping the rest of them"
+            );
+        }
+        self.call_trace_count <= MAX_CALL_TRACES_PER_FRAME
+    }
+
+    /// Allows more calls to be logged, at the start of a new frame.
+    pub fn reset_call_trace_count(&mut self) {
+        self.call_trace_count = 0;
+    }
+}
+
+/// Whether a function, named as by `Executable::debug_full_name`, matches any of the call
+/// trace patterns. No patterns at all match every function.
+fn call_trace_matches(patterns: &[String], name: &str) -> bool {
+    if patterns.is_empty() {
+        return true;
+    }
+    // Match in the same way as the optimizer denylist, like `pkg.Class.method` or `Class.method`.
+    let qualified_name = name
+        .trim_end_matches("()")
+        .replace("::", ".")
+        .replace('/', ".");
+    let local_name = name
+        .rsplit_once("::")
+        .map_or(qualified_name.as_str(), |(_, local)| {
+            local.trim_end_matches("()")
+        })
+        .replace('/', ".");
+    patterns.iter().any(|pattern| {
+        verify::glob_matches(pattern, &qualified_name) || verify::glob_matches(pattern, &local_name)
+    })
 }
 
 /// If the provided `DisplayObjectWeak` should have frames run, returns
@@ -771,3 +903,48 @@ fn valid_orphan<'gc>(
     }
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(pattern: &str, name: &str) -> bool {
+        call_trace_matches(&[pattern.to_string()], name)
+    }
+
+    #[test]
+    fn call_trace_without_patterns_matches_everything() {
+        assert!(call_trace_matches(&[], "Main/init()"));
+    }
+
+    #[test]
+    fn call_trace_exact_matches() {
+        assert!(matches("Main.init", "Main/init()"));
+        assert!(!matches("Main.init", "Main/initAll()"));
+        assert!(!matches("Main", "Main/init()"));
+        assert!(matches("topLevel", "topLevel()"));
+    }
+
+    #[test]
+    fn call_trace_partial_matches() {
+        assert!(matches("Main.*", "Main/init()"));
+        assert!(matches("*.init", "Main/init()"));
+        assert!(matches("Main.in*", "Main/init()"));
+        assert!(!matches("Other.*", "Main/init()"));
+        assert!(!matches("Main.in", "Main/init()"));
+    }
+
+    #[test]
+    fn call_trace_namespaced_matches() {
+        let name = "flash.display::Sprite/addChild()";
+        assert!(matches("flash.display.Sprite.addChild", name));
+        assert!(matches("Sprite.addChild", name));
+        assert!(matches("flash.display.*", name));
+        assert!(!matches("display.Sprite.addChild", name));
+        assert!(!matches("flash.display::Sprite/addChild()", name));
+        assert!(call_trace_matches(
+            &["Other.method".to_string(), "Sprite.*".to_string()],
+            name
+        ));
+    }
+}
@@ -21,6 +21,7 @@ use crate::avm2::value::Value;
 use crate::avm2::Multiname;
 use crate::avm2::Namespace;
 use crate::avm2::{Avm2, Error};
+use crate::backend::ui::ScriptTimeoutAction;
 use crate::context::{GcContext, UpdateContext};
 use crate::string::{AvmAtom, AvmString};
 use crate::tag_utils::SwfMovie;
@@ -787,6 +788,17 @@ impl<'a, 'gc> Activation<'a, 'gc> {
         &mut self,
         method: Gc<'gc, BytecodeMethod<'gc>>,
     ) -> Result<Value<'gc>, Error<'gc>> {
+        // Once halted, movie code is skipped entirely, but playerglobals keep working
+        // so that the rest of the player (e.g. the display list) still behaves.
+        if self.context.avm2.is_halted()
+            && !method
+                .translation_unit()
+                .domain()
+                .is_playerglobals_domain(self.context.avm2)
+        {
+            return Ok(Value::Undefined);
+        }
+
         // The method must be verified at this point
 
         let verified_info = method.verified_info.read();
@@ -863,11 +875,22 @@ impl<'a, 'gc> Activation<'a, 'gc> {
         self.actions_since_timeout_check += 1;
         if self.actions_since_timeout_check >= 64000 {
             self.actions_since_timeout_check = 0;
-            if self.context.update_start.elapsed() >= self.context.max_execution_duration {
-                return Err(
-                    "A script in this movie has taken too long to execute and has been terminated."
-                        .into(),
-                );
+            self.avm2().sample_call_stack();
+            match self.context.check_execution_timeout() {
+                Some(ScriptTimeoutAction::Halt) => {
+                    self.context.avm2.halt();
+                    return Err(
+                        "A script in this movie has taken too long to execute and has been terminated."
+                            .into(),
+                    );
+                }
+                Some(ScriptTimeoutAction::Abort) => {
+                    return Err(
+                        "A script in this movie has taken too long to execute and has been aborted."
+                            .into(),
+                    );
+                }
+                Some(ScriptTimeoutAction::Continue) | None => {}
             }
         }
 
@@ -2,7 +2,7 @@
 
 use crate::avm2::activation::Activation;
 use crate::avm2::method::{BytecodeMethod, Method, NativeMethod, ParamConfig};
-use crate::avm2::object::{ClassObject, Object};
+use crate::avm2::object::{ClassObject, Object, TObject};
 use crate::avm2::scope::ScopeChain;
 use crate::avm2::traits::TraitKind;
 use crate::avm2::value::Value;
@@ -109,6 +109,27 @@ impl<'gc> Executable<'gc> {
         activation: &mut Activation<'_, 'gc>,
         callee: Object<'gc>,
     ) -> Result<Value<'gc>, Error<'gc>> {
+        let traced_name = if activation.context.avm2.is_tracing_calls() {
+            let name = self.debug_full_name().to_string();
+            activation
+                .context
+                .avm2
+                .should_trace_call(&name)
+                .then_some(name)
+        } else {
+            None
+        };
+        if let Some(name) = &traced_name {
+            let arguments = arguments
+                .iter()
+                .map(describe_value)
+                .collect::<Vec<_>>()
+                .join(", ");
+            activation
+                .context
+                .avm_trace(&format!("-> {}({arguments})", name.trim_end_matches("()")));
+        }
+
         let ret = match self {
             Executable::Native(bm) => {
                 let method = bm.method.method;
@@ -203,10 +224,20 @@ impl<'gc> Executable<'gc> {
                 activation.run_actions(bm.method)
             }
         };
+        activation.context.avm2.sample_call_stack();
         activation
             .context
             .avm2
             .pop_call(activation.context.gc_context);
+
+        if let Some(name) = &traced_name {
+            let name = name.trim_end_matches("()");
+            let message = match &ret {
+                Ok(value) => format!("<- {name} = {}", describe_value(value)),
+                Err(_) => format!("<- {name} threw"),
+            };
+            activation.context.avm_trace(&message);
+        }
         ret
     }
 
@@ -377,3 +408,22 @@ pub fn display_function<'gc>(
     }
     output.push_utf8("()");
 }
+
+/// Describes a value briefly for call traces, without calling into any ActionScript.
+fn describe_value(value: &Value<'_>) -> String {
+    match value {
+        Value::Undefined => "undefined".to_string(),
+        Value::Null => "null".to_string(),
+        Value::Bool(value) => value.to_string(),
+        Value::Number(value) => value.to_string(),
+        Value::Integer(value) => value.to_string(),
+        Value::String(value) => format!("{value:?}"),
+        Value::Object(object) => {
+            let name = object
+                .instance_of_class_definition()
+                .map(|class| class.name().local_name().to_string())
+                .unwrap_or_else(|| "Object".to_string());
+            format!("[object {name}]")
+        }
+    }
+}
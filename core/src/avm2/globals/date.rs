@@ -7,7 +7,7 @@ use crate::avm2::object::{date_allocator, DateObject, FunctionObject, Object, TO
 use crate::avm2::value::Value;
 use crate::avm2::Error;
 use crate::avm2::QName;
-use crate::locale::{get_current_date_time, get_timezone};
+use crate::locale::get_timezone;
 use crate::string::{utils as string_utils, AvmString, WStr};
 use chrono::{DateTime, Datelike, Duration, FixedOffset, LocalResult, TimeZone, Timelike, Utc};
 use num_traits::ToPrimitive;
@@ -272,7 +272,7 @@ pub fn instance_init<'gc>(
                 }
             }
         } else {
-            date.set_date_time(Some(get_current_date_time()))
+            date.set_date_time(Some(activation.context.clock.now()))
         }
     }
 
@@ -22,10 +22,7 @@ package flash.system {
             stub_getter("flash.system.Capabilities", "manufacturer");
             return "Adobe Windows"
         }
-        public static function get language(): String {
-            stub_getter("flash.system.Capabilities", "language");
-            return "en"
-        }
+        public native static function get language(): String;
         public static function get isDebugger(): Boolean {
             return false
         }
@@ -36,6 +36,24 @@ pub fn get_player_type<'gc>(
     Ok(AvmString::new_utf8(activation.context.gc_context, player_type).into())
 }
 
+/// Implements `flash.system.Capabilities.language`
+pub fn get_language<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    _this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<Value<'gc>, Error<'gc>> {
+    let player_version = activation.avm2().player_version;
+    Ok(AvmString::new_utf8(
+        activation.context.gc_context,
+        activation
+            .context
+            .system
+            .language
+            .get_language_code(player_version),
+    )
+    .into())
+}
+
 /// Implements `flash.system.Capabilities.screenResolutionX`
 pub fn get_screen_resolution_x<'gc>(
     activation: &mut Activation<'_, 'gc>,
@@ -3,7 +3,7 @@ use crate::avm2::error::{
     make_error_1014, make_error_1021, make_error_1025, make_error_1032, make_error_1054,
     make_error_1107, verify_error,
 };
-use crate::avm2::method::{BytecodeMethod, ParamConfig, ResolvedParamConfig};
+use crate::avm2::method::{BytecodeMethod, Method, ParamConfig, ResolvedParamConfig};
 use crate::avm2::multiname::Multiname;
 use crate::avm2::op::Op;
 use crate::avm2::script::TranslationUnit;
@@ -575,7 +575,7 @@ pub fn verify_method<'gc>(
         verified_code.push(resolved_op);
     }
 
-    if activation.avm2().optimizer_enabled() {
+    if activation.avm2().optimizer_enabled() && !is_optimizer_denied(activation, method) {
         crate::avm2::optimize::optimize(
             activation,
             method,
@@ -594,6 +594,88 @@ pub fn verify_method<'gc>(
     })
 }
 
+/// Checks the method against the optimizer denylist, logging it if it won't be optimized.
+fn is_optimizer_denied<'gc>(
+    activation: &mut Activation<'_, 'gc>,
+    method: &BytecodeMethod<'gc>,
+) -> bool {
+    if activation.avm2().optimizer_denylist().is_empty() {
+        return false;
+    }
+
+    let class = activation
+        .subclass_object()
+        .map(|class| class.inner_class_definition());
+    let method_name = class
+        .and_then(|class| method_trait_name(class, method))
+        .unwrap_or_else(|| method.method_name().into_owned());
+    let (local_name, qualified_name) = match class {
+        Some(class) => {
+            let name = class.name();
+            let local_name = format!("{}.{method_name}", name.local_name());
+            let uri = name.namespace().as_uri();
+            if uri.is_empty() {
+                (local_name.clone(), local_name)
+            } else {
+                (local_name.clone(), format!("{uri}.{local_name}"))
+            }
+        }
+        None => (method_name.clone(), method_name),
+    };
+
+    let denied = activation
+        .avm2()
+        .optimizer_denylist()
+        .iter()
+        .any(|pattern| {
+            glob_matches(pattern, &local_name) || glob_matches(pattern, &qualified_name)
+        });
+    if denied {
+        tracing::info!("Not optimizing {qualified_name}, as it is on the optimizer denylist");
+    }
+    denied
+}
+
+/// Finds the name a class uses for one of its methods.
+fn method_trait_name<'gc>(class: Class<'gc>, method: &BytecodeMethod<'gc>) -> Option<String> {
+    let is_method = |candidate: Method<'gc>| {
+        candidate
+            .into_bytecode()
+            .map(|candidate| std::ptr::eq(&*candidate, method))
+            .unwrap_or(false)
+    };
+
+    if is_method(class.instance_init()) {
+        return Some(class.name().local_name().to_string());
+    }
+    if is_method(class.class_init()) {
+        return Some("$cinit".to_string());
+    }
+
+    let instance_traits = class.instance_traits();
+    let class_traits = class.class_traits();
+    instance_traits
+        .iter()
+        .chain(class_traits.iter())
+        .find(|t| t.as_method().map(is_method).unwrap_or(false))
+        .map(|t| t.name().local_name().to_string())
+}
+
+/// Matches a name against a pattern, where `*` stands for any sequence of characters.
+pub(crate) fn glob_matches(pattern: &str, name: &str) -> bool {
+    match pattern.split_once('*') {
+        None => pattern == name,
+        Some((prefix, rest)) => {
+            let Some(name) = name.strip_prefix(prefix) else {
+                return false;
+            };
+            (0..=name.len())
+                .filter(|&i| name.is_char_boundary(i))
+                .any(|i| glob_matches(rest, &name[i..]))
+        }
+    }
+}
+
 pub fn resolve_param_config<'gc>(
     activation: &mut Activation<'_, 'gc>,
     param_config: &[ParamConfig<'gc>],
@@ -1111,3 +1193,27 @@ fn resolve_op<'gc>(
         AbcOp::Throw => Op::Throw,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::glob_matches;
+
+    #[test]
+    fn glob_exact() {
+        assert!(glob_matches("Main.update", "Main.update"));
+        assert!(!glob_matches("Main.update", "Main.updateAll"));
+        assert!(!glob_matches("Main.update", "Other.update"));
+    }
+
+    #[test]
+    fn glob_wildcards() {
+        assert!(glob_matches("Main.*", "Main.update"));
+        assert!(glob_matches("Main.*", "Main.$cinit"));
+        assert!(!glob_matches("Main.*", "MainMenu.update"));
+        assert!(glob_matches("com.example.*", "com.example.Main.update"));
+        assert!(glob_matches("*.update", "Main.update"));
+        assert!(glob_matches("*", "Main.update"));
+        assert!(glob_matches("Ma*.up*e", "Main.update"));
+        assert!(!glob_matches("Ma*.up*e", "Main.draw"));
+    }
+}
@@ -45,6 +45,15 @@ impl CircBuf {
     }
 }
 
+/// Receives the audio of event sounds and of stream sounds separately as it's mixed, so that
+/// they can be recorded as separate stems.
+pub trait StemSink: Send {
+    /// Receives the next sample frame of all playing event sounds and of all playing stream
+    /// sounds, before the master volume is applied. Sounds played from a substream, such as
+    /// those of a `NetStream`, count as stream sounds.
+    fn push(&mut self, events: [f32; 2], streams: [f32; 2]);
+}
+
 /// An audio mixer for a Flash movie.
 ///
 /// `AudioMixer` manages the audio state for a Flash movie. This can be used by any backend that
@@ -70,6 +79,9 @@ pub struct AudioMixer {
 
     /// The last two windows of output samples.
     output_memory: Arc<RwLock<CircBuf>>,
+
+    /// Where the audio of event and stream sounds is sent separately, if anywhere.
+    stem_sink: Arc<Mutex<Option<Box<dyn StemSink>>>>,
 }
 
 /// An audio stream.
@@ -169,7 +181,6 @@ struct Sound {
 struct SoundInstance {
     /// The handle the sound definition inside `sounds`.
     /// `None` if this is a stream sound.
-    #[allow(dead_code)]
     handle: Option<SoundHandle>,
 
     /// The audio stream. Call `next()` to yield sample frames.
@@ -245,6 +256,7 @@ impl AudioMixer {
             num_output_channels,
             output_sample_rate,
             output_memory: Arc::new(RwLock::new(CircBuf::new())),
+            stem_sink: Arc::new(Mutex::new(None)),
         }
     }
 
@@ -255,9 +267,15 @@ impl AudioMixer {
             volume: Arc::clone(&self.volume),
             num_output_channels: self.num_output_channels,
             output_memory: Arc::clone(&self.output_memory),
+            stem_sink: Arc::clone(&self.stem_sink),
         }
     }
 
+    /// Starts sending the audio of event sounds and of stream sounds to `sink` as it's mixed.
+    pub fn set_stem_sink(&mut self, sink: Box<dyn StemSink>) {
+        *self.stem_sink.lock().expect("Cannot be called reentrant") = Some(sink);
+    }
+
     /// Mixes audio into the given `output_buffer`.
     ///
     /// All playing sound instances will be sampled and mixed to fill `output_buffer`.
@@ -279,12 +297,14 @@ impl AudioMixer {
             .output_memory
             .write()
             .expect("Cannot be called reentrant");
+        let mut stem_sink = self.stem_sink.lock().expect("Cannot be called reentrant");
         Self::mix_audio::<T>(
             &mut sound_instances,
             volume,
             self.num_output_channels,
             output_buffer,
             &mut output_memory,
+            &mut stem_sink,
         );
     }
 
@@ -433,6 +453,7 @@ impl AudioMixer {
         num_channels: u8,
         mut output_buffer: &mut [T],
         output_memory: &mut CircBuf,
+        stem_sink: &mut Option<Box<dyn StemSink>>,
     ) where
         T: 'a
             + Default
@@ -455,6 +476,8 @@ impl AudioMixer {
             .chunks_exact_mut(num_channels.into())
         {
             let mut output_frame = Stereo::<T::Signed>::EQUILIBRIUM;
+            let mut event_frame = Stereo::<f32>::EQUILIBRIUM;
+            let mut stream_frame = Stereo::<f32>::EQUILIBRIUM;
             for (_, sound) in sound_instances.iter_mut() {
                 if sound.active && !sound.stream.is_exhausted() {
                     let sound_frame = sound.stream.next();
@@ -464,6 +487,15 @@ impl AudioMixer {
                         Sample::add_amp(left_0, left_1).to_sample(),
                         Sample::add_amp(right_0, right_1).to_sample(),
                     ];
+                    if stem_sink.is_some() {
+                        let stem_frame: Stereo<f32> =
+                            [sound_frame[0].to_sample(), sound_frame[1].to_sample()];
+                        if sound.handle.is_some() {
+                            event_frame = event_frame.add_amp(stem_frame);
+                        } else {
+                            stream_frame = stream_frame.add_amp(stem_frame);
+                        }
+                    }
                     sound_frame = sound_frame.scale_amp(volume);
 
                     sound.range.0[0] = sound.range.0[0].min(sound_frame[0].to_sample());
@@ -480,6 +512,10 @@ impl AudioMixer {
 
             output_memory.push([output_frame[0].to_sample(), output_frame[1].to_sample()]);
 
+            if let Some(stem_sink) = stem_sink {
+                stem_sink.push(event_frame, stream_frame);
+            }
+
             if output_memory.pos == 0 || output_memory.pos == 1024 {
                 for (_, sound) in sound_instances.iter_mut() {
                     sound.update_peak();
@@ -726,6 +762,8 @@ pub struct AudioMixerProxy {
     num_output_channels: u8,
 
     output_memory: Arc<RwLock<CircBuf>>,
+
+    stem_sink: Arc<Mutex<Option<Box<dyn StemSink>>>>,
 }
 
 impl AudioMixerProxy {
@@ -750,12 +788,14 @@ impl AudioMixerProxy {
             .output_memory
             .write()
             .expect("Cannot be called reentrant");
+        let mut stem_sink = self.stem_sink.lock().expect("Cannot be called reentrant");
         AudioMixer::mix_audio::<T>(
             &mut sound_instances,
             volume,
             self.num_output_channels,
             output_buffer,
             &mut output_memory,
+            &mut stem_sink,
         )
     }
 }
@@ -70,6 +70,10 @@ pub trait UiBackend: Downcast {
     /// Changes the mouse cursor image.
     fn set_mouse_cursor(&mut self, cursor: MouseCursor);
 
+    /// Informs the backend whether the movie is drawing its own cursor,
+    /// by dragging a display object locked to the mouse position.
+    fn set_custom_cursor_active(&mut self, active: bool);
+
     /// Get the clipboard content
     fn clipboard_content(&mut self) -> String;
 
@@ -93,6 +97,21 @@ pub trait UiBackend: Downcast {
 
     fn display_unsupported_video(&self, url: Url);
 
+    /// Called when the root movie couldn't be loaded because its compression isn't supported.
+    fn display_unsupported_compression(&self, compression: swf::Compression);
+
+    /// Called when a video can't be shown because its codec can't be decoded.
+    /// This may be called for every frame of the video, so backends should avoid repeating themselves.
+    fn display_unsupported_video_codec(
+        &self,
+        codec: swf::VideoCodec,
+        reason: UnsupportedVideoReason,
+    );
+
+    /// Called when a script has been running for longer than the maximum execution duration,
+    /// to decide what to do about it. This may ask the user, like a browser would.
+    fn script_timeout(&mut self) -> ScriptTimeoutAction;
+
     /// Called when a previously unknown device font is requested by a movie.
     /// The backend is requested to call `register` with any fonts that match the given name.
     ///
@@ -127,6 +146,42 @@ pub trait UiBackend: Downcast {
 }
 impl_downcast!(UiBackend);
 
+/// What to do about a script that has been running for too long.
+/// Decided by the UI backend via `UiBackend::script_timeout`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScriptTimeoutAction {
+    /// Keep running the script, allowing it another maximum execution duration.
+    Continue,
+
+    /// Terminate the script, but keep running any other scripts in the movie.
+    Abort,
+
+    /// Terminate the script, and don't run any further scripts in the movie.
+    Halt,
+}
+
+/// Why a video's codec couldn't be decoded.
+/// Communicated from the core to the UI backend via `UiBackend::display_unsupported_video_codec`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UnsupportedVideoReason {
+    /// The video backend doesn't decode any video, such as when none was compiled in.
+    NoVideoBackend,
+
+    /// The video backend decodes video, but not with this codec.
+    CodecNotImplemented,
+}
+
+impl UnsupportedVideoReason {
+    /// The reason behind an error from the video backend, if it was caused by the codec.
+    pub fn from_error(error: &ruffle_video::error::Error) -> Option<Self> {
+        match error {
+            ruffle_video::error::Error::DecodingNotSupported => Some(Self::NoVideoBackend),
+            ruffle_video::error::Error::UnsupportedCodec(_) => Some(Self::CodecNotImplemented),
+            _ => None,
+        }
+    }
+}
+
 /// A mouse cursor icon displayed by the Flash Player.
 /// Communicated from the core to the UI backend via `UiBackend::set_mouse_cursor`.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -265,6 +320,8 @@ impl UiBackend for NullUiBackend {
 
     fn set_mouse_cursor(&mut self, _cursor: MouseCursor) {}
 
+    fn set_custom_cursor_active(&mut self, _active: bool) {}
+
     fn clipboard_content(&mut self) -> String {
         "".into()
     }
@@ -281,6 +338,19 @@ impl UiBackend for NullUiBackend {
 
     fn display_unsupported_video(&self, _url: Url) {}
 
+    fn display_unsupported_compression(&self, _compression: swf::Compression) {}
+
+    fn display_unsupported_video_codec(
+        &self,
+        _codec: swf::VideoCodec,
+        _reason: UnsupportedVideoReason,
+    ) {
+    }
+
+    fn script_timeout(&mut self) -> ScriptTimeoutAction {
+        ScriptTimeoutAction::Halt
+    }
+
     fn load_device_font(
         &self,
         _name: &str,
@@ -0,0 +1,115 @@
+//! The clock that movies read the time from.
+
+use crate::locale::get_current_date_time;
+use chrono::{DateTime, Utc};
+use std::time::Duration;
+use web_time::Instant;
+
+/// The clock that movies read the time from, with `Date` and `getTimer`.
+///
+/// This can be faked to start at another time and to run faster or slower than real time,
+/// to test movies with content that depends on the date.
+#[derive(Debug, Clone, Copy)]
+pub struct Clock {
+    /// The instant at which the SWF was launched.
+    launched: Instant,
+    /// The time reported at launch, or `None` to report the current time.
+    start: Option<DateTime<Utc>>,
+    /// How much faster than real time the clock runs.
+    rate: f64,
+}
+
+impl Clock {
+    /// Starts a clock at `start`, or at the current time if `None`, running at `rate` times
+    /// real time.
+    pub fn new(start: Option<DateTime<Utc>>, rate: f64) -> Self {
+        let start = start.or_else(|| (rate != 1.0).then(get_current_date_time));
+        Self {
+            launched: Instant::now(),
+            start,
+            rate,
+        }
+    }
+
+    /// How much time has passed on this clock since the SWF was launched.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed_at(Instant::now())
+    }
+
+    /// The current date and time on this clock.
+    pub fn now(&self) -> DateTime<Utc> {
+        self.now_at(Instant::now())
+    }
+
+    fn elapsed_at(&self, instant: Instant) -> Duration {
+        let elapsed = instant.duration_since(self.launched);
+        if self.rate == 1.0 {
+            elapsed
+        } else {
+            elapsed.mul_f64(self.rate)
+        }
+    }
+
+    fn now_at(&self, instant: Instant) -> DateTime<Utc> {
+        match self.start {
+            Some(start) => chrono::Duration::from_std(self.elapsed_at(instant))
+                .ok()
+                .and_then(|elapsed| start.checked_add_signed(elapsed))
+                .unwrap_or(DateTime::<Utc>::MAX_UTC),
+            None => get_current_date_time(),
+        }
+    }
+}
+
+impl Default for Clock {
+    fn default() -> Self {
+        Self::new(None, 1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn date(hour: u32, minute: u32, second: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2010, 6, 15, hour, minute, second)
+            .single()
+            .expect("Unambiguous time")
+    }
+
+    #[test]
+    fn fixed_start_runs_in_real_time() {
+        let clock = Clock::new(Some(date(12, 0, 0)), 1.0);
+        let later = clock.launched + Duration::from_secs(90);
+        assert_eq!(clock.now_at(clock.launched), date(12, 0, 0));
+        assert_eq!(clock.elapsed_at(later), Duration::from_secs(90));
+        assert_eq!(clock.now_at(later), date(12, 1, 30));
+    }
+
+    #[test]
+    fn rate_scales_elapsed_time() {
+        let clock = Clock::new(Some(date(12, 0, 0)), 60.0);
+        let later = clock.launched + Duration::from_secs(2);
+        assert_eq!(clock.elapsed_at(later), Duration::from_secs(120));
+        assert_eq!(clock.now_at(later), date(12, 2, 0));
+    }
+
+    #[test]
+    fn rate_alone_starts_at_the_current_time() {
+        let clock = Clock::new(None, 2.0);
+        assert_eq!(clock.start, Some(get_current_date_time()));
+        let later = clock.launched + Duration::from_secs(5);
+        assert_eq!(
+            clock.now_at(later),
+            get_current_date_time() + chrono::Duration::seconds(10)
+        );
+    }
+
+    #[test]
+    fn default_reports_the_current_time() {
+        let clock = Clock::default();
+        assert_eq!(clock.start, None);
+        assert_eq!(clock.now(), get_current_date_time());
+    }
+}
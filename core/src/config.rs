@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use swf::Color;
 
 /// Controls whether the content is letterboxed or pillarboxed when the
 /// player's aspect ratio does not match the movie's aspect ratio.
@@ -22,6 +23,59 @@ pub enum Letterbox {
     On,
 }
 
+/// How the bars around letterboxed content look.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LetterboxStyle {
+    /// The color of the bars.
+    pub color: Color,
+
+    /// The pattern drawn over the bars.
+    pub pattern: LetterboxPattern,
+}
+
+impl Default for LetterboxStyle {
+    fn default() -> Self {
+        Self {
+            color: Color::BLACK,
+            pattern: LetterboxPattern::Solid,
+        }
+    }
+}
+
+/// A pattern drawn over the bars around letterboxed content.
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename = "letterbox_pattern")]
+pub enum LetterboxPattern {
+    /// The bars are a solid color.
+    #[default]
+    #[serde(rename = "solid")]
+    Solid,
+
+    /// Thin lines in a slightly different shade run across the bars.
+    #[serde(rename = "lines")]
+    Lines,
+}
+
+/// Controls what happens when the root timeline reaches its last frame.
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename = "loop")]
+pub enum LoopMode {
+    /// The movie decides, as it would in Flash Player.
+    #[default]
+    #[serde(rename = "default")]
+    Default,
+
+    /// The root timeline always rewinds to the first frame, even if the movie stopped it.
+    #[serde(rename = "always")]
+    Always,
+
+    /// The root timeline stops at its last frame, and playback is complete.
+    #[serde(rename = "never")]
+    Never,
+}
+
 /// The networking API access mode of the Ruffle player.
 /// This setting is only used on web.
 #[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
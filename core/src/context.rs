@@ -18,8 +18,10 @@ use crate::backend::{
     log::LogBackend,
     navigator::NavigatorBackend,
     storage::StorageBackend,
-    ui::{InputManager, UiBackend},
+    ui::{InputManager, ScriptTimeoutAction, UiBackend, UnsupportedVideoReason},
 };
+use crate::clock::Clock;
+use crate::config::LoopMode;
 use crate::context_menu::ContextMenuState;
 use crate::display_object::{EditText, MovieClip, SoundTransform, Stage};
 use crate::external::ExternalInterface;
@@ -47,9 +49,11 @@ use ruffle_render::backend::{BitmapCacheEntry, RenderBackend};
 use ruffle_render::commands::{CommandHandler, CommandList};
 use ruffle_render::transform::TransformStack;
 use ruffle_video::backend::VideoBackend;
+use ruffle_video::error::Error as VideoError;
 use std::collections::{HashMap, VecDeque};
 use std::sync::{Arc, Mutex, Weak};
 use std::time::Duration;
+use swf::VideoCodec;
 use web_time::Instant;
 
 /// Minimal context, useful for manipulating the GC heap.
@@ -201,11 +205,12 @@ pub struct UpdateContext<'a, 'gc> {
     /// External interface for (for example) JavaScript <-> ActionScript interaction
     pub external_interface: &'a mut ExternalInterface<'gc>,
 
-    /// The instant at which the SWF was launched.
-    pub start_time: Instant,
+    /// The clock movies read the time from.
+    pub clock: Clock,
 
     /// The instant at which the current update started.
-    pub update_start: Instant,
+    /// This is moved forward when the user allows a long running script to continue.
+    pub update_start: &'a mut Instant,
 
     /// The maximum amount of time that can be called before a `Error::ExecutionTimeout`
     /// is raised. This defaults to 15 seconds but can be changed.
@@ -226,6 +231,12 @@ pub struct UpdateContext<'a, 'gc> {
     /// Whether movies are prevented from changing the stage frame rate.
     pub forced_frame_rate: bool,
 
+    /// Overrides what happens when the root timeline reaches its last frame.
+    pub loop_mode: LoopMode,
+
+    /// Set once the root timeline has stopped at its last frame because of `LoopMode::Never`.
+    pub playback_complete: &'a mut bool,
+
     /// Amount of actions performed since the last timeout check
     pub actions_since_timeout_check: &'a mut u16,
 
@@ -518,7 +529,7 @@ impl<'a, 'gc> UpdateContext<'a, 'gc> {
             avm1: self.avm1,
             avm2: self.avm2,
             external_interface: self.external_interface,
-            start_time: self.start_time,
+            clock: self.clock,
             update_start: self.update_start,
             max_execution_duration: self.max_execution_duration,
             focus_tracker: self.focus_tracker,
@@ -526,6 +537,8 @@ impl<'a, 'gc> UpdateContext<'a, 'gc> {
             time_offset: self.time_offset,
             frame_rate: self.frame_rate,
             forced_frame_rate: self.forced_frame_rate,
+            loop_mode: self.loop_mode,
+            playback_complete: self.playback_complete,
             actions_since_timeout_check: self.actions_since_timeout_check,
             frame_phase: self.frame_phase,
             stream_manager: self.stream_manager,
@@ -537,6 +550,33 @@ impl<'a, 'gc> UpdateContext<'a, 'gc> {
         }
     }
 
+    /// Checks whether the current script has been running for longer than the maximum
+    /// execution duration, and if so asks the UI what to do about it.
+    ///
+    /// Returns `None` if the script should keep running.
+    pub fn check_execution_timeout(&mut self) -> Option<ScriptTimeoutAction> {
+        if self.update_start.elapsed() < self.max_execution_duration {
+            return None;
+        }
+
+        let action = self.ui.script_timeout();
+        // Whether the script goes on or is aborted, whatever runs next gets a maximum
+        // execution duration of its own, rather than timing out right away.
+        *self.update_start = Instant::now();
+        match action {
+            ScriptTimeoutAction::Continue => None,
+            action => Some(action),
+        }
+    }
+
+    /// Lets the UI know when an error from the video backend means that a video's codec
+    /// can't be decoded, rather than that something went wrong with one of its frames.
+    pub fn report_video_error(&self, codec: VideoCodec, error: &VideoError) {
+        if let Some(reason) = UnsupportedVideoReason::from_error(error) {
+            self.ui.display_unsupported_video_codec(codec, reason);
+        }
+    }
+
     #[inline]
     pub fn borrow_gc<'b>(&'b mut self) -> GcContext<'b, 'gc>
     where
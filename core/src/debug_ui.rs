@@ -1,5 +1,6 @@
 mod avm1;
 mod avm2;
+mod callstack;
 mod display_object;
 mod domain;
 mod handle;
@@ -8,6 +9,8 @@ mod movie;
 use crate::context::{RenderContext, UpdateContext};
 use crate::debug_ui::avm1::Avm1ObjectWindow;
 use crate::debug_ui::avm2::Avm2ObjectWindow;
+use crate::debug_ui::callstack::CallstackWindow;
+pub(crate) use crate::debug_ui::display_object::display_object_type;
 use crate::debug_ui::display_object::{DisplayObjectSearchWindow, DisplayObjectWindow};
 use crate::debug_ui::domain::DomainListWindow;
 use crate::debug_ui::handle::{
@@ -35,6 +38,7 @@ pub struct DebugUi {
     movie_list: Option<MovieListWindow>,
     domain_list: Option<DomainListWindow>,
     display_object_search: Option<DisplayObjectSearchWindow>,
+    callstack: Option<CallstackWindow>,
 }
 
 #[derive(Debug)]
@@ -50,6 +54,7 @@ pub enum Message {
     ShowDomains,
     SaveFile(ItemToSave),
     SearchForDisplayObject,
+    ToggleCallstack,
 }
 
 impl DebugUi {
@@ -97,6 +102,12 @@ impl DebugUi {
             }
         }
 
+        if let Some(mut callstack) = self.callstack.take() {
+            if callstack.show(egui_ctx, context) {
+                self.callstack = Some(callstack);
+            }
+        }
+
         for message in messages {
             match message {
                 Message::TrackDisplayObject(object) => {
@@ -132,8 +143,16 @@ impl DebugUi {
                 Message::SearchForDisplayObject => {
                     self.display_object_search = Some(Default::default());
                 }
+                Message::ToggleCallstack => {
+                    self.callstack = match self.callstack {
+                        Some(_) => None,
+                        None => Some(Default::default()),
+                    };
+                }
             }
         }
+
+        context.avm2.set_sample_call_stack(self.callstack.is_some());
     }
 
     pub fn should_suspend_player(&self) -> bool {
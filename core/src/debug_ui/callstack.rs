@@ -0,0 +1,38 @@
+use crate::context::UpdateContext;
+use egui::{Label, RichText, Window};
+
+/// Shows the ActionScript callstack, as sampled while scripts run.
+///
+/// Scripts never run while the debug UI is drawn, so the stack is sampled by the
+/// AVM2 interpreter as functions return, and during long-running scripts.
+/// This only happens while the window is open.
+#[derive(Debug, Default)]
+pub struct CallstackWindow {}
+
+impl CallstackWindow {
+    pub fn show(&mut self, egui_ctx: &egui::Context, context: &mut UpdateContext) -> bool {
+        let mut keep_open = true;
+
+        Window::new("ActionScript Callstack")
+            .open(&mut keep_open)
+            .scroll2([true, true])
+            .show(egui_ctx, |ui| {
+                if !context.swf.is_action_script_3() {
+                    ui.weak("The callstack is only available for ActionScript 3 movies.");
+                    return;
+                }
+
+                match context.avm2.sampled_call_stack() {
+                    Some(callstack) if !callstack.trim().is_empty() => {
+                        ui.label("Most recently sampled:");
+                        ui.add(Label::new(RichText::new(callstack.trim()).monospace()).wrap(false));
+                    }
+                    _ => {
+                        ui.weak("No ActionScript has run since this window was opened.");
+                    }
+                }
+            });
+
+        keep_open
+    }
+}
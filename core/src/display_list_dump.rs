@@ -0,0 +1,120 @@
+//! A JSON dump of the display list, for debugging why content isn't showing.
+
+use crate::context::UpdateContext;
+use crate::debug_ui::display_object_type;
+use crate::prelude::*;
+use serde::Serialize;
+
+/// An object on the display list, along with everything below it.
+#[derive(Serialize)]
+struct DumpedObject {
+    depth: Depth,
+    #[serde(rename = "type")]
+    object_type: &'static str,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    name: String,
+    character: CharacterId,
+    x: f64,
+    y: f64,
+    visible: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    children: Vec<DumpedObject>,
+}
+
+/// Describes the display list of the stage as a JSON document.
+///
+/// Each object has its depth, type, name and character, followed by its position
+/// within its parent, whether it's visible and its children, such as:
+///
+/// ```json
+/// [
+///   {
+///     "depth": 0,
+///     "type": "MovieClip",
+///     "name": "root1",
+///     "character": 0,
+///     "x": 0.0,
+///     "y": 0.0,
+///     "visible": true,
+///     "children": [
+///       { "depth": 1, "type": "Graphic", "character": 3, "x": 10.0, "y": 20.5, "visible": false }
+///     ]
+///   }
+/// ]
+/// ```
+///
+/// Only the structure is included, so dumps of different frames can be diffed.
+pub fn dump_stage(context: &mut UpdateContext<'_, '_>) -> String {
+    let objects: Vec<_> = context
+        .stage
+        .as_container()
+        .map(|container| container.iter_render_list().map(dump_object).collect())
+        .unwrap_or_default();
+    dump_json(&objects)
+}
+
+fn dump_object(object: DisplayObject<'_>) -> DumpedObject {
+    DumpedObject {
+        depth: object.depth(),
+        object_type: display_object_type(object),
+        name: object.name().to_string(),
+        character: object.id(),
+        x: object.x().to_pixels(),
+        y: object.y().to_pixels(),
+        visible: object.visible(),
+        children: object
+            .as_container()
+            .map(|container| container.iter_render_list().map(dump_object).collect())
+            .unwrap_or_default(),
+    }
+}
+
+fn dump_json(objects: &[DumpedObject]) -> String {
+    serde_json::to_string_pretty(objects).expect("Display list dump is always valid JSON")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn object(depth: Depth, children: Vec<DumpedObject>) -> DumpedObject {
+        DumpedObject {
+            depth,
+            object_type: "MovieClip",
+            name: String::new(),
+            character: 0,
+            x: 0.0,
+            y: 0.0,
+            visible: true,
+            children,
+        }
+    }
+
+    #[test]
+    fn empty_names_and_children_are_left_out() {
+        let json: serde_json::Value =
+            serde_json::from_str(&dump_json(&[object(1, vec![])])).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!([{
+                "depth": 1,
+                "type": "MovieClip",
+                "character": 0,
+                "x": 0.0,
+                "y": 0.0,
+                "visible": true,
+            }])
+        );
+    }
+
+    #[test]
+    fn children_are_nested() {
+        let mut root = object(0, vec![object(2, vec![])]);
+        root.name = "root1".to_string();
+        root.visible = false;
+        let json: serde_json::Value = serde_json::from_str(&dump_json(&[root])).unwrap();
+        assert_eq!(json[0]["name"], "root1");
+        assert_eq!(json[0]["visible"], false);
+        assert_eq!(json[0]["children"][0]["depth"], 2);
+    }
+}
@@ -20,7 +20,7 @@ use crate::display_object::interactive::{
 use crate::display_object::{DisplayObjectBase, DisplayObjectPtr};
 use crate::drawing::Drawing;
 use crate::events::{ClipEvent, ClipEventResult, TextControlCode};
-use crate::font::{round_down_to_pixel, FontType, Glyph, TextRenderSettings};
+use crate::font::{render_glyph, round_down_to_pixel, FontType, Glyph, TextRenderSettings};
 use crate::html::{
     BoxBounds, FormatSpans, LayoutBox, LayoutContent, LayoutMetrics, Position, TextFormat,
 };
@@ -1051,9 +1051,7 @@ impl<'gc> EditText<'gc> {
                         }
 
                         // Render glyph.
-                        context
-                            .commands
-                            .render_shape(glyph_shape_handle, context.transform_stack.transform());
+                        render_glyph(context, glyph_shape_handle);
                         context.transform_stack.pop();
                     }
 
@@ -1413,6 +1411,17 @@ impl<'gc> EditText<'gc> {
         }
     }
 
+    /// Reads the clipboard and prepares its text for pasting into this field.
+    fn clipboard_text_to_paste(self, context: &mut UpdateContext<'_, 'gc>) -> WString {
+        let text = context.ui.clipboard_content();
+        text_to_paste(
+            &text,
+            self.is_multiline(),
+            &self.0.read().restrict,
+            self.available_chars(),
+        )
+    }
+
     pub fn text_control_input(
         self,
         control_code: TextControlCode,
@@ -1496,20 +1505,9 @@ impl<'gc> EditText<'gc> {
                     }
                 }
                 TextControlCode::Paste => {
-                    let text = context.ui.clipboard_content();
-                    let mut text = self.0.read().restrict.filter_allowed(&text);
-
-                    if text.len() > self.available_chars() && self.available_chars() > 0 {
-                        text = text[0..self.available_chars()].to_owned();
-                    }
-
+                    let text = self.clipboard_text_to_paste(context);
                     if text.len() <= self.available_chars() {
-                        self.replace_text(
-                            selection.start(),
-                            selection.end(),
-                            &WString::from_utf8(&text),
-                            context,
-                        );
+                        self.replace_text(selection.start(), selection.end(), &text, context);
                         let new_pos = selection.start() + text.len();
                         if is_selectable {
                             self.set_selection(
@@ -2616,6 +2614,31 @@ enum EditTextRestrictToken {
     Caret,
 }
 
+/// Prepares `text` for pasting into a field which `available_chars` more characters fit in.
+///
+/// Line breaks are converted to the newline character used by input fields, only the first
+/// line is kept when the field isn't multiline, and the text is cut to what fits, counted
+/// in UTF-16 units like the field's contents.
+fn text_to_paste(
+    text: &str,
+    is_multiline: bool,
+    restrict: &EditTextRestrict,
+    available_chars: usize,
+) -> WString {
+    let newline = EditText::INPUT_NEWLINE.to_string();
+    let mut text = text.replace("\r\n", &newline).replace('\n', &newline);
+    if !is_multiline {
+        if let Some(end) = text.find(EditText::INPUT_NEWLINE) {
+            text.truncate(end);
+        }
+    }
+    let mut text = WString::from_utf8(&restrict.filter_allowed(&text));
+    if text.len() > available_chars && available_chars > 0 {
+        text = text[0..available_chars].to_owned();
+    }
+    text
+}
+
 impl EditTextRestrict {
     const INTERVAL_ALL: (char, char) = ('\0', char::MAX);
 
@@ -2804,3 +2827,44 @@ impl EditTextRestrict {
         filtered
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paste_is_cut_in_utf16_units() {
+        let restrict = EditTextRestrict::allow_all();
+        // "é" is 2 bytes and "😀" is 4 bytes in UTF-8, but 1 and 2 units in UTF-16.
+        assert_eq!(
+            text_to_paste("é😀xyz", true, &restrict, 3),
+            WString::from_utf8("é😀")
+        );
+        assert_eq!(
+            text_to_paste("é😀xyz", true, &restrict, usize::MAX),
+            WString::from_utf8("é😀xyz")
+        );
+    }
+
+    #[test]
+    fn paste_converts_line_breaks() {
+        let restrict = EditTextRestrict::allow_all();
+        assert_eq!(
+            text_to_paste("one\r\ntwo\nthree", true, &restrict, usize::MAX),
+            WString::from_utf8("one\rtwo\rthree")
+        );
+    }
+
+    #[test]
+    fn paste_into_single_line_keeps_first_line() {
+        let restrict = EditTextRestrict::allow_all();
+        assert_eq!(
+            text_to_paste("one\r\ntwo", false, &restrict, usize::MAX),
+            WString::from_utf8("one")
+        );
+        assert_eq!(
+            text_to_paste("one\ntwo", false, &restrict, usize::MAX),
+            WString::from_utf8("one")
+        );
+    }
+}
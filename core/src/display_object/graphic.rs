@@ -13,6 +13,7 @@ use core::fmt;
 use gc_arena::{Collect, GcCell, Mutation};
 use ruffle_render::backend::ShapeHandle;
 use ruffle_render::commands::CommandHandler;
+use ruffle_render::shape_utils::DistilledShape;
 use std::cell::{Ref, RefMut};
 use std::sync::Arc;
 
@@ -107,6 +108,17 @@ impl<'gc> Graphic<'gc> {
             w.drawing.get_or_insert_with(Drawing::new)
         })
     }
+
+    /// Calls `f` with the paths making up this graphic, whether they come
+    /// from the original shape tag or from the drawing API.
+    pub fn with_distilled_shape<R>(self, f: impl FnOnce(&DistilledShape) -> R) -> R {
+        let read = self.0.read();
+        if let Some(drawing) = &read.drawing {
+            f(&drawing.distilled_shape())
+        } else {
+            f(&DistilledShape::from(&read.static_data.shape))
+        }
+    }
 }
 
 impl<'gc> TDisplayObject<'gc> for Graphic<'gc> {
@@ -10,6 +10,7 @@ use core::fmt;
 use gc_arena::{Collect, Gc, GcCell, Mutation};
 use ruffle_render::backend::ShapeHandle;
 use ruffle_render::commands::CommandHandler;
+use ruffle_render::shape_utils::DistilledShape;
 use std::cell::{Ref, RefCell, RefMut};
 use std::sync::Arc;
 use swf::{Fixed16, Fixed8};
@@ -62,6 +63,13 @@ impl<'gc> MorphShape<'gc> {
         self.0.write(gc_context).ratio = ratio;
         self.invalidate_cached_bitmap(gc_context);
     }
+
+    /// Calls `f` with the paths making up this morph shape at its current ratio.
+    pub fn with_distilled_shape<R>(self, f: impl FnOnce(&DistilledShape) -> R) -> R {
+        let read = self.0.read();
+        let frame = read.static_data.get_frame(read.ratio);
+        f(&DistilledShape::from(&frame.shape))
+    }
 }
 
 impl<'gc> TDisplayObject<'gc> for MorphShape<'gc> {
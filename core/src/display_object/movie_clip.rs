@@ -16,6 +16,7 @@ use crate::avm1::Avm1;
 use crate::avm1::{Activation as Avm1Activation, ActivationIdentifier};
 use crate::binary_data::BinaryData;
 use crate::character::{Character, CompressedBitmap};
+use crate::config::LoopMode;
 use crate::context::{ActionType, RenderContext, UpdateContext};
 use crate::context_stub;
 use crate::display_object::container::{dispatch_removed_event, ChildContainer};
@@ -1503,6 +1504,25 @@ impl<'gc> MovieClip<'gc> {
         actions.into_iter()
     }
 
+    /// Whether this is the root timeline of the main movie.
+    fn is_stage_root(self, context: &UpdateContext<'_, 'gc>) -> bool {
+        context
+            .stage
+            .root_clip()
+            .map_or(false, |root| DisplayObject::ptr_eq(root, self.into()))
+    }
+
+    /// Rewinds the root timeline if it was stopped at its last frame, for `LoopMode::Always`.
+    pub fn loop_if_finished(self, context: &mut UpdateContext<'_, 'gc>) {
+        if !self.playing()
+            && self.total_frames() > 1
+            && self.current_frame() >= self.total_frames()
+            && self.is_stage_root(context)
+        {
+            self.goto_frame(context, 1, false);
+        }
+    }
+
     /// Determine what the clip's next frame should be.
     fn determine_next_frame(self) -> NextFrame {
         if self.current_frame() < self.total_frames() {
@@ -1522,6 +1542,15 @@ impl<'gc> MovieClip<'gc> {
         is_action_script_3: bool,
     ) {
         let next_frame = self.determine_next_frame();
+        if matches!(next_frame, NextFrame::First)
+            && context.loop_mode == LoopMode::Never
+            && self.is_stage_root(context)
+        {
+            // The root timeline plays only once.
+            self.stop(context);
+            *context.playback_complete = true;
+            return;
+        }
         match next_frame {
             NextFrame::Next => {
                 let mut write = self.0.write(context.gc_context);
@@ -7,7 +7,7 @@ use crate::avm2::{
     ScriptObject as Avm2ScriptObject, StageObject as Avm2StageObject, Value as Avm2Value,
 };
 use crate::backend::ui::MouseCursor;
-use crate::config::Letterbox;
+use crate::config::{Letterbox, LetterboxPattern, LetterboxStyle};
 use crate::context::{RenderContext, UpdateContext};
 use crate::display_object::container::ChildContainer;
 use crate::display_object::interactive::{
@@ -31,6 +31,9 @@ use std::fmt::{self, Display, Formatter};
 use std::str::FromStr;
 use std::sync::Arc;
 
+/// The distance between the lines of `LetterboxPattern::Lines`, in pixels.
+const LETTERBOX_LINE_SPACING: f32 = 4.0;
+
 /// The Stage is the root of the display object hierarchy. It contains all AVM1
 /// levels as well as AVM2 movies.
 #[derive(Clone, Collect, Copy)]
@@ -70,6 +73,10 @@ pub struct StageData<'gc> {
     #[collect(require_static)]
     letterbox: Letterbox,
 
+    /// How the letterbox bars look, when they're shown.
+    #[collect(require_static)]
+    letterbox_style: LetterboxStyle,
+
     /// The dimensions of the SWF file.
     #[collect(require_static)]
     movie_size: (u32, u32),
@@ -123,6 +130,9 @@ pub struct StageData<'gc> {
     #[collect(require_static)]
     window_mode: WindowMode,
 
+    /// Whether the edges of text are anti-aliased. Without it, glyphs are aligned to whole pixels.
+    text_anti_aliasing: bool,
+
     /// Whether objects display a glowing border when they have focus.
     stage_focus_rect: bool,
 
@@ -162,6 +172,7 @@ impl<'gc> Stage<'gc> {
                 child: ChildContainer::new(movie.clone()),
                 background_color: None,
                 letterbox: Letterbox::Fullscreen,
+                letterbox_style: Default::default(),
                 // This is updated when we set the root movie
                 movie_size: (0, 0),
                 quality: Default::default(),
@@ -181,6 +192,7 @@ impl<'gc> Stage<'gc> {
                 use_bitmap_downsampling: false,
                 view_bounds: Default::default(),
                 window_mode: Default::default(),
+                text_anti_aliasing: true,
                 show_menu: true,
                 stage_focus_rect: true,
                 avm2_object: Avm2ScriptObject::custom_object(gc_context, None, None),
@@ -224,6 +236,14 @@ impl<'gc> Stage<'gc> {
         self.0.write(gc_context).letterbox = letterbox
     }
 
+    pub fn letterbox_style(self) -> LetterboxStyle {
+        self.0.read().letterbox_style
+    }
+
+    pub fn set_letterbox_style(self, gc_context: &Mutation<'gc>, style: LetterboxStyle) {
+        self.0.write(gc_context).letterbox_style = style
+    }
+
     /// Get the size of the SWF file.
     pub fn movie_size(self) -> (u32, u32) {
         self.0.read().movie_size
@@ -442,6 +462,17 @@ impl<'gc> Stage<'gc> {
         self.0.write(context.gc_context).window_mode = window_mode;
     }
 
+    /// Whether the edges of text are anti-aliased, which is a setting of the player
+    /// rather than something the movie can change.
+    pub fn text_anti_aliasing(self) -> bool {
+        self.0.read().text_anti_aliasing
+    }
+
+    /// Sets whether the edges of text are anti-aliased.
+    pub fn set_text_anti_aliasing(self, gc_context: &Mutation<'gc>, value: bool) {
+        self.0.write(gc_context).text_anti_aliasing = value;
+    }
+
     pub fn view_bounds(self) -> Rectangle<Twips> {
         self.0.read().view_bounds.clone()
     }
@@ -610,58 +641,83 @@ impl<'gc> Stage<'gc> {
         if margin_top + margin_bottom > margin_left + margin_right {
             // Top + bottom
             if margin_top > 0.0 {
-                context.commands.draw_rect(
-                    Color::BLACK,
-                    Matrix::create_box(
-                        viewport_width,
-                        margin_top,
-                        0.0,
-                        Twips::default(),
-                        Twips::default(),
-                    ),
-                );
+                self.draw_letterbox_bar(context, 0.0, 0.0, viewport_width, margin_top);
             }
             if margin_bottom > 0.0 {
-                context.commands.draw_rect(
-                    Color::BLACK,
-                    Matrix::create_box(
-                        viewport_width,
-                        margin_bottom,
-                        0.0,
-                        Twips::default(),
-                        Twips::from_pixels((viewport_height - margin_bottom) as f64),
-                    ),
+                self.draw_letterbox_bar(
+                    context,
+                    0.0,
+                    viewport_height - margin_bottom,
+                    viewport_width,
+                    margin_bottom,
                 );
             }
         } else {
             // Left + right
             if margin_left > 0.0 {
-                context.commands.draw_rect(
-                    Color::BLACK,
-                    Matrix::create_box(
-                        margin_left,
-                        viewport_height,
-                        0.0,
-                        Twips::default(),
-                        Twips::default(),
-                    ),
-                );
+                self.draw_letterbox_bar(context, 0.0, 0.0, margin_left, viewport_height);
             }
             if margin_right > 0.0 {
-                context.commands.draw_rect(
-                    Color::BLACK,
-                    Matrix::create_box(
-                        margin_right,
-                        viewport_height,
-                        0.0,
-                        Twips::from_pixels((viewport_width - margin_right) as f64),
-                        Twips::default(),
-                    ),
+                self.draw_letterbox_bar(
+                    context,
+                    viewport_width - margin_right,
+                    0.0,
+                    margin_right,
+                    viewport_height,
                 );
             }
         }
     }
 
+    /// Draw a single letterbox bar in the letterbox style, in viewport pixels.
+    fn draw_letterbox_bar(
+        &self,
+        context: &mut RenderContext<'_, 'gc>,
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+    ) {
+        let style = self.0.read().letterbox_style;
+        let draw_box = |context: &mut RenderContext<'_, 'gc>, color: Color, y: f32, height: f32| {
+            context.commands.draw_rect(
+                color,
+                Matrix::create_box(
+                    width,
+                    height,
+                    0.0,
+                    Twips::from_pixels(x as f64),
+                    Twips::from_pixels(y as f64),
+                ),
+            );
+        };
+
+        draw_box(context, style.color, y, height);
+        if style.pattern == LetterboxPattern::Lines {
+            // Lines are a little lighter than the bar, or a little darker if it's already light.
+            let color = style.color;
+            let luminance = u16::from(color.r) + u16::from(color.g) + u16::from(color.b);
+            let shade = |c: u8| {
+                if luminance > 3 * 128 {
+                    c.saturating_sub(16)
+                } else {
+                    c.saturating_add(16)
+                }
+            };
+            let line_color = Color {
+                r: shade(color.r),
+                g: shade(color.g),
+                b: shade(color.b),
+                a: color.a,
+            };
+            let mut line_y = y;
+            while line_y < y + height {
+                draw_box(context, line_color, line_y, 1.0f32.min(y + height - line_y));
+                line_y += LETTERBOX_LINE_SPACING;
+            }
+        }
+    }
+
     /// Obtain the root movie on the stage.
     ///
     /// It is not a guarantee that the root clip exists, as it can be deliberately removed.
@@ -3,13 +3,12 @@ use crate::avm2::{
 };
 use crate::context::{RenderContext, UpdateContext};
 use crate::display_object::{DisplayObjectBase, DisplayObjectPtr};
-use crate::font::TextRenderSettings;
+use crate::font::{render_glyph, Glyph, TextRenderSettings};
 use crate::prelude::*;
 use crate::tag_utils::SwfMovie;
 use crate::vminterface::Instantiator;
 use core::fmt;
 use gc_arena::{Collect, GcCell, Mutation};
-use ruffle_render::commands::CommandHandler;
 use ruffle_render::transform::Transform;
 use std::cell::{Ref, RefMut};
 use std::sync::Arc;
@@ -66,6 +65,50 @@ impl<'gc> Text<'gc> {
         self.0.write(gc_context).render_settings = settings;
         self.invalidate_cached_bitmap(gc_context);
     }
+
+    /// Calls `f` with every glyph of the text, along with the color it's drawn in
+    /// and the matrix that places it within the text.
+    pub fn for_each_glyph(
+        self,
+        context: &UpdateContext<'_, 'gc>,
+        mut f: impl FnMut(&Matrix, Color, &Glyph),
+    ) {
+        let tf = self.0.read();
+        let Some(library) = context.library.library_for_movie(self.movie()) else {
+            return;
+        };
+
+        let mut color = Color::from_rgba(0);
+        let mut font_id = 0;
+        let mut height = Twips::ZERO;
+        let mut glyph_matrix = Matrix::default();
+        for block in &tf.static_data.text_blocks {
+            if let Some(x) = block.x_offset {
+                glyph_matrix.tx = x;
+            }
+            if let Some(y) = block.y_offset {
+                glyph_matrix.ty = y;
+            }
+            color = block.color.unwrap_or(color);
+            font_id = block.font_id.unwrap_or(font_id);
+            height = block.height.unwrap_or(height);
+            if let Some(font) = library.get_font(font_id) {
+                let scale = (height.get() as f32) / font.scale();
+                glyph_matrix.a = scale;
+                glyph_matrix.d = scale;
+                for c in &block.glyphs {
+                    if let Some(glyph) = font.get_glyph(c.index as usize) {
+                        f(
+                            &(tf.static_data.text_transform * glyph_matrix),
+                            color,
+                            glyph,
+                        );
+                        glyph_matrix.tx += Twips::new(c.advance);
+                    }
+                }
+            }
+        }
+    }
 }
 
 impl<'gc> TDisplayObject<'gc> for Text<'gc> {
@@ -150,10 +193,7 @@ impl<'gc> TDisplayObject<'gc> for Text<'gc> {
                     if let Some(glyph) = font.get_glyph(c.index as usize) {
                         if let Some(glyph_shape_handle) = glyph.shape_handle(context.renderer) {
                             context.transform_stack.push(&transform);
-                            context.commands.render_shape(
-                                glyph_shape_handle,
-                                context.transform_stack.transform(),
-                            );
+                            render_glyph(context, glyph_shape_handle);
                             context.transform_stack.pop();
                         }
 
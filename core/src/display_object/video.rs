@@ -311,9 +311,11 @@ impl<'gc> Video<'gc> {
             return;
         };
 
+        let mut codec = VideoCodec::None;
         let res = match &*source.read() {
             VideoSource::Swf { streamdef, frames } => match frames.get(&frame_id) {
                 Some((slice_start, slice_end)) => {
+                    codec = streamdef.codec;
                     let encframe = EncodedFrame {
                         codec: streamdef.codec,
                         data: &read.movie.data()[*slice_start..*slice_end],
@@ -343,7 +345,10 @@ impl<'gc> Video<'gc> {
                 self.invalidate_cached_bitmap(context.gc_context);
                 *context.needs_render = true;
             }
-            Err(e) => tracing::error!("Got error when seeking to video frame {}: {}", frame_id, e),
+            Err(e) => {
+                tracing::error!("Got error when seeking to video frame {}: {}", frame_id, e);
+                context.report_video_error(codec, &e);
+            }
         }
     }
 }
@@ -399,15 +404,14 @@ impl<'gc> TDisplayObject<'gc> for Video<'gc> {
                         streamdef.codec,
                         streamdef.deblocking,
                     );
-                    if stream.is_err() {
-                        tracing::error!(
-                            "Got error when post-instantiating video: {}",
-                            stream.unwrap_err()
-                        );
-                        return;
-                    }
-
-                    let stream = stream.unwrap();
+                    let stream = match stream {
+                        Ok(stream) => stream,
+                        Err(e) => {
+                            tracing::error!("Got error when post-instantiating video: {}", e);
+                            context.report_video_error(streamdef.codec, &e);
+                            return;
+                        }
+                    };
                     let mut keyframes = BTreeSet::new();
 
                     for (frame_id, (frame_start, frame_end)) in frames {
@@ -1,3 +1,4 @@
+use crate::context::RenderContext;
 use crate::drawing::Drawing;
 use crate::html::TextSpan;
 use crate::prelude::*;
@@ -5,7 +6,9 @@ use crate::string::WStr;
 use gc_arena::{Collect, Gc, Mutation};
 use ruffle_render::backend::null::NullBitmapSource;
 use ruffle_render::backend::{RenderBackend, ShapeHandle};
-use ruffle_render::shape_utils::{DrawCommand, FillRule};
+use ruffle_render::bitmap::PixelSnapping;
+use ruffle_render::commands::CommandHandler;
+use ruffle_render::shape_utils::{DistilledShape, DrawCommand, FillRule};
 use ruffle_render::transform::Transform;
 use std::borrow::Cow;
 use std::cell::{OnceCell, RefCell};
@@ -15,6 +18,18 @@ use swf::FillStyle;
 
 pub use swf::TextGridFit;
 
+/// Draws a glyph with the transform at the top of the transform stack.
+///
+/// Without text anti-aliasing, the glyph is moved to the nearest whole pixel, so that the edges
+/// of a pixel font drawn at its intended size fall on pixel boundaries instead of being blurred.
+pub fn render_glyph(context: &mut RenderContext<'_, '_>, shape: ShapeHandle) {
+    let mut transform = context.transform_stack.transform();
+    if !context.stage.text_anti_aliasing() {
+        PixelSnapping::Always.apply(&mut transform.matrix);
+    }
+    context.commands.render_shape(shape, transform);
+}
+
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 pub enum DefaultFont {
     /// `_sans`, a Sans-Serif font (similar to Helvetica or Arial)
@@ -834,6 +849,18 @@ impl Glyph {
         self.shape.hit_test(point, local_matrix)
     }
 
+    /// Calls `f` with the outline of the glyph, if it has one.
+    pub fn with_distilled_shape<R>(&self, f: impl FnOnce(&DistilledShape) -> R) -> Option<R> {
+        match &self.shape {
+            GlyphShape::Swf(glyph) => {
+                let mut glyph = glyph.borrow_mut();
+                Some(f(&DistilledShape::from(&*glyph.shape())))
+            }
+            GlyphShape::Drawing(drawing) => Some(f(&drawing.distilled_shape())),
+            GlyphShape::None => None,
+        }
+    }
+
     pub fn advance(&self) -> Twips {
         self.advance
     }
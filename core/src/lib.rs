@@ -25,8 +25,10 @@ mod binary_data;
 pub mod bitmap;
 pub mod buffer;
 mod character;
+mod clock;
 pub mod context;
 pub mod context_menu;
+mod display_list_dump;
 mod drawing;
 mod ecma_conversions;
 pub mod events;
@@ -46,6 +48,7 @@ mod prelude;
 pub mod socket;
 mod streams;
 pub mod string;
+mod svg_export;
 pub mod tag_utils;
 pub mod timer;
 mod types;
@@ -66,6 +69,7 @@ pub use context_menu::ContextMenuItem;
 pub use events::PlayerEvent;
 pub use font::DefaultFont;
 pub use indexmap;
+pub use library::AssetStats;
 pub use loader::LoadBehavior;
 pub use player::{Player, PlayerBuilder, PlayerRuntime, StaticCallstack};
 pub use ruffle_render::backend::ViewportDimensions;
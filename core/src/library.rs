@@ -1,7 +1,7 @@
 use crate::avm1::{PropertyMap as Avm1PropertyMap, PropertyMap};
 use crate::avm2::{ClassObject as Avm2ClassObject, Domain as Avm2Domain};
-use crate::backend::audio::SoundHandle;
-use crate::character::Character;
+use crate::backend::audio::{AudioBackend, SoundHandle};
+use crate::character::{Character, CompressedBitmap};
 use std::borrow::Cow;
 
 use crate::display_object::{Bitmap, Graphic, MorphShape, Text};
@@ -390,6 +390,23 @@ impl<'a, 'gc> ruffle_render::bitmap::BitmapSource for MovieLibrarySource<'a, 'gc
     }
 }
 
+/// How many assets the movies have loaded, and how much memory they use.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AssetStats {
+    pub bitmaps: usize,
+    /// The size of the bitmaps as compressed in their movies, in bytes.
+    pub bitmap_bytes: usize,
+    /// How many bitmaps have been decoded and given to the renderer, which can be released.
+    pub decoded_bitmaps: usize,
+    /// The size of the decoded bitmaps, in bytes.
+    pub decoded_bitmap_bytes: usize,
+    pub sounds: usize,
+    /// The size of the sounds as given to the audio backend, in bytes.
+    pub sound_bytes: usize,
+    /// The embedded, global and device fonts loaded so far.
+    pub fonts: usize,
+}
+
 /// Symbol library for multiple movies.
 pub struct Library<'gc> {
     /// All the movie libraries.
@@ -462,6 +479,61 @@ impl<'gc> Library<'gc> {
         self.movie_libraries.keys().collect()
     }
 
+    pub fn asset_stats(&self, audio: &dyn AudioBackend) -> AssetStats {
+        let mut stats = AssetStats {
+            fonts: self.device_fonts.0.len() + self.global_fonts.0.len(),
+            ..Default::default()
+        };
+        for (_, library) in self.movie_libraries.iter() {
+            for character in library.characters.values() {
+                match character {
+                    Character::Bitmap {
+                        compressed, handle, ..
+                    } => {
+                        stats.bitmaps += 1;
+                        stats.bitmap_bytes += match compressed {
+                            CompressedBitmap::Jpeg { data, alpha, .. } => {
+                                data.len() + alpha.as_ref().map_or(0, Vec::len)
+                            }
+                            CompressedBitmap::Lossless(lossless) => lossless.data.len(),
+                        };
+                        if handle.borrow().is_some() {
+                            let size = compressed.size();
+                            stats.decoded_bitmaps += 1;
+                            stats.decoded_bitmap_bytes +=
+                                size.width as usize * size.height as usize * 4;
+                        }
+                    }
+                    Character::Sound(sound) => {
+                        stats.sounds += 1;
+                        stats.sound_bytes += audio.get_sound_size(*sound).unwrap_or(0) as usize;
+                    }
+                    Character::Font(_) => stats.fonts += 1,
+                    _ => {}
+                }
+            }
+        }
+        stats
+    }
+
+    /// Releases the decoded copy of every bitmap character, which is decoded again the next
+    /// time it's drawn. Shapes already using a bitmap keep it alive until they're gone.
+    ///
+    /// Returns how many bitmaps were released.
+    pub fn release_decoded_bitmaps(&mut self) -> usize {
+        let mut released = 0;
+        for (_, library) in self.movie_libraries.iter() {
+            for character in library.characters.values() {
+                if let Character::Bitmap { handle, .. } = character {
+                    if handle.borrow_mut().take().is_some() {
+                        released += 1;
+                    }
+                }
+            }
+        }
+        released
+    }
+
     /// Returns the default Font implementations behind the built in names (ie `_sans`)
     pub fn default_font(
         &mut self,
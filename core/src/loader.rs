@@ -943,11 +943,12 @@ impl<'gc> Loader<'gc> {
 
             let mut movie =
                 SwfMovie::from_data(&body, spoofed_or_swf_url, None).map_err(|error| {
-                    player
-                        .lock()
-                        .unwrap()
-                        .ui()
-                        .display_root_movie_download_failed_message(true);
+                    let player = player.lock().unwrap();
+                    if let crate::tag_utils::Error::UnsupportedCompression(compression) = &error {
+                        player.ui().display_unsupported_compression(*compression);
+                    } else {
+                        player.ui().display_root_movie_download_failed_message(true);
+                    }
                     error
                 })?;
             on_metadata(movie.header());
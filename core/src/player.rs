@@ -1,4 +1,4 @@
-use crate::avm1::globals::system::SandboxType;
+use crate::avm1::globals::system::{Language, SandboxType};
 use crate::avm1::Attribute;
 use crate::avm1::Avm1;
 use crate::avm1::Object;
@@ -9,7 +9,7 @@ use crate::avm1::{TObject, Value};
 use crate::avm2::{
     object::TObject as _, Activation as Avm2Activation, Avm2, CallStack, Object as Avm2Object,
 };
-use crate::backend::ui::FontDefinition;
+use crate::backend::ui::{FontDefinition, LanguageIdentifier};
 use crate::backend::{
     audio::{AudioBackend, AudioManager},
     log::LogBackend,
@@ -17,8 +17,9 @@ use crate::backend::{
     storage::StorageBackend,
     ui::{InputManager, MouseCursor, UiBackend},
 };
+use crate::clock::Clock;
 use crate::compatibility_rules::CompatibilityRules;
-use crate::config::Letterbox;
+use crate::config::{Letterbox, LetterboxStyle, LoopMode};
 use crate::context::GcContext;
 use crate::context::{ActionQueue, ActionType, RenderContext, UpdateContext};
 use crate::context_menu::{
@@ -34,7 +35,7 @@ use crate::events::{ButtonKeyCode, ClipEvent, ClipEventResult, KeyCode, MouseBut
 use crate::external::{ExternalInterface, ExternalInterfaceProvider, NullFsCommandProvider};
 use crate::external::{FsCommandProvider, Value as ExternalValue};
 use crate::frame_lifecycle::{run_all_phases_avm2, FramePhase};
-use crate::library::Library;
+use crate::library::{AssetStats, Library};
 use crate::limits::ExecutionLimit;
 use crate::loader::{LoadBehavior, LoadManager};
 use crate::local_connection::LocalConnections;
@@ -49,6 +50,7 @@ use crate::tag_utils::SwfMovie;
 use crate::timer::Timers;
 use crate::vminterface::Instantiator;
 use crate::DefaultFont;
+use chrono::{DateTime, Utc};
 use gc_arena::{Collect, DynamicRootSet, GcCell, Rootable};
 use rand::{rngs::SmallRng, SeedableRng};
 use ruffle_render::backend::{null::NullRenderer, RenderBackend, ViewportDimensions};
@@ -300,6 +302,12 @@ pub struct Player {
     forced_frame_rate: bool,
     actions_since_timeout_check: u16,
 
+    /// Overrides what happens when the root timeline reaches its last frame.
+    loop_mode: LoopMode,
+
+    /// Whether the root timeline has stopped at its last frame because of `LoopMode::Never`.
+    playback_complete: bool,
+
     frame_phase: FramePhase,
 
     stub_tracker: StubCollection,
@@ -311,6 +319,9 @@ pub struct Player {
     frame_accumulator: f64,
     recent_run_frame_timings: VecDeque<f64>,
 
+    /// The number of times `run_frame` has been called.
+    frames_run: u64,
+
     /// Faked time passage for fooling hand-written busy-loop FPS limiters.
     time_offset: u32,
 
@@ -334,7 +345,11 @@ pub struct Player {
     time_til_next_timer: Option<f64>,
 
     /// The instant at which the SWF was launched.
-    start_time: Instant,
+    clock: Clock,
+
+    /// The instant at which the current update started.
+    /// Used to detect scripts running for longer than `max_execution_duration`.
+    update_start: Instant,
 
     /// The maximum amount of time that can be called before a `Error::ExecutionTimeout`
     /// is raised. This defaults to 15 seconds but can be changed.
@@ -394,6 +409,11 @@ impl Player {
         });
     }
 
+    /// The number of frames run since the player was created, including any spent preloading.
+    pub fn frames_run(&self) -> u64 {
+        self.frames_run
+    }
+
     /// Get rough estimate of the max # of times we can update the frame.
     ///
     /// In some cases, we might want to update several times in a row.
@@ -795,6 +815,16 @@ impl Player {
         })
     }
 
+    pub fn letterbox_style(&mut self) -> LetterboxStyle {
+        self.mutate_with_update_context(|context| context.stage.letterbox_style())
+    }
+
+    pub fn set_letterbox_style(&mut self, style: LetterboxStyle) {
+        self.mutate_with_update_context(|context| {
+            context.stage.set_letterbox_style(context.gc_context, style)
+        })
+    }
+
     pub fn movie_width(&mut self) -> u32 {
         self.mutate_with_update_context(|context| context.stage.movie_size().0)
     }
@@ -829,12 +859,61 @@ impl Player {
         })
     }
 
+    pub fn quality(&mut self) -> StageQuality {
+        self.mutate_with_update_context(|context| context.stage.quality())
+    }
+
     pub fn set_quality(&mut self, quality: StageQuality) {
         self.mutate_with_update_context(|context| {
             context.stage.set_quality(context, quality);
         })
     }
 
+    pub fn scale_mode(&mut self) -> StageScaleMode {
+        self.mutate_with_update_context(|context| context.stage.scale_mode())
+    }
+
+    /// Sets the scale mode of the stage, unless it was forced to stay the same.
+    pub fn set_scale_mode(&mut self, scale_mode: StageScaleMode) {
+        self.mutate_with_update_context(|context| {
+            let stage = context.stage;
+            stage.set_scale_mode(context, scale_mode);
+        })
+    }
+
+    pub fn align(&mut self) -> StageAlign {
+        self.mutate_with_update_context(|context| context.stage.align())
+    }
+
+    pub fn text_anti_aliasing(&mut self) -> bool {
+        self.mutate_with_update_context(|context| context.stage.text_anti_aliasing())
+    }
+
+    /// Sets whether the edges of text are anti-aliased. Without it, glyphs are aligned
+    /// to whole pixels, which keeps pixel fonts crisp while shapes stay anti-aliased.
+    pub fn set_text_anti_aliasing(&mut self, value: bool) {
+        self.mutate_with_update_context(|context| {
+            context
+                .stage
+                .set_text_anti_aliasing(context.gc_context, value)
+        });
+        self.needs_render = true;
+    }
+
+    /// Counts the assets loaded by every movie, and how much memory they use.
+    pub fn loaded_asset_stats(&mut self) -> AssetStats {
+        self.mutate_with_update_context(|context| context.library.asset_stats(context.audio))
+    }
+
+    /// Releases the assets that can be loaded again when they're next needed, which are
+    /// currently the decoded bitmaps. Returns how many assets were released.
+    pub fn clear_asset_cache(&mut self) -> usize {
+        let released =
+            self.mutate_with_update_context(|context| context.library.release_decoded_bitmaps());
+        self.needs_render = true;
+        released
+    }
+
     pub fn set_window_mode(&mut self, window_mode: &str) {
         self.mutate_with_update_context(|context| {
             let stage = context.stage;
@@ -1307,6 +1386,7 @@ impl Player {
     /// Updates the hover state of buttons.
     fn update_mouse_state(&mut self, is_mouse_button_changed: bool, is_mouse_moved: bool) -> bool {
         let mut new_cursor = self.mouse_cursor;
+        let mut custom_cursor_active = false;
         let mut mouse_cursor_needs_check = self.mouse_cursor_needs_check;
         let mouse_in_stage = self.mouse_in_stage();
 
@@ -1561,6 +1641,12 @@ impl Player {
                 refresh
             };
             Self::run_actions(context);
+
+            // A clip locked to the center of the mouse is how movies usually draw their own cursor.
+            custom_cursor_active = context
+                .drag_object
+                .as_ref()
+                .is_some_and(|drag_object| drag_object.lock_center);
             needs_render
         });
 
@@ -1569,6 +1655,7 @@ impl Player {
             self.mouse_cursor = new_cursor;
             self.ui.set_mouse_cursor(new_cursor)
         }
+        self.ui.set_custom_cursor_active(custom_cursor_active);
         self.mouse_cursor_needs_check = mouse_cursor_needs_check;
 
         needs_render
@@ -1662,6 +1749,7 @@ impl Player {
 
     #[instrument(level = "debug", skip_all)]
     pub fn run_frame(&mut self) {
+        self.frames_run += 1;
         let frame_time = Duration::from_nanos((750_000_000.0 / self.frame_rate) as u64);
         let (mut execution_limit, may_execute_while_streaming) = match self.load_behavior {
             LoadBehavior::Streaming => (
@@ -1681,6 +1769,18 @@ impl Player {
         }
 
         self.update(|context| {
+            context.avm2.reset_call_trace_count();
+
+            if context.loop_mode == LoopMode::Always {
+                if let Some(root) = context
+                    .stage
+                    .root_clip()
+                    .and_then(|root| root.as_movie_clip())
+                {
+                    root.loop_if_finished(context);
+                }
+            }
+
             // TODO: Is this order correct?
             run_all_phases_avm2(context);
             Avm1::run_frame(context);
@@ -1696,6 +1796,11 @@ impl Player {
         self.needs_render = true;
     }
 
+    /// Whether the root timeline has stopped at its last frame, because it isn't allowed to loop.
+    pub fn playback_complete(&self) -> bool {
+        self.playback_complete
+    }
+
     #[instrument(level = "debug", skip_all)]
     pub fn render(&mut self) {
         let invalidated = self
@@ -1907,6 +2012,7 @@ impl Player {
     where
         F: for<'a, 'gc> FnOnce(&mut UpdateContext<'a, 'gc>) -> R,
     {
+        self.update_start = Instant::now();
         self.gc_arena.borrow().mutate(|gc_context, gc_root| {
             let mut root_data = gc_root.data.write(gc_context);
 
@@ -1970,8 +2076,8 @@ impl Player {
                 avm1,
                 avm2,
                 external_interface,
-                start_time: self.start_time,
-                update_start: Instant::now(),
+                clock: self.clock,
+                update_start: &mut self.update_start,
                 max_execution_duration: self.max_execution_duration,
                 focus_tracker: stage.focus_tracker(),
                 times_get_time_called: 0,
@@ -1979,6 +2085,8 @@ impl Player {
                 audio_manager,
                 frame_rate: &mut self.frame_rate,
                 forced_frame_rate: self.forced_frame_rate,
+                loop_mode: self.loop_mode,
+                playback_complete: &mut self.playback_complete,
                 actions_since_timeout_check: &mut self.actions_since_timeout_check,
                 frame_phase: &mut self.frame_phase,
                 stub_tracker: &mut self.stub_tracker,
@@ -2161,6 +2269,37 @@ impl Player {
         }
     }
 
+    /// Serializes the current frame into an SVG document.
+    ///
+    /// Shapes, bitmaps, text fields and static text are exported as vectors wherever possible;
+    /// anything that can't be represented is skipped with a warning.
+    pub fn export_svg(&mut self) -> String {
+        self.mutate_with_update_context(crate::svg_export::export_stage)
+    }
+
+    /// Describes the display list of the stage as a JSON document.
+    ///
+    /// Only the structure is included, so dumps of different frames can be diffed.
+    pub fn dump_display_list(&mut self) -> String {
+        self.mutate_with_update_context(crate::display_list_dump::dump_stage)
+    }
+
+    /// Returns an estimate, in bytes, of the memory currently held by the garbage collected heap.
+    ///
+    /// This includes any external allocations reported to the arena, but not
+    /// memory owned by backends (such as textures held by the renderer).
+    pub fn total_allocation(&self) -> usize {
+        self.gc_arena.borrow().metrics().total_allocation()
+    }
+
+    /// Runs a full garbage collection cycle right away, instead of spreading it over the
+    /// coming frames. Returns how many bytes were reclaimed, as told by [`Self::total_allocation`].
+    pub fn collect_garbage(&mut self) -> usize {
+        let before = self.total_allocation();
+        self.gc_arena.borrow_mut().collect_all();
+        before.saturating_sub(self.total_allocation())
+    }
+
     /// Eagerly load any device fonts.
     /// It's preferable to use [UiBackend::load_device_font] for lazy font loading,
     /// but this is for situations where you don't know the names of the fonts you're going to register.
@@ -2201,6 +2340,7 @@ pub struct PlayerBuilder {
     allow_fullscreen: bool,
     fullscreen: bool,
     letterbox: Letterbox,
+    letterbox_style: LetterboxStyle,
     max_execution_duration: Duration,
     viewport_width: u32,
     viewport_height: u32,
@@ -2213,6 +2353,9 @@ pub struct PlayerBuilder {
     player_runtime: PlayerRuntime,
     quality: StageQuality,
     sandbox_type: SandboxType,
+    language: Option<LanguageIdentifier>,
+    fake_time: Option<DateTime<Utc>>,
+    time_rate: f64,
     page_url: Option<String>,
     frame_rate: Option<f64>,
     external_interface_providers: Vec<Box<dyn ExternalInterfaceProvider>>,
@@ -2220,6 +2363,9 @@ pub struct PlayerBuilder {
     #[cfg(feature = "known_stubs")]
     stub_report_output: Option<std::path::PathBuf>,
     avm2_optimizer_enabled: bool,
+    avm2_optimizer_denylist: Vec<String>,
+    avm2_call_trace: Option<Vec<String>>,
+    loop_mode: LoopMode,
 }
 
 impl PlayerBuilder {
@@ -2249,6 +2395,7 @@ impl PlayerBuilder {
             fullscreen: false,
             // Disable script timeout in debug builds by default.
             letterbox: Letterbox::Fullscreen,
+            letterbox_style: Default::default(),
             max_execution_duration: Duration::from_secs(if cfg!(debug_assertions) {
                 u64::MAX
             } else {
@@ -2265,6 +2412,9 @@ impl PlayerBuilder {
             player_runtime: PlayerRuntime::default(),
             quality: StageQuality::High,
             sandbox_type: SandboxType::LocalTrusted,
+            language: None,
+            fake_time: None,
+            time_rate: 1.0,
             page_url: None,
             frame_rate: None,
             external_interface_providers: vec![],
@@ -2272,6 +2422,9 @@ impl PlayerBuilder {
             #[cfg(feature = "known_stubs")]
             stub_report_output: None,
             avm2_optimizer_enabled: true,
+            avm2_optimizer_denylist: Vec::new(),
+            avm2_call_trace: None,
+            loop_mode: LoopMode::Default,
         }
     }
 
@@ -2360,6 +2513,13 @@ impl PlayerBuilder {
         self
     }
 
+    /// Sets how the letterbox bars look, when they're shown.
+    #[inline]
+    pub fn with_letterbox_style(mut self, style: LetterboxStyle) -> Self {
+        self.letterbox_style = style;
+        self
+    }
+
     /// Sets the maximum execution time of ActionScript code.
     #[inline]
     pub fn with_max_execution_duration(mut self, duration: Duration) -> Self {
@@ -2437,6 +2597,20 @@ impl PlayerBuilder {
         self
     }
 
+    /// Configures the locale reported to movies as the language of the system (default is English)
+    pub fn with_language(mut self, language: LanguageIdentifier) -> Self {
+        self.language = Some(language);
+        self
+    }
+
+    /// Configures the clock movies read the time from to start at `start` instead of the
+    /// current time, and to run `rate` times faster than real time (default is real time)
+    pub fn with_fake_time(mut self, start: Option<DateTime<Utc>>, rate: f64) -> Self {
+        self.fake_time = start;
+        self.time_rate = rate;
+        self
+    }
+
     // Configure the embedding page's URL (if applicable)
     pub fn with_page_url(mut self, page_url: Option<String>) -> Self {
         self.page_url = page_url;
@@ -2449,6 +2623,13 @@ impl PlayerBuilder {
         self
     }
 
+    /// Sets whether the root timeline loops, overriding the movie.
+    #[inline]
+    pub fn with_loop_mode(mut self, loop_mode: LoopMode) -> Self {
+        self.loop_mode = loop_mode;
+        self
+    }
+
     /// Adds an External Interface provider for movies to communicate with
     pub fn with_external_interface(mut self, provider: Box<dyn ExternalInterfaceProvider>) -> Self {
         self.external_interface_providers.push(provider);
@@ -2479,6 +2660,25 @@ impl PlayerBuilder {
         self
     }
 
+    /// Excludes methods from the AVM2 optimizer, which is useful for narrowing down optimizer bugs.
+    ///
+    /// Each pattern is matched against `Class.method` (optionally with the package, as in
+    /// `com.example.Class.method`), where `*` matches anything, such as in `Class.*`.
+    pub fn with_avm2_optimizer_denylist(mut self, patterns: Vec<String>) -> Self {
+        self.avm2_optimizer_denylist = patterns;
+        self
+    }
+
+    /// Logs AVM2 function calls with their arguments and return values through the trace output.
+    ///
+    /// Only functions matching one of the patterns are logged, which are matched like in
+    /// `with_avm2_optimizer_denylist`; if there are no patterns, every call is logged.
+    /// `None` disables call tracing. At most a few hundred calls are logged per frame.
+    pub fn with_avm2_call_trace(mut self, patterns: Option<Vec<String>>) -> Self {
+        self.avm2_call_trace = patterns;
+        self
+    }
+
     fn create_gc_root<'gc>(
         gc_context: &'gc gc_arena::Mutation<'gc>,
         player_version: u8,
@@ -2587,10 +2787,14 @@ impl PlayerBuilder {
                 // Timing
                 frame_rate,
                 forced_frame_rate,
+                loop_mode: self.loop_mode,
+                playback_complete: false,
                 frame_phase: Default::default(),
                 frame_accumulator: 0.0,
                 recent_run_frame_timings: VecDeque::with_capacity(10),
-                start_time: Instant::now(),
+                frames_run: 0,
+                clock: Clock::new(self.fake_time, self.time_rate),
+                update_start: Instant::now(),
                 time_offset: 0,
                 time_til_next_timer: None,
                 max_execution_duration: self.max_execution_duration,
@@ -2605,7 +2809,13 @@ impl PlayerBuilder {
 
                 // Misc. state
                 rng: SmallRng::seed_from_u64(get_current_date_time().timestamp_millis() as u64),
-                system: SystemProperties::new(self.sandbox_type),
+                system: {
+                    let mut system = SystemProperties::new(self.sandbox_type);
+                    if let Some(language) = &self.language {
+                        system.language = Language::from_language_identifier(language);
+                    }
+                    system
+                },
                 page_url: self.page_url.clone(),
                 transform_stack: TransformStack::new(),
                 instance_counter: 0,
@@ -2665,6 +2875,10 @@ impl PlayerBuilder {
             context
                 .avm2
                 .set_optimizer_enabled(self.avm2_optimizer_enabled);
+            context
+                .avm2
+                .set_optimizer_denylist(self.avm2_optimizer_denylist);
+            context.avm2.set_call_trace(self.avm2_call_trace);
             Avm2::load_player_globals(context).expect("Unable to load AVM2 globals");
 
             let stage = context.stage;
@@ -2686,6 +2900,7 @@ impl PlayerBuilder {
         });
         player_lock.audio.set_frame_rate(frame_rate);
         player_lock.set_letterbox(self.letterbox);
+        player_lock.set_letterbox_style(self.letterbox_style);
         player_lock.set_quality(self.quality);
         player_lock.set_viewport_dimensions(ViewportDimensions {
             width: self.viewport_width,
@@ -914,6 +914,7 @@ impl<'gc> NetStream<'gc> {
                                     "Got error when registering FLV video stream: {}",
                                     e
                                 );
+                                context.report_video_error(codec, &e);
                                 return; //TODO: This originally breaks and halts tag processing
                             }
                         }
@@ -967,6 +968,7 @@ impl<'gc> NetStream<'gc> {
                     }
                     Err(e) => {
                         tracing::error!("Decoding video frame {} failed: {}", frame_id, e);
+                        context.report_video_error(codec, &e);
                     }
                 }
             }
@@ -1076,7 +1078,8 @@ impl<'gc> NetStream<'gc> {
                             _ => unreachable!(),
                         },
                         Err(e) => {
-                            tracing::error!("Got error when registering FLV video stream: {}", e)
+                            tracing::error!("Got error when registering FLV video stream: {}", e);
+                            context.report_video_error(video_codec, &e);
                         }
                     }
                 } else {
@@ -0,0 +1,628 @@
+//! Serialization of the current frame into an SVG document.
+
+use crate::context::UpdateContext;
+use crate::display_object::{Bitmap, EditText, Text};
+use crate::prelude::*;
+use base64::prelude::BASE64_STANDARD;
+use base64::Engine;
+use ruffle_render::shape_utils::{DistilledShape, DrawCommand, DrawPath, FillRule};
+use std::fmt::Write;
+use std::io::Cursor;
+use swf::{ColorTransform, FillStyle, Gradient, LineCapStyle, LineJoinStyle, LineStyle};
+
+/// Half the size of the gradient square that SWF gradient matrices map onto, in pixels.
+const GRADIENT_SIZE: f64 = 819.2;
+
+/// Serializes the visible display list of the stage into an SVG document.
+///
+/// Static text is exported as the outlines of its glyphs. Content that can't be represented
+/// (such as video or bitmap fills) is left out of the document, and a warning is logged for each skipped object.
+pub fn export_stage(context: &mut UpdateContext<'_, '_>) -> String {
+    let stage = context.stage;
+    let (width, height) = stage.stage_size();
+
+    let mut exporter = SvgExporter::default();
+    if let Some(color) = stage.background_color() {
+        let _ = write!(
+            exporter.body,
+            r#"<rect width="{width}" height="{height}" {}/>"#,
+            paint("fill", &color)
+        );
+    }
+    exporter.export_object(context, stage.into(), &ColorTransform::IDENTITY);
+
+    let mut document = String::new();
+    let _ = write!(
+        document,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" width="{width}" height="{height}" viewBox="0 0 {width} {height}">"#
+    );
+    if !exporter.defs.is_empty() {
+        let _ = write!(document, "<defs>{}</defs>", exporter.defs);
+    }
+    document.push_str(&exporter.body);
+    document.push_str("</svg>\n");
+    document
+}
+
+#[derive(Default)]
+struct SvgExporter {
+    /// Shared definitions, such as gradients.
+    defs: String,
+    body: String,
+    next_id: usize,
+}
+
+impl SvgExporter {
+    fn export_object<'gc>(
+        &mut self,
+        context: &mut UpdateContext<'_, 'gc>,
+        object: DisplayObject<'gc>,
+        parent_color_transform: &ColorTransform,
+    ) {
+        if !object.visible() || object.maskee().is_some() {
+            return;
+        }
+        if object.masker().is_some() {
+            warn!("SVG export: ignoring the mask of {}", describe(object));
+        }
+        if !object.filters().is_empty() {
+            warn!("SVG export: ignoring the filters of {}", describe(object));
+        }
+
+        let color_transform = *parent_color_transform * *object.base().color_transform();
+        let _ = write!(self.body, "<g {}>", transform(object.base().matrix()));
+
+        match object {
+            DisplayObject::Graphic(graphic) => {
+                graphic.with_distilled_shape(|shape| self.write_shape(shape, &color_transform))
+            }
+            DisplayObject::MorphShape(morph_shape) => {
+                morph_shape.with_distilled_shape(|shape| self.write_shape(shape, &color_transform))
+            }
+            DisplayObject::MovieClip(_) => {
+                if let Some(drawing) = object.as_drawing(context.gc_context) {
+                    self.write_shape(&drawing.distilled_shape(), &color_transform);
+                }
+            }
+            DisplayObject::Bitmap(bitmap) => self.write_bitmap(context, bitmap),
+            DisplayObject::EditText(edit_text) => self.write_edit_text(edit_text, &color_transform),
+            DisplayObject::Text(text) => self.write_static_text(context, text, &color_transform),
+            DisplayObject::Video(_) => {
+                warn!(
+                    "SVG export: skipping {}, as it can't be represented",
+                    describe(object)
+                );
+            }
+            _ => {}
+        }
+
+        if let Some(container) = object.as_container() {
+            for child in container.iter_render_list() {
+                self.export_object(context, child, &color_transform);
+            }
+        }
+
+        self.body.push_str("</g>");
+    }
+
+    fn write_shape(&mut self, shape: &DistilledShape, color_transform: &ColorTransform) {
+        for path in &shape.paths {
+            match path {
+                DrawPath::Fill {
+                    style,
+                    commands,
+                    winding_rule,
+                } => {
+                    let Some(fill) = self.fill_style_paint("fill", style, color_transform) else {
+                        continue;
+                    };
+                    let fill_rule = match winding_rule {
+                        FillRule::EvenOdd => "evenodd",
+                        FillRule::NonZero => "nonzero",
+                    };
+                    let _ = write!(
+                        self.body,
+                        r#"<path d="{}" {fill} fill-rule="{fill_rule}"/>"#,
+                        path_data(commands, false)
+                    );
+                }
+                DrawPath::Stroke {
+                    style,
+                    commands,
+                    is_closed,
+                } => {
+                    let Some(stroke) =
+                        self.fill_style_paint("stroke", style.fill_style(), color_transform)
+                    else {
+                        continue;
+                    };
+                    let _ = write!(
+                        self.body,
+                        r#"<path d="{}" fill="none" {stroke} {}/>"#,
+                        path_data(commands, *is_closed),
+                        stroke_attributes(style)
+                    );
+                }
+            }
+        }
+    }
+
+    /// Returns the attributes painting `property` (`fill` or `stroke`) with the given style,
+    /// or `None` if the style can't be represented.
+    fn fill_style_paint(
+        &mut self,
+        property: &str,
+        style: &FillStyle,
+        color_transform: &ColorTransform,
+    ) -> Option<String> {
+        match style {
+            FillStyle::Color(color) => Some(paint(property, &(color_transform * *color))),
+            FillStyle::LinearGradient(gradient) => {
+                let id = self.define_gradient(
+                    "linearGradient",
+                    &format!(
+                        r#"x1="{}" y1="0" x2="{GRADIENT_SIZE}" y2="0""#,
+                        -GRADIENT_SIZE
+                    ),
+                    gradient,
+                    color_transform,
+                );
+                Some(format!(r#"{property}="url(#{id})""#))
+            }
+            FillStyle::RadialGradient(gradient) => {
+                let id = self.define_gradient(
+                    "radialGradient",
+                    &format!(r#"cx="0" cy="0" r="{GRADIENT_SIZE}""#),
+                    gradient,
+                    color_transform,
+                );
+                Some(format!(r#"{property}="url(#{id})""#))
+            }
+            FillStyle::FocalGradient {
+                gradient,
+                focal_point,
+            } => {
+                let id = self.define_gradient(
+                    "radialGradient",
+                    &format!(
+                        r#"cx="0" cy="0" r="{GRADIENT_SIZE}" fx="{}" fy="0""#,
+                        focal_point.to_f64() * GRADIENT_SIZE
+                    ),
+                    gradient,
+                    color_transform,
+                );
+                Some(format!(r#"{property}="url(#{id})""#))
+            }
+            FillStyle::Bitmap { id, .. } => {
+                warn!("SVG export: skipping a path filled with bitmap {id}");
+                None
+            }
+        }
+    }
+
+    fn define_gradient(
+        &mut self,
+        element: &str,
+        geometry: &str,
+        gradient: &Gradient,
+        color_transform: &ColorTransform,
+    ) -> String {
+        let id = format!("gradient{}", self.next_id);
+        self.next_id += 1;
+
+        let matrix = Matrix::from(gradient.matrix);
+        let spread = match gradient.spread {
+            swf::GradientSpread::Pad => "pad",
+            swf::GradientSpread::Reflect => "reflect",
+            swf::GradientSpread::Repeat => "repeat",
+        };
+        let _ = write!(
+            self.defs,
+            r#"<{element} id="{id}" gradientUnits="userSpaceOnUse" spreadMethod="{spread}" gradientTransform="matrix({} {} {} {} {} {})" {geometry}>"#,
+            matrix.a,
+            matrix.b,
+            matrix.c,
+            matrix.d,
+            matrix.tx.to_pixels(),
+            matrix.ty.to_pixels()
+        );
+        for record in &gradient.records {
+            let color = color_transform * record.color;
+            let _ = write!(
+                self.defs,
+                r##"<stop offset="{}" stop-color="#{:02x}{:02x}{:02x}" stop-opacity="{}"/>"##,
+                f64::from(record.ratio) / 255.0,
+                color.r,
+                color.g,
+                color.b,
+                f64::from(color.a) / 255.0
+            );
+        }
+        let _ = write!(self.defs, "</{element}>");
+        id
+    }
+
+    fn write_bitmap<'gc>(&mut self, context: &mut UpdateContext<'_, 'gc>, bitmap: Bitmap<'gc>) {
+        let bitmap_data = bitmap.bitmap_data(context.renderer);
+        let bitmap_data = bitmap_data.read();
+        let pixels = bitmap_data
+            .pixels()
+            .iter()
+            .flat_map(|p| {
+                let p = p.to_un_multiplied_alpha();
+                [p.red(), p.green(), p.blue(), p.alpha()]
+            })
+            .collect();
+
+        let Some(image) =
+            image::RgbaImage::from_raw(bitmap_data.width(), bitmap_data.height(), pixels)
+        else {
+            warn!("SVG export: skipping a bitmap with invalid dimensions");
+            return;
+        };
+        let mut png = Vec::new();
+        if let Err(e) = image.write_to(&mut Cursor::new(&mut png), image::ImageFormat::Png) {
+            warn!("SVG export: skipping a bitmap that couldn't be encoded: {e}");
+            return;
+        }
+
+        let _ = write!(
+            self.body,
+            r#"<image width="{}" height="{}" xlink:href="data:image/png;base64,{}"/>"#,
+            bitmap_data.width(),
+            bitmap_data.height(),
+            BASE64_STANDARD.encode(png)
+        );
+    }
+
+    fn write_static_text<'gc>(
+        &mut self,
+        context: &mut UpdateContext<'_, 'gc>,
+        text: Text<'gc>,
+        color_transform: &ColorTransform,
+    ) {
+        text.for_each_glyph(context, |matrix, color, glyph| {
+            let _ = write!(self.body, "<g {}>", transform(matrix));
+            let color_transform = glyph_color_transform(color_transform, &color);
+            glyph.with_distilled_shape(|shape| self.write_shape(shape, &color_transform));
+            self.body.push_str("</g>");
+        });
+    }
+
+    fn write_edit_text(&mut self, edit_text: EditText<'_>, color_transform: &ColorTransform) {
+        if edit_text.is_password() {
+            warn!("SVG export: skipping the contents of a password field");
+            return;
+        }
+
+        let format = edit_text.new_text_format();
+        let size = format.size.unwrap_or(12.0);
+        let color = color_transform * format.color.unwrap_or(Color::BLACK);
+        let bounds = edit_text.self_bounds();
+        // Text fields have a 2 pixel gutter around their contents.
+        let x = bounds.x_min.to_pixels() + 2.0;
+        let y = bounds.y_min.to_pixels() + 2.0;
+
+        let _ = write!(
+            self.body,
+            r#"<text x="{x}" y="{y}" font-size="{size}" {}"#,
+            paint("fill", &color)
+        );
+        if let Some(font) = &format.font {
+            let _ = write!(self.body, r#" font-family="{}""#, escape(&font.to_string()));
+        }
+        self.body.push('>');
+        let text = edit_text.text().to_string();
+        for line in text.split(['\r', '\n']) {
+            let _ = write!(
+                self.body,
+                r#"<tspan x="{x}" dy="{}">{}</tspan>"#,
+                size * 1.2,
+                escape(line)
+            );
+        }
+        self.body.push_str("</text>");
+    }
+}
+
+/// The `transform` attribute for a matrix.
+fn transform(matrix: &Matrix) -> String {
+    format!(
+        r#"transform="matrix({} {} {} {} {} {})""#,
+        matrix.a,
+        matrix.b,
+        matrix.c,
+        matrix.d,
+        matrix.tx.to_pixels(),
+        matrix.ty.to_pixels()
+    )
+}
+
+/// Glyph outlines are white, and tinted to the color of their text.
+fn glyph_color_transform(color_transform: &ColorTransform, color: &Color) -> ColorTransform {
+    let mut tint = ColorTransform::IDENTITY;
+    tint.set_mult_color(color);
+    *color_transform * tint
+}
+
+fn describe(object: DisplayObject<'_>) -> String {
+    let name = object.name();
+    if name.is_empty() {
+        format!("character {}", object.id())
+    } else {
+        format!("'{name}' (character {})", object.id())
+    }
+}
+
+fn paint(property: &str, color: &Color) -> String {
+    let mut attributes = format!(
+        r##"{property}="#{:02x}{:02x}{:02x}""##,
+        color.r, color.g, color.b
+    );
+    if color.a != 255 {
+        let _ = write!(
+            attributes,
+            r#" {property}-opacity="{}""#,
+            f64::from(color.a) / 255.0
+        );
+    }
+    attributes
+}
+
+fn stroke_attributes(style: &LineStyle) -> String {
+    let width = style.width();
+    let mut attributes = if width == Twips::ZERO {
+        // Hairlines are always one pixel wide, no matter how they're scaled.
+        r#"stroke-width="1" vector-effect="non-scaling-stroke""#.to_string()
+    } else {
+        format!(r#"stroke-width="{}""#, width.to_pixels())
+    };
+    let cap = match style.start_cap() {
+        LineCapStyle::Round => "round",
+        LineCapStyle::None => "butt",
+        LineCapStyle::Square => "square",
+    };
+    let _ = write!(attributes, r#" stroke-linecap="{cap}""#);
+    match style.join_style() {
+        LineJoinStyle::Round => attributes.push_str(r#" stroke-linejoin="round""#),
+        LineJoinStyle::Bevel => attributes.push_str(r#" stroke-linejoin="bevel""#),
+        LineJoinStyle::Miter(limit) => {
+            let _ = write!(
+                attributes,
+                r#" stroke-linejoin="miter" stroke-miterlimit="{}""#,
+                limit.to_f64().max(1.0)
+            );
+        }
+    }
+    attributes
+}
+
+fn path_data(commands: &[DrawCommand], is_closed: bool) -> String {
+    let mut data = String::new();
+    for command in commands {
+        let _ = match command {
+            DrawCommand::MoveTo(point) => {
+                write!(data, "M{} {}", point.x.to_pixels(), point.y.to_pixels())
+            }
+            DrawCommand::LineTo(point) => {
+                write!(data, "L{} {}", point.x.to_pixels(), point.y.to_pixels())
+            }
+            DrawCommand::QuadraticCurveTo { control, anchor } => write!(
+                data,
+                "Q{} {} {} {}",
+                control.x.to_pixels(),
+                control.y.to_pixels(),
+                anchor.x.to_pixels(),
+                anchor.y.to_pixels()
+            ),
+            DrawCommand::CubicCurveTo {
+                control_a,
+                control_b,
+                anchor,
+            } => write!(
+                data,
+                "C{} {} {} {} {} {}",
+                control_a.x.to_pixels(),
+                control_a.y.to_pixels(),
+                control_b.x.to_pixels(),
+                control_b.y.to_pixels(),
+                anchor.x.to_pixels(),
+                anchor.y.to_pixels()
+            ),
+        };
+    }
+    if is_closed {
+        data.push('Z');
+    }
+    data
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use swf::{Fixed8, GradientInterpolation, GradientRecord, GradientSpread};
+
+    #[test]
+    fn path_data_is_in_pixels() {
+        let commands = [
+            DrawCommand::MoveTo(Point::from_pixels(0.0, 0.5)),
+            DrawCommand::LineTo(Point::from_pixels(10.0, 0.0)),
+            DrawCommand::QuadraticCurveTo {
+                control: Point::from_pixels(20.0, 5.0),
+                anchor: Point::from_pixels(10.0, 10.0),
+            },
+            DrawCommand::CubicCurveTo {
+                control_a: Point::from_pixels(5.0, 15.0),
+                control_b: Point::from_pixels(-5.0, 15.0),
+                anchor: Point::from_pixels(0.0, 10.0),
+            },
+        ];
+        assert_eq!(
+            path_data(&commands, false),
+            "M0 0.5L10 0Q20 5 10 10C5 15 -5 15 0 10"
+        );
+        assert_eq!(path_data(&commands[..2], true), "M0 0.5L10 0Z");
+        assert_eq!(path_data(&[], true), "Z");
+    }
+
+    #[test]
+    fn paint_only_writes_opacity_when_translucent() {
+        assert_eq!(
+            paint("fill", &Color::from_rgb(0xFF8000, 255)),
+            r##"fill="#ff8000""##
+        );
+        assert_eq!(
+            paint("stroke", &Color::from_rgb(0x00000A, 51)),
+            r##"stroke="#00000a" stroke-opacity="0.2""##
+        );
+    }
+
+    #[test]
+    fn color_fills_are_color_transformed() {
+        let mut exporter = SvgExporter::default();
+        let color_transform = ColorTransform {
+            r_add: 255,
+            ..ColorTransform::IDENTITY
+        };
+        assert_eq!(
+            exporter.fill_style_paint(
+                "fill",
+                &FillStyle::Color(Color::from_rgb(0x0000FF, 255)),
+                &color_transform
+            ),
+            Some(r##"fill="#ff00ff""##.to_string())
+        );
+        assert!(exporter.defs.is_empty());
+    }
+
+    #[test]
+    fn gradient_fills_are_defined_once_each() {
+        let mut exporter = SvgExporter::default();
+        let gradient = Gradient {
+            matrix: swf::Matrix::IDENTITY,
+            spread: GradientSpread::Reflect,
+            interpolation: GradientInterpolation::Rgb,
+            records: vec![
+                GradientRecord {
+                    ratio: 0,
+                    color: Color::from_rgb(0xFF0000, 255),
+                },
+                GradientRecord {
+                    ratio: 255,
+                    color: Color::from_rgb(0x0000FF, 0),
+                },
+            ],
+        };
+        assert_eq!(
+            exporter.fill_style_paint(
+                "stroke",
+                &FillStyle::LinearGradient(gradient.clone()),
+                &ColorTransform::IDENTITY
+            ),
+            Some(r#"stroke="url(#gradient0)""#.to_string())
+        );
+        assert_eq!(
+            exporter.fill_style_paint(
+                "fill",
+                &FillStyle::RadialGradient(gradient),
+                &ColorTransform::IDENTITY
+            ),
+            Some(r#"fill="url(#gradient1)""#.to_string())
+        );
+        assert_eq!(
+            exporter.defs,
+            concat!(
+                r#"<linearGradient id="gradient0" gradientUnits="userSpaceOnUse" spreadMethod="reflect" gradientTransform="matrix(1 0 0 1 0 0)" x1="-819.2" y1="0" x2="819.2" y2="0">"#,
+                r##"<stop offset="0" stop-color="#ff0000" stop-opacity="1"/>"##,
+                r##"<stop offset="1" stop-color="#0000ff" stop-opacity="0"/>"##,
+                r#"</linearGradient>"#,
+                r#"<radialGradient id="gradient1" gradientUnits="userSpaceOnUse" spreadMethod="reflect" gradientTransform="matrix(1 0 0 1 0 0)" cx="0" cy="0" r="819.2">"#,
+                r##"<stop offset="0" stop-color="#ff0000" stop-opacity="1"/>"##,
+                r##"<stop offset="1" stop-color="#0000ff" stop-opacity="0"/>"##,
+                r#"</radialGradient>"#,
+            )
+        );
+    }
+
+    #[test]
+    fn bitmap_fills_are_skipped() {
+        let mut exporter = SvgExporter::default();
+        let style = FillStyle::Bitmap {
+            id: 1,
+            matrix: swf::Matrix::IDENTITY,
+            is_smoothed: true,
+            is_repeating: false,
+        };
+        assert_eq!(
+            exporter.fill_style_paint("fill", &style, &ColorTransform::IDENTITY),
+            None
+        );
+    }
+
+    #[test]
+    fn stroke_attributes_follow_the_line_style() {
+        let style = LineStyle::new()
+            .with_width(Twips::from_pixels(2.5))
+            .with_start_cap(LineCapStyle::None)
+            .with_join_style(LineJoinStyle::Miter(Fixed8::from_f64(3.0)));
+        assert_eq!(
+            stroke_attributes(&style),
+            r#"stroke-width="2.5" stroke-linecap="butt" stroke-linejoin="miter" stroke-miterlimit="3""#
+        );
+
+        let hairline = LineStyle::new()
+            .with_width(Twips::ZERO)
+            .with_start_cap(LineCapStyle::Square)
+            .with_join_style(LineJoinStyle::Bevel);
+        assert_eq!(
+            stroke_attributes(&hairline),
+            r#"stroke-width="1" vector-effect="non-scaling-stroke" stroke-linecap="square" stroke-linejoin="bevel""#
+        );
+    }
+
+    #[test]
+    fn escape_covers_markup_characters() {
+        assert_eq!(
+            escape(r#"<a href="x">Tom & Jerry</a>"#),
+            "&lt;a href=&quot;x&quot;&gt;Tom &amp; Jerry&lt;/a&gt;"
+        );
+        assert_eq!(escape("&lt;"), "&amp;lt;");
+        assert_eq!(escape("plain"), "plain");
+    }
+
+    #[test]
+    fn transform_translates_in_pixels() {
+        let matrix = Matrix {
+            a: 2.0,
+            b: 0.0,
+            c: 0.0,
+            d: 0.5,
+            tx: Twips::from_pixels(10.0),
+            ty: Twips::from_pixels(-2.5),
+        };
+        assert_eq!(
+            transform(&matrix),
+            r#"transform="matrix(2 0 0 0.5 10 -2.5)""#
+        );
+    }
+
+    #[test]
+    fn glyphs_are_tinted_to_their_text_color() {
+        let text_color = Color::from_rgba(0xFFFF0000);
+        let color_transform = glyph_color_transform(&ColorTransform::IDENTITY, &text_color);
+        assert_eq!(&color_transform * Color::WHITE, text_color);
+
+        // The text's own color transform still applies on top.
+        let mut translucent = ColorTransform::IDENTITY;
+        translucent.a_multiply = Fixed8::from_f32(0.5);
+        let color_transform = glyph_color_transform(&translucent, &text_color);
+        assert_eq!(&color_transform * Color::WHITE, &translucent * text_color);
+    }
+}
@@ -1,6 +1,6 @@
 use gc_arena::Collect;
 use std::sync::Arc;
-use swf::{CharacterId, Fixed8, HeaderExt, Rectangle, TagCode, Twips};
+use swf::{CharacterId, Compression, Fixed8, HeaderExt, Rectangle, TagCode, Twips};
 use thiserror::Error;
 use url::Url;
 
@@ -9,6 +9,9 @@ pub enum Error {
     #[error("Couldn't read SWF: {0}")]
     InvalidSwf(#[from] swf::error::Error),
 
+    #[error("This SWF is {0} compressed, which isn't supported by this build of Ruffle")]
+    UnsupportedCompression(Compression),
+
     #[error("Couldn't register bitmap: {0}")]
     InvalidBitmap(#[from] ruffle_render::error::Error),
 
@@ -158,7 +161,18 @@ impl SwfMovie {
         loader_url: Option<String>,
     ) -> Result<Self, Error> {
         let compressed_len = swf_data.len();
-        let swf_buf = swf::read::decompress_swf(swf_data)?;
+        let swf_buf = swf::read::decompress_swf(swf_data).map_err(|error| {
+            // Decompression is only unsupported when the relevant feature is disabled,
+            // so name the compression to make that clear.
+            match (&error, swf::read::read_compression_type(swf_data)) {
+                (swf::error::Error::Unsupported(_), Ok(compression))
+                    if compression != Compression::None =>
+                {
+                    Error::UnsupportedCompression(compression)
+                }
+                _ => error.into(),
+            }
+        })?;
         let encoding = swf::SwfStr::encoding_for_version(swf_buf.header.version());
         let mut movie = Self {
             header: swf_buf.header,
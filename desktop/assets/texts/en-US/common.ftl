@@ -7,4 +7,6 @@ cancel = Cancel
 remove = Remove
 
 enable = Enable
-disable = Disable
\ No newline at end of file
+disable = Disable
+
+click-to-play = Click to play
\ No newline at end of file
@@ -10,17 +10,45 @@
 file-menu = File
 file-menu-open-quick = Open...
 file-menu-open-advanced = Open Advanced...
+file-menu-open-new-window = Open in New Window...
 file-menu-close = Close
+file-menu-reopen-closed = Reopen Closed Movie
 file-menu-reload = Reload
+file-menu-export-svg = Save Frame as SVG...
+file-menu-copy-url = Copy Movie URL
+file-menu-show-in-folder = Show in Folder
 file-menu-recents = Recents
 file-menu-recents-empty = No recent entries
+file-menu-recents-missing = { $name } (missing)
+file-menu-recents-pin = Pin
+file-menu-recents-unpin = Unpin
+file-menu-recents-clear = Clear Recents
 file-menu-preferences = Preferences...
+file-menu-reload-preferences = Reload Preferences
+file-menu-reset-preferences = Reset Preferences...
 file-menu-exit = Exit
 
 controls-menu = Controls
 controls-menu-suspend = Suspend
 controls-menu-resume = Resume
 controls-menu-volume = Volume controls
+controls-menu-zoom-in = Zoom In
+controls-menu-zoom-out = Zoom Out
+controls-menu-reset-zoom = Reset Zoom
+controls-menu-color-filter = Color Filter
+controls-menu-bitmap-smoothing = Bitmap Smoothing
+controls-menu-bitmap-smoothing-shortcut = Switch with { $shortcut }
+controls-menu-bitmap-smoothing-movie = As the movie asks
+controls-menu-bitmap-smoothing-on = Always smooth
+controls-menu-bitmap-smoothing-off = Never smooth
+controls-menu-text-anti-aliasing = Anti-alias Text
+
+color-filter-none = None
+color-filter-grayscale = Grayscale
+color-filter-high-contrast = High Contrast
+color-filter-protanopia = Protanopia (red-blind)
+color-filter-deuteranopia = Deuteranopia (green-blind)
+color-filter-tritanopia = Tritanopia (blue-blind)
 
 help-menu = Help
 help-menu-join-discord = Join Discord
@@ -39,4 +67,14 @@ debug-menu-open-movie = View Movie
 debug-menu-open-movie-list = Show Known Movies
 debug-menu-open-domain-list = Show Domains
 debug-menu-search-display-objects = Search Display Objects...
+debug-menu-toggle-callstack = Show ActionScript Callstack
+debug-menu-dump-display-list = Save Display List...
+debug-menu-show-asset-stats = Show Loaded Assets
+debug-menu-clear-asset-cache = Release Cached Assets
+debug-menu-collect-garbage = Collect Garbage
+debug-menu-swap-root-swf = Swap Root SWF...
 
+network-policy-offline = 🔒 Offline
+network-policy-offline-tooltip = This bundle isn't allowed to use the network.
+network-policy-restricted = 🔒 Network restricted
+network-policy-restricted-tooltip = This bundle may only connect to: { $hosts }
@@ -23,4 +23,39 @@ storage-backend-disk = Disk
 storage-backend-memory = Memory
 
 recent-limit = Recent Limit
-recent-clear = Clear
\ No newline at end of file
+recent-clear = Clear
+
+recent-match-content = Moved Files
+recent-match-content-check = Recognize recent movies that were moved or renamed
+
+auto-hide-cursor = Custom Cursors
+auto-hide-cursor-check = Hide the system cursor when the movie draws its own
+
+autoplay = Autoplay
+autoplay-check = Start movies as soon as they're opened, instead of on the first click
+
+keep-awake = Screen Sleep
+keep-awake-check = Keep the screen awake while a movie plays
+
+play-stats = Play Statistics
+play-stats-check = Keep track of how long each movie is played, only on this computer
+
+fullscreen-requests = Fullscreen Requests
+fullscreen-requests-allow = Allow
+fullscreen-requests-deny = Deny
+fullscreen-requests-ask = Ask Every Time
+
+open-url-requests = Website Requests
+open-url-requests-allow = Allow
+open-url-requests-deny = Deny
+open-url-requests-ask = Ask Every Time
+open-url-requests-forget = Forget Remembered Websites
+
+mouse-wheel-up = Mouse Wheel Up
+mouse-wheel-down = Mouse Wheel Down
+mouse-wheel-key-none = Scroll Normally
+
+touch-emulation = Touchscreen Mouse
+touch-emulation-off = Left to the System
+touch-emulation-first-touch = Follow the First Finger
+touch-emulation-latest-touch = Follow the Latest Finger
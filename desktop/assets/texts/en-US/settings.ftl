@@ -3,6 +3,7 @@ player-settings = Player Settings
 movie-parameters = Movie Parameters
 
 custom-base-url = Custom Base URL
+custom-base-url-remember = Remember for this movie
 
 spoof-swf-url = Spoof SWF URL
 
@@ -0,0 +1,23 @@
+toast-unsupported-file = { $filename } isn't a Flash movie or Ruffle bundle, so it can't be opened.
+toast-last-recent-missing = { $filename } can't be reopened, as it no longer exists.
+toast-multiple-files = Only one movie can be open at a time, so only { $filename } was opened.
+toast-script-aborted = A script took too long to run and was stopped.
+toast-script-halted = A script took too long to run, so scripting has been disabled for this movie.
+toast-preferences-reloaded = Preferences reloaded.
+toast-preferences-reloaded-restart = Preferences reloaded. Restart Ruffle for changes to { $preferences } to take effect.
+toast-preferences-reload-failed = Preferences couldn't be reloaded: { $error }
+toast-preferences-reset = Preferences reset to their defaults.
+toast-preferences-reset-restart = Preferences reset to their defaults. Restart Ruffle for changes to { $preferences } to take effect.
+toast-preferences-reset-failed = Preferences couldn't be reset: { $error }
+toast-movie-url-copied = Copied { $url }
+toast-show-in-folder-not-local = Only movies opened from this computer can be shown in their folder.
+toast-show-in-folder-failed = The folder couldn't be opened: { $error }
+toast-video-unsupported = This movie contains video, but video playback is turned off or isn't included in this build of Ruffle.
+toast-video-codec-unsupported = This movie contains { $codec } video, which Ruffle can't play yet.
+toast-broke-on-error = The movie was paused on an ActionScript error: { $error }
+    Use Controls > Resume to continue.
+toast-playback-complete = The movie has finished playing.
+toast-asset-stats = Bitmaps: { $bitmaps } ({ $bitmap-bytes } bytes), { $decoded-bitmaps } decoded ({ $decoded-bitmap-bytes } bytes)
+    Sounds: { $sounds } ({ $sound-bytes } bytes)
+    Fonts: { $fonts }
+toast-display-list-saved = Saved the display list to { $path }.
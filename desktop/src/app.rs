@@ -1,43 +1,111 @@
-use crate::custom_event::RuffleEvent;
-use crate::gui::{GuiController, MENU_HEIGHT};
+use crate::custom_event::{RuffleEvent, RuffleEventProxy, WindowedRuffleEvent};
+use crate::frame_skip::FrameSkipper;
+use crate::gui::{text, text_with_args, GuiController, MovieZoom, MENU_HEIGHT};
+use crate::keep_awake::KeepAwake;
 use crate::player::{LaunchOptions, PlayerController};
-use crate::preferences::GlobalPreferences;
+use crate::preferences::{restart_needed_names, GlobalPreferences, MouseWheelDirection};
+use crate::touch::{PointerAction, TouchEmulator};
 use crate::util::{
-    get_screen_size, gilrs_button_to_gamepad_button, parse_url, pick_file, plot_stats_in_tracy,
+    borderless_fullscreen, get_screen_size, gilrs_button_to_gamepad_button, is_movie_or_bundle,
+    parse_url, pick_file, pick_save_file, place_window, plot_stats_in_tracy, show_in_folder,
     winit_to_ruffle_key_code, winit_to_ruffle_text_control,
 };
 use anyhow::{Context, Error};
 use gilrs::{Event, EventType, Gilrs};
+use rfd::{MessageButtons, MessageDialog, MessageDialogResult, MessageLevel};
+use ruffle_core::backend::ui::{ScriptTimeoutAction, UnsupportedVideoReason};
+use ruffle_core::swf::VideoCodec;
 use ruffle_core::{PlayerEvent, StageDisplayState};
 use ruffle_render::backend::ViewportDimensions;
+use ruffle_render_wgpu::descriptors::Descriptors;
 use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 use url::Url;
 use winit::dpi::{LogicalSize, PhysicalPosition, PhysicalSize, Size};
 use winit::event::{ElementState, KeyEvent, Modifiers, WindowEvent};
-use winit::event_loop::{ControlFlow, EventLoop, EventLoopBuilder};
+use winit::event_loop::{
+    ControlFlow, EventLoop, EventLoopBuilder, EventLoopProxy, EventLoopWindowTarget,
+};
 use winit::keyboard::{Key, NamedKey};
-use winit::window::{Fullscreen, Icon, Window, WindowBuilder};
+use winit::window::{Icon, Window, WindowBuilder, WindowId};
 
 pub struct App {
+    preferences: GlobalPreferences,
+    event_loop: Option<EventLoop<WindowedRuffleEvent>>,
+    event_loop_proxy: EventLoopProxy<WindowedRuffleEvent>,
+    windows: HashMap<WindowId, PlayerWindow>,
+    /// The window focused last, which gamepad input is sent to.
+    focused_window: WindowId,
+    /// Shared by every window, so that they all draw with the same graphics device.
+    descriptors: Arc<Descriptors>,
+    font_database: Rc<fontdb::Database>,
+    icon: Icon,
+    min_window_size: LogicalSize<u32>,
+    max_window_size: PhysicalSize<u32>,
+    /// Keeps the screen awake while any window is playing a movie.
+    keep_awake: KeepAwake,
+}
+
+enum LoadingState {
+    Loading,
+    WaitingForResize,
+    Loaded,
+}
+
+/// A window playing a movie, with its own GUI and player.
+struct PlayerWindow {
     preferences: GlobalPreferences,
     window: Rc<Window>,
-    event_loop: Option<EventLoop<RuffleEvent>>,
     gui: Rc<RefCell<GuiController>>,
     player: PlayerController,
     min_window_size: LogicalSize<u32>,
     max_window_size: PhysicalSize<u32>,
-    initial_movie_url: Option<Url>,
     no_gui: bool,
-    preferred_width: Option<f64>,
-    preferred_height: Option<f64>,
     start_fullscreen: bool,
+    lock_aspect: bool,
+    not_resizable: bool,
+    loaded: LoadingState,
+    mouse_pos: PhysicalPosition<f64>,
+    time: Instant,
+    next_frame_time: Option<Instant>,
+    minimized: bool,
+    focused: bool,
+    modifiers: Modifiers,
+    fullscreen_down: bool,
+    dropped_files: Vec<PathBuf>,
+    /// The aspect ratio of the loaded movie, if the window should keep to it.
+    locked_aspect_ratio: Option<f64>,
+    previous_size: PhysicalSize<u32>,
+    /// Where the middle mouse button was last seen while dragging a zoomed movie around.
+    panning_from: Option<PhysicalPosition<f64>>,
+    /// The shortest time allowed between two ticks, if `--max-fps` is used.
+    min_tick_interval: Option<Duration>,
+    /// Skips rendering late frames, if `--frame-skip` is used.
+    frame_skipper: Option<FrameSkipper>,
+    touch_emulator: TouchEmulator,
 }
 
 impl App {
     pub fn new(preferences: GlobalPreferences) -> Result<Self, Error> {
-        let movie_url = preferences.cli.movie_url.clone();
+        let mut movie_url = preferences.cli.movie_url.clone();
+        let mut missing_recent = None;
+        if movie_url.is_none() && preferences.cli.open_last {
+            let last = preferences
+                .recents(|recents| recents.iter().rev().find(|x| !x.is_invalid()).cloned());
+            match last {
+                Some(recent) if recent.is_available() => movie_url = Some(recent.url),
+                Some(recent) => {
+                    tracing::warn!("Not opening {}, as it no longer exists", recent.url);
+                    missing_recent = Some(recent);
+                }
+                None => {}
+            }
+        }
+
         let icon_bytes = include_bytes!("../assets/favicon-32.rgba");
         let icon =
             Icon::from_rgba(icon_bytes.to_vec(), 32, 32).context("Couldn't load app icon")?;
@@ -47,36 +115,36 @@ impl App {
         let no_gui = preferences.cli.no_gui;
         let min_window_size = (16, if no_gui { 16 } else { MENU_HEIGHT + 16 }).into();
         let max_window_size = get_screen_size(&event_loop);
-        let preferred_width = preferences.cli.width;
-        let preferred_height = preferences.cli.height;
-        let start_fullscreen = preferences.cli.fullscreen;
-
-        let window = WindowBuilder::new()
-            .with_visible(false)
-            .with_title("Ruffle")
-            .with_window_icon(Some(icon))
-            .with_min_inner_size(min_window_size)
-            .with_max_inner_size(max_window_size)
-            .build(&event_loop)?;
-        let window = Rc::new(window);
+
+        let window = build_window(
+            &event_loop,
+            &icon,
+            min_window_size,
+            max_window_size,
+            preferences.cli.transparent,
+        )?;
 
         let mut font_database = fontdb::Database::default();
         font_database.load_system_fonts();
+        let font_database = Rc::new(font_database);
 
+        let window_event_loop = RuffleEventProxy::new(event_loop.create_proxy(), window.id());
         let mut gui = GuiController::new(
             window.clone(),
-            &event_loop,
+            window_event_loop.clone(),
+            None,
             preferences.clone(),
             &font_database,
             movie_url.clone(),
             no_gui,
         )?;
+        let descriptors = gui.descriptors().clone();
 
         let mut player = PlayerController::new(
-            event_loop.create_proxy(),
+            window_event_loop,
             window.clone(),
-            gui.descriptors().clone(),
-            font_database,
+            descriptors.clone(),
+            font_database.clone(),
             preferences.clone(),
         );
 
@@ -90,42 +158,43 @@ impl App {
             gui.show_open_dialog();
         }
 
+        if let Some(recent) = missing_recent {
+            let args = HashMap::from([("filename", recent.display_name().into_owned().into())]);
+            gui.show_toast(
+                text_with_args(&preferences.language(), "toast-last-recent-missing", &args)
+                    .into_owned(),
+            );
+        }
+
+        let mut player_window = PlayerWindow::new(
+            preferences.clone(),
+            window,
+            gui,
+            player,
+            min_window_size,
+            max_window_size,
+        );
+        if movie_url.is_none() {
+            player_window.show_without_movie();
+        }
+        let focused_window = player_window.window.id();
+
         Ok(Self {
             preferences,
-            window,
+            event_loop_proxy: event_loop.create_proxy(),
             event_loop: Some(event_loop),
-            gui: Rc::new(RefCell::new(gui)),
-            player,
+            windows: HashMap::from([(focused_window, player_window)]),
+            focused_window,
+            descriptors,
+            font_database,
+            icon,
             min_window_size,
             max_window_size,
-            initial_movie_url: movie_url,
-            no_gui,
-            preferred_width,
-            preferred_height,
-            start_fullscreen,
+            keep_awake: KeepAwake::default(),
         })
     }
 
     pub fn run(mut self) -> Result<(), Error> {
-        enum LoadingState {
-            Loading,
-            WaitingForResize,
-            Loaded,
-        }
-        let mut loaded = LoadingState::Loading;
-        let mut mouse_pos = PhysicalPosition::new(0.0, 0.0);
-        let mut time = Instant::now();
-        let mut next_frame_time = None;
-        let mut minimized = false;
-        let mut modifiers = Modifiers::default();
-        let mut fullscreen_down = false;
-
-        if self.initial_movie_url.is_none() {
-            // No SWF provided on command line; show window with dummy movie immediately.
-            self.window.set_visible(true);
-            loaded = LoadingState::Loaded;
-        }
-
         let mut gilrs = Gilrs::new()
             .inspect_err(|err| {
                 tracing::warn!("Gamepad support could not be initialized: {err}");
@@ -135,11 +204,10 @@ impl App {
         // Poll UI events.
         let event_loop = self.event_loop.take().expect("App already running");
         event_loop.run(move |event, elwt| {
-            let mut check_redraw = false;
             match event {
                 winit::event::Event::LoopExiting => {
-                    if let Some(mut player) = self.player.get() {
-                        player.flush_shared_objects();
+                    for window in self.windows.values_mut() {
+                        window.on_exit();
                     }
                     crate::shutdown();
                     return;
@@ -148,393 +216,1378 @@ impl App {
                 // Core loop
                 // [NA] This used to be called `MainEventsCleared`, but I think the behaviour is different now.
                 // We should look at changing our tick to happen somewhere else if we see any behavioural problems.
-                winit::event::Event::AboutToWait if matches!(loaded, LoadingState::Loaded) => {
-                    let new_time = Instant::now();
-                    let dt = new_time.duration_since(time).as_micros();
-                    if dt > 0 {
-                        time = new_time;
-                        if let Some(mut player) = self.player.get() {
-                            player.tick(dt as f64 / 1000.0);
-                            next_frame_time = Some(new_time + player.time_til_next_frame());
-                        } else {
-                            next_frame_time = None;
-                        }
-                        check_redraw = true;
+                winit::event::Event::AboutToWait => {
+                    for window in self.windows.values_mut() {
+                        window.about_to_wait();
                     }
+                    let keep_awake = self.preferences.keep_awake()
+                        && self.windows.values().any(PlayerWindow::is_playing_in_focus);
+                    self.keep_awake.set_inhibited(keep_awake);
                 }
 
-                // Render
-                winit::event::Event::WindowEvent {
-                    event: WindowEvent::RedrawRequested,
-                    ..
-                } => {
-                    // Don't render when minimized to avoid potential swap chain errors in `wgpu`.
-                    if !minimized {
-                        if let Some(mut player) = self.player.get() {
-                            // Even if the movie is paused, user interaction with debug tools can change the render output
-                            player.render();
-                            self.gui.borrow_mut().render(Some(player));
-                        } else {
-                            self.gui.borrow_mut().render(None);
-                        }
-                        plot_stats_in_tracy(&self.gui.borrow().descriptors().wgpu_instance);
+                winit::event::Event::WindowEvent { window_id, event } => {
+                    if matches!(event, WindowEvent::Focused(true)) {
+                        self.focused_window = window_id;
+                    }
+                    let Some(window) = self.windows.get_mut(&window_id) else {
+                        return;
+                    };
+                    if window.handle_window_event(event) {
+                        self.close_window(window_id, elwt);
                     }
                 }
 
-                winit::event::Event::WindowEvent { event, .. } => {
-                    if self.gui.borrow_mut().handle_event(&event) {
-                        // Event consumed by GUI.
-                        return;
+                winit::event::Event::UserEvent(WindowedRuffleEvent { window_id, event }) => {
+                    self.handle_user_event(window_id, event, elwt)
+                }
+
+                _ => (),
+            }
+
+            if let Some(Event { event, .. }) = gilrs.as_mut().and_then(|gilrs| gilrs.next_event()) {
+                if let Some(window) = self.windows.get_mut(&self.focused_window) {
+                    window.handle_gamepad_event(event);
+                }
+            }
+
+            // After polling events, sleep the event loop until the next event or the next frame of any window.
+            let wake_time = self
+                .windows
+                .values()
+                .filter_map(PlayerWindow::wake_time)
+                .min();
+            elwt.set_control_flow(match wake_time {
+                Some(wake_time) => ControlFlow::WaitUntil(wake_time),
+                None => ControlFlow::Wait,
+            });
+        })?;
+        Ok(())
+    }
+
+    /// Handles the events of the app, which mostly concern the window they were sent from.
+    fn handle_user_event(
+        &mut self,
+        window_id: WindowId,
+        event: RuffleEvent,
+        elwt: &EventLoopWindowTarget<WindowedRuffleEvent>,
+    ) {
+        match event {
+            RuffleEvent::TaskPoll => {
+                for window in self.windows.values() {
+                    window.player.poll();
+                }
+            }
+
+            RuffleEvent::BrowseAndOpenInNewWindow(options) => {
+                if let Some(url) = pick_file(false, None).and_then(|p| Url::from_file_path(p).ok())
+                {
+                    if let Err(e) = self.open_in_new_window(elwt, url, *options) {
+                        tracing::error!("Couldn't open a new window: {e:#}");
                     }
-                    let height_offset = if self.window.fullscreen().is_some() || self.no_gui {
-                        0.0
-                    } else {
-                        MENU_HEIGHT as f64 * self.window.scale_factor()
-                    };
-                    match event {
-                        WindowEvent::CloseRequested => {
-                            elwt.exit();
-                            return;
-                        }
-                        WindowEvent::Resized(size) => {
-                            // TODO: Change this when winit adds a `Window::minimized` or `WindowEvent::Minimize`.
-                            minimized = size.width == 0 && size.height == 0;
-
-                            if let Some(mut player) = self.player.get() {
-                                let viewport_scale_factor = self.window.scale_factor();
-                                player.set_viewport_dimensions(ViewportDimensions {
-                                    width: size.width,
-                                    height: size.height - height_offset as u32,
-                                    scale_factor: viewport_scale_factor,
-                                });
-                            }
-                            self.window.request_redraw();
-                            if matches!(loaded, LoadingState::WaitingForResize) {
-                                loaded = LoadingState::Loaded;
-                            }
-                        }
-                        WindowEvent::CursorMoved { position, .. } => {
-                            if self.gui.borrow_mut().is_context_menu_visible() {
-                                return;
-                            }
+                }
+            }
 
-                            mouse_pos = position;
-                            let event = PlayerEvent::MouseMove {
-                                x: position.x,
-                                y: position.y - height_offset,
-                            };
-                            self.player.handle_event(event);
-                            check_redraw = true;
-                        }
-                        WindowEvent::DroppedFile(file) => {
-                            if let Ok(url) = parse_url(&file) {
-                                self.gui.borrow_mut().create_movie(
-                                    &mut self.player,
-                                    LaunchOptions::from(&self.preferences),
-                                    url,
-                                );
-                            }
-                        }
-                        WindowEvent::MouseInput { button, state, .. } => {
-                            if self.gui.borrow_mut().is_context_menu_visible() {
-                                return;
-                            }
+            RuffleEvent::RecentsChanged => {
+                for window in self.windows.values() {
+                    window.gui.borrow_mut().on_recents_changed();
+                }
+            }
 
-                            use ruffle_core::events::MouseButton as RuffleMouseButton;
-                            use winit::event::MouseButton;
-                            let x = mouse_pos.x;
-                            let y = mouse_pos.y - height_offset;
-                            let button = match button {
-                                MouseButton::Left => RuffleMouseButton::Left,
-                                MouseButton::Right => RuffleMouseButton::Right,
-                                MouseButton::Middle => RuffleMouseButton::Middle,
-                                _ => RuffleMouseButton::Unknown,
-                            };
-                            let event = match state {
-                                ElementState::Pressed => PlayerEvent::MouseDown { x, y, button },
-                                ElementState::Released => PlayerEvent::MouseUp { x, y, button },
-                            };
-                            if state == ElementState::Released && button == RuffleMouseButton::Right
-                            {
-                                // Show context menu.
-                                // TODO: Should be squelched if player consumes the right click event.
-                                if let Some(mut player) = self.player.get() {
-                                    let context_menu = player.prepare_context_menu();
-                                    self.gui.borrow_mut().show_context_menu(context_menu);
-                                }
-                            }
-                            self.player.handle_event(event);
-                            check_redraw = true;
+            RuffleEvent::ColorFilterChanged => {
+                for window in self.windows.values() {
+                    window.gui.borrow().update_color_filter();
+                }
+            }
+
+            RuffleEvent::ReloadPreferences | RuffleEvent::ResetPreferences => {
+                if let Some(window) = self.windows.get_mut(&window_id) {
+                    window.handle_user_event(event);
+                }
+                // The preferences are shared, so the other windows need to pick up the changes too.
+                for (id, window) in &self.windows {
+                    if *id != window_id {
+                        window.on_preferences_reloaded();
+                    }
+                }
+            }
+
+            RuffleEvent::ForgetClosedFile => {
+                for window in self.windows.values_mut() {
+                    window.player.forget_last_closed();
+                }
+            }
+
+            // Only the window that asked is closed, the others keep running.
+            RuffleEvent::ExitRequested => self.close_window(window_id, elwt),
+
+            RuffleEvent::MovieAskedToQuit => {
+                let guard = self
+                    .windows
+                    .get(&window_id)
+                    .map(|window| window.player.blocking_dialog_guard());
+                let quit = MessageDialog::new()
+                    .set_level(MessageLevel::Info)
+                    .set_title("Ruffle - Quit")
+                    .set_description("The movie asked to quit. Close its window?")
+                    .set_buttons(MessageButtons::YesNo)
+                    .show()
+                    == MessageDialogResult::Yes;
+                drop(guard);
+                if quit {
+                    self.close_window(window_id, elwt);
+                }
+            }
+
+            event => {
+                if let Some(window) = self.windows.get_mut(&window_id) {
+                    window.handle_user_event(event);
+                }
+            }
+        }
+    }
+
+    /// Opens `url` in a new window, next to the existing ones.
+    fn open_in_new_window(
+        &mut self,
+        elwt: &EventLoopWindowTarget<WindowedRuffleEvent>,
+        url: Url,
+        mut options: LaunchOptions,
+    ) -> Result<(), Error> {
+        // Input given on the command line is only recorded, replayed or scripted in the first window.
+        options.record_input = None;
+        options.replay = None;
+        options.script = None;
+
+        let window = build_window(
+            elwt,
+            &self.icon,
+            self.min_window_size,
+            self.max_window_size,
+            self.preferences.cli.transparent,
+        )?;
+        let event_loop = RuffleEventProxy::new(self.event_loop_proxy.clone(), window.id());
+        let mut gui = GuiController::new(
+            window.clone(),
+            event_loop.clone(),
+            Some(self.descriptors.clone()),
+            self.preferences.clone(),
+            &self.font_database,
+            Some(url.clone()),
+            self.preferences.cli.no_gui,
+        )?;
+        let mut player = PlayerController::new(
+            event_loop,
+            window.clone(),
+            self.descriptors.clone(),
+            self.font_database.clone(),
+            self.preferences.clone(),
+        );
+        gui.create_movie(&mut player, options, url);
+
+        let id = window.id();
+        self.windows.insert(
+            id,
+            PlayerWindow::new(
+                self.preferences.clone(),
+                window,
+                gui,
+                player,
+                self.min_window_size,
+                self.max_window_size,
+            ),
+        );
+        self.focused_window = id;
+        Ok(())
+    }
+
+    /// Closes a window without affecting the others, and exits once the last one is closed.
+    fn close_window(
+        &mut self,
+        window_id: WindowId,
+        elwt: &EventLoopWindowTarget<WindowedRuffleEvent>,
+    ) {
+        if self.windows.len() <= 1 {
+            elwt.exit();
+            return;
+        }
+        if let Some(mut window) = self.windows.remove(&window_id) {
+            window.on_exit();
+            window.player.destroy();
+            window.window.set_visible(false);
+        }
+        if self.focused_window == window_id {
+            if let Some(&id) = self.windows.keys().next() {
+                self.focused_window = id;
+            }
+        }
+    }
+}
+
+impl PlayerWindow {
+    fn new(
+        preferences: GlobalPreferences,
+        window: Rc<Window>,
+        gui: GuiController,
+        player: PlayerController,
+        min_window_size: LogicalSize<u32>,
+        max_window_size: PhysicalSize<u32>,
+    ) -> Self {
+        let min_tick_interval = preferences
+            .cli
+            .max_fps
+            .map(|fps| Duration::from_secs_f64(1.0 / fps));
+        let frame_skipper = preferences.cli.frame_skip.map(FrameSkipper::new);
+        let focused = window.has_focus();
+        Self {
+            no_gui: preferences.cli.no_gui,
+            start_fullscreen: preferences.cli.fullscreen,
+            lock_aspect: preferences.cli.lock_aspect,
+            not_resizable: preferences.cli.not_resizable,
+            preferences,
+            previous_size: window.inner_size(),
+            window,
+            gui: Rc::new(RefCell::new(gui)),
+            player,
+            min_window_size,
+            max_window_size,
+            loaded: LoadingState::Loading,
+            mouse_pos: PhysicalPosition::new(0.0, 0.0),
+            time: Instant::now(),
+            next_frame_time: None,
+            minimized: false,
+            focused,
+            modifiers: Modifiers::default(),
+            fullscreen_down: false,
+            dropped_files: Vec::new(),
+            locked_aspect_ratio: None,
+            panning_from: None,
+            min_tick_interval,
+            frame_skipper,
+            touch_emulator: TouchEmulator::default(),
+        }
+    }
+
+    /// Shows the window with a dummy movie immediately, as there's no SWF to wait for.
+    fn show_without_movie(&mut self) {
+        self.place_window();
+        self.window.set_visible(true);
+        self.loaded = LoadingState::Loaded;
+    }
+
+    /// Saves what the movie needs to keep, before the window is closed or the app exits.
+    fn on_exit(&mut self) {
+        if let Some(mut player) = self.player.get() {
+            player.flush_shared_objects();
+        }
+        if let Err(e) = self.player.stop_recording() {
+            tracing::error!("Couldn't finish recording input: {e}");
+        }
+        self.player.end_session();
+    }
+
+    /// When the event loop should wake up for this window, or `None` to wait for an event.
+    fn wake_time(&self) -> Option<Instant> {
+        if !matches!(self.loaded, LoadingState::Loaded) {
+            return None;
+        }
+        // prevent 100% cpu use
+        // TODO: use set_request_repaint_callback to correctly get egui repaint requests.
+        Some(
+            self.next_frame_time
+                .unwrap_or_else(|| Instant::now() + Duration::from_millis(10)),
+        )
+    }
+
+    /// Redraws the window if either the movie or the GUI changed.
+    fn request_redraw_if_needed(&self) {
+        let player = self.player.get();
+        let gui = self.gui.borrow_mut();
+        if player.map(|p| p.needs_render()).unwrap_or_default() || gui.needs_render() {
+            self.window.request_redraw();
+        }
+    }
+
+    /// Moves the window where requested with `--window-position` or `--center-window`, if at all.
+    fn place_window(&self) {
+        let cli = &self.preferences.cli;
+        if let Some((x, y)) = cli.window_position {
+            place_window(&self.window, Some(PhysicalPosition::new(x, y)));
+        } else if cli.center_window {
+            place_window(&self.window, None);
+        }
+    }
+
+    /// The scale factor the movie is rendered with, which is the one of the window
+    /// unless overridden with `--ui-scale`.
+    fn movie_scale_factor(&self) -> f64 {
+        self.preferences
+            .cli
+            .ui_scale
+            .unwrap_or_else(|| self.window.scale_factor())
+    }
+
+    /// The dimensions the movie is rendered at for a window of `size`, which are scaled by
+    /// `--render-scale` before the movie is stretched over the window.
+    fn viewport_dimensions(
+        &self,
+        size: PhysicalSize<u32>,
+        height_offset: f64,
+    ) -> ViewportDimensions {
+        let render_scale = self.preferences.cli.render_scale;
+        let scale = |pixels: u32| ((f64::from(pixels) * render_scale).round() as u32).max(1);
+        ViewportDimensions {
+            width: scale(size.width),
+            height: scale(size.height.saturating_sub(height_offset as u32)),
+            scale_factor: self.movie_scale_factor() * render_scale,
+        }
+    }
+
+    /// Converts a position in the window to where it is in the movie, as it's zoomed
+    /// and rendered with `--render-scale`.
+    fn movie_position(&self, position: PhysicalPosition<f64>, height_offset: f64) -> (f64, f64) {
+        let (width, height) = self.movie_view_size(height_offset);
+        let (x, y) = self.gui.borrow().movie_zoom().view_to_stage(
+            position.x,
+            position.y - height_offset,
+            width,
+            height,
+        );
+        let render_scale = self.preferences.cli.render_scale;
+        (x * render_scale, y * render_scale)
+    }
+
+    /// The size of the area the movie is shown in, below the menu bar.
+    fn movie_view_size(&self, height_offset: f64) -> (f64, f64) {
+        let size = self.window.inner_size();
+        (
+            f64::from(size.width),
+            f64::from(size.height) - height_offset,
+        )
+    }
+
+    /// Resizes the window back to the aspect ratio of the movie after the user resized it,
+    /// following whichever side they changed the most.
+    fn keep_aspect_ratio(
+        &self,
+        size: PhysicalSize<u32>,
+        previous_size: PhysicalSize<u32>,
+        aspect_ratio: f64,
+        height_offset: f64,
+    ) {
+        let width = f64::from(size.width);
+        let height = f64::from(size.height) - height_offset;
+        let width_change = (width - f64::from(previous_size.width)).abs();
+        let height_change = (f64::from(previous_size.height) - height_offset - height).abs();
+        let (width, height) = if width_change >= height_change {
+            (width, width / aspect_ratio)
+        } else {
+            (height * aspect_ratio, height)
+        };
+        let new_size = PhysicalSize::new(
+            width.round().max(1.0) as u32,
+            (height + height_offset).round().max(1.0) as u32,
+        );
+        // Small differences come from rounding, and chasing them would resize the window forever.
+        if new_size.width.abs_diff(size.width) > 1 || new_size.height.abs_diff(size.height) > 1 {
+            let _ = self.window.request_inner_size(new_size);
+        }
+    }
+
+    /// Draws the movie and the GUI over it.
+    fn render(&self) {
+        // Don't render when minimized to avoid potential swap chain errors in `wgpu`.
+        if !self.minimized {
+            if let Some(mut player) = self.player.get() {
+                // Even if the movie is paused, user interaction with debug tools can change the render output
+                player.render();
+                self.gui.borrow_mut().render(Some(player));
+            } else {
+                self.gui.borrow_mut().render(None);
+            }
+            plot_stats_in_tracy(&self.gui.borrow().descriptors().wgpu_instance);
+        }
+    }
+
+    /// Handles an event of this window. Returns whether the user asked to close it.
+    fn handle_window_event(&mut self, event: WindowEvent) -> bool {
+        if matches!(event, WindowEvent::RedrawRequested) {
+            self.render();
+            return false;
+        }
+        if let WindowEvent::Focused(focused) = event {
+            self.focused = focused;
+        }
+
+        if self.gui.borrow_mut().handle_event(&event) {
+            // Event consumed by GUI.
+            return false;
+        }
+        let mut check_redraw = false;
+        let height_offset = if self.window.fullscreen().is_some() || self.no_gui {
+            0.0
+        } else {
+            MENU_HEIGHT as f64 * self.window.scale_factor()
+        };
+        match event {
+            WindowEvent::CloseRequested => return true,
+            WindowEvent::Resized(size) => {
+                // TODO: Change this when winit adds a `Window::minimized` or `WindowEvent::Minimize`.
+                self.minimized = size.width == 0 && size.height == 0;
+
+                // Keep the stage as it was while minimized, rather than making it empty.
+                if !self.minimized {
+                    let result = self
+                        .player
+                        .set_viewport_dimensions(self.viewport_dimensions(size, height_offset));
+                    if let Err(e) = result {
+                        tracing::warn!("Couldn't resize the stage: {e}");
+                    }
+                }
+                if let Some(aspect_ratio) = self.locked_aspect_ratio {
+                    if !self.minimized && self.window.fullscreen().is_none() {
+                        self.keep_aspect_ratio(
+                            size,
+                            self.previous_size,
+                            aspect_ratio,
+                            height_offset,
+                        );
+                    }
+                }
+                self.previous_size = size;
+
+                self.window.request_redraw();
+                if matches!(self.loaded, LoadingState::WaitingForResize) {
+                    self.loaded = LoadingState::Loaded;
+                }
+            }
+            WindowEvent::CursorMoved { position, .. } => {
+                if self.gui.borrow_mut().is_context_menu_visible() {
+                    return false;
+                }
+
+                let (width, height) = self.movie_view_size(height_offset);
+                if let Some(from) = self.panning_from.filter(|_| width > 0.0 && height > 0.0) {
+                    let mut gui = self.gui.borrow_mut();
+                    let mut zoom = gui.movie_zoom();
+                    zoom.pan_by((
+                        (position.x - from.x) / width,
+                        (position.y - from.y) / height,
+                    ));
+                    gui.set_movie_zoom(zoom);
+                    self.panning_from = Some(position);
+                }
+
+                self.mouse_pos = position;
+                let (x, y) = self.movie_position(position, height_offset);
+                let event = PlayerEvent::MouseMove { x, y };
+                self.player.handle_event(event);
+                check_redraw = true;
+            }
+            WindowEvent::Touch(touch) => {
+                let actions = self.touch_emulator.handle_touch(
+                    self.preferences.touch_emulation(),
+                    touch.id,
+                    touch.phase,
+                    touch.location,
+                );
+                // Handled just like the mouse, so that zooming and the context menu work too.
+                for action in actions {
+                    use winit::event::MouseButton;
+                    let (device_id, button) = (touch.device_id, MouseButton::Left);
+                    self.handle_window_event(match action {
+                        PointerAction::Move(position) => WindowEvent::CursorMoved {
+                            device_id,
+                            position,
+                        },
+                        PointerAction::Press => WindowEvent::MouseInput {
+                            device_id,
+                            state: ElementState::Pressed,
+                            button,
+                        },
+                        PointerAction::Release => WindowEvent::MouseInput {
+                            device_id,
+                            state: ElementState::Released,
+                            button,
+                        },
+                    });
+                }
+            }
+            WindowEvent::DroppedFile(file) => {
+                // Each dropped file is its own event, so wait for all of them before opening.
+                self.dropped_files.push(file);
+            }
+            WindowEvent::MouseInput { button, state, .. } => {
+                if self.gui.borrow_mut().is_context_menu_visible() {
+                    return false;
+                }
+
+                use ruffle_core::events::MouseButton as RuffleMouseButton;
+                use winit::event::MouseButton;
+                if button == MouseButton::Middle
+                    && (self.panning_from.is_some() || self.gui.borrow().movie_zoom().is_zoomed())
+                {
+                    // The middle button drags a zoomed movie around instead.
+                    self.panning_from = (state == ElementState::Pressed).then_some(self.mouse_pos);
+                    return false;
+                }
+                let (x, y) = self.movie_position(self.mouse_pos, height_offset);
+                let button = match button {
+                    MouseButton::Left => RuffleMouseButton::Left,
+                    MouseButton::Right => RuffleMouseButton::Right,
+                    MouseButton::Middle => RuffleMouseButton::Middle,
+                    _ => RuffleMouseButton::Unknown,
+                };
+                if state == ElementState::Pressed {
+                    self.on_user_interaction();
+                    if self.gui.borrow().is_awaiting_click_to_play() {
+                        // The click only starts the movie, without reaching it.
+                        if let Some(mut player) = self.player.get() {
+                            self.gui.borrow_mut().click_to_play(&mut player);
                         }
-                        WindowEvent::MouseWheel { delta, .. } => {
-                            use ruffle_core::events::MouseWheelDelta;
-                            use winit::event::MouseScrollDelta;
-                            let delta = match delta {
-                                MouseScrollDelta::LineDelta(_, dy) => {
-                                    MouseWheelDelta::Lines(dy.into())
-                                }
-                                MouseScrollDelta::PixelDelta(pos) => MouseWheelDelta::Pixels(pos.y),
-                            };
-                            let event = PlayerEvent::MouseWheel { delta };
-                            self.player.handle_event(event);
-                            check_redraw = true;
+                        return false;
+                    }
+                }
+                let event = match state {
+                    ElementState::Pressed => PlayerEvent::MouseDown { x, y, button },
+                    ElementState::Released => PlayerEvent::MouseUp { x, y, button },
+                };
+                if state == ElementState::Released && button == RuffleMouseButton::Right {
+                    // Show context menu.
+                    // TODO: Should be squelched if player consumes the right click event.
+                    if let Some(mut player) = self.player.get() {
+                        let context_menu = player.prepare_context_menu();
+                        self.gui.borrow_mut().show_context_menu(context_menu);
+                    }
+                }
+                self.player.handle_event(event);
+                check_redraw = true;
+            }
+            WindowEvent::MouseWheel { delta, .. } if self.modifiers.state().control_key() => {
+                use winit::event::MouseScrollDelta;
+                let steps = match delta {
+                    MouseScrollDelta::LineDelta(_, dy) => f64::from(dy),
+                    // Roughly how many pixels a single line scrolls by.
+                    MouseScrollDelta::PixelDelta(pos) => pos.y / 40.0,
+                };
+                let (width, height) = self.movie_view_size(height_offset);
+                if width > 0.0 && height > 0.0 {
+                    let mut gui = self.gui.borrow_mut();
+                    let mut zoom = gui.movie_zoom();
+                    zoom.zoom_by(
+                        MovieZoom::STEP.powf(steps),
+                        (
+                            self.mouse_pos.x / width,
+                            (self.mouse_pos.y - height_offset) / height,
+                        ),
+                    );
+                    gui.set_movie_zoom(zoom);
+                }
+            }
+            WindowEvent::MouseWheel { delta, .. } => {
+                use ruffle_core::events::MouseWheelDelta;
+                use winit::event::MouseScrollDelta;
+                let delta = match delta {
+                    MouseScrollDelta::LineDelta(_, dy) => MouseWheelDelta::Lines(dy.into()),
+                    MouseScrollDelta::PixelDelta(pos) => MouseWheelDelta::Pixels(pos.y),
+                };
+                let direction = match delta {
+                    MouseWheelDelta::Lines(dy) | MouseWheelDelta::Pixels(dy) if dy > 0.0 => {
+                        Some(MouseWheelDirection::Up)
+                    }
+                    MouseWheelDelta::Lines(dy) | MouseWheelDelta::Pixels(dy) if dy < 0.0 => {
+                        Some(MouseWheelDirection::Down)
+                    }
+                    _ => None,
+                };
+                // Scrolling may be mapped to a key press, like gamepad buttons can be.
+                if let Some(key_code) =
+                    direction.and_then(|direction| self.preferences.mouse_wheel_key(direction))
+                {
+                    self.player.handle_event(PlayerEvent::KeyDown {
+                        key_code,
+                        key_char: None,
+                    });
+                    self.player.handle_event(PlayerEvent::KeyUp {
+                        key_code,
+                        key_char: None,
+                    });
+                } else {
+                    self.player.handle_event(PlayerEvent::MouseWheel { delta });
+                }
+                check_redraw = true;
+            }
+            WindowEvent::CursorEntered { .. } => {
+                if let Some(mut player) = self.player.get() {
+                    player.set_mouse_in_stage(true);
+                    if player.needs_render() {
+                        self.window.request_redraw();
+                    }
+                }
+            }
+            WindowEvent::CursorLeft { .. } => {
+                if let Some(mut player) = self.player.get() {
+                    player.set_mouse_in_stage(false);
+                }
+                self.player.handle_event(PlayerEvent::MouseLeave);
+                check_redraw = true;
+            }
+            WindowEvent::ModifiersChanged(new_modifiers) => {
+                self.modifiers = new_modifiers;
+            }
+            WindowEvent::KeyboardInput { event, .. } => {
+                // Handle fullscreen keyboard shortcuts: Alt+Return, Escape.
+                match event {
+                    KeyEvent {
+                        state: ElementState::Pressed,
+                        logical_key: Key::Named(NamedKey::Enter),
+                        ..
+                    } if self.modifiers.state().alt_key() => {
+                        if !self.fullscreen_down {
+                            self.player.toggle_fullscreen();
                         }
-                        WindowEvent::CursorEntered { .. } => {
-                            if let Some(mut player) = self.player.get() {
-                                player.set_mouse_in_stage(true);
-                                if player.needs_render() {
-                                    self.window.request_redraw();
-                                }
+                        self.fullscreen_down = true;
+                        return false;
+                    }
+                    KeyEvent {
+                        state: ElementState::Released,
+                        logical_key: Key::Named(NamedKey::Enter),
+                        ..
+                    } if self.fullscreen_down => {
+                        self.fullscreen_down = false;
+                    }
+                    KeyEvent {
+                        state: ElementState::Pressed,
+                        logical_key: Key::Named(NamedKey::Escape),
+                        ..
+                    } => {
+                        if let Some(mut player) = self.player.get() {
+                            if player.is_playing() {
+                                player.update(|uc| {
+                                    uc.stage.set_display_state(uc, StageDisplayState::Normal);
+                                })
                             }
                         }
-                        WindowEvent::CursorLeft { .. } => {
-                            if let Some(mut player) = self.player.get() {
-                                player.set_mouse_in_stage(false);
+                    }
+                    _ => (),
+                }
+
+                let key_code = winit_to_ruffle_key_code(&event);
+                // [NA] TODO: This event used to give a single char. `last()` is functionally the same,
+                // but we may want to be better at this in the future.
+                let key_char = event.text.clone().and_then(|text| text.chars().last());
+
+                match &event.state {
+                    ElementState::Pressed => {
+                        self.on_user_interaction();
+                        self.player
+                            .handle_event(PlayerEvent::KeyDown { key_code, key_char });
+                        if let Some(control_code) =
+                            winit_to_ruffle_text_control(&event, &self.modifiers)
+                        {
+                            self.player
+                                .handle_event(PlayerEvent::TextControl { code: control_code });
+                        } else if let Some(text) = event.text {
+                            for codepoint in text.chars() {
+                                self.player
+                                    .handle_event(PlayerEvent::TextInput { codepoint });
                             }
-                            self.player.handle_event(PlayerEvent::MouseLeave);
-                            check_redraw = true;
-                        }
-                        WindowEvent::ModifiersChanged(new_modifiers) => {
-                            modifiers = new_modifiers;
                         }
-                        WindowEvent::KeyboardInput { event, .. } => {
-                            // Handle fullscreen keyboard shortcuts: Alt+Return, Escape.
-                            match event {
-                                KeyEvent {
-                                    state: ElementState::Pressed,
-                                    logical_key: Key::Named(NamedKey::Enter),
-                                    ..
-                                } if modifiers.state().alt_key() => {
-                                    if !fullscreen_down {
-                                        if let Some(mut player) = self.player.get() {
-                                            player.update(|uc| {
-                                                uc.stage.toggle_display_state(uc);
-                                            });
-                                        }
-                                    }
-                                    fullscreen_down = true;
-                                    return;
-                                }
-                                KeyEvent {
-                                    state: ElementState::Released,
-                                    logical_key: Key::Named(NamedKey::Enter),
-                                    ..
-                                } if fullscreen_down => {
-                                    fullscreen_down = false;
-                                }
-                                KeyEvent {
-                                    state: ElementState::Pressed,
-                                    logical_key: Key::Named(NamedKey::Escape),
-                                    ..
-                                } => {
-                                    if let Some(mut player) = self.player.get() {
-                                        if player.is_playing() {
-                                            player.update(|uc| {
-                                                uc.stage.set_display_state(
-                                                    uc,
-                                                    StageDisplayState::Normal,
-                                                );
-                                            })
-                                        }
-                                    }
-                                }
-                                _ => (),
-                            }
+                    }
+                    ElementState::Released => {
+                        self.player
+                            .handle_event(PlayerEvent::KeyUp { key_code, key_char });
+                    }
+                };
+                check_redraw = true;
+            }
+            _ => (),
+        }
+        if check_redraw {
+            self.request_redraw_if_needed();
+        }
+        false
+    }
 
-                            let key_code = winit_to_ruffle_key_code(&event);
-                            // [NA] TODO: This event used to give a single char. `last()` is functionally the same,
-                            // but we may want to be better at this in the future.
-                            let key_char = event.text.clone().and_then(|text| text.chars().last());
-
-                            match &event.state {
-                                ElementState::Pressed => {
-                                    self.player
-                                        .handle_event(PlayerEvent::KeyDown { key_code, key_char });
-                                    if let Some(control_code) =
-                                        winit_to_ruffle_text_control(&event, &modifiers)
-                                    {
-                                        self.player.handle_event(PlayerEvent::TextControl {
-                                            code: control_code,
-                                        });
-                                    } else if let Some(text) = event.text {
-                                        for codepoint in text.chars() {
-                                            self.player
-                                                .handle_event(PlayerEvent::TextInput { codepoint });
-                                        }
-                                    }
-                                }
-                                ElementState::Released => {
-                                    self.player
-                                        .handle_event(PlayerEvent::KeyUp { key_code, key_char });
-                                }
-                            };
-                            check_redraw = true;
-                        }
-                        _ => (),
+    /// Whether this window is focused and shows a movie that's playing, rather than paused.
+    fn is_playing_in_focus(&self) -> bool {
+        self.focused
+            && !self.minimized
+            && self.player.get().is_some_and(|player| player.is_playing())
+    }
+
+    /// Advances the movie if it's time for its next frame, and opens any files dropped onto the window.
+    fn about_to_wait(&mut self) {
+        let mut check_redraw = false;
+        let mut skip_render = false;
+        if matches!(self.loaded, LoadingState::Loaded) {
+            // Dialogs that blocked the event loop shouldn't make the movie catch up on that time.
+            let blocked_time = self.player.take_blocked_time();
+            self.time = (self.time + blocked_time).min(Instant::now());
+            let new_time = Instant::now();
+            let dt = new_time.duration_since(self.time).as_micros();
+            let throttled = matches!(self.min_tick_interval, Some(interval) if new_time.duration_since(self.time) < interval);
+            if dt > 0 && !throttled {
+                let elapsed = new_time.duration_since(self.time);
+                self.time = new_time;
+                self.player.replay_input();
+                self.player.run_script();
+                if let Some(mut player) = self.player.get() {
+                    if let Some(skipper) = &mut self.frame_skipper {
+                        let frame_time = Duration::from_secs_f64(1.0 / player.frame_rate());
+                        skip_render = !skipper.should_render(elapsed, frame_time);
                     }
-                }
-                winit::event::Event::UserEvent(RuffleEvent::TaskPoll) => self.player.poll(),
-                winit::event::Event::UserEvent(RuffleEvent::OnMetadata(swf_header)) => {
-                    let movie_width = swf_header.stage_size().width().to_pixels();
-                    let movie_height = swf_header.stage_size().height().to_pixels();
-                    let height_offset = if self.window.fullscreen().is_some() || self.no_gui {
-                        0.0
-                    } else {
-                        MENU_HEIGHT as f64
+                    let mut dt = dt as f64 / 1000.0;
+                    if self.min_tick_interval.is_some() {
+                        // Run at most one frame per tick, so that the movie slows down
+                        // instead of catching up on the frames it missed.
+                        dt = dt.min(1000.0 / player.frame_rate());
+                    }
+                    player.tick(dt);
+                    let until_next_frame = match self.min_tick_interval {
+                        Some(interval) => player.time_til_next_frame().max(interval),
+                        None => player.time_til_next_frame(),
                     };
+                    self.next_frame_time = Some(new_time + until_next_frame);
+                } else {
+                    self.next_frame_time = None;
+                }
+                self.player.check_memory_limit();
+                self.player.check_auto_gc();
+                self.player.check_playback_complete();
+                self.player.check_stage_settings();
+                self.player.check_first_frame();
+                self.player.check_avm_error();
+                check_redraw = true;
+            }
+        }
 
-                    let window_size: Size = match (self.preferred_width, self.preferred_height) {
-                        (None, None) => {
-                            LogicalSize::new(movie_width, movie_height + height_offset).into()
-                        }
-                        (Some(width), None) => {
-                            let scale = width / movie_width;
-                            let height = movie_height * scale;
-                            PhysicalSize::new(
-                                width.max(1.0),
-                                height.max(1.0) + height_offset * self.window.scale_factor(),
-                            )
-                            .into()
-                        }
-                        (None, Some(height)) => {
-                            let scale = height / movie_height;
-                            let width = movie_width * scale;
-                            PhysicalSize::new(
-                                width.max(1.0),
-                                height.max(1.0) + height_offset * self.window.scale_factor(),
-                            )
-                            .into()
-                        }
-                        (Some(width), Some(height)) => PhysicalSize::new(
-                            width.max(1.0),
-                            height.max(1.0) + height_offset * self.window.scale_factor(),
-                        )
-                        .into(),
-                    };
+        if !self.dropped_files.is_empty() {
+            let files = std::mem::take(&mut self.dropped_files);
+            self.open_dropped_files(files);
+        }
 
-                    let window_size = Size::clamp(
-                        window_size,
-                        self.min_window_size.into(),
-                        self.max_window_size.into(),
-                        self.window.scale_factor(),
-                    );
+        if check_redraw && !skip_render {
+            self.request_redraw_if_needed();
+        }
+    }
 
-                    let viewport_size = self.window.inner_size();
-                    let mut window_resize_denied = false;
+    fn handle_user_event(&mut self, event: RuffleEvent) {
+        match event {
+            RuffleEvent::OnMetadata(swf_header) => {
+                if let Err(e) = self.player.check_required_avm(&swf_header) {
+                    tracing::error!("{e}");
+                    self.window.set_title("Ruffle");
+                    self.player.destroy();
+                    let _guard = self.player.blocking_dialog_guard();
+                    MessageDialog::new()
+                        .set_level(MessageLevel::Error)
+                        .set_title("Ruffle - Wrong ActionScript version")
+                        .set_description(e.to_string())
+                        .set_buttons(MessageButtons::Ok)
+                        .show();
+                    return;
+                }
+                self.player.wait_for_first_frame();
 
-                    if let Some(new_viewport_size) = self.window.request_inner_size(window_size) {
-                        if new_viewport_size != viewport_size {
-                            self.gui.borrow_mut().resize(new_viewport_size);
+                let movie_width = swf_header.stage_size().width().to_pixels();
+                let movie_height = swf_header.stage_size().height().to_pixels();
+                let height_offset = if self.window.fullscreen().is_some() || self.no_gui {
+                    0.0
+                } else {
+                    MENU_HEIGHT as f64
+                };
+
+                let window_size: Size = match self.player.window_size() {
+                    (None, None) => match self.preferences.cli.ui_scale {
+                        Some(scale) => PhysicalSize::new(
+                            movie_width * scale,
+                            movie_height * scale + height_offset * self.window.scale_factor(),
+                        )
+                        .into(),
+                        None => LogicalSize::new(movie_width, movie_height + height_offset).into(),
+                    },
+                    (Some(width), None) => {
+                        let scale = width / movie_width;
+                        let height = movie_height * scale;
+                        PhysicalSize::new(
+                            width.max(1.0),
+                            height.max(1.0) + height_offset * self.window.scale_factor(),
+                        )
+                        .into()
+                    }
+                    (None, Some(height)) => {
+                        let scale = height / movie_height;
+                        let width = movie_width * scale;
+                        PhysicalSize::new(
+                            width.max(1.0),
+                            height.max(1.0) + height_offset * self.window.scale_factor(),
+                        )
+                        .into()
+                    }
+                    (Some(width), Some(height)) => {
+                        // With a locked aspect ratio, fit the movie into the requested size instead.
+                        let (width, height) = if self.lock_aspect {
+                            let scale = (width / movie_width).min(height / movie_height);
+                            (movie_width * scale, movie_height * scale)
                         } else {
-                            tracing::warn!("Unable to resize window");
-                            window_resize_denied = true;
-                        }
+                            (width, height)
+                        };
+                        PhysicalSize::new(
+                            width.max(1.0),
+                            height.max(1.0) + height_offset * self.window.scale_factor(),
+                        )
+                        .into()
                     }
-                    self.window.set_fullscreen(if self.start_fullscreen {
-                        Some(Fullscreen::Borderless(None))
-                    } else {
-                        None
-                    });
-                    self.window.set_visible(true);
+                };
 
-                    let viewport_size = self.window.inner_size();
+                let window_size = Size::clamp(
+                    window_size,
+                    self.min_window_size.into(),
+                    self.max_window_size.into(),
+                    self.window.scale_factor(),
+                );
 
-                    // On X11 (and possibly other platforms), the window size is not updated immediately.
-                    // On a successful resize request, wait for the window to be resized to the requested size
-                    // before we start running the SWF (which can observe the viewport size in "noScale" mode)
-                    if !window_resize_denied && window_size != viewport_size.into() {
-                        loaded = LoadingState::WaitingForResize;
+                let viewport_size = self.window.inner_size();
+                let mut window_resize_denied = false;
+
+                if let Some(new_viewport_size) = self.window.request_inner_size(window_size) {
+                    if new_viewport_size != viewport_size {
+                        self.gui.borrow_mut().resize(new_viewport_size);
                     } else {
-                        loaded = LoadingState::Loaded;
+                        tracing::warn!("Unable to resize window");
+                        window_resize_denied = true;
                     }
+                }
+                self.window.set_fullscreen(if self.start_fullscreen {
+                    Some(borderless_fullscreen(
+                        &self.window,
+                        self.preferences.cli.fullscreen_monitor,
+                    ))
+                } else {
+                    None
+                });
+                if !self.start_fullscreen {
+                    self.place_window();
+                }
+                self.window.set_visible(true);
 
-                    let viewport_scale_factor = self.window.scale_factor();
-                    if let Some(mut player) = self.player.get() {
-                        player.set_viewport_dimensions(ViewportDimensions {
-                            width: viewport_size.width,
-                            height: viewport_size.height - height_offset as u32,
-                            scale_factor: viewport_scale_factor,
-                        });
-                    }
+                // Wait for the movie size before constraining the window, as it isn't known earlier.
+                if self.lock_aspect && movie_width > 0.0 && movie_height > 0.0 {
+                    self.locked_aspect_ratio = Some(movie_width / movie_height);
+                }
+                if self.not_resizable {
+                    self.window.set_resizable(false);
                 }
 
-                winit::event::Event::UserEvent(RuffleEvent::ContextMenuItemClicked(index)) => {
-                    if let Some(mut player) = self.player.get() {
-                        player.run_context_menu_callback(index);
-                    }
+                let viewport_size = self.window.inner_size();
+
+                // On X11 (and possibly other platforms), the window size is not updated immediately.
+                // On a successful resize request, wait for the window to be resized to the requested size
+                // before we start running the SWF (which can observe the viewport size in "noScale" mode)
+                if !window_resize_denied && window_size != viewport_size.into() {
+                    self.loaded = LoadingState::WaitingForResize;
+                } else {
+                    self.loaded = LoadingState::Loaded;
                 }
 
-                winit::event::Event::UserEvent(RuffleEvent::BrowseAndOpen(options)) => {
-                    if let Some(url) =
-                        pick_file(false, None).and_then(|p| Url::from_file_path(p).ok())
-                    {
-                        self.gui
-                            .borrow_mut()
-                            .create_movie(&mut self.player, *options, url);
-                    }
+                let result = self.player.set_viewport_dimensions(
+                    self.viewport_dimensions(viewport_size, height_offset),
+                );
+                if let Err(e) = result {
+                    tracing::warn!("Couldn't resize the stage: {e}");
+                }
+            }
+
+            RuffleEvent::ContextMenuItemClicked(index) => {
+                if let Some(mut player) = self.player.get() {
+                    player.run_context_menu_callback(index);
                 }
+            }
 
-                winit::event::Event::UserEvent(RuffleEvent::OpenURL(url, options)) => {
+            RuffleEvent::BrowseAndOpen(options) => {
+                if let Some(url) = pick_file(false, None).and_then(|p| Url::from_file_path(p).ok())
+                {
                     self.gui
                         .borrow_mut()
                         .create_movie(&mut self.player, *options, url);
                 }
+            }
 
-                winit::event::Event::UserEvent(RuffleEvent::CloseFile) => {
-                    self.window.set_title("Ruffle"); // Reset title since file has been closed.
-                    self.player.destroy();
+            RuffleEvent::BrowseAndSwapRootSwf => {
+                if let Some(path) = pick_file(false, None) {
+                    self.gui.borrow_mut().swap_root_swf(&mut self.player, &path);
                 }
+            }
 
-                winit::event::Event::UserEvent(RuffleEvent::ExitRequested) => {
-                    elwt.exit();
-                    return;
+            RuffleEvent::OpenURL(url, options) => {
+                self.gui
+                    .borrow_mut()
+                    .create_movie(&mut self.player, *options, url);
+            }
+
+            RuffleEvent::CloseFile => {
+                self.window.set_title("Ruffle"); // Reset title since file has been closed.
+                self.player.destroy();
+            }
+
+            RuffleEvent::ReopenClosedFile => {
+                if let Some((url, options)) = self.player.take_last_closed() {
+                    // Whatever is open now is closed first, so that it can be reopened in turn.
+                    self.player.destroy();
+                    self.gui
+                        .borrow_mut()
+                        .create_movie(&mut self.player, options, url);
                 }
+            }
 
-                _ => (),
+            RuffleEvent::ExportFrameSvg => {
+                if let Some(path) = pick_save_file("SVG Images", &["svg"]) {
+                    if let Err(e) = self.player.export_frame_svg(&path) {
+                        tracing::error!("Couldn't save frame to {}: {e}", path.display());
+                    }
+                }
             }
 
-            if let Some(Event { event, .. }) = gilrs.as_mut().and_then(|gilrs| gilrs.next_event()) {
-                match event {
-                    EventType::ButtonPressed(button, _) => {
-                        if let Some(button) = gilrs_button_to_gamepad_button(button) {
-                            self.player
-                                .handle_event(PlayerEvent::GamepadButtonDown { button });
-                            check_redraw = true;
+            RuffleEvent::DumpDisplayList => {
+                if let Some(path) = pick_save_file("JSON Files", &["json"]) {
+                    match self.player.save_display_list(&path) {
+                        Ok(()) => {
+                            tracing::info!("Saved display list to {}", path.display());
+                            let args = HashMap::from([("path", path.display().to_string().into())]);
+                            self.gui.borrow_mut().show_toast(
+                                text_with_args(
+                                    &self.preferences.language(),
+                                    "toast-display-list-saved",
+                                    &args,
+                                )
+                                .into_owned(),
+                            );
                         }
-                    }
-                    EventType::ButtonReleased(button, _) => {
-                        if let Some(button) = gilrs_button_to_gamepad_button(button) {
-                            self.player
-                                .handle_event(PlayerEvent::GamepadButtonUp { button });
-                            check_redraw = true;
+                        Err(e) => {
+                            tracing::error!("Couldn't save display list to {}: {e}", path.display())
                         }
                     }
-                    _ => {}
                 }
             }
 
-            // Check for a redraw request.
-            if check_redraw {
-                let player = self.player.get();
-                let gui = self.gui.borrow_mut();
-                if player.map(|p| p.needs_render()).unwrap_or_default() || gui.needs_render() {
-                    self.window.request_redraw();
+            RuffleEvent::ShowAssetStats => {
+                if let Some(stats) = self.player.loaded_asset_stats() {
+                    tracing::info!("Loaded assets: {stats:?}");
+                    let args = HashMap::from([
+                        ("bitmaps", stats.bitmaps.into()),
+                        ("bitmap-bytes", stats.bitmap_bytes.into()),
+                        ("decoded-bitmaps", stats.decoded_bitmaps.into()),
+                        ("decoded-bitmap-bytes", stats.decoded_bitmap_bytes.into()),
+                        ("sounds", stats.sounds.into()),
+                        ("sound-bytes", stats.sound_bytes.into()),
+                        ("fonts", stats.fonts.into()),
+                    ]);
+                    self.gui.borrow_mut().show_toast(
+                        text_with_args(&self.preferences.language(), "toast-asset-stats", &args)
+                            .into_owned(),
+                    );
                 }
             }
 
-            // After polling events, sleep the event loop until the next event or the next frame.
-            elwt.set_control_flow(if matches!(loaded, LoadingState::Loaded) {
-                if let Some(next_frame_time) = next_frame_time {
-                    ControlFlow::WaitUntil(next_frame_time)
+            RuffleEvent::ClearAssetCache => {
+                let released = self.player.clear_asset_cache();
+                tracing::info!("Released {released} cached assets");
+            }
+
+            RuffleEvent::CollectGarbage => {
+                let reclaimed = self.player.collect_garbage();
+                tracing::info!("Collected garbage, reclaiming {reclaimed} bytes");
+            }
+
+            RuffleEvent::CopyMovieUrl => {
+                self.copy_movie_url();
+            }
+
+            RuffleEvent::ShowMovieInFolder => {
+                self.show_movie_in_folder();
+            }
+
+            RuffleEvent::ReloadPreferences => {
+                self.reload_preferences();
+            }
+
+            RuffleEvent::ResetPreferences => {
+                self.reset_preferences();
+            }
+
+            RuffleEvent::PlaybackComplete => {
+                tracing::info!("Playback complete");
+                self.gui.borrow_mut().show_toast(
+                    text(&self.preferences.language(), "toast-playback-complete").into_owned(),
+                );
+            }
+
+            RuffleEvent::StageSettingsChanged {
+                quality,
+                scale_mode,
+                align,
+            } => {
+                tracing::debug!(
+                    "Stage settings changed: quality {quality:?}, scale mode {scale_mode:?}, align {align:?}"
+                );
+                // The menus read the settings from the player, so drawing them again is enough.
+                self.window.request_redraw();
+            }
+
+            RuffleEvent::BrokeOnError(error) => {
+                let args = HashMap::from([("error", error.into())]);
+                self.gui.borrow_mut().show_toast(
+                    text_with_args(&self.preferences.language(), "toast-broke-on-error", &args)
+                        .into_owned(),
+                );
+            }
+
+            RuffleEvent::SetVolume(volume) => {
+                if let Some(mut player) = self.player.get() {
+                    self.gui.borrow_mut().set_volume(&mut player, volume);
+                }
+            }
+
+            RuffleEvent::ScriptTimeout(action) => {
+                let id = match action {
+                    ScriptTimeoutAction::Abort => "toast-script-aborted",
+                    _ => "toast-script-halted",
+                };
+                self.gui
+                    .borrow_mut()
+                    .show_toast(text(&self.preferences.language(), id).into_owned());
+            }
+
+            RuffleEvent::UnsupportedVideo(codec, reason) => {
+                let id = match reason {
+                    UnsupportedVideoReason::NoVideoBackend => "toast-video-unsupported",
+                    UnsupportedVideoReason::CodecNotImplemented => "toast-video-codec-unsupported",
+                };
+                let args = HashMap::from([("codec", video_codec_name(codec).into())]);
+                self.gui.borrow_mut().show_toast(
+                    text_with_args(&self.preferences.language(), id, &args).into_owned(),
+                );
+            }
+
+            RuffleEvent::ZoomMovie(factor) => {
+                let mut gui = self.gui.borrow_mut();
+                let mut zoom = gui.movie_zoom();
+                zoom.zoom_by(factor, (0.5, 0.5));
+                gui.set_movie_zoom(zoom);
+            }
+
+            RuffleEvent::ResetMovieZoom => {
+                self.gui.borrow_mut().set_movie_zoom(MovieZoom::default());
+            }
+
+            RuffleEvent::SetBitmapSmoothing(smoothing) => {
+                self.player.set_bitmap_smoothing(smoothing);
+            }
+
+            RuffleEvent::SetTextAntiAliasing(value) => {
+                self.player.set_text_anti_aliasing(value);
+            }
+
+            RuffleEvent::CycleBitmapSmoothing => {
+                let smoothing = match self.player.bitmap_smoothing() {
+                    None => Some(false),
+                    Some(false) => Some(true),
+                    Some(true) => None,
+                };
+                self.player.set_bitmap_smoothing(smoothing);
+            }
+
+            RuffleEvent::ModalDialogOpened => {
+                self.player.on_modal_dialog_opened();
+            }
+
+            RuffleEvent::ModalDialogClosed => {
+                self.player.on_modal_dialog_closed();
+            }
+
+            RuffleEvent::NavigateWithinBundle(url) => {
+                self.gui
+                    .borrow_mut()
+                    .navigate_within_bundle(&mut self.player, url);
+            }
+
+            RuffleEvent::NavigateInPlace(url) => {
+                if let Some(options) = self.player.in_place_launch_options() {
+                    tracing::info!("Navigating to {url} in place of the movie");
+                    self.gui
+                        .borrow_mut()
+                        .create_movie(&mut self.player, options, url);
+                }
+            }
+
+            RuffleEvent::LoadError(message) => {
+                tracing::error!("Couldn't load movie: {message}");
+                let _guard = self.player.blocking_dialog_guard();
+                MessageDialog::new()
+                    .set_level(MessageLevel::Error)
+                    .set_title("Ruffle - Load failed")
+                    .set_description(message)
+                    .set_buttons(MessageButtons::Ok)
+                    .show();
+            }
+
+            RuffleEvent::FirstFrameTimeout => {
+                let guard = self.player.blocking_dialog_guard();
+                let keep_waiting = MessageDialog::new()
+                    .set_level(MessageLevel::Warning)
+                    .set_title("Ruffle - Movie not responding")
+                    .set_description(format!(
+                        "The movie hasn't shown anything {} seconds after it was downloaded, and may be stuck.\n\nKeep waiting for it?",
+                        self.preferences.cli.first_frame_timeout.as_secs_f64()
+                    ))
+                    .set_buttons(MessageButtons::YesNo)
+                    .show()
+                    == MessageDialogResult::Yes;
+                drop(guard);
+                if keep_waiting {
+                    self.player.wait_for_first_frame();
                 } else {
-                    // prevent 100% cpu use
-                    // TODO: use set_request_repaint_callback to correctly get egui repaint requests.
-                    ControlFlow::WaitUntil(Instant::now() + Duration::from_millis(10))
+                    self.window.set_title("Ruffle");
+                    self.player.destroy();
                 }
-            } else {
-                ControlFlow::Wait
-            });
-        })?;
-        Ok(())
+            }
+
+            RuffleEvent::MemoryLimitExceeded => {
+                self.window.set_title("Ruffle");
+                self.player.destroy();
+                let _guard = self.player.blocking_dialog_guard();
+                MessageDialog::new()
+                    .set_level(MessageLevel::Error)
+                    .set_title("Ruffle - Memory limit exceeded")
+                    .set_description(
+                        "The movie used more memory than allowed by --max-memory and has been closed.",
+                    )
+                    .set_buttons(MessageButtons::Ok)
+                    .show();
+            }
+
+            // The remaining events concern every window, and are handled by the app.
+            _ => (),
+        }
+    }
+
+    fn handle_gamepad_event(&mut self, event: EventType) {
+        match event {
+            EventType::ButtonPressed(button, _) => {
+                if let Some(button) = gilrs_button_to_gamepad_button(button) {
+                    self.player
+                        .handle_event(PlayerEvent::GamepadButtonDown { button });
+                    self.request_redraw_if_needed();
+                }
+            }
+            EventType::ButtonReleased(button, _) => {
+                if let Some(button) = gilrs_button_to_gamepad_button(button) {
+                    self.player
+                        .handle_event(PlayerEvent::GamepadButtonUp { button });
+                    self.request_redraw_if_needed();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Applies preferences that were changed from another window, or reloaded from disk.
+    fn on_preferences_reloaded(&self) {
+        self.gui
+            .borrow_mut()
+            .on_preferences_reloaded(self.player.get().as_deref_mut());
+    }
+
+    /// Reads the preferences from disk again and applies them to the running movie.
+    fn reload_preferences(&mut self) {
+        let locale = self.preferences.language();
+        let message = match self.preferences.reload() {
+            Ok(needs_restart) => {
+                self.on_preferences_reloaded();
+                if needs_restart.is_empty() {
+                    text(&locale, "toast-preferences-reloaded").into_owned()
+                } else {
+                    tracing::info!(
+                        "Changes to {} will take effect after restarting",
+                        needs_restart.join(", ")
+                    );
+                    let names = restart_needed_names(&locale, &needs_restart);
+                    let args = HashMap::from([("preferences", names.into())]);
+                    text_with_args(&locale, "toast-preferences-reloaded-restart", &args)
+                        .into_owned()
+                }
+            }
+            Err(e) => {
+                tracing::error!("Couldn't reload preferences: {e:#}");
+                let args = HashMap::from([("error", format!("{e:#}").into())]);
+                text_with_args(&locale, "toast-preferences-reload-failed", &args).into_owned()
+            }
+        };
+        self.gui.borrow_mut().show_toast(message);
+    }
+
+    fn copy_movie_url(&self) {
+        let Some(url) = self.player.url() else {
+            return;
+        };
+        let args = HashMap::from([("url", url.to_string().into())]);
+        let message = text_with_args(
+            &self.preferences.language(),
+            "toast-movie-url-copied",
+            &args,
+        )
+        .into_owned();
+        let mut gui = self.gui.borrow_mut();
+        gui.copy_to_clipboard(url.to_string());
+        gui.show_toast(message);
+    }
+
+    /// Opens the folder of the current movie in the system file manager, if it's a local file.
+    fn show_movie_in_folder(&self) {
+        let Some(url) = self.player.url() else {
+            return;
+        };
+        let locale = self.preferences.language();
+        let message = match url.to_file_path() {
+            Ok(path) => match show_in_folder(&path) {
+                Ok(()) => return,
+                Err(e) => {
+                    tracing::error!("Couldn't show {} in its folder: {e}", path.display());
+                    let args = HashMap::from([("error", e.to_string().into())]);
+                    text_with_args(&locale, "toast-show-in-folder-failed", &args).into_owned()
+                }
+            },
+            Err(()) => text(&locale, "toast-show-in-folder-not-local").into_owned(),
+        };
+        self.gui.borrow_mut().show_toast(message);
+    }
+
+    /// Resets the preferences to their defaults, once the user confirms it.
+    fn reset_preferences(&mut self) {
+        let guard = self.player.blocking_dialog_guard();
+        let confirmed = MessageDialog::new()
+            .set_level(MessageLevel::Warning)
+            .set_title("Ruffle - Reset preferences")
+            .set_description(format!(
+                "Reset all preferences to their defaults? Recents, bookmarks and saves are kept, \
+                 and the current preferences are copied to {}.",
+                self.preferences.preferences_backup_path().display()
+            ))
+            .set_buttons(MessageButtons::YesNo)
+            .show()
+            == MessageDialogResult::Yes;
+        drop(guard);
+        if !confirmed {
+            return;
+        }
+
+        let locale = self.preferences.language();
+        let message = match self.preferences.reset() {
+            Ok(needs_restart) => {
+                // The language may have changed too, so the message uses the new one.
+                let locale = self.preferences.language();
+                self.on_preferences_reloaded();
+                if needs_restart.is_empty() {
+                    text(&locale, "toast-preferences-reset").into_owned()
+                } else {
+                    let names = restart_needed_names(&locale, &needs_restart);
+                    let args = HashMap::from([("preferences", names.into())]);
+                    text_with_args(&locale, "toast-preferences-reset-restart", &args).into_owned()
+                }
+            }
+            Err(e) => {
+                tracing::error!("Couldn't reset preferences: {e:#}");
+                let args = HashMap::from([("error", format!("{e:#}").into())]);
+                text_with_args(&locale, "toast-preferences-reset-failed", &args).into_owned()
+            }
+        };
+        self.gui.borrow_mut().show_toast(message);
+    }
+
+    /// Lets the GUI know that the user clicked on or typed into the movie.
+    fn on_user_interaction(&self) {
+        if let Some(mut player) = self.player.get() {
+            self.gui.borrow_mut().on_user_interaction(&mut player);
+        }
+    }
+
+    /// Opens the first movie or bundle out of the files dropped onto the window,
+    /// replacing the current movie.
+    fn open_dropped_files(&mut self, files: Vec<PathBuf>) {
+        let locale = self.preferences.language();
+        let (movies, unsupported): (Vec<_>, Vec<_>) =
+            files.into_iter().partition(|path| is_movie_or_bundle(path));
+
+        let mut messages = Vec::new();
+        if let Some(path) = unsupported.first() {
+            tracing::warn!("Not opening dropped file {path:?}, as it isn't a movie or bundle");
+            let args = HashMap::from([("filename", file_name(path).into())]);
+            messages.push(text_with_args(&locale, "toast-unsupported-file", &args).into_owned());
+        }
+
+        let Some(path) = movies.first() else {
+            self.gui.borrow_mut().show_toast(messages.join("\n"));
+            return;
+        };
+        if movies.len() > 1 {
+            tracing::warn!(
+                "{} movies were dropped, but only one can be open at a time; opening {path:?}",
+                movies.len()
+            );
+            let args = HashMap::from([("filename", file_name(path).into())]);
+            messages.push(text_with_args(&locale, "toast-multiple-files", &args).into_owned());
+        }
+
+        match parse_url(path) {
+            Ok(url) => {
+                self.player.destroy();
+                let mut gui = self.gui.borrow_mut();
+                gui.create_movie(
+                    &mut self.player,
+                    LaunchOptions::from(&self.preferences),
+                    url,
+                );
+                if !messages.is_empty() {
+                    gui.show_toast(messages.join("\n"));
+                }
+            }
+            Err(e) => tracing::error!("Couldn't open dropped file {path:?}: {e}"),
+        }
+    }
+}
+
+fn build_window(
+    elwt: &EventLoopWindowTarget<WindowedRuffleEvent>,
+    icon: &Icon,
+    min_window_size: LogicalSize<u32>,
+    max_window_size: PhysicalSize<u32>,
+    transparent: bool,
+) -> Result<Rc<Window>, Error> {
+    let window = WindowBuilder::new()
+        .with_visible(false)
+        .with_transparent(transparent)
+        .with_title("Ruffle")
+        .with_window_icon(Some(icon.clone()))
+        .with_min_inner_size(min_window_size)
+        .with_max_inner_size(max_window_size)
+        .build(elwt)?;
+    Ok(Rc::new(window))
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .unwrap_or(path.as_os_str())
+        .to_string_lossy()
+        .into_owned()
+}
+
+fn video_codec_name(codec: VideoCodec) -> &'static str {
+    match codec {
+        VideoCodec::None => "None",
+        VideoCodec::H263 => "Sorenson H.263",
+        VideoCodec::ScreenVideo => "Screen Video",
+        VideoCodec::Vp6 => "On2 VP6",
+        VideoCodec::Vp6WithAlpha => "On2 VP6 with alpha",
+        VideoCodec::ScreenVideoV2 => "Screen Video V2",
     }
 }
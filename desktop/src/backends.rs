@@ -1,11 +1,13 @@
 mod audio;
 mod external_interface;
 mod fscommand;
+mod modal;
 mod navigator;
 mod ui;
 
 pub use audio::CpalAudioBackend;
-pub use external_interface::DesktopExternalInterfaceProvider;
+pub use external_interface::{DesktopExternalInterfaceProvider, ExternalInterfaceValues};
 pub use fscommand::DesktopFSCommandProvider;
+pub use modal::{BlockedTime, ModalDialogGuard, ModalDialogs};
 pub use navigator::RfdNavigatorInterface;
-pub use ui::DesktopUiBackend;
+pub use ui::{CustomCursor, DesktopUiBackend, FullscreenRequests};
@@ -1,4 +1,5 @@
 use crate::preferences::GlobalPreferences;
+use crate::stems::WavStems;
 use anyhow::{anyhow, Context, Error};
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
 use ruffle_core::backend::audio::{
@@ -6,11 +7,11 @@ use ruffle_core::backend::audio::{
     SoundStreamInfo, SoundTransform,
 };
 use ruffle_core::impl_audio_mixer_backend;
+use std::path::Path;
 
 pub struct CpalAudioBackend {
     #[allow(dead_code)]
     device: cpal::Device,
-    #[allow(dead_code)]
     config: cpal::StreamConfig,
     stream: cpal::Stream,
     mixer: AudioMixer,
@@ -76,6 +77,14 @@ impl CpalAudioBackend {
             mixer,
         })
     }
+
+    /// Starts recording the audio of event sounds, of stream sounds and of both mixed together
+    /// to separate WAV files in `directory`.
+    pub fn record_stems(&mut self, directory: &Path) -> Result<(), Error> {
+        let stems = WavStems::create(directory, self.config.sample_rate.0)?;
+        self.mixer.set_stem_sink(Box::new(stems));
+        Ok(())
+    }
 }
 
 impl AudioBackend for CpalAudioBackend {
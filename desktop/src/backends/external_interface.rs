@@ -1,11 +1,73 @@
+use anyhow::{anyhow, Error};
 use ruffle_core::context::UpdateContext;
 use ruffle_core::external::{
     ExternalInterfaceMethod, ExternalInterfaceProvider, Value as ExternalValue,
 };
+use std::collections::{HashMap, HashSet};
 use url::Url;
 
 pub struct DesktopExternalInterfaceProvider {
     pub spoof_url: Option<Url>,
+    pub values: ExternalInterfaceValues,
+    /// Whether every call made by the movie is logged, with its arguments and result.
+    pub log_calls: bool,
+    /// The only methods the movie may call, if limited. Any others return null.
+    pub allowed_methods: Option<HashSet<String>>,
+}
+
+/// The values returned by ExternalInterface methods of the dummy External Interface,
+/// for movies that check the page they're embedded in.
+#[derive(Debug, Clone, Default)]
+pub struct ExternalInterfaceValues {
+    methods: HashMap<String, ExternalValue>,
+    /// Returned by any method that isn't in `methods`.
+    fallback: Option<ExternalValue>,
+}
+
+impl ExternalInterfaceValues {
+    /// The key of the value returned by methods that aren't listed.
+    const FALLBACK_KEY: &'static str = "*";
+
+    /// Parses a JSON object mapping method names to the values they return,
+    /// where the `"*"` key holds the value returned by any other method.
+    pub fn from_json(json: &str) -> Result<Self, Error> {
+        let serde_json::Value::Object(object) = serde_json::from_str::<serde_json::Value>(json)?
+        else {
+            return Err(anyhow!(
+                "Expected an object of method names to the values they return"
+            ));
+        };
+        let mut methods: HashMap<_, _> = object
+            .into_iter()
+            .map(|(name, value)| (name, json_to_external(value)))
+            .collect();
+        let fallback = methods.remove(Self::FALLBACK_KEY);
+        Ok(Self { methods, fallback })
+    }
+
+    fn get(&self, name: &str) -> Option<&ExternalValue> {
+        self.methods.get(name).or(self.fallback.as_ref())
+    }
+}
+
+fn json_to_external(value: serde_json::Value) -> ExternalValue {
+    match value {
+        serde_json::Value::Null => ExternalValue::Null,
+        serde_json::Value::Bool(value) => ExternalValue::Bool(value),
+        serde_json::Value::Number(value) => {
+            ExternalValue::Number(value.as_f64().unwrap_or(f64::NAN))
+        }
+        serde_json::Value::String(value) => ExternalValue::String(value),
+        serde_json::Value::Array(values) => {
+            ExternalValue::List(values.into_iter().map(json_to_external).collect())
+        }
+        serde_json::Value::Object(values) => ExternalValue::Object(
+            values
+                .into_iter()
+                .map(|(key, value)| (key, json_to_external(value)))
+                .collect(),
+        ),
+    }
 }
 
 struct FakeWindowLocationHrefToString(Url);
@@ -16,17 +78,150 @@ impl ExternalInterfaceMethod for FakeWindowLocationHrefToString {
     }
 }
 
-impl ExternalInterfaceProvider for DesktopExternalInterfaceProvider {
-    fn get_method(&self, name: &str) -> Option<Box<dyn ExternalInterfaceMethod>> {
+struct ScriptedValue(ExternalValue);
+
+impl ExternalInterfaceMethod for ScriptedValue {
+    fn call(&self, _context: &mut UpdateContext<'_, '_>, _args: &[ExternalValue]) -> ExternalValue {
+        self.0.clone()
+    }
+}
+
+/// Logs the calls made to a method, which returns null when it's blocked or unknown.
+struct LoggedMethod {
+    name: String,
+    blocked: bool,
+    method: Option<Box<dyn ExternalInterfaceMethod>>,
+}
+
+impl ExternalInterfaceMethod for LoggedMethod {
+    fn call(&self, context: &mut UpdateContext<'_, '_>, args: &[ExternalValue]) -> ExternalValue {
+        let result = match &self.method {
+            Some(method) => method.call(context, args),
+            None => ExternalValue::Null,
+        };
+        let name = &self.name;
+        if self.blocked {
+            tracing::info!(target: "ruffle_external_interface", "{name}{args:?} was blocked");
+        } else {
+            tracing::info!(target: "ruffle_external_interface", "{name}{args:?} returned {result:?}");
+        }
+        result
+    }
+}
+
+impl DesktopExternalInterfaceProvider {
+    fn is_allowed(&self, name: &str) -> bool {
+        match &self.allowed_methods {
+            Some(allowed) => allowed.contains(name),
+            None => true,
+        }
+    }
+
+    fn find_method(&self, name: &str) -> Option<Box<dyn ExternalInterfaceMethod>> {
+        // Methods given explicitly win over the spoofed URL, which wins over the value for any method.
+        if let Some(value) = self.values.methods.get(name) {
+            return Some(Box::new(ScriptedValue(value.clone())));
+        }
+
         if let Some(ref url) = self.spoof_url {
             if name == "window.location.href.toString" || name == "top.location.href.toString" {
                 return Some(Box::new(FakeWindowLocationHrefToString(url.clone())));
             }
         }
 
+        if let Some(value) = self.values.get(name) {
+            return Some(Box::new(ScriptedValue(value.clone())));
+        }
+
         tracing::warn!("Trying to call unknown ExternalInterface method: {name}");
         None
     }
+}
+
+impl ExternalInterfaceProvider for DesktopExternalInterfaceProvider {
+    fn get_method(&self, name: &str) -> Option<Box<dyn ExternalInterfaceMethod>> {
+        let blocked = !self.is_allowed(name);
+        let method = if blocked {
+            None
+        } else {
+            self.find_method(name)
+        };
+        if self.log_calls {
+            return Some(Box::new(LoggedMethod {
+                name: name.to_string(),
+                blocked,
+                method,
+            }));
+        }
+        if blocked {
+            tracing::warn!("Blocked a call to the ExternalInterface method {name}");
+            return Some(Box::new(ScriptedValue(ExternalValue::Null)));
+        }
+        method
+    }
 
     fn on_callback_available(&self, _name: &str) {}
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn values_from_json() {
+        let values = ExternalInterfaceValues::from_json(
+            r#"{"getUserAgent": "Ruffle", "getSize": [800, 600.5], "getConfig": {"debug": true, "user": null}, "*": false}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            values.get("getUserAgent"),
+            Some(&ExternalValue::String("Ruffle".to_string()))
+        );
+        assert_eq!(
+            values.get("getSize"),
+            Some(&ExternalValue::List(vec![
+                ExternalValue::Number(800.0),
+                ExternalValue::Number(600.5)
+            ]))
+        );
+        assert_eq!(
+            values.get("getConfig"),
+            Some(&ExternalValue::Object(BTreeMap::from([
+                ("debug".to_string(), ExternalValue::Bool(true)),
+                ("user".to_string(), ExternalValue::Null)
+            ])))
+        );
+        assert_eq!(
+            values.get("anythingElse"),
+            Some(&ExternalValue::Bool(false))
+        );
+    }
+
+    #[test]
+    fn only_allowed_methods_are_called() {
+        let provider = DesktopExternalInterfaceProvider {
+            spoof_url: None,
+            values: ExternalInterfaceValues::from_json(r#"{"*": true}"#).unwrap(),
+            log_calls: false,
+            allowed_methods: Some(HashSet::from(["getUserAgent".to_string()])),
+        };
+        assert!(provider.is_allowed("getUserAgent"));
+        assert!(!provider.is_allowed("eval"));
+        assert!(DesktopExternalInterfaceProvider {
+            allowed_methods: None,
+            ..provider
+        }
+        .is_allowed("eval"));
+    }
+
+    #[test]
+    fn values_must_be_an_object() {
+        assert!(ExternalInterfaceValues::from_json("[1, 2]").is_err());
+        assert!(ExternalInterfaceValues::from_json("{").is_err());
+        assert!(ExternalInterfaceValues::from_json("{}")
+            .unwrap()
+            .get("anything")
+            .is_none());
+    }
+}
@@ -1,26 +1,41 @@
-use crate::custom_event::RuffleEvent;
+use crate::cli::QuitMode;
+use crate::custom_event::{RuffleEvent, RuffleEventProxy};
+use crate::util::borderless_fullscreen;
 
 use ruffle_core::external::FsCommandProvider;
 use std::rc::Rc;
-use winit::event_loop::EventLoopProxy;
-use winit::window::{Fullscreen, Window};
+use winit::window::Window;
 
 pub struct DesktopFSCommandProvider {
-    pub event_loop: EventLoopProxy<RuffleEvent>,
+    pub event_loop: RuffleEventProxy,
     pub window: Rc<Window>,
+    pub fullscreen_monitor: Option<usize>,
+    pub quit_mode: QuitMode,
 }
 
 impl FsCommandProvider for DesktopFSCommandProvider {
     fn on_fs_command(&self, command: &str, args: &str) -> bool {
         match command {
             "quit" => {
-                let _ = self.event_loop.send_event(RuffleEvent::ExitRequested);
+                let event = match self.quit_mode {
+                    QuitMode::Exit => RuffleEvent::ExitRequested,
+                    QuitMode::Close => RuffleEvent::CloseFile,
+                    QuitMode::Ask => RuffleEvent::MovieAskedToQuit,
+                    QuitMode::Ignore => {
+                        tracing::info!(
+                            "Ignoring fscommand(\"quit\"), as requested by --fscommand-quit"
+                        );
+                        return true;
+                    }
+                };
+                let _ = self.event_loop.send_event(event);
             }
             "fullscreen" => {
                 match args {
-                    "true" => self
-                        .window
-                        .set_fullscreen(Some(Fullscreen::Borderless(None))),
+                    "true" => self.window.set_fullscreen(Some(borderless_fullscreen(
+                        &self.window,
+                        self.fullscreen_monitor,
+                    ))),
                     "false" => self.window.set_fullscreen(None),
                     _ => {}
                 };
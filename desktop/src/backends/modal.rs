@@ -0,0 +1,150 @@
+use crate::custom_event::{RuffleEvent, RuffleEventProxy};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Keeps the movie paused for as long as it's alive, while a modal dialog is shown over it.
+///
+/// Dialogs may be nested, so the movie only resumes once every guard has been dropped,
+/// and only if it was playing before the first one was created.
+pub struct ModalDialogGuard {
+    event_loop: RuffleEventProxy,
+    blocked_time: Option<BlockedTime>,
+}
+
+impl ModalDialogGuard {
+    /// Guards a dialog shown asynchronously, while the event loop keeps running to pause the movie.
+    pub fn new(event_loop: &RuffleEventProxy) -> Self {
+        let _ = event_loop.send_event(RuffleEvent::ModalDialogOpened);
+        Self {
+            event_loop: event_loop.clone(),
+            blocked_time: None,
+        }
+    }
+
+    /// Guards a dialog that blocks the event loop until it's closed.
+    ///
+    /// The movie can't be paused before such a dialog is shown, so the time it's shown for
+    /// is taken out of the movie's frame timer instead, rather than being caught up on.
+    pub fn blocking(event_loop: &RuffleEventProxy, blocked_time: &BlockedTime) -> Self {
+        blocked_time.start();
+        let mut guard = Self::new(event_loop);
+        guard.blocked_time = Some(blocked_time.clone());
+        guard
+    }
+}
+
+impl Drop for ModalDialogGuard {
+    fn drop(&mut self) {
+        if let Some(blocked_time) = &self.blocked_time {
+            blocked_time.stop();
+        }
+        let _ = self.event_loop.send_event(RuffleEvent::ModalDialogClosed);
+    }
+}
+
+/// How long blocking modal dialogs were shown over a movie, which it shouldn't catch up on.
+#[derive(Clone, Default)]
+pub struct BlockedTime(Arc<Mutex<BlockedTimeState>>);
+
+#[derive(Default)]
+struct BlockedTimeState {
+    /// How many blocking dialogs are currently shown, as they may be nested.
+    depth: usize,
+    /// When the outermost dialog currently shown was opened.
+    since: Option<Instant>,
+    total: Duration,
+}
+
+impl BlockedTime {
+    fn start(&self) {
+        let mut state = self.0.lock().expect("Blocked time is not reentrant");
+        if state.depth == 0 {
+            state.since = Some(Instant::now());
+        }
+        state.depth += 1;
+    }
+
+    fn stop(&self) {
+        let mut state = self.0.lock().expect("Blocked time is not reentrant");
+        state.depth = state.depth.saturating_sub(1);
+        if state.depth == 0 {
+            if let Some(since) = state.since.take() {
+                state.total += since.elapsed();
+            }
+        }
+    }
+
+    /// Takes how long dialogs were shown for since this was last called.
+    pub fn take(&self) -> Duration {
+        std::mem::take(&mut self.0.lock().expect("Blocked time is not reentrant").total)
+    }
+}
+
+/// Counts the modal dialogs shown over a movie, so that it's paused while there are any.
+#[derive(Debug, Default)]
+pub struct ModalDialogs {
+    open: usize,
+    /// Set when the movie was playing as the first dialog opened, and should resume
+    /// once they're all closed.
+    resume_after: bool,
+}
+
+impl ModalDialogs {
+    /// Counts a dialog as opened, returning whether the movie should be paused for it.
+    pub fn opened(&mut self, is_playing: bool) -> bool {
+        self.open += 1;
+        if self.open > 1 {
+            return false;
+        }
+        self.resume_after = is_playing;
+        true
+    }
+
+    /// Counts a dialog as closed, returning whether the movie should resume now.
+    pub fn closed(&mut self) -> bool {
+        // The player may have been recreated while a dialog was shown.
+        if self.open == 0 {
+            return false;
+        }
+        self.open -= 1;
+        self.open == 0 && std::mem::take(&mut self.resume_after)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resumes_once_every_dialog_is_closed() {
+        let mut dialogs = ModalDialogs::default();
+        assert!(dialogs.opened(true));
+        assert!(!dialogs.opened(false));
+        assert!(!dialogs.closed());
+        assert!(dialogs.closed());
+        // A close without an open, such as for a previous player, does nothing.
+        assert!(!dialogs.closed());
+    }
+
+    #[test]
+    fn stays_paused_if_it_was_paused() {
+        let mut dialogs = ModalDialogs::default();
+        assert!(dialogs.opened(false));
+        assert!(!dialogs.closed());
+        assert!(dialogs.opened(true));
+        assert!(dialogs.closed());
+    }
+
+    #[test]
+    fn nested_blocking_time_is_counted_once() {
+        let blocked_time = BlockedTime::default();
+        blocked_time.start();
+        blocked_time.start();
+        std::thread::sleep(Duration::from_millis(10));
+        blocked_time.stop();
+        assert_eq!(blocked_time.take(), Duration::ZERO);
+        blocked_time.stop();
+        assert!(blocked_time.take() >= Duration::from_millis(10));
+        assert_eq!(blocked_time.take(), Duration::ZERO);
+    }
+}
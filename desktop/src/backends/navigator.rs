@@ -1,3 +1,6 @@
+use super::modal::{BlockedTime, ModalDialogGuard};
+use crate::custom_event::{RuffleEvent, RuffleEventProxy};
+use crate::preferences::GlobalPreferences;
 use rfd::{AsyncMessageDialog, MessageButtons, MessageDialog, MessageDialogResult, MessageLevel};
 use ruffle_frontend_utils::backends::navigator::NavigatorInterface;
 use std::fs::File;
@@ -7,19 +10,129 @@ use std::path::Path;
 use url::Url;
 
 #[derive(Clone)]
-pub struct RfdNavigatorInterface;
+pub struct RfdNavigatorInterface {
+    event_loop: RuffleEventProxy,
+    /// How long the dialogs shown here blocked the movie.
+    blocked_time: BlockedTime,
+    preferences: GlobalPreferences,
+}
 
-impl NavigatorInterface for RfdNavigatorInterface {
-    fn confirm_website_navigation(&self, url: &Url) -> bool {
-        let message = format!("The SWF file wants to open the website {}", url);
-        // TODO: Add a checkbox with a GUI toolkit
-        MessageDialog::new()
+impl RfdNavigatorInterface {
+    pub fn new(
+        event_loop: RuffleEventProxy,
+        blocked_time: BlockedTime,
+        preferences: GlobalPreferences,
+    ) -> Self {
+        Self {
+            event_loop,
+            blocked_time,
+            preferences,
+        }
+    }
+}
+
+/// Whether the movie may open the given website, asking the user unless they already chose
+/// to always or never open it.
+pub fn confirm_open_url(
+    preferences: &GlobalPreferences,
+    event_loop: &RuffleEventProxy,
+    blocked_time: &BlockedTime,
+    url: &Url,
+) -> bool {
+    remembered_open_url_or(preferences, url, || {
+        ask_open_url(preferences, event_loop, blocked_time, url)
+    })
+}
+
+/// The choice remembered for the given website, or else what `ask` answers.
+fn remembered_open_url_or(
+    preferences: &GlobalPreferences,
+    url: &Url,
+    ask: impl FnOnce() -> bool,
+) -> bool {
+    preferences.remembered_open_url(url).unwrap_or_else(ask)
+}
+
+/// Asks whether the movie may open the given website, letting the user remember their choice.
+fn ask_open_url(
+    preferences: &GlobalPreferences,
+    event_loop: &RuffleEventProxy,
+    blocked_time: &BlockedTime,
+    url: &Url,
+) -> bool {
+    const OPEN: &str = "Open";
+    const REMEMBER: &str = "Remember My Choice...";
+    const DONT_OPEN: &str = "Don't Open";
+
+    let message = format!("The SWF file wants to open the website {}", url);
+    // TODO: Add a checkbox with a GUI toolkit
+    let _guard = ModalDialogGuard::blocking(event_loop, blocked_time);
+    let Some(host) = url.host_str() else {
+        // Choices are remembered per host, so there's nothing to remember here.
+        return MessageDialog::new()
             .set_title("Open website?")
             .set_level(MessageLevel::Info)
             .set_description(message)
             .set_buttons(MessageButtons::OkCancel)
             .show()
-            == MessageDialogResult::Ok
+            == MessageDialogResult::Ok;
+    };
+    let result = MessageDialog::new()
+        .set_title("Open website?")
+        .set_level(MessageLevel::Info)
+        .set_description(message)
+        .set_buttons(MessageButtons::YesNoCancelCustom(
+            OPEN.to_string(),
+            REMEMBER.to_string(),
+            DONT_OPEN.to_string(),
+        ))
+        .show();
+    match result {
+        MessageDialogResult::Yes => true,
+        MessageDialogResult::Custom(button) if button == OPEN => true,
+        MessageDialogResult::No => remember_open_url(preferences, host),
+        MessageDialogResult::Custom(button) if button == REMEMBER => {
+            remember_open_url(preferences, host)
+        }
+        _ => false,
+    }
+}
+
+/// Asks whether the websites of `host` should always or never be opened, and remembers it.
+fn remember_open_url(preferences: &GlobalPreferences, host: &str) -> bool {
+    let always = format!("Always Open {host}");
+    let never = format!("Never Open {host}");
+    let result = MessageDialog::new()
+        .set_title("Remember for this website?")
+        .set_level(MessageLevel::Info)
+        .set_description(format!(
+            "Choose whether movies may open the websites of {host} from now on, without asking. \
+            This can be undone in the preferences."
+        ))
+        .set_buttons(MessageButtons::YesNoCancelCustom(
+            always.clone(),
+            never.clone(),
+            "Cancel".to_string(),
+        ))
+        .show();
+    let allow = match result {
+        MessageDialogResult::Yes => true,
+        MessageDialogResult::Custom(button) if button == always => true,
+        MessageDialogResult::No => false,
+        MessageDialogResult::Custom(button) if button == never => false,
+        _ => return false,
+    };
+    if let Err(e) =
+        preferences.write_preferences(|writer| writer.remember_open_url_host(host, allow))
+    {
+        tracing::warn!("Couldn't remember the choice for {host}: {e}");
+    }
+    allow
+}
+
+impl NavigatorInterface for RfdNavigatorInterface {
+    fn confirm_website_navigation(&self, url: &Url) -> bool {
+        confirm_open_url(&self.preferences, &self.event_loop, &self.blocked_time, url)
     }
 
     fn open_file(&self, path: &Path) -> io::Result<File> {
@@ -29,6 +142,8 @@ impl NavigatorInterface for RfdNavigatorInterface {
                 let parent_path = path.parent().unwrap_or(path);
 
                 if e.kind() == ErrorKind::PermissionDenied {
+                    let _guard =
+                        ModalDialogGuard::blocking(&self.event_loop, &self.blocked_time);
                     let attempt_sandbox_open = MessageDialog::new()
                         .set_level(MessageLevel::Warning)
                         .set_description(format!("The current movie is attempting to read files stored in {parent_path:?}.\n\nTo allow it to do so, click Yes, and then Open to grant read access to that directory.\n\nOtherwise, click No to deny access."))
@@ -48,8 +163,78 @@ impl NavigatorInterface for RfdNavigatorInterface {
     }
 
     async fn confirm_socket(&self, host: &str, port: u16) -> bool {
+        let _guard = ModalDialogGuard::new(&self.event_loop);
         AsyncMessageDialog::new().set_level(MessageLevel::Warning).set_description(format!("The current movie is attempting to connect to {:?} (port {}).\n\nTo allow it to do so, click Yes to grant network access to that host.\n\nOtherwise, click No to deny access.", host, port)).set_buttons(MessageButtons::YesNo)
             .show()
             .await == MessageDialogResult::Yes
     }
+
+    async fn confirm_large_file(&self, url: &Url, size: u64) -> bool {
+        let _guard = ModalDialogGuard::new(&self.event_loop);
+        AsyncMessageDialog::new()
+            .set_level(MessageLevel::Warning)
+            .set_description(format!(
+                "{url} is {:.1} MB large, which may take a long time to load.\n\nClick Yes to load it anyway, or No to skip it.",
+                size as f64 / (1024.0 * 1024.0)
+            ))
+            .set_buttons(MessageButtons::YesNo)
+            .show()
+            .await
+            == MessageDialogResult::Yes
+    }
+
+    fn navigate_within_bundle(&self, url: &Url) {
+        let _ = self
+            .event_loop
+            .send_event(RuffleEvent::NavigateWithinBundle(url.clone()));
+    }
+
+    fn navigate_in_place(&self, url: &Url) {
+        let _ = self
+            .event_loop
+            .send_event(RuffleEvent::NavigateInPlace(url.clone()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::Opt;
+    use clap::Parser;
+    use std::ffi::OsStr;
+
+    #[test]
+    fn remembered_choice_skips_the_prompt() {
+        let directory = tempfile::tempdir().expect("Must be able to create a temporary directory");
+        let opt = Opt::parse_from([
+            OsStr::new("ruffle"),
+            OsStr::new("--config"),
+            directory.path().as_os_str(),
+        ]);
+        let preferences = GlobalPreferences::load(opt).expect("Preferences should load");
+        preferences
+            .write_preferences(|writer| {
+                writer.remember_open_url_host("allowed.example", true);
+                writer.remember_open_url_host("denied.example", false);
+            })
+            .expect("Preferences should be written");
+
+        let url = |url: &str| Url::parse(url).expect("URL should be valid");
+        let no_prompt = || -> bool { panic!("A remembered choice shouldn't prompt") };
+        assert!(remembered_open_url_or(
+            &preferences,
+            &url("https://allowed.example/page"),
+            no_prompt
+        ));
+        assert!(!remembered_open_url_or(
+            &preferences,
+            &url("https://denied.example/page"),
+            no_prompt
+        ));
+        assert!(remembered_open_url_or(
+            &preferences,
+            &url("https://other.example/page"),
+            || true
+        ));
+    }
 }
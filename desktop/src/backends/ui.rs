@@ -1,4 +1,9 @@
+use super::modal::{BlockedTime, ModalDialogGuard};
+use super::navigator::confirm_open_url;
+use crate::cli::ScriptTimeoutMode;
+use crate::custom_event::{RuffleEvent, RuffleEventProxy};
 use crate::preferences::GlobalPreferences;
+use crate::util::borderless_fullscreen;
 use anyhow::Error;
 use chrono::{DateTime, Utc};
 use egui_winit::clipboard::Clipboard;
@@ -9,13 +14,53 @@ use rfd::{
 use ruffle_core::backend::navigator::OpenURLMode;
 use ruffle_core::backend::ui::{
     DialogLoaderError, DialogResultFuture, FileDialogResult, FileFilter, FontDefinition,
-    FullscreenError, LanguageIdentifier, MouseCursor, UiBackend,
+    FullscreenError, LanguageIdentifier, MouseCursor, ScriptTimeoutAction, UiBackend,
+    UnsupportedVideoReason,
 };
+use ruffle_core::swf::VideoCodec;
+use ruffle_frontend_utils::bundle::Bundle;
+use std::cell::{OnceCell, RefCell};
 use std::rc::Rc;
+use std::str::FromStr;
 use tracing::error;
 use url::Url;
 use winit::raw_window_handle::HasDisplayHandle;
-use winit::window::{Fullscreen, Window};
+use winit::window::Window;
+
+/// How to respond when a movie asks to go fullscreen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FullscreenRequests {
+    /// Go fullscreen straight away.
+    #[default]
+    Allow,
+    /// Ignore the request, and keep the movie in its window.
+    Deny,
+    /// Ask the user every time.
+    Ask,
+}
+
+impl FromStr for FullscreenRequests {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "allow" => Ok(FullscreenRequests::Allow),
+            "deny" => Ok(FullscreenRequests::Deny),
+            "ask" => Ok(FullscreenRequests::Ask),
+            _ => Err(()),
+        }
+    }
+}
+
+impl FullscreenRequests {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FullscreenRequests::Allow => "allow",
+            FullscreenRequests::Deny => "deny",
+            FullscreenRequests::Ask => "ask",
+        }
+    }
+}
 
 pub struct DesktopFileDialogResult {
     handle: Option<FileHandle>,
@@ -111,24 +156,95 @@ impl FileDialogResult for DesktopFileDialogResult {
     }
 }
 
+/// A cursor image that replaces the arrow cursor while it's over the movie.
+///
+/// winit can't set custom cursor images, so this is drawn by egui on top of everything else.
+pub struct CustomCursor {
+    image: egui::ColorImage,
+    hotspot: egui::Vec2,
+    texture: OnceCell<egui::TextureHandle>,
+}
+
+impl CustomCursor {
+    pub fn new(data: &[u8], hotspot_x: u32, hotspot_y: u32) -> Result<Self, Error> {
+        let image = image::load_from_memory(data)?.to_rgba8();
+        let size = [image.width() as usize, image.height() as usize];
+        Ok(Self {
+            image: egui::ColorImage::from_rgba_unmultiplied(size, image.as_raw()),
+            hotspot: egui::vec2(hotspot_x as f32, hotspot_y as f32),
+            texture: OnceCell::new(),
+        })
+    }
+
+    /// Loads the cursor declared in a bundle's information, if any.
+    pub fn from_bundle(bundle: &Bundle) -> Option<Self> {
+        let cursor = bundle.information().cursor.as_ref()?;
+        let result = bundle
+            .source()
+            .read_file(&cursor.image)
+            .map_err(Error::from)
+            .and_then(|data| Self::new(&data, cursor.hotspot_x, cursor.hotspot_y));
+        match result {
+            Ok(cursor) => Some(cursor),
+            Err(e) => {
+                tracing::warn!("Couldn't load bundle cursor {}: {e}", cursor.image);
+                None
+            }
+        }
+    }
+
+    /// Draws the cursor so that its hotspot is at `pos`.
+    pub fn paint(&self, context: &egui::Context, pos: egui::Pos2) {
+        let texture = self.texture.get_or_init(|| {
+            context.load_texture("custom_cursor", self.image.clone(), Default::default())
+        });
+        let rect = egui::Rect::from_min_size(pos - self.hotspot, texture.size_vec2());
+        context
+            .layer_painter(egui::LayerId::new(
+                egui::Order::Tooltip,
+                egui::Id::new("custom_cursor"),
+            ))
+            .image(
+                texture.id(),
+                rect,
+                egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
+                egui::Color32::WHITE,
+            );
+    }
+}
+
 pub struct DesktopUiBackend {
     window: Rc<Window>,
+    event_loop: RuffleEventProxy,
+    /// How long the dialogs shown here blocked the movie.
+    blocked_time: BlockedTime,
     cursor_visible: bool,
     clipboard: Clipboard,
     preferences: GlobalPreferences,
     preferred_cursor: MouseCursor,
+    /// Is the movie currently drawing its own cursor
+    custom_cursor_active: bool,
+    /// Image to use instead of the arrow cursor, if the content provided one
+    custom_cursor: Option<CustomCursor>,
     open_url_mode: OpenURLMode,
     font_database: Rc<fontdb::Database>,
     /// Is a dialog currently open
     dialog_open: bool,
+    /// Video codecs which the user has already been told can't be played
+    reported_video_codecs: RefCell<Vec<VideoCodec>>,
+    /// Is the user changing fullscreen, rather than the movie
+    user_fullscreen_request: bool,
 }
 
 impl DesktopUiBackend {
     pub fn new(
         window: Rc<Window>,
+        event_loop: RuffleEventProxy,
+        blocked_time: BlockedTime,
         open_url_mode: OpenURLMode,
         font_database: Rc<fontdb::Database>,
         preferences: GlobalPreferences,
+        custom_cursor: Option<CustomCursor>,
     ) -> Result<Self, Error> {
         // The window handle is only relevant to linux/wayland
         // If it fails it'll fallback to x11 or wlr-data-control
@@ -141,18 +257,65 @@ impl DesktopUiBackend {
         );
         Ok(Self {
             window,
+            event_loop,
+            blocked_time,
             cursor_visible: true,
             clipboard,
             preferences,
             preferred_cursor: MouseCursor::Arrow,
+            custom_cursor_active: false,
+            custom_cursor,
             open_url_mode,
             dialog_open: false,
             font_database,
+            reported_video_codecs: RefCell::default(),
+            user_fullscreen_request: false,
         })
     }
 
+    /// Marks the fullscreen changes that follow as asked for by the user,
+    /// which are always allowed regardless of [`FullscreenRequests`].
+    pub fn set_user_fullscreen_request(&mut self, user_fullscreen_request: bool) {
+        self.user_fullscreen_request = user_fullscreen_request;
+    }
+
+    /// Whether the movie may go fullscreen, according to the preferences.
+    fn allow_fullscreen_request(&self) -> bool {
+        match self.preferences.fullscreen_requests() {
+            FullscreenRequests::Allow => true,
+            FullscreenRequests::Deny => false,
+            FullscreenRequests::Ask => {
+                let _guard = ModalDialogGuard::blocking(&self.event_loop, &self.blocked_time);
+                MessageDialog::new()
+                    .set_level(MessageLevel::Info)
+                    .set_title("Ruffle - Fullscreen")
+                    .set_description("The movie wants to go fullscreen. Allow it?")
+                    .set_buttons(MessageButtons::YesNo)
+                    .show()
+                    == MessageDialogResult::Yes
+            }
+        }
+    }
+
     pub fn cursor(&self) -> egui::CursorIcon {
-        if self.cursor_visible {
+        if self.custom_cursor().is_some() {
+            egui::CursorIcon::None
+        } else {
+            self.system_cursor()
+        }
+    }
+
+    /// The custom cursor image to draw, if it should currently replace the arrow cursor.
+    pub fn custom_cursor(&self) -> Option<&CustomCursor> {
+        self.custom_cursor
+            .as_ref()
+            .filter(|_| self.system_cursor() == egui::CursorIcon::Default)
+    }
+
+    fn system_cursor(&self) -> egui::CursorIcon {
+        let hide_cursor = self.preferences.cli.hide_cursor
+            || (self.custom_cursor_active && self.preferences.auto_hide_cursor());
+        if self.cursor_visible && !hide_cursor {
             match self.preferred_cursor {
                 MouseCursor::Arrow => egui::CursorIcon::Default,
                 MouseCursor::Hand => egui::CursorIcon::PointingHand,
@@ -163,9 +326,55 @@ impl DesktopUiBackend {
             egui::CursorIcon::None
         }
     }
+
+    /// Asks the user what to do about a script that is taking too long.
+    ///
+    /// The script can't go on while the player is locked for it anyway, so the dialog blocks it
+    /// until the user answers, and the time it's shown for isn't caught up on afterwards.
+    fn ask_script_timeout(&self) -> ScriptTimeoutAction {
+        let guard = ModalDialogGuard::blocking(&self.event_loop, &self.blocked_time);
+        let result = MessageDialog::new()
+            .set_level(MessageLevel::Warning)
+            .set_title("Ruffle - Script not responding")
+            .set_description(SCRIPT_TIMEOUT_MESSAGE)
+            .set_buttons(MessageButtons::YesNoCancelCustom(
+                SCRIPT_TIMEOUT_WAIT.to_string(),
+                SCRIPT_TIMEOUT_STOP.to_string(),
+                SCRIPT_TIMEOUT_CLOSE.to_string(),
+            ))
+            .show();
+        drop(guard);
+        let action = script_timeout_action(result);
+        if action == ScriptTimeoutAction::Halt {
+            let _ = self.event_loop.send_event(RuffleEvent::CloseFile);
+        }
+        action
+    }
+}
+
+const SCRIPT_TIMEOUT_WAIT: &str = "Wait";
+const SCRIPT_TIMEOUT_STOP: &str = "Stop Script";
+const SCRIPT_TIMEOUT_CLOSE: &str = "Close Movie";
+
+/// What the user chose to do about a script that is taking too long.
+/// Closing the dialog any other way closes the movie, as it may not be responding at all.
+fn script_timeout_action(result: MessageDialogResult) -> ScriptTimeoutAction {
+    match result {
+        MessageDialogResult::Yes => ScriptTimeoutAction::Continue,
+        MessageDialogResult::No => ScriptTimeoutAction::Abort,
+        MessageDialogResult::Custom(button) if button == SCRIPT_TIMEOUT_WAIT => {
+            ScriptTimeoutAction::Continue
+        }
+        MessageDialogResult::Custom(button) if button == SCRIPT_TIMEOUT_STOP => {
+            ScriptTimeoutAction::Abort
+        }
+        _ => ScriptTimeoutAction::Halt,
+    }
 }
 
 const DOWNLOAD_FAILED_MESSAGE: &str = "Ruffle failed to open or download this file.";
+const SCRIPT_TIMEOUT_MESSAGE: &str = "A script in this movie is taking a long time to run. \
+    You can wait for it to finish, stop it and keep playing the movie, or close the movie.";
 
 impl UiBackend for DesktopUiBackend {
     fn mouse_visible(&self) -> bool {
@@ -180,6 +389,10 @@ impl UiBackend for DesktopUiBackend {
         self.preferred_cursor = cursor;
     }
 
+    fn set_custom_cursor_active(&mut self, active: bool) {
+        self.custom_cursor_active = active;
+    }
+
     fn clipboard_content(&mut self) -> String {
         self.clipboard.get().unwrap_or_default()
     }
@@ -189,8 +402,16 @@ impl UiBackend for DesktopUiBackend {
     }
 
     fn set_fullscreen(&mut self, is_full: bool) -> Result<(), FullscreenError> {
+        // Leaving fullscreen is always allowed, so that the movie can't keep the user stuck in it.
+        if is_full && !self.user_fullscreen_request && !self.allow_fullscreen_request() {
+            tracing::info!("Denied the movie's request to go fullscreen");
+            return Err("Fullscreen was denied".into());
+        }
         self.window.set_fullscreen(if is_full {
-            Some(Fullscreen::Borderless(None))
+            Some(borderless_fullscreen(
+                &self.window,
+                self.preferences.cli.fullscreen_monitor,
+            ))
         } else {
             None
         });
@@ -198,6 +419,7 @@ impl UiBackend for DesktopUiBackend {
     }
 
     fn display_root_movie_download_failed_message(&self, _invalid_swf: bool) {
+        let _guard = ModalDialogGuard::blocking(&self.event_loop, &self.blocked_time);
         let dialog = MessageDialog::new()
             .set_level(MessageLevel::Warning)
             .set_title("Ruffle - Load failed")
@@ -207,6 +429,7 @@ impl UiBackend for DesktopUiBackend {
     }
 
     fn message(&self, message: &str) {
+        let _guard = ModalDialogGuard::blocking(&self.event_loop, &self.blocked_time);
         let dialog = MessageDialog::new()
             .set_level(MessageLevel::Info)
             .set_title("Ruffle")
@@ -215,6 +438,35 @@ impl UiBackend for DesktopUiBackend {
         dialog.show();
     }
 
+    fn script_timeout(&mut self) -> ScriptTimeoutAction {
+        let action = match self.preferences.cli.script_timeout {
+            ScriptTimeoutMode::Halt => ScriptTimeoutAction::Halt,
+            ScriptTimeoutMode::Abort => ScriptTimeoutAction::Abort,
+            ScriptTimeoutMode::Ask => return self.ask_script_timeout(),
+        };
+        // The user wasn't asked, so let them know why the movie stopped responding.
+        let _ = self
+            .event_loop
+            .send_event(RuffleEvent::ScriptTimeout(action));
+        action
+    }
+
+    fn display_unsupported_compression(&self, compression: ruffle_core::swf::Compression) {
+        let _ = self.event_loop.send_event(RuffleEvent::LoadError(format!(
+            "This movie is {compression} compressed, which isn't supported by this build of Ruffle."
+        )));
+    }
+
+    fn display_unsupported_video_codec(&self, codec: VideoCodec, reason: UnsupportedVideoReason) {
+        let mut reported = self.reported_video_codecs.borrow_mut();
+        if !reported.contains(&codec) {
+            reported.push(codec);
+            let _ = self
+                .event_loop
+                .send_event(RuffleEvent::UnsupportedVideo(codec, reason));
+        }
+    }
+
     fn display_unsupported_video(&self, url: Url) {
         if url.scheme() == "javascript" {
             tracing::warn!(
@@ -224,16 +476,12 @@ impl UiBackend for DesktopUiBackend {
         }
 
         if self.open_url_mode == OpenURLMode::Confirm {
-            let message = format!("The SWF file wants to open the website {}", url);
-            // TODO: Add a checkbox with a GUI toolkit
-            let confirm = MessageDialog::new()
-                .set_title("Open website?")
-                .set_level(MessageLevel::Info)
-                .set_description(message)
-                .set_buttons(MessageButtons::OkCancel)
-                .show()
-                == MessageDialogResult::Ok;
-            if !confirm {
+            if !confirm_open_url(
+                &self.preferences,
+                &self.event_loop,
+                &self.blocked_time,
+                &url,
+            ) {
                 tracing::info!("SWF tried to open a website, but the user declined the request");
                 return;
             }
@@ -376,3 +624,38 @@ impl UiBackend for DesktopUiBackend {
         self.dialog_open = false;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn script_timeout_buttons() {
+        assert_eq!(
+            script_timeout_action(MessageDialogResult::Yes),
+            ScriptTimeoutAction::Continue
+        );
+        assert_eq!(
+            script_timeout_action(MessageDialogResult::No),
+            ScriptTimeoutAction::Abort
+        );
+        assert_eq!(
+            script_timeout_action(MessageDialogResult::Custom(SCRIPT_TIMEOUT_WAIT.to_string())),
+            ScriptTimeoutAction::Continue
+        );
+        assert_eq!(
+            script_timeout_action(MessageDialogResult::Custom(SCRIPT_TIMEOUT_STOP.to_string())),
+            ScriptTimeoutAction::Abort
+        );
+        assert_eq!(
+            script_timeout_action(MessageDialogResult::Custom(
+                SCRIPT_TIMEOUT_CLOSE.to_string()
+            )),
+            ScriptTimeoutAction::Halt
+        );
+        assert_eq!(
+            script_timeout_action(MessageDialogResult::Cancel),
+            ScriptTimeoutAction::Halt
+        );
+    }
+}
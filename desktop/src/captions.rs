@@ -0,0 +1,162 @@
+//! Captions loaded from an SRT or WebVTT sidecar file, shown over the movie in time with it.
+
+use anyhow::{anyhow, Context, Error};
+use std::path::Path;
+use std::time::Duration;
+
+/// A single caption, shown between two points in time.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cue {
+    pub start: Duration,
+    pub end: Duration,
+    pub text: String,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct Captions {
+    cues: Vec<Cue>,
+}
+
+impl Captions {
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Couldn't read captions from {}", path.display()))?;
+        let captions = Self::parse(&contents);
+        if captions.cues.is_empty() {
+            return Err(anyhow!("{} doesn't contain any captions", path.display()));
+        }
+        Ok(captions)
+    }
+
+    /// Parses the cues of an SRT or WebVTT file, skipping anything that isn't one.
+    ///
+    /// Both formats are made of blocks separated by blank lines, where a cue is any block
+    /// with a `start --> end` timing line followed by its text.
+    pub fn parse(input: &str) -> Self {
+        let input = input.trim_start_matches('\u{feff}').replace("\r\n", "\n");
+        let mut cues = Vec::new();
+        for block in input.split("\n\n") {
+            let mut lines = block.lines().skip_while(|line| !line.contains("-->"));
+            let Some((start, end)) = lines.next().and_then(parse_timing) else {
+                continue;
+            };
+            let text = lines
+                .map(strip_tags)
+                .filter(|line| !line.trim().is_empty())
+                .collect::<Vec<_>>()
+                .join("\n");
+            if !text.is_empty() && end > start {
+                cues.push(Cue { start, end, text });
+            }
+        }
+        Self { cues }
+    }
+
+    /// The text to show at `time`, with overlapping cues shown on separate lines.
+    pub fn text_at(&self, time: Duration) -> Option<String> {
+        let text = self
+            .cues
+            .iter()
+            .filter(|cue| cue.start <= time && time < cue.end)
+            .map(|cue| cue.text.as_str())
+            .collect::<Vec<_>>();
+        (!text.is_empty()).then(|| text.join("\n"))
+    }
+}
+
+/// Parses a timing line like `00:00:01,000 --> 00:00:04,000`,
+/// ignoring any WebVTT cue settings after it.
+fn parse_timing(line: &str) -> Option<(Duration, Duration)> {
+    let (start, rest) = line.split_once("-->")?;
+    let end = rest.split_whitespace().next()?;
+    Some((parse_timestamp(start.trim())?, parse_timestamp(end)?))
+}
+
+/// Parses `hh:mm:ss,mmm` (SRT) or `[hh:]mm:ss.mmm` (WebVTT).
+fn parse_timestamp(value: &str) -> Option<Duration> {
+    let (clock, millis) = value.split_once([',', '.'])?;
+    let mut seconds = 0u64;
+    for part in clock.split(':') {
+        seconds = seconds.checked_mul(60)?.checked_add(part.parse().ok()?)?;
+    }
+    let millis: u64 = millis.parse().ok()?;
+    Some(Duration::from_secs(seconds) + Duration::from_millis(millis))
+}
+
+/// Removes formatting such as `<i>` or `<v Speaker>`, which isn't supported.
+fn strip_tags(line: &str) -> String {
+    let mut result = String::with_capacity(line.len());
+    let mut in_tag = false;
+    for c in line.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if !in_tag => result.push(c),
+            _ => {}
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cue(start: u64, end: u64, text: &str) -> Cue {
+        Cue {
+            start: Duration::from_millis(start),
+            end: Duration::from_millis(end),
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn srt() {
+        let captions = Captions::parse(
+            "1\r\n00:00:01,000 --> 00:00:04,500\r\nHello there!\r\n\r\n2\r\n00:01:05,250 --> 00:01:07,000\r\n<i>Two</i>\r\nlines\r\n",
+        );
+        assert_eq!(
+            captions.cues,
+            vec![
+                cue(1000, 4500, "Hello there!"),
+                cue(65250, 67000, "Two\nlines")
+            ]
+        );
+    }
+
+    #[test]
+    fn webvtt() {
+        let captions = Captions::parse(
+            "WEBVTT\n\nNOTE This is a comment\n\n00:01.000 --> 00:02.000 line:10%\n<v Narrator>Hello\n\nintro\n01:00:00.000 --> 01:00:01.000\nLate\n",
+        );
+        assert_eq!(
+            captions.cues,
+            vec![cue(1000, 2000, "Hello"), cue(3_600_000, 3_601_000, "Late")]
+        );
+    }
+
+    #[test]
+    fn invalid_cues_are_skipped() {
+        let captions = Captions::parse(
+            "1\nnonsense --> 00:00:01,000\nBroken\n\n2\n00:00:02,000 --> 00:00:01,000\nBackwards\n",
+        );
+        assert_eq!(captions, Captions::default());
+    }
+
+    #[test]
+    fn text_at() {
+        let captions = Captions {
+            cues: vec![cue(1000, 3000, "First"), cue(2000, 4000, "Second")],
+        };
+        assert_eq!(captions.text_at(Duration::from_millis(500)), None);
+        assert_eq!(
+            captions.text_at(Duration::from_millis(1000)),
+            Some("First".to_string())
+        );
+        assert_eq!(
+            captions.text_at(Duration::from_millis(2500)),
+            Some("First\nSecond".to_string())
+        );
+        assert_eq!(captions.text_at(Duration::from_millis(4000)), None);
+    }
+}
@@ -1,15 +1,24 @@
+use crate::backends::ExternalInterfaceValues;
+use crate::lut::ColorLut;
 use crate::preferences::storage::StorageBackend;
 use crate::RUFFLE_VERSION;
 use anyhow::{anyhow, Error};
+use chrono::{DateTime, Utc};
 use clap::{Parser, ValueEnum};
 use ruffle_core::backend::navigator::{OpenURLMode, SocketMode};
-use ruffle_core::config::Letterbox;
+use ruffle_core::config::{Letterbox, LetterboxPattern, LoopMode};
 use ruffle_core::events::{GamepadButton, KeyCode};
+use ruffle_core::swf::Color;
 use ruffle_core::{LoadBehavior, PlayerRuntime, StageAlign, StageScaleMode};
+use ruffle_frontend_utils::backends::navigator::FileRoot;
 use ruffle_render::quality::StageQuality;
 use ruffle_render_wgpu::clap::{GraphicsBackend, PowerPreference};
+use std::collections::HashSet;
+use std::num::{NonZeroU32, NonZeroUsize};
 use std::path::Path;
+use std::sync::Arc;
 use std::time::Duration;
+use unic_langid::LanguageIdentifier;
 use url::Url;
 
 fn get_default_save_directory() -> std::path::PathBuf {
@@ -38,6 +47,8 @@ pub struct Opt {
 
     /// A "flashvars" parameter to provide to the movie.
     /// This can be repeated multiple times, for example -Pkey=value -Pfoo=bar.
+    /// Parameters in the query string of the movie's URL, as in `movie.swf?key=value`,
+    /// are also given to the movie, but these take precedence over them.
     #[clap(short = 'P', action = clap::ArgAction::Append)]
     parameters: Vec<String>,
 
@@ -56,6 +67,10 @@ pub struct Opt {
     #[clap(long, short)]
     pub power: Option<PowerPreference>,
 
+    /// How to decode video in movies. `none` disables video entirely.
+    #[clap(long, value_name = "BACKEND", default_value = "software")]
+    pub video_backend: VideoBackendMode,
+
     /// Type of storage backend to use. This determines where local storage data is saved (e.g. shared objects).
     ///
     /// This option temporarily overrides any stored preference.
@@ -70,10 +85,80 @@ pub struct Opt {
     #[clap(long, display_order = 2)]
     pub height: Option<f64>,
 
+    /// Size of the window in pixels, such as `1280x720`, regardless of the size of the movie.
+    /// The movie is scaled to fit into it. Ignored in fullscreen.
+    #[clap(
+        long,
+        display_order = 3,
+        value_name = "WIDTHxHEIGHT",
+        value_parser(parse_window_size),
+        conflicts_with_all = ["width", "height"]
+    )]
+    pub window_size: Option<(f64, f64)>,
+
+    /// Position of the top left corner of the window in pixels, such as `100,50`.
+    /// The window is kept on a monitor if it would be out of sight.
+    #[clap(long, value_name = "X,Y", value_parser(parse_window_position))]
+    pub window_position: Option<(i32, i32)>,
+
+    /// Center the window on the current monitor.
+    #[clap(long, conflicts_with = "window_position")]
+    pub center_window: bool,
+
+    /// Scale factor to render the movie with, instead of the one of the display.
+    /// For example, 1 renders the movie as if on a display without high DPI scaling.
+    #[clap(long, value_name = "FACTOR", value_parser(parse_positive_f64))]
+    pub ui_scale: Option<f64>,
+
+    /// Render the movie at this many times the resolution of the window, then stretch it over
+    /// the window. Below 1 renders faster, such as on a weak GPU or for a pixelated look,
+    /// and above 1 supersamples the movie. This is independent of the quality.
+    #[clap(
+        long,
+        value_name = "FACTOR",
+        default_value = "1",
+        value_parser(parse_positive_f64)
+    )]
+    pub render_scale: f64,
+
+    /// How the movie is stretched over the window when it's rendered at another resolution,
+    /// such as with --render-scale or when zoomed in.
+    #[clap(long, default_value = "linear")]
+    pub upscale_filter: UpscaleFilter,
+
     /// Maximum number of seconds a script can run before scripting is disabled.
     #[clap(long, short, value_parser(parse_duration_seconds))]
     pub max_execution_duration: Option<Duration>,
 
+    /// What to do when a script runs for longer than the maximum execution duration.
+    #[clap(long, default_value = "halt")]
+    pub script_timeout: ScriptTimeoutMode,
+
+    /// What to do when the movie asks to quit with `fscommand("quit")`.
+    #[clap(long, default_value = "exit")]
+    pub fscommand_quit: QuitMode,
+
+    /// Maximum amount of memory (in megabytes) a movie may allocate before it is halted.
+    /// By default there is no limit.
+    #[clap(long, value_parser(parse_megabytes::<usize>))]
+    pub max_memory: Option<usize>,
+
+    /// Run a full garbage collection every this many seconds, to keep the memory use of movies
+    /// which are left running for a long time from growing. This can pause the movie briefly,
+    /// so by default garbage is only collected bit by bit as the movie plays.
+    #[clap(long, value_name = "SECONDS", value_parser(parse_duration_seconds))]
+    pub auto_gc: Option<Duration>,
+
+    /// Number of seconds to wait for the movie to show its first frame once it has been downloaded,
+    /// before asking whether to keep waiting or close it. 0 waits forever.
+    #[clap(
+        long,
+        value_name = "SECONDS",
+        default_value = "60",
+        value_parser(parse_duration_seconds)
+    )]
+    pub first_frame_timeout: Duration,
+
     /// Base directory or URL used to resolve all relative path statements in the SWF file.
     /// The default is the current directory.
     #[clap(long)]
@@ -99,6 +184,25 @@ pub struct Opt {
     #[clap(long, short)]
     pub volume: Option<f32>,
 
+    /// Start every movie muted, and unmute it on the first click or key press,
+    /// like the autoplay policy of a browser.
+    #[clap(long, action)]
+    pub mute_on_start_until_click: bool,
+
+    /// Wait for a click on the movie before starting it, instead of playing it right away.
+    #[clap(long, action)]
+    pub no_autoplay: bool,
+
+    /// Whether to keep the screen from going to sleep while a movie plays in a focused window.
+    /// This overrides the preference, which is on by default.
+    #[clap(long, value_name = "BOOL", num_args = 0..=1, default_missing_value = "true")]
+    pub keep_awake: Option<bool>,
+
+    /// Whether to keep track of how long each movie is played, only on this computer.
+    /// This overrides the preference, which is off by default. See --dump-stats.
+    #[clap(long, value_name = "BOOL", num_args = 0..=1, default_missing_value = "true")]
+    pub play_stats: Option<bool>,
+
     /// Prevent movies from changing the stage scale mode.
     #[clap(long, action)]
     pub force_scale: bool,
@@ -118,6 +222,47 @@ pub struct Opt {
     #[clap(long, default_value_os_t=get_default_config_directory())]
     pub config: std::path::PathBuf,
 
+    /// Use a separate set of preferences, recents and saves, kept under the given name.
+    /// Without this, the default profile is used.
+    #[clap(long, value_name = "NAME", value_parser(parse_profile_name))]
+    pub profile: Option<String>,
+
+    /// Log more information, which helps when reporting problems.
+    /// Give it once for info, twice for debug and three times for trace messages.
+    ///
+    /// The RUST_LOG environment variable takes precedence over this when it's set.
+    /// (-v is taken by --volume, so only the long form exists.)
+    #[clap(long, action = clap::ArgAction::Count, conflicts_with = "quiet")]
+    pub verbose: u8,
+
+    /// Only log errors.
+    ///
+    /// The RUST_LOG environment variable takes precedence over this when it's set.
+    /// (-q is taken by --quality, so only the long form exists.)
+    #[clap(long)]
+    pub quiet: bool,
+
+    /// Also write the log to this file, for example to attach it to a bug report.
+    /// Credentials such as proxy passwords and cookies are redacted from it.
+    #[clap(long, value_name = "PATH")]
+    pub log_file: Option<std::path::PathBuf>,
+
+    /// Size (in megabytes) the --log-file may reach before it's rotated.
+    /// The previous logs are kept next to it, as <PATH>.1 to <PATH>.3.
+    #[clap(
+        long,
+        default_value = "10",
+        value_parser(parse_megabytes::<usize>),
+        requires = "log_file"
+    )]
+    pub log_file_max_size: usize,
+
+    /// What to write to the --log-file, in the same format as the RUST_LOG environment variable
+    /// (for example `debug` or `warn,ruffle=trace`).
+    /// Defaults to the same as the console.
+    #[clap(long, value_name = "FILTER", requires = "log_file")]
+    pub log_file_filter: Option<String>,
+
     /// Proxy to use when loading movies via URL.
     #[clap(long)]
     pub proxy: Option<Url>,
@@ -130,6 +275,63 @@ pub struct Opt {
     #[clap(long = "tcp-connections")]
     pub tcp_connections: Option<SocketMode>,
 
+    /// Ignore the network restrictions that a bundle asks for, letting it reach any host.
+    #[clap(long)]
+    pub ignore_bundle_network_policy: bool,
+
+    /// Log every file the movie loads, with the URL it resolved to, its status and its size.
+    /// The log can be filtered on its own with `ruffle_network=info`.
+    #[clap(long)]
+    pub log_requests: bool,
+
+    /// Log a summary of what each movie loaded once it's closed: how many requests it made,
+    /// how many of them failed, how many bytes it loaded and how many sockets it connected.
+    #[clap(long)]
+    pub network_stats: bool,
+
+    /// The most requests the movie may make over the network at once. Any others wait
+    /// until one of them has received its response. By default there's no limit.
+    #[clap(long, value_name = "COUNT")]
+    pub max_connections: Option<NonZeroUsize>,
+
+    /// Ask before loading any file larger than this many megabytes, whether it's the movie
+    /// itself or something it loads. Files whose size isn't known in advance are always loaded.
+    #[clap(long, value_name = "MEGABYTES", value_parser(parse_megabytes::<u64>))]
+    pub confirm_larger_than: Option<u64>,
+
+    /// Read local files again for a second or so when they seem to still be written,
+    /// such as a movie opened while an exporter is saving it, instead of failing to load them.
+    #[clap(long)]
+    pub retry_busy_files: bool,
+
+    /// Only allow movies to load local files from within this directory, refusing any others,
+    /// such as when playing downloaded content that shouldn't read the rest of the disk.
+    /// The movie itself must be in it too. Files inside of a bundle are always allowed.
+    #[clap(long, value_name = "DIRECTORY", value_parser(parse_file_root))]
+    pub file_root: Option<FileRoot>,
+
+    /// Record the input given to the movie to a file, so that it can be replayed with --replay.
+    #[clap(long, value_name = "PATH", conflicts_with = "replay")]
+    pub record_input: Option<std::path::PathBuf>,
+
+    /// Record the movie's audio to a directory as separate WAV files for event sounds
+    /// (events.wav), stream sounds (streams.wav) and everything mixed together (mixed.wav),
+    /// so that it can be remixed. Audio that isn't from either, such as that of videos,
+    /// is counted as stream sounds.
+    #[clap(long, value_name = "DIRECTORY")]
+    pub record_audio_stems: Option<std::path::PathBuf>,
+
+    /// Replay input recorded with --record-input, giving each event to the movie
+    /// at the frame it was recorded at. Input from the user is ignored until the replay ends.
+    #[clap(long, value_name = "PATH")]
+    pub replay: Option<std::path::PathBuf>,
+
+    /// Run a script of timed commands against the movie, such as clicks, key presses and
+    /// screenshots. Each command runs once the movie has reached the frame it's due at.
+    /// See the documentation of `script.rs` for the commands.
+    #[clap(long, value_name = "PATH")]
+    pub script: Option<std::path::PathBuf>,
+
     /// Replace all embedded HTTP URLs with HTTPS.
     #[clap(long, action)]
     pub upgrade_to_https: bool,
@@ -138,6 +340,19 @@ pub struct Opt {
     #[clap(long, action)]
     pub fullscreen: bool,
 
+    /// The monitor to go fullscreen on, by its index in the list of monitors starting from 0,
+    /// instead of the one the window is on. Used both at launch and when toggling fullscreen.
+    #[clap(long, value_name = "INDEX")]
+    pub fullscreen_monitor: Option<usize>,
+
+    /// Keep the window at the aspect ratio of the movie while resizing it.
+    #[clap(long)]
+    pub lock_aspect: bool,
+
+    /// Don't allow the window to be resized once a movie has been loaded.
+    #[clap(long)]
+    pub not_resizable: bool,
+
     #[clap(long)]
     pub load_behavior: Option<LoadBehavior>,
 
@@ -145,6 +360,19 @@ pub struct Opt {
     #[clap(long)]
     pub letterbox: Option<Letterbox>,
 
+    /// The color of the bars around the movie when it's letterboxed, such as `#202020`.
+    #[clap(
+        long,
+        value_name = "COLOR",
+        default_value = "#000000",
+        value_parser(parse_color)
+    )]
+    pub letterbox_color: Color,
+
+    /// A pattern to draw over the bars around the movie when it's letterboxed.
+    #[clap(long, value_name = "PATTERN", default_value = "solid")]
+    pub letterbox_pattern: LetterboxPattern,
+
     /// Spoofs the root SWF URL provided to ActionScript.
     #[clap(long, value_parser)]
     pub spoof_url: Option<Url>,
@@ -153,28 +381,210 @@ pub struct Opt {
     #[clap(long)]
     pub player_version: Option<u8>,
 
+    /// The locale reported to the movie as the language of the system, such as `fr-FR`.
+    /// Defaults to the locale of this system.
+    #[clap(long, value_name = "TAG")]
+    pub locale: Option<LanguageIdentifier>,
+
+    /// Start the clock the movie reads the time from at this time instead of the current one,
+    /// such as `2010-12-24T18:00:00Z`, to test content that depends on the date.
+    #[clap(long, value_name = "ISO8601", value_parser(parse_fake_time))]
+    pub fake_time: Option<DateTime<Utc>>,
+
+    /// How many times faster than real time the clock the movie reads the time from runs.
+    #[clap(
+        long,
+        value_name = "MULTIPLIER",
+        default_value = "1",
+        value_parser(parse_positive_f64)
+    )]
+    pub fake_time_rate: f64,
+
     /// The runtime to emulate (Flash Player or Adobe AIR)
     #[clap(long)]
     pub player_runtime: Option<PlayerRuntime>,
 
+    /// Only play movies written for the given ActionScript virtual machine.
+    ///
+    /// This is a filter rather than an override: a movie always runs in the virtual machine
+    /// its SWF asks for, so one written for the other is refused once its header is loaded.
+    /// The runtime emulated within it is still chosen with --player-runtime.
+    #[clap(long, value_name = "1|2")]
+    pub require_avm: Option<AvmVersion>,
+
+    /// Whether the root timeline loops, overriding the movie.
+    ///
+    /// `never` stops at the last frame, and `always` rewinds to the first frame
+    /// even if the movie stopped there.
+    #[clap(long = "loop", default_value = "default")]
+    pub loop_mode: LoopMode,
+
     /// Set and lock the player's frame rate, overriding the movie's frame rate.
     #[clap(long)]
     pub frame_rate: Option<f64>,
 
+    /// Advance and render the movie at most this many times per second.
+    /// Movies with a higher frame rate play slower instead of skipping frames,
+    /// which keeps them from using a whole CPU core.
+    #[clap(long, value_name = "FPS", value_parser(parse_positive_f64))]
+    pub max_fps: Option<f64>,
+
+    /// When the movie falls behind real time, skip rendering up to this many frames in a row
+    /// so that it can catch up, as Flash Player does. The movie still runs every frame.
+    /// This can't be used with --max-fps, which slows the movie down instead.
+    #[clap(long, value_name = "FRAMES", conflicts_with = "max_fps")]
+    pub frame_skip: Option<NonZeroU32>,
+
+    /// Show an image in the middle of the window while the movie loads, until its first frame is ready.
+    /// Without an image, a spinner is shown instead.
+    /// Bundles may have their own splash image, which is shown even without this.
+    #[clap(long, value_name = "IMAGE", num_args = 0..=1)]
+    pub splash: Option<Option<std::path::PathBuf>>,
+
+    /// Show captions from an SRT or WebVTT file over the movie,
+    /// timed by the current frame of its main timeline.
+    #[clap(long, value_name = "FILE")]
+    pub captions: Option<std::path::PathBuf>,
+
+    /// Where to show the --captions.
+    #[clap(long, default_value = "bottom", requires = "captions")]
+    pub captions_position: CaptionsPosition,
+
+    /// The font size of the --captions, in points.
+    #[clap(long, value_name = "SIZE", default_value = "20", requires = "captions")]
+    pub captions_size: f32,
+
     /// The handling mode of links opening a new website.
     #[clap(long, default_value = "allow")]
     pub open_url_mode: OpenURLMode,
 
+    /// Play SWFs that the movie opens in its own window (such as with a `_self` target) in place of
+    /// the movie, as a browser would, so that sites made of several linked movies can be browsed.
+    /// Anything else is still opened in a browser, as allowed by --open-url-mode.
+    #[clap(long, action)]
+    pub open_swfs_in_place: bool,
+
     /// Provide a dummy (completely empty) External Interface to the movie.
     /// This may break some movies that expect an External Interface to be functional,
     /// but may fix others that always require an External Interface.
     #[clap(long)]
     pub dummy_external_interface: bool,
 
+    /// Provide a dummy External Interface that returns the values from the given JSON file,
+    /// an object from method names to values such as `{"getUserAgent": "Ruffle"}`.
+    /// The value of the "*" key is returned by any other method.
+    #[clap(
+        long,
+        value_name = "FILE",
+        value_parser(parse_external_interface_values)
+    )]
+    pub external_interface_values: Option<ExternalInterfaceValues>,
+
+    /// Log every ExternalInterface call the movie makes, with its arguments and result.
+    /// The log can be filtered on its own with `ruffle_external_interface=info`.
+    /// This provides a dummy External Interface to the movie.
+    #[clap(long)]
+    pub log_external_interface: bool,
+
+    /// Only let the movie call the given ExternalInterface method, so that any other returns null.
+    /// This can be repeated multiple times, for example --allow-external-interface getUserAgent.
+    /// This provides a dummy External Interface to the movie.
+    #[clap(long, value_name = "METHOD", action = clap::ArgAction::Append)]
+    allow_external_interface: Vec<String>,
+
+    /// Open the most recently opened movie when no movie is given.
+    #[clap(long)]
+    pub open_last: bool,
+
+    /// Print the recently opened movies as JSON and exit, for use by launchers and scripts.
+    ///
+    /// The output is an array, newest first, of objects with the fields
+    /// `url` (string), `name` (string or null), `last_opened` (seconds since the Unix epoch, or null)
+    /// and `pinned` (boolean).
+    #[clap(long)]
+    pub dump_recents: bool,
+
+    /// Print how long each movie was played as JSON and exit, most played first.
+    /// This is only kept while --play-stats or its preference is on, and never leaves this computer.
+    ///
+    /// The output is an array of objects with the fields `url` (string),
+    /// `seconds` (the total time the movie was open) and `sessions` (how many times it was opened).
+    #[clap(long)]
+    pub dump_stats: bool,
+
+    /// Print the audio output devices and exit, marking the one used by default.
+    /// Any of these names can be picked as the audio output device in the preferences.
+    #[clap(long)]
+    pub list_audio_devices: bool,
+
+    /// Print the graphics adapters of every graphics backend and exit, marking the one used by default
+    /// with the current --graphics and --power.
+    #[clap(long)]
+    pub list_gpus: bool,
+
+    /// Reset all preferences to their defaults and exit, keeping a backup of the preferences file.
+    /// Recents, bookmarks and saves are kept.
+    #[clap(long)]
+    pub reset_preferences: bool,
+
+    /// Check that the bundle at the given path is valid and its movie can be loaded, without playing it.
+    /// Exits with an error if it isn't valid.
+    #[clap(long, value_name = "PATH")]
+    pub validate_bundle: Option<std::path::PathBuf>,
+
+    /// Create a new bundle directory at the path given with --out, which plays the SWF given with --swf,
+    /// and exit. Zip the directory and rename it to end in `.ruf` to share it as a single file.
+    #[clap(long, requires_all = ["swf", "out"])]
+    pub create_bundle: bool,
+
+    /// The SWF to put into the bundle made with --create-bundle.
+    #[clap(long, value_name = "PATH", requires = "create_bundle")]
+    pub swf: Option<std::path::PathBuf>,
+
+    /// Where to create the bundle made with --create-bundle. It must not exist yet, or be empty.
+    #[clap(long, value_name = "PATH", requires = "create_bundle")]
+    pub out: Option<std::path::PathBuf>,
+
+    /// The name of the bundle made with --create-bundle, which defaults to the name of the SWF.
+    #[clap(long, value_name = "NAME", requires = "create_bundle")]
+    pub bundle_name: Option<String>,
+
+    /// Print information about the movie, such as its SWF version, size and compression,
+    /// instead of playing it.
+    #[clap(long)]
+    pub info: bool,
+
+    /// Print which features the movie uses, such as its ActionScript version, the tags it
+    /// contains, whether it uses sockets, ExternalInterface, shared objects or video, and any
+    /// tags Ruffle doesn't support, instead of playing it.
+    #[clap(long, value_name = "FORMAT", num_args = 0..=1, default_missing_value = "text")]
+    pub feature_report: Option<ReportFormat>,
+
+    /// Play the movie headless for a while and print what went wrong, such as features Ruffle
+    /// doesn't support, ActionScript errors, videos that can't be decoded and whether it showed
+    /// its first frame, instead of playing it in a window. Exits with 0 if nothing went wrong,
+    /// 2 if the movie used unsupported features, 3 if there were errors and 4 if it never
+    /// showed its first frame.
+    #[clap(long, value_name = "FORMAT", num_args = 0..=1, default_missing_value = "text")]
+    pub compat_check: Option<ReportFormat>,
+
+    /// Number of seconds to play the movie for with --compat-check.
+    #[clap(
+        long,
+        value_name = "SECONDS",
+        default_value = "10",
+        value_parser(parse_duration_seconds)
+    )]
+    pub compat_check_duration: Duration,
+
     /// Hides the menu bar (the bar at the top of the window).
     #[clap(long)]
     pub no_gui: bool,
 
+    /// Always hide the system cursor while it's over the movie, for example in kiosks.
+    #[clap(long)]
+    pub hide_cursor: bool,
+
     /// Remaps a specific button on a gamepad to a keyboard key.
     /// This can be used to add new gamepad support to existing games, for example mapping
     /// the D-pad to the arrow keys with -B d-pad-up=up -B d-pad-down=down etc.
@@ -203,21 +613,299 @@ pub struct Opt {
     )]
     pub gamepad_button: Vec<(GamepadButton, KeyCode)>,
 
+    /// Render the movie offscreen and save it as an animated GIF at the given path, instead of opening a window.
+    #[clap(long, value_name = "PATH")]
+    pub export_gif: Option<std::path::PathBuf>,
+
+    /// Number of frames to export with --export-gif.
+    /// Defaults to the length of the main timeline.
+    #[clap(long, requires = "export_gif")]
+    pub frames: Option<u32>,
+
+    /// Maximum number of colors in each exported GIF frame, between 2 and 256.
+    #[clap(long, default_value = "256", value_parser = clap::value_parser!(u16).range(2..=256))]
+    pub gif_colors: u16,
+
+    /// Make the exported GIF loop forever instead of playing once.
+    #[clap(long)]
+    pub gif_loop: bool,
+
+    /// The amount to scale the exported GIF by, relative to the size of the movie.
+    #[clap(long, default_value = "1.0")]
+    pub gif_scale: f64,
+
+    /// Render the movie offscreen and save a screenshot of it as a PNG at the given path,
+    /// instead of opening a window.
+    /// The screenshot is taken once the movie stops loading anything over the network.
+    #[clap(long, value_name = "PATH", conflicts_with = "export_gif")]
+    pub screenshot: Option<std::path::PathBuf>,
+
+    /// Number of seconds the movie must go without loading anything before the --screenshot is taken.
+    #[clap(
+        long,
+        value_name = "SECONDS",
+        default_value = "0.5",
+        value_parser(parse_duration_seconds)
+    )]
+    pub network_idle: Duration,
+
+    /// Maximum number of seconds to wait for the movie to stop loading,
+    /// after which the --screenshot is taken regardless.
+    #[clap(
+        long,
+        value_name = "SECONDS",
+        default_value = "30",
+        value_parser(parse_duration_seconds)
+    )]
+    pub screenshot_timeout: Duration,
+
+    /// Take the --screenshot at the given frame instead of once the movie stops loading,
+    /// which avoids capturing a blank preloader.
+    /// Frames are run back to back instead of in real time, so the same image is captured every time.
+    #[clap(
+        long,
+        value_name = "FRAME",
+        requires = "screenshot",
+        value_parser = clap::value_parser!(u32).range(1..)
+    )]
+    pub thumbnail_frame: Option<u32>,
+
+    /// What the --screenshot shows. `displayed` shows the movie as a window of the --window-size would,
+    /// including letterbox bars, while `stage` shows only the movie at its own size.
+    #[clap(long, value_name = "AREA", default_value = "displayed")]
+    pub screenshot_area: ScreenshotArea,
+
     /// Disable AVM2 optimizer.
     /// Note that some early opcode conversions
     /// (like inlining constant pool entries) can't be disabled.
     #[clap(long)]
     pub no_avm2_optimizer: bool,
+
+    /// Don't run the AVM2 optimizer on the given methods, to help pin down optimizer bugs.
+    /// This can be repeated multiple times, for example --no-optimize Main.update --no-optimize 'Player.*'.
+    ///
+    /// Methods are given as `Class.method`, optionally with the package (`com.example.Class.method`).
+    /// A `*` matches anything, so `Class.*` matches every method of a class.
+    #[clap(long, value_name = "CLASS.METHOD", action = clap::ArgAction::Append)]
+    pub no_optimize: Vec<String>,
+
+    /// Read methods to exclude from the AVM2 optimizer from a file, one per line, like --no-optimize.
+    /// Empty lines and lines starting with `#` are ignored.
+    #[clap(long, value_name = "PATH")]
+    pub no_optimize_file: Option<std::path::PathBuf>,
+
+    /// Log every ActionScript 3 function call with its arguments and return value,
+    /// through the same output as `trace()`. At most 500 calls are logged per frame.
+    #[clap(long)]
+    pub trace_avm: bool,
+
+    /// Only log calls to functions matching this pattern with --trace-avm, like `Class.method`
+    /// or `com.example.Class.*`. May be given multiple times.
+    #[clap(long, value_name = "CLASS.METHOD", action = clap::ArgAction::Append, requires = "trace_avm")]
+    pub trace_avm_filter: Vec<String>,
+
+    /// Pause the movie the first time ActionScript reports an error, so that its state can
+    /// be inspected with the debug tools. Warnings don't pause it.
+    /// Playing can be continued with "Controls > Resume".
+    #[clap(long)]
+    pub break_on_error: bool,
+
+    /// Play the movie in the most compatible way, with the most information for a bug report.
+    /// This disables the AVM2 optimizer, prefers a software renderer,
+    /// logs debug messages and pauses on the first ActionScript error (like --break-on-error).
+    #[clap(long)]
+    pub safe_mode: bool,
+
+    /// Play the movie as if it was embedded with `wmode=transparent`, leaving out its background
+    /// color so that what's behind the window shows through where the system allows it.
+    /// Screenshots taken with --screenshot keep the transparency too.
+    #[clap(long)]
+    pub transparent: bool,
+
+    /// Draw text without anti-aliasing, aligning each glyph to whole pixels so that pixel fonts
+    /// stay crisp. Shapes are still anti-aliased as the quality asks.
+    /// This can be changed while playing with "Controls > Anti-alias Text".
+    #[clap(long)]
+    pub no_text_anti_aliasing: bool,
+
+    /// Correct the colors of the presented movie with a 3D lookup table from a `.cube` file,
+    /// such as to match the display the movie was made for. It's applied after any color filter.
+    #[clap(long, value_name = "PATH", value_parser(parse_color_lut))]
+    pub color_lut: Option<Arc<ColorLut>>,
+}
+
+/// How to deal with scripts running for longer than the maximum execution duration.
+#[derive(ValueEnum, Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum ScriptTimeoutMode {
+    /// Stop the script and disable scripting for the rest of the movie.
+    #[default]
+    Halt,
+
+    /// Stop only the script that took too long, and keep playing.
+    Abort,
+
+    /// Ask whether to keep waiting, stop the script, or close the movie.
+    Ask,
+}
+
+/// How to deal with movies asking to quit with `fscommand("quit")`.
+#[derive(ValueEnum, Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum QuitMode {
+    /// Exit Ruffle.
+    #[default]
+    Exit,
+
+    /// Close the movie, but keep Ruffle open.
+    Close,
+
+    /// Keep playing the movie, such as in kiosks where it mustn't be closed.
+    Ignore,
+
+    /// Ask whether to exit Ruffle.
+    Ask,
+}
+
+/// How the rendered movie is sampled when it's stretched over the window.
+#[derive(ValueEnum, Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum UpscaleFilter {
+    /// Blend neighboring pixels, for a smooth image.
+    #[default]
+    Linear,
+
+    /// Use the nearest pixel, keeping pixels sharp.
+    Nearest,
+}
+
+/// Where captions are shown over the movie.
+#[derive(ValueEnum, Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum CaptionsPosition {
+    Top,
+    #[default]
+    Bottom,
+}
+
+/// Which video backend to decode video in movies with.
+#[derive(ValueEnum, Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum VideoBackendMode {
+    /// Decode video on the CPU.
+    #[default]
+    Software,
+
+    /// Don't decode video at all.
+    None,
+}
+
+/// How to print the report made with --feature-report.
+#[derive(ValueEnum, Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ReportFormat {
+    Text,
+    Json,
+}
+
+/// What is captured by --screenshot.
+#[derive(ValueEnum, Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ScreenshotArea {
+    Displayed,
+    Stage,
+}
+
+/// An ActionScript virtual machine, for --require-avm.
+#[derive(ValueEnum, Copy, Clone, PartialEq, Eq, Debug)]
+pub enum AvmVersion {
+    /// AVM1, which runs ActionScript 1 and 2.
+    #[value(name = "1")]
+    Avm1,
+    /// AVM2, which runs ActionScript 3.
+    #[value(name = "2")]
+    Avm2,
 }
 
 fn parse_movie_file_or_url(path: &str) -> Result<Url, Error> {
     crate::util::parse_url(Path::new(path))
 }
 
+fn parse_external_interface_values(path: &str) -> Result<ExternalInterfaceValues, Error> {
+    let json = std::fs::read_to_string(path)?;
+    ExternalInterfaceValues::from_json(&json)
+}
+
 fn parse_duration_seconds(value: &str) -> Result<Duration, Error> {
     Ok(Duration::from_secs_f64(value.parse()?))
 }
 
+fn parse_profile_name(value: &str) -> Result<String, Error> {
+    // The name becomes a directory, so it mustn't be able to point anywhere else.
+    let is_valid = !value.is_empty()
+        && !value.starts_with('.')
+        && value
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if !is_valid {
+        return Err(anyhow!(
+            "invalid profile name `{value}`, only letters, digits, `-`, `_` and `.` are allowed"
+        ));
+    }
+    Ok(value.to_owned())
+}
+
+fn parse_window_size(value: &str) -> Result<(f64, f64), Error> {
+    let (width, height) = value
+        .split_once(['x', 'X'])
+        .ok_or_else(|| anyhow!("invalid window size `{value}`, expected WIDTHxHEIGHT"))?;
+    let (width, height): (f64, f64) = (width.trim().parse()?, height.trim().parse()?);
+    if !(width >= 1.0 && height >= 1.0) {
+        return Err(anyhow!("{value} is too small for a window"));
+    }
+    Ok((width, height))
+}
+
+fn parse_window_position(value: &str) -> Result<(i32, i32), Error> {
+    let (x, y) = value
+        .split_once(',')
+        .ok_or_else(|| anyhow!("invalid window position `{value}`, expected X,Y"))?;
+    Ok((x.trim().parse()?, y.trim().parse()?))
+}
+
+fn parse_color(value: &str) -> Result<Color, Error> {
+    let hex = value.strip_prefix('#').unwrap_or(value);
+    if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(anyhow!("invalid color `{value}`, expected #RRGGBB"));
+    }
+    Ok(Color::from_rgb(u32::from_str_radix(hex, 16)?, 255))
+}
+
+fn parse_positive_f64(value: &str) -> Result<f64, Error> {
+    match value.parse::<f64>() {
+        Ok(number) if number.is_finite() && number > 0.0 => Ok(number),
+        _ => Err(anyhow!("{value} is not a positive number")),
+    }
+}
+
+fn parse_fake_time(value: &str) -> Result<DateTime<Utc>, Error> {
+    let time = DateTime::parse_from_rfc3339(value)
+        .map_err(|e| anyhow!("{value} is not an ISO 8601 date and time: {e}"))?;
+    Ok(time.with_timezone(&Utc))
+}
+
+fn parse_file_root(value: &str) -> Result<FileRoot, Error> {
+    FileRoot::new(Path::new(value))
+        .map_err(|e| anyhow!("Couldn't use {value} as the file root: {e}"))
+}
+
+fn parse_color_lut(value: &str) -> Result<Arc<ColorLut>, Error> {
+    // Clap only shows the outermost context of an error, so the causes are included in it.
+    let lut = ColorLut::load(Path::new(value)).map_err(|e| anyhow!("{e:#}"))?;
+    Ok(Arc::new(lut))
+}
+
+fn parse_megabytes<T: TryFrom<u64>>(value: &str) -> Result<T, Error> {
+    let megabytes: u64 = value.parse()?;
+    megabytes
+        .checked_mul(1024 * 1024)
+        .and_then(|bytes| T::try_from(bytes).ok())
+        .ok_or_else(|| anyhow!("{megabytes} megabytes is too large"))
+}
+
 fn parse_gamepad_button(mapping: &str) -> Result<(GamepadButton, KeyCode), Error> {
     let pos = mapping.find('=').ok_or_else(|| {
         anyhow!("invalid <gamepad button>=<key name>: no `=` found in `{mapping}`")
@@ -270,6 +958,77 @@ impl Opt {
         None
     }
 
+    /// Changes the options that `--safe-mode` stands for, returning a description of each one
+    /// which wasn't already set that way.
+    pub fn apply_safe_mode(&mut self) -> Vec<&'static str> {
+        let mut changes = vec![];
+        if !self.no_avm2_optimizer {
+            self.no_avm2_optimizer = true;
+            changes.push("AVM2 optimizer disabled");
+        }
+        if self.quiet || self.verbose < 2 {
+            self.quiet = false;
+            self.verbose = 2;
+            changes.push("debug messages logged");
+        }
+        if !self.break_on_error {
+            self.break_on_error = true;
+            changes.push("paused on the first ActionScript error");
+        }
+        changes.push("software renderer preferred");
+        changes
+    }
+
+    /// The tracing filter chosen by `--verbose` or `--quiet`, which `RUST_LOG` overrides.
+    pub fn log_filter(&self) -> &'static str {
+        if self.quiet {
+            return "error";
+        }
+        match self.verbose {
+            0 => "warn,ruffle=info,avm_trace=info",
+            1 => "info",
+            2 => "debug",
+            _ => "trace",
+        }
+    }
+
+    /// The only ExternalInterface methods the movie may call, if limited with `--allow-external-interface`.
+    pub fn allowed_external_interface_methods(&self) -> Option<HashSet<String>> {
+        (!self.allow_external_interface.is_empty())
+            .then(|| self.allow_external_interface.iter().cloned().collect())
+    }
+
+    /// All methods excluded from the AVM2 optimizer, from both `--no-optimize` and `--no-optimize-file`.
+    pub fn avm2_optimizer_denylist(&self) -> Vec<String> {
+        let mut patterns = self.no_optimize.clone();
+        if let Some(path) = &self.no_optimize_file {
+            match std::fs::read_to_string(path) {
+                Ok(contents) => patterns.extend(
+                    contents
+                        .lines()
+                        .map(str::trim)
+                        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                        .map(str::to_owned),
+                ),
+                Err(e) => tracing::warn!("Couldn't read {}: {e}", path.display()),
+            }
+        }
+        patterns
+    }
+
+    /// The preferred width and height of the window, from either `--window-size` or `--width` and `--height`.
+    pub fn window_size(&self) -> (Option<f64>, Option<f64>) {
+        match self.window_size {
+            Some((width, height)) => (Some(width), Some(height)),
+            None => (self.width, self.height),
+        }
+    }
+
+    /// The function patterns to trace calls to, or `None` if `--trace-avm` isn't given.
+    pub fn avm2_call_trace(&self) -> Option<Vec<String>> {
+        self.trace_avm.then(|| self.trace_avm_filter.clone())
+    }
+
     pub fn parameters(&self) -> impl '_ + Iterator<Item = (String, String)> {
         self.parameters.iter().map(|parameter| {
             let mut split = parameter.splitn(2, '=');
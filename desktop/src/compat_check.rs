@@ -0,0 +1,413 @@
+//! A compatibility check, which plays a movie headless for a while and reports what went wrong.
+
+use crate::export::{FramePollRequester, HeadlessNavigatorInterface};
+use crate::feature_report::FeatureReport;
+use crate::preferences::GlobalPreferences;
+use anyhow::{anyhow, Context as _, Error};
+use ruffle_core::backend::ui::{
+    DialogResultFuture, FileFilter, FontDefinition, FullscreenError, LanguageIdentifier,
+    MouseCursor, NullUiBackend, ScriptTimeoutAction, UiBackend, UnsupportedVideoReason,
+};
+use ruffle_core::limits::ExecutionLimit;
+use ruffle_core::swf::{Compression, TagCode, VideoCodec};
+use ruffle_core::tag_utils::SwfMovie;
+use ruffle_core::PlayerBuilder;
+use ruffle_frontend_utils::backends::executor::AsyncExecutor;
+use ruffle_frontend_utils::backends::navigator::{ExternalNavigatorBackend, NavigatorOptions};
+use ruffle_frontend_utils::content::PlayingContent;
+use std::collections::BTreeSet;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::Layer;
+use url::Url;
+
+/// How bad the problems found by a compatibility check are, from least to most severe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// Nothing went wrong.
+    Compatible,
+    /// The movie uses features Ruffle doesn't support, which may or may not matter.
+    Unsupported,
+    /// Scripts failed or videos couldn't be decoded.
+    Errors,
+    /// The movie never showed its first frame.
+    Broken,
+}
+
+impl Severity {
+    /// The status Ruffle exits with after a compatibility check.
+    pub fn exit_code(self) -> i32 {
+        match self {
+            Severity::Compatible => 0,
+            Severity::Unsupported => 2,
+            Severity::Errors => 3,
+            Severity::Broken => 4,
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Severity::Compatible => "compatible",
+            Severity::Unsupported => "unsupported features",
+            Severity::Errors => "errors",
+            Severity::Broken => "broken",
+        }
+    }
+}
+
+/// What went wrong while a movie was played for a compatibility check.
+#[derive(Debug, Default)]
+pub struct Findings {
+    /// ActionScript APIs which Ruffle doesn't fully implement, that the movie used.
+    pub stubs: BTreeSet<String>,
+    /// Errors reported by AVM1 or AVM2, each only once.
+    pub avm_errors: BTreeSet<String>,
+    /// Other errors reported by Ruffle, each only once.
+    pub errors: BTreeSet<String>,
+    /// Videos or compression that couldn't be decoded.
+    pub unsupported_media: BTreeSet<String>,
+}
+
+#[derive(Debug)]
+pub struct CompatReport {
+    pub reached_first_frame: bool,
+    pub frames: u32,
+    pub unsupported_tags: Vec<u16>,
+    pub findings: Findings,
+}
+
+impl CompatReport {
+    pub fn severity(&self) -> Severity {
+        if !self.reached_first_frame {
+            Severity::Broken
+        } else if !self.findings.avm_errors.is_empty()
+            || !self.findings.errors.is_empty()
+            || !self.findings.unsupported_media.is_empty()
+        {
+            Severity::Errors
+        } else if !self.findings.stubs.is_empty() || !self.unsupported_tags.is_empty() {
+            Severity::Unsupported
+        } else {
+            Severity::Compatible
+        }
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut text = format!(
+            "Verdict: {}\nReached first frame: {}\nFrames run: {}\n",
+            self.severity().as_str(),
+            if self.reached_first_frame {
+                "yes"
+            } else {
+                "no"
+            },
+            self.frames
+        );
+        let mut section = |title: &str, items: Vec<String>| {
+            if !items.is_empty() {
+                text += &format!("\n{title}:\n");
+                for item in items {
+                    text += &format!("  {item}\n");
+                }
+            }
+        };
+        section(
+            "Unsupported tags",
+            self.unsupported_tags
+                .iter()
+                .map(|&code| TagCode::format(code))
+                .collect(),
+        );
+        section(
+            "Unsupported APIs",
+            self.findings.stubs.iter().cloned().collect(),
+        );
+        section(
+            "Unsupported media",
+            self.findings.unsupported_media.iter().cloned().collect(),
+        );
+        section(
+            "ActionScript errors",
+            self.findings.avm_errors.iter().cloned().collect(),
+        );
+        section(
+            "Other errors",
+            self.findings.errors.iter().cloned().collect(),
+        );
+        text.trim_end().to_string()
+    }
+
+    pub fn to_json(&self) -> String {
+        let severity = self.severity();
+        let unsupported_tags = self
+            .unsupported_tags
+            .iter()
+            .map(|&code| TagCode::format(code))
+            .collect::<Vec<_>>();
+        let json = serde_json::json!({
+            "verdict": severity.as_str(),
+            "exit_code": severity.exit_code(),
+            "reached_first_frame": self.reached_first_frame,
+            "frames": self.frames,
+            "unsupported_tags": unsupported_tags,
+            "unsupported_apis": self.findings.stubs,
+            "unsupported_media": self.findings.unsupported_media,
+            "avm_errors": self.findings.avm_errors,
+            "errors": self.findings.errors,
+        });
+        serde_json::to_string_pretty(&json).unwrap_or_default()
+    }
+}
+
+/// Plays the movie given on the command line headless for `--compat-check-duration`,
+/// and reports the problems it ran into.
+pub fn check_compatibility(preferences: &GlobalPreferences) -> Result<CompatReport, Error> {
+    let opt = &preferences.cli;
+    let movie_url = opt
+        .movie_url
+        .as_ref()
+        .ok_or_else(|| anyhow!("A movie is required to check its compatibility"))?;
+    let movie_path = movie_url
+        .to_file_path()
+        .map_err(|_| anyhow!("Only local movies can be checked, not {movie_url}"))?;
+    let data = std::fs::read(&movie_path)
+        .with_context(|| format!("Couldn't read {}", movie_path.display()))?;
+    let feature_report = FeatureReport::from_data(&data)
+        .with_context(|| format!("{} isn't a valid SWF", movie_path.display()))?;
+    let mut movie = SwfMovie::from_data(&data, movie_url.to_string(), None)
+        .map_err(|e| anyhow!("Couldn't load {}: {e}", movie_path.display()))?;
+    movie.append_parameters(opt.parameters());
+
+    let findings = Arc::new(Mutex::new(Findings::default()));
+    let subscriber = tracing_subscriber::registry().with(FindingsLayer(findings.clone()));
+    let (reached_first_frame, frames) = tracing::subscriber::with_default(subscriber, || {
+        play(preferences, movie_url, movie, findings.clone())
+    });
+
+    let findings = std::mem::take(&mut *findings.lock().expect("Findings are not reentrant"));
+    Ok(CompatReport {
+        reached_first_frame,
+        frames,
+        unsupported_tags: feature_report.unsupported_tags(),
+        findings,
+    })
+}
+
+/// Plays the movie in real time for `--compat-check-duration`, returning whether it reached
+/// its first frame and how many frames were run.
+fn play(
+    preferences: &GlobalPreferences,
+    movie_url: &Url,
+    movie: SwfMovie,
+    findings: Arc<Mutex<Findings>>,
+) -> (bool, u32) {
+    let opt = &preferences.cli;
+    let frame_rate = opt
+        .frame_rate
+        .unwrap_or_else(|| movie.frame_rate().to_f64());
+    let frame_duration = Duration::from_secs_f64(1.0 / frame_rate.max(1.0));
+
+    let (executor, future_spawner) = AsyncExecutor::new(FramePollRequester);
+    let navigator = ExternalNavigatorBackend::new(
+        opt.base.clone().unwrap_or_else(|| movie_url.clone()),
+        future_spawner,
+        // Nothing may be opened outside of the player, as there's no one to ask.
+        NavigatorOptions {
+            proxy: opt.proxy.clone(),
+            upgrade_to_https: opt.upgrade_to_https,
+            log_requests: opt.log_requests,
+            max_connections: opt.max_connections,
+            file_root: opt.file_root.clone(),
+            ..Default::default()
+        },
+        Rc::new(PlayingContent::DirectFile(movie_url.clone())),
+        HeadlessNavigatorInterface,
+    );
+    let mut builder = PlayerBuilder::new()
+        .with_navigator(navigator)
+        .with_ui(CompatUiBackend {
+            inner: NullUiBackend::new(),
+            findings,
+        })
+        .with_movie(movie)
+        .with_autoplay(true)
+        .with_frame_rate(opt.frame_rate)
+        .with_player_version(opt.player_version);
+    if cfg!(feature = "software_video") {
+        builder = builder.with_video(ruffle_video_software::backend::SoftwareVideoBackend::new());
+    }
+    let player = builder.build();
+
+    let started = Instant::now();
+    let mut reached_first_frame = false;
+    let mut frames = 0;
+    while started.elapsed() < opt.compat_check_duration {
+        {
+            let mut player = player.lock().expect("Player lock must be available");
+            player.preload(&mut ExecutionLimit::none());
+            player.run_frame();
+            reached_first_frame |= player.current_frame().unwrap_or_default() > 0;
+        }
+        // Loaders lock the player themselves, so it can't be locked while they're polled.
+        executor.poll_all();
+        frames += 1;
+        std::thread::sleep(frame_duration);
+    }
+    (reached_first_frame, frames)
+}
+
+/// Collects the stubs and errors logged while the movie plays.
+struct FindingsLayer(Arc<Mutex<Findings>>);
+
+impl<S: Subscriber> Layer<S> for FindingsLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let target = event.metadata().target();
+        let level = *event.metadata().level();
+        if !target.starts_with("ruffle") || level > Level::WARN {
+            return;
+        }
+        let message = crate::log::event_message(event);
+        let mut findings = self.0.lock().expect("Findings are not reentrant");
+        if let Some(stub) = message.strip_prefix("Encountered stub: ") {
+            findings.stubs.insert(stub.to_string());
+        } else if level == Level::ERROR {
+            if target.starts_with("ruffle_core::avm1") || target.starts_with("ruffle_core::avm2") {
+                findings.avm_errors.insert(message);
+            } else {
+                findings.errors.insert(message);
+            }
+        }
+    }
+}
+
+/// Does nothing, like a [`NullUiBackend`], except remembering the media the movie couldn't play.
+struct CompatUiBackend {
+    inner: NullUiBackend,
+    findings: Arc<Mutex<Findings>>,
+}
+
+impl CompatUiBackend {
+    fn add_unsupported_media(&self, description: String) {
+        self.findings
+            .lock()
+            .expect("Findings are not reentrant")
+            .unsupported_media
+            .insert(description);
+    }
+}
+
+impl UiBackend for CompatUiBackend {
+    fn mouse_visible(&self) -> bool {
+        self.inner.mouse_visible()
+    }
+
+    fn set_mouse_visible(&mut self, visible: bool) {
+        self.inner.set_mouse_visible(visible)
+    }
+
+    fn set_mouse_cursor(&mut self, cursor: MouseCursor) {
+        self.inner.set_mouse_cursor(cursor)
+    }
+
+    fn set_custom_cursor_active(&mut self, active: bool) {
+        self.inner.set_custom_cursor_active(active)
+    }
+
+    fn clipboard_content(&mut self) -> String {
+        self.inner.clipboard_content()
+    }
+
+    fn set_clipboard_content(&mut self, content: String) {
+        self.inner.set_clipboard_content(content)
+    }
+
+    fn set_fullscreen(&mut self, is_full: bool) -> Result<(), FullscreenError> {
+        self.inner.set_fullscreen(is_full)
+    }
+
+    fn display_root_movie_download_failed_message(&self, invalid_swf: bool) {
+        self.inner
+            .display_root_movie_download_failed_message(invalid_swf)
+    }
+
+    fn message(&self, message: &str) {
+        self.inner.message(message)
+    }
+
+    fn open_virtual_keyboard(&self) {
+        self.inner.open_virtual_keyboard()
+    }
+
+    fn language(&self) -> LanguageIdentifier {
+        self.inner.language()
+    }
+
+    fn display_unsupported_video(&self, url: Url) {
+        self.add_unsupported_media(format!("Video at {url}"));
+    }
+
+    fn display_unsupported_compression(&self, compression: Compression) {
+        self.add_unsupported_media(format!("{compression:?} compression"));
+    }
+
+    fn display_unsupported_video_codec(&self, codec: VideoCodec, reason: UnsupportedVideoReason) {
+        self.add_unsupported_media(format!("{codec:?} video ({reason:?})"));
+    }
+
+    fn script_timeout(&mut self) -> ScriptTimeoutAction {
+        self.inner.script_timeout()
+    }
+
+    fn load_device_font(
+        &self,
+        name: &str,
+        is_bold: bool,
+        is_italic: bool,
+        register: &mut dyn FnMut(FontDefinition),
+    ) {
+        self.inner
+            .load_device_font(name, is_bold, is_italic, register)
+    }
+
+    fn display_file_open_dialog(&mut self, filters: Vec<FileFilter>) -> Option<DialogResultFuture> {
+        self.inner.display_file_open_dialog(filters)
+    }
+
+    fn display_file_save_dialog(
+        &mut self,
+        file_name: String,
+        title: String,
+    ) -> Option<DialogResultFuture> {
+        self.inner.display_file_save_dialog(file_name, title)
+    }
+
+    fn close_file_dialog(&mut self) {
+        self.inner.close_file_dialog()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn severity_reflects_the_worst_finding() {
+        let mut report = CompatReport {
+            reached_first_frame: true,
+            frames: 10,
+            unsupported_tags: vec![],
+            findings: Findings::default(),
+        };
+        assert_eq!(report.severity(), Severity::Compatible);
+        report.unsupported_tags.push(TagCode::SetTabIndex as u16);
+        assert_eq!(report.severity(), Severity::Unsupported);
+        report.findings.avm_errors.insert("Error #1009".to_string());
+        assert_eq!(report.severity(), Severity::Errors);
+        assert_eq!(report.severity().exit_code(), 3);
+        report.reached_first_frame = false;
+        assert_eq!(report.severity(), Severity::Broken);
+    }
+}
@@ -1,6 +1,11 @@
 //! Custom event type for desktop ruffle
 
 use crate::player::LaunchOptions;
+use ruffle_core::backend::ui::{ScriptTimeoutAction, UnsupportedVideoReason};
+use ruffle_core::{StageAlign, StageScaleMode};
+use ruffle_render::quality::StageQuality;
+use winit::event_loop::{EventLoopClosed, EventLoopProxy};
+use winit::window::WindowId;
 
 /// User-defined events.
 pub enum RuffleEvent {
@@ -13,15 +18,155 @@ pub enum RuffleEvent {
     /// The user requested to pick and then open a file.
     BrowseAndOpen(Box<LaunchOptions>),
 
+    /// The user requested to pick a file and open it in a new window, next to the current one.
+    BrowseAndOpenInNewWindow(Box<LaunchOptions>),
+
     /// The user requested to open a URL.
     OpenURL(url::Url, Box<LaunchOptions>),
 
     /// The user requested to close the current SWF.
     CloseFile,
 
-    /// The user requested to exit Ruffle.
+    /// The user requested to open the SWF closed last again, with the options it had.
+    ReopenClosedFile,
+
+    /// The user cleared their history, which includes the SWF closed last.
+    ForgetClosedFile,
+
+    /// The user or movie requested to close the window, exiting Ruffle if it's the last one.
     ExitRequested,
 
+    /// The movie asked to quit with `fscommand("quit")`, and the user should be asked whether to close its window.
+    MovieAskedToQuit,
+
     /// The user selected an item in the right-click context menu.
     ContextMenuItemClicked(usize),
+
+    /// The user requested to save the current frame as an SVG document.
+    ExportFrameSvg,
+
+    /// The user requested to save the display list of the movie to a JSON file.
+    DumpDisplayList,
+
+    /// The user requested to see how many assets the movie has loaded.
+    ShowAssetStats,
+
+    /// The user requested to release the assets the movie has cached, which are loaded again when needed.
+    ClearAssetCache,
+
+    /// The user requested a full garbage collection to be run right away.
+    CollectGarbage,
+
+    /// The stored recents were changed, and anything showing them should be refreshed.
+    RecentsChanged,
+
+    /// The user requested to read the preferences from disk again.
+    ReloadPreferences,
+
+    /// The user asked to reset all preferences to their defaults.
+    ResetPreferences,
+
+    /// The movie allocated more memory than allowed by `--max-memory` and has been halted.
+    MemoryLimitExceeded,
+
+    /// The movie was downloaded, but still hasn't shown its first frame after `--first-frame-timeout`.
+    FirstFrameTimeout,
+
+    /// The movie played to the end of its root timeline and stopped, as it wasn't allowed to loop.
+    PlaybackComplete,
+
+    /// The quality, scale mode or alignment of the stage changed, such as when the movie set them
+    /// itself, so anything showing them should be refreshed.
+    StageSettingsChanged {
+        quality: StageQuality,
+        scale_mode: StageScaleMode,
+        align: StageAlign,
+    },
+
+    /// The movie was paused for an ActionScript error, as requested by `--break-on-error`.
+    BrokeOnError(String),
+
+    /// A `--script` asked to change the volume of the movie.
+    SetVolume(f32),
+
+    /// A script ran for longer than the maximum execution duration,
+    /// and was stopped without asking the user.
+    ScriptTimeout(ScriptTimeoutAction),
+
+    /// A video in the movie can't be shown, as its codec can't be decoded for the given reason.
+    UnsupportedVideo(ruffle_core::swf::VideoCodec, UnsupportedVideoReason),
+
+    /// The user asked to copy the URL of the current movie to the clipboard.
+    CopyMovieUrl,
+
+    /// The user asked to show the current movie in the system file manager.
+    ShowMovieInFolder,
+
+    /// The movie couldn't be loaded, for the given reason.
+    LoadError(String),
+
+    /// The user asked to zoom into the presented movie by the given factor, around its middle.
+    /// A factor below 1 zooms out.
+    ZoomMovie(f64),
+
+    /// The user asked to show the whole movie again, without any zoom.
+    ResetMovieZoom,
+
+    /// The user picked a different color filter, which should now be applied to the movie.
+    ColorFilterChanged,
+
+    /// The user asked for bitmaps to always be smoothed, never be smoothed,
+    /// or be smoothed as the movie asks (`None`).
+    SetBitmapSmoothing(Option<bool>),
+
+    /// The user asked to switch to the next bitmap smoothing override, as with `SetBitmapSmoothing`.
+    CycleBitmapSmoothing,
+
+    /// The user turned anti-aliasing of text on or off.
+    SetTextAntiAliasing(bool),
+
+    /// A modal dialog was shown over the movie, which is paused until it's closed.
+    ModalDialogOpened,
+
+    /// A modal dialog shown over the movie was closed.
+    ModalDialogClosed,
+
+    /// The movie navigated to another SWF of the bundle it's from, which should be played instead.
+    NavigateWithinBundle(url::Url),
+
+    /// The movie navigated to another SWF in its own window, which should be played in its place.
+    NavigateInPlace(url::Url),
+
+    /// The user requested to pick a SWF to play in place of the root SWF of the current bundle.
+    BrowseAndSwapRootSwf,
+}
+
+/// A [`RuffleEvent`] along with the window it was sent from, which is the one it concerns.
+pub struct WindowedRuffleEvent {
+    pub window_id: WindowId,
+    pub event: RuffleEvent,
+}
+
+/// Sends events to the event loop on behalf of a window, so that they're handled
+/// by that window rather than whichever one happens to be focused.
+#[derive(Clone)]
+pub struct RuffleEventProxy {
+    proxy: EventLoopProxy<WindowedRuffleEvent>,
+    window_id: WindowId,
+}
+
+impl RuffleEventProxy {
+    pub fn new(proxy: EventLoopProxy<WindowedRuffleEvent>, window_id: WindowId) -> Self {
+        Self { proxy, window_id }
+    }
+
+    pub fn send_event(
+        &self,
+        event: RuffleEvent,
+    ) -> Result<(), EventLoopClosed<WindowedRuffleEvent>> {
+        self.proxy.send_event(WindowedRuffleEvent {
+            window_id: self.window_id,
+            event,
+        })
+    }
 }
@@ -0,0 +1,342 @@
+//! Offscreen rendering of movies, used to export them without opening a window.
+
+use crate::cli::ScreenshotArea;
+use crate::gui::create_wgpu_instance;
+use crate::preferences::GlobalPreferences;
+use anyhow::{anyhow, Context, Error};
+use color_quant::NeuQuant;
+use image::RgbaImage;
+use ruffle_core::config::{Letterbox, LetterboxStyle};
+use ruffle_core::limits::ExecutionLimit;
+use ruffle_core::tag_utils::SwfMovie;
+use ruffle_core::{PlayerBuilder, StageScaleMode};
+use ruffle_frontend_utils::backends::executor::{AsyncExecutor, PollRequester};
+use ruffle_frontend_utils::backends::navigator::{
+    ExternalNavigatorBackend, NavigatorInterface, NavigatorOptions,
+};
+use ruffle_frontend_utils::content::PlayingContent;
+use ruffle_render_wgpu::backend::{request_adapter_and_device, WgpuRenderBackend};
+use ruffle_render_wgpu::descriptors::Descriptors;
+use ruffle_render_wgpu::target::TextureTarget;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use url::Url;
+
+/// Renders the requested frames of the movie given on the command line
+/// and saves them as an animated GIF at `output`.
+pub fn export_gif(preferences: &GlobalPreferences, output: &Path) -> Result<(), Error> {
+    let opt = &preferences.cli;
+    let movie_url = opt
+        .movie_url
+        .as_ref()
+        .ok_or_else(|| anyhow!("A movie is required to export a GIF"))?;
+    let (movie_path, movie) = load_local_movie(preferences, movie_url)?;
+
+    let frame_count = opt
+        .frames
+        .unwrap_or_else(|| u32::from(movie.num_frames()))
+        .max(1);
+    let frame_rate = opt
+        .frame_rate
+        .unwrap_or_else(|| movie.frame_rate().to_f64());
+    let width = (movie.width().to_pixels() * opt.gif_scale).round().max(1.0) as u32;
+    let height = (movie.height().to_pixels() * opt.gif_scale)
+        .round()
+        .max(1.0) as u32;
+    let (Ok(gif_width), Ok(gif_height)) = (u16::try_from(width), u16::try_from(height)) else {
+        return Err(anyhow!(
+            "{width}x{height} is too large for a GIF, try a smaller --gif-scale"
+        ));
+    };
+
+    let renderer = create_offscreen_renderer(preferences, width, height)?;
+    let player = PlayerBuilder::new()
+        .with_renderer(renderer)
+        .with_movie(movie)
+        .with_viewport_dimensions(width, height, opt.gif_scale)
+        .with_frame_rate(opt.frame_rate)
+        .build();
+
+    let file =
+        File::create(output).with_context(|| format!("Couldn't create {}", output.display()))?;
+    let mut encoder = gif::Encoder::new(BufWriter::new(file), gif_width, gif_height, &[])?;
+    encoder.set_repeat(if opt.gif_loop {
+        gif::Repeat::Infinite
+    } else {
+        gif::Repeat::Finite(0)
+    })?;
+
+    // GIF frame delays are measured in hundredths of a second.
+    let delay = (100.0 / frame_rate).round().clamp(1.0, f64::from(u16::MAX)) as u16;
+    for frame in 0..frame_count {
+        let image = {
+            let mut player = player.lock().expect("Player lock must be available");
+            player.preload(&mut ExecutionLimit::none());
+            player.run_frame();
+            player.render();
+            player
+                .renderer_mut()
+                .downcast_mut::<WgpuRenderBackend<TextureTarget>>()
+                .and_then(|renderer| renderer.capture_frame())
+                .ok_or_else(|| anyhow!("Unable to capture frame {frame}"))?
+        };
+        let mut gif_frame = quantize(&image, opt.gif_colors);
+        gif_frame.delay = delay;
+        encoder.write_frame(&gif_frame)?;
+    }
+
+    tracing::info!(
+        "Saved {frame_count} frames of {} to {}",
+        movie_path.display(),
+        output.display()
+    );
+    Ok(())
+}
+
+/// Plays the movie given on the command line until it stops loading anything over the network,
+/// and saves a screenshot of it as a PNG at `output`.
+///
+/// The movie plays in real time, as that's how long its requests take. If it keeps loading
+/// for longer than `--screenshot-timeout`, the screenshot is taken regardless.
+///
+/// With `--thumbnail-frame`, the movie instead runs back to back up to that frame,
+/// or its last frame if it's shorter, and the screenshot is taken there.
+///
+/// With `--screenshot-area stage`, only the stage is captured at the movie's own size,
+/// regardless of `--window-size` and letterboxing.
+pub fn export_screenshot(preferences: &GlobalPreferences, output: &Path) -> Result<(), Error> {
+    let opt = &preferences.cli;
+    let movie_url = opt
+        .movie_url
+        .as_ref()
+        .ok_or_else(|| anyhow!("A movie is required to take a screenshot"))?;
+    let (movie_path, movie) = load_local_movie(preferences, movie_url)?;
+
+    let target_frame = opt.thumbnail_frame.map(|frame| {
+        let last_frame = u32::from(movie.num_frames()).max(1);
+        if frame > last_frame {
+            tracing::warn!(
+                "{} only has {last_frame} frames, capturing the last one instead of frame {frame}",
+                movie_path.display()
+            );
+        }
+        frame.min(last_frame)
+    });
+    let frame_rate = opt
+        .frame_rate
+        .unwrap_or_else(|| movie.frame_rate().to_f64());
+    let frame_duration = Duration::from_secs_f64(1.0 / frame_rate.max(1.0));
+    let stage_width = movie.width().to_pixels().round().max(1.0) as u32;
+    let stage_height = movie.height().to_pixels().round().max(1.0) as u32;
+    let (width, height) = match opt.screenshot_area {
+        ScreenshotArea::Displayed => {
+            let (window_width, window_height) = opt.window_size();
+            let to_pixels = |size: f64| size.round().max(1.0) as u32;
+            (
+                window_width.map_or(stage_width, to_pixels),
+                window_height.map_or(stage_height, to_pixels),
+            )
+        }
+        ScreenshotArea::Stage => (stage_width, stage_height),
+    };
+
+    let (executor, future_spawner) = AsyncExecutor::new(FramePollRequester);
+    let navigator = ExternalNavigatorBackend::new(
+        opt.base.clone().unwrap_or_else(|| movie_url.clone()),
+        future_spawner,
+        // Nothing may be opened outside of the player, as there's no one to ask.
+        NavigatorOptions {
+            proxy: opt.proxy.clone(),
+            upgrade_to_https: opt.upgrade_to_https,
+            log_requests: opt.log_requests,
+            max_connections: opt.max_connections,
+            file_root: opt.file_root.clone(),
+            ..Default::default()
+        },
+        Rc::new(PlayingContent::DirectFile(movie_url.clone())),
+        HeadlessNavigatorInterface,
+    );
+    let in_flight_requests = navigator.in_flight_requests();
+
+    let renderer = create_offscreen_renderer(preferences, width, height)?;
+    let player = PlayerBuilder::new()
+        .with_renderer(renderer)
+        .with_navigator(navigator)
+        .with_movie(movie)
+        .with_viewport_dimensions(width, height, 1.0)
+        .with_frame_rate(opt.frame_rate);
+    let player = match opt.screenshot_area {
+        ScreenshotArea::Displayed => player
+            .with_letterbox(opt.letterbox.unwrap_or(Letterbox::On))
+            .with_letterbox_style(LetterboxStyle {
+                color: opt.letterbox_color,
+                pattern: opt.letterbox_pattern,
+            })
+            .with_align(opt.align.unwrap_or_default(), opt.force_align)
+            .with_scale_mode(opt.scale.unwrap_or_default(), opt.force_scale),
+        // The viewport is exactly the size of the stage, so it's shown unscaled and without bars.
+        ScreenshotArea::Stage => player
+            .with_letterbox(Letterbox::Off)
+            .with_scale_mode(StageScaleMode::ExactFit, true),
+    }
+    .build();
+    if opt.transparent {
+        player
+            .lock()
+            .expect("Player lock must be available")
+            .set_window_mode("transparent");
+    }
+
+    let started = Instant::now();
+    let mut idle_since = None;
+    let mut frames = 0u32;
+    loop {
+        {
+            let mut player = player.lock().expect("Player lock must be available");
+            player.preload(&mut ExecutionLimit::none());
+            player.run_frame();
+        }
+        // Loaders lock the player themselves, so it can't be locked while they're polled.
+        executor.poll_all();
+        frames += 1;
+
+        if let Some(target_frame) = target_frame {
+            if frames >= target_frame {
+                break;
+            }
+            continue;
+        }
+
+        let now = Instant::now();
+        let pending = in_flight_requests.count();
+        if pending > 0 {
+            idle_since = None;
+        } else if now - *idle_since.get_or_insert(now) >= opt.network_idle {
+            break;
+        }
+        if now - started >= opt.screenshot_timeout {
+            tracing::warn!(
+                "Movie was still loading {pending} requests after {:?}, taking the screenshot anyway",
+                opt.screenshot_timeout
+            );
+            break;
+        }
+        std::thread::sleep(frame_duration);
+    }
+
+    let image = {
+        let mut player = player.lock().expect("Player lock must be available");
+        player.render();
+        player
+            .renderer_mut()
+            .downcast_mut::<WgpuRenderBackend<TextureTarget>>()
+            .and_then(|renderer| renderer.capture_frame())
+            .ok_or_else(|| anyhow!("Unable to capture frame {frames}"))?
+    };
+    image
+        .save(output)
+        .with_context(|| format!("Couldn't save the screenshot to {}", output.display()))?;
+
+    tracing::info!(
+        "Saved frame {frames} of {} to {}",
+        movie_path.display(),
+        output.display()
+    );
+    Ok(())
+}
+
+/// Loads a movie from the local filesystem, with the parameters given on the command line.
+fn load_local_movie(
+    preferences: &GlobalPreferences,
+    movie_url: &Url,
+) -> Result<(PathBuf, SwfMovie), Error> {
+    let movie_path = movie_url
+        .to_file_path()
+        .map_err(|_| anyhow!("Only local movies can be exported, not {movie_url}"))?;
+    let mut movie = SwfMovie::from_path(&movie_path, None)
+        .map_err(|e| anyhow!("Couldn't load {}: {e}", movie_path.display()))?;
+    movie.append_parameters(preferences.cli.parameters());
+    Ok((movie_path, movie))
+}
+
+/// Creates a renderer which draws to a texture of the given size, instead of a window.
+fn create_offscreen_renderer(
+    preferences: &GlobalPreferences,
+    width: u32,
+    height: u32,
+) -> Result<WgpuRenderBackend<TextureTarget>, Error> {
+    let (instance, backend) = create_wgpu_instance(preferences.graphics_backends().into())?;
+    let (adapter, device, queue) = futures::executor::block_on(request_adapter_and_device(
+        backend,
+        &instance,
+        None,
+        preferences.graphics_power_preference().into(),
+        preferences.cli.trace_path(),
+    ))
+    .map_err(|e| anyhow!(e.to_string()))?;
+    let descriptors = Arc::new(Descriptors::new(instance, adapter, device, queue));
+
+    let target = TextureTarget::new(&descriptors.device, (width, height))
+        .map_err(|e| anyhow!(e.to_string()))?;
+    WgpuRenderBackend::new(descriptors, target).map_err(|e| anyhow!(e.to_string()))
+}
+
+/// Nothing needs to be woken up when a task is ready, as tasks are polled after every frame.
+#[derive(Clone)]
+pub struct FramePollRequester;
+
+impl PollRequester for FramePollRequester {
+    fn request_poll(&self) {}
+}
+
+/// There's nobody to ask when exporting, so anything that needs confirmation is denied.
+#[derive(Clone)]
+pub struct HeadlessNavigatorInterface;
+
+impl NavigatorInterface for HeadlessNavigatorInterface {
+    fn confirm_website_navigation(&self, _url: &Url) -> bool {
+        false
+    }
+
+    fn open_file(&self, path: &Path) -> std::io::Result<File> {
+        File::open(path)
+    }
+
+    async fn confirm_socket(&self, _host: &str, _port: u16) -> bool {
+        false
+    }
+
+    async fn confirm_large_file(&self, _url: &Url, _size: u64) -> bool {
+        true
+    }
+
+    fn navigate_within_bundle(&self, url: &Url) {
+        // Only single movies are exported, not bundles.
+        tracing::warn!("Movie tried to navigate to {url}, which isn't supported when exporting");
+    }
+
+    fn navigate_in_place(&self, url: &Url) {
+        tracing::warn!("Movie tried to navigate to {url}, which isn't supported when exporting");
+    }
+}
+
+/// Reduces a frame to a palette of at most `colors` colors.
+fn quantize(image: &RgbaImage, colors: u16) -> gif::Frame<'static> {
+    // GIFs can't hold partial transparency, so everything is treated as opaque.
+    let quantizer = NeuQuant::new(10, usize::from(colors), image.as_raw());
+    let pixels: Vec<u8> = image
+        .pixels()
+        .map(|pixel| quantizer.index_of(&pixel.0) as u8)
+        .collect();
+    gif::Frame::from_palette_pixels(
+        image.width() as u16,
+        image.height() as u16,
+        pixels,
+        quantizer.color_map_rgb(),
+        None,
+    )
+}
@@ -0,0 +1,205 @@
+//! A static report of what a movie uses, made by looking through its tags without playing it.
+
+use anyhow::{anyhow, Error};
+use ruffle_core::swf::read::Reader;
+use ruffle_core::swf::TagCode;
+use std::collections::BTreeMap;
+
+/// Tags which Ruffle reads past without acting on, so anything relying on them won't work.
+const UNSUPPORTED_TAGS: &[TagCode] = &[
+    TagCode::DefineFontInfo,
+    TagCode::DefineFontInfo2,
+    TagCode::SetTabIndex,
+    TagCode::StartSound2,
+];
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct FeatureReport {
+    pub swf_version: u8,
+    pub action_script_3: bool,
+
+    /// How many times each tag appears, including inside movie clips.
+    pub tags: BTreeMap<u16, usize>,
+
+    pub uses_sockets: bool,
+    pub uses_external_interface: bool,
+    pub uses_shared_objects: bool,
+    pub uses_video: bool,
+}
+
+impl FeatureReport {
+    pub fn from_data(data: &[u8]) -> Result<Self, Error> {
+        let swf_buf = ruffle_core::swf::decompress_swf(data).map_err(|e| anyhow!("{e}"))?;
+        let mut report = Self {
+            swf_version: swf_buf.header.version(),
+            action_script_3: swf_buf.header.is_action_script_3(),
+            ..Default::default()
+        };
+        report.count_tags(&swf_buf.data, swf_buf.header.version())?;
+
+        // Scripts refer to these classes by name, whether in AVM1 actions or an ABC constant pool,
+        // so finding the name is a good enough hint without decoding every script.
+        let contains = |needle: &[u8]| {
+            swf_buf
+                .data
+                .windows(needle.len())
+                .any(|window| window == needle)
+        };
+        report.uses_sockets = contains(b"Socket");
+        report.uses_external_interface = contains(b"ExternalInterface");
+        report.uses_shared_objects = contains(b"SharedObject");
+        report.uses_video =
+            report.tag_count(TagCode::DefineVideoStream) > 0 || contains(b"NetStream");
+        Ok(report)
+    }
+
+    fn count_tags(&mut self, data: &[u8], version: u8) -> Result<(), Error> {
+        let mut reader = Reader::new(data, version);
+        while !reader.get_ref().is_empty() {
+            let (code, length) = reader
+                .read_tag_code_and_length()
+                .map_err(|e| anyhow!("{e}"))?;
+            let rest = reader.get_ref();
+            let body = rest
+                .get(..length)
+                .ok_or_else(|| anyhow!("{} tag is cut short", TagCode::format(code)))?;
+            *reader.get_mut() = &rest[length..];
+
+            if code == TagCode::End as u16 {
+                break;
+            }
+            *self.tags.entry(code).or_default() += 1;
+            if code == TagCode::DefineSprite as u16 {
+                // Skip the sprite's ID and frame count to reach its own tags.
+                self.count_tags(body.get(4..).unwrap_or_default(), version)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn tag_count(&self, tag_code: TagCode) -> usize {
+        self.tags
+            .get(&(tag_code as u16))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Tags in the movie which Ruffle either doesn't know or ignores.
+    pub fn unsupported_tags(&self) -> Vec<u16> {
+        self.tags
+            .keys()
+            .copied()
+            .filter(|&code| match TagCode::from_u16(code) {
+                Some(tag) => UNSUPPORTED_TAGS.contains(&tag),
+                None => true,
+            })
+            .collect()
+    }
+
+    pub fn to_text(&self) -> String {
+        let yes_no = |value: bool| if value { "yes" } else { "no" };
+        let mut text = format!(
+            "SWF version: {}\nActionScript version: {}\n\nTags:\n",
+            self.swf_version,
+            if self.action_script_3 { 3 } else { 2 }
+        );
+        for (&code, count) in &self.tags {
+            text += &format!("  {}: {count}\n", TagCode::format(code));
+        }
+        text += &format!(
+            "\nSockets: {}\nExternalInterface: {}\nShared objects: {}\nVideo: {}\n",
+            yes_no(self.uses_sockets),
+            yes_no(self.uses_external_interface),
+            yes_no(self.uses_shared_objects),
+            yes_no(self.uses_video)
+        );
+        let unsupported = self.unsupported_tags();
+        if unsupported.is_empty() {
+            text += "\nNo unsupported tags";
+        } else {
+            text += "\nUnsupported tags:\n";
+            for code in unsupported {
+                text += &format!("  {}\n", TagCode::format(code));
+            }
+        }
+        text.trim_end().to_string()
+    }
+
+    pub fn to_json(&self) -> String {
+        let tags = self
+            .tags
+            .iter()
+            .map(|(&code, &count)| (TagCode::format(code), serde_json::Value::from(count)))
+            .collect::<serde_json::Map<_, _>>();
+        let unsupported = self
+            .unsupported_tags()
+            .into_iter()
+            .map(TagCode::format)
+            .collect::<Vec<_>>();
+        let json = serde_json::json!({
+            "swf_version": self.swf_version,
+            "action_script_version": if self.action_script_3 { 3 } else { 2 },
+            "tags": tags,
+            "uses_sockets": self.uses_sockets,
+            "uses_external_interface": self.uses_external_interface,
+            "uses_shared_objects": self.uses_shared_objects,
+            "uses_video": self.uses_video,
+            "unsupported_tags": unsupported,
+        });
+        serde_json::to_string_pretty(&json).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ruffle_core::swf::{Header, Sprite, Tag};
+
+    fn swf(tags: &[Tag]) -> Vec<u8> {
+        let mut data = vec![];
+        ruffle_core::swf::write_swf(&Header::default_with_swf_version(10), tags, &mut data)
+            .expect("SWF should be written");
+        data
+    }
+
+    #[test]
+    fn counts_tags_inside_sprites() {
+        let data = swf(&[
+            Tag::DefineSprite(Sprite {
+                id: 1,
+                num_frames: 1,
+                tags: vec![
+                    Tag::ShowFrame,
+                    Tag::SetTabIndex {
+                        depth: 1,
+                        tab_index: 2,
+                    },
+                ],
+            }),
+            Tag::ShowFrame,
+            Tag::Unknown {
+                tag_code: 500,
+                data: &[1, 2, 3],
+            },
+        ]);
+        let report = FeatureReport::from_data(&data).expect("Report should be made");
+        assert_eq!(report.swf_version, 10);
+        assert!(!report.action_script_3);
+        assert_eq!(report.tag_count(TagCode::ShowFrame), 2);
+        assert_eq!(report.tag_count(TagCode::DefineSprite), 1);
+        assert_eq!(
+            report.unsupported_tags(),
+            vec![TagCode::SetTabIndex as u16, 500]
+        );
+    }
+
+    #[test]
+    fn finds_features_used_by_scripts() {
+        let data = swf(&[Tag::DoAction(b"\x96\x13\x00\x00ExternalInterface\x00")]);
+        let report = FeatureReport::from_data(&data).expect("Report should be made");
+        assert!(report.uses_external_interface);
+        assert!(!report.uses_sockets);
+        assert!(!report.uses_shared_objects);
+        assert!(!report.uses_video);
+    }
+}
@@ -0,0 +1,58 @@
+//! Skipping the rendering of frames when a movie falls behind real time, so that it keeps to
+//! its frame rate on slow machines.
+
+use std::num::NonZeroU32;
+use std::time::Duration;
+
+/// Decides which ticks are rendered, skipping those that are late.
+#[derive(Debug)]
+pub struct FrameSkipper {
+    /// The most ticks in a row that may go without being rendered.
+    max_skipped: NonZeroU32,
+    /// How many ticks in a row have gone without being rendered.
+    skipped: u32,
+}
+
+impl FrameSkipper {
+    pub fn new(max_skipped: NonZeroU32) -> Self {
+        Self {
+            max_skipped,
+            skipped: 0,
+        }
+    }
+
+    /// Whether a tick that happened `elapsed` after the previous one should be rendered,
+    /// for a movie with a frame every `frame_time`.
+    ///
+    /// A tick is late when it came more than a frame and a half after the previous one.
+    /// Late ticks aren't rendered, which gives the movie time to catch up, unless too many
+    /// of them in a row already weren't, so that the movie is never left frozen.
+    pub fn should_render(&mut self, elapsed: Duration, frame_time: Duration) -> bool {
+        let late = elapsed.as_secs_f64() > frame_time.as_secs_f64() * 1.5;
+        if late && self.skipped < self.max_skipped.get() {
+            self.skipped += 1;
+            false
+        } else {
+            self.skipped = 0;
+            true
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn skips_a_limited_number_of_late_ticks() {
+        let mut skipper = FrameSkipper::new(NonZeroU32::new(2).unwrap());
+        let frame_time = Duration::from_millis(40);
+        let late = Duration::from_millis(100);
+        assert!(skipper.should_render(Duration::from_millis(40), frame_time));
+        assert!(!skipper.should_render(late, frame_time));
+        assert!(!skipper.should_render(late, frame_time));
+        assert!(skipper.should_render(late, frame_time));
+        assert!(!skipper.should_render(late, frame_time));
+        assert!(skipper.should_render(Duration::from_millis(10), frame_time));
+    }
+}
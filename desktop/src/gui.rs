@@ -3,16 +3,23 @@ mod controller;
 mod dialogs;
 mod menu_bar;
 mod movie;
+mod splash;
+mod toast;
 mod widgets;
 
-pub use controller::GuiController;
-pub use movie::MovieView;
-use ruffle_frontend_utils::recents::Recent;
+pub use controller::{create_wgpu_instance, GuiController};
+pub use movie::{ColorFilter, MovieView, MovieZoom};
+use ruffle_frontend_utils::backends::navigator::NetworkPolicy;
+use ruffle_frontend_utils::recents::{content_hash, Recent};
+pub use splash::Splash;
 use std::borrow::Cow;
 use url::Url;
 
-use crate::custom_event::RuffleEvent;
+use crate::captions::Captions;
+use crate::cli::CaptionsPosition;
+use crate::custom_event::RuffleEventProxy;
 use crate::gui::context_menu::ContextMenu;
+use crate::gui::toast::Toast;
 use crate::player::LaunchOptions;
 use crate::preferences::GlobalPreferences;
 use dialogs::Dialogs;
@@ -26,8 +33,8 @@ use ruffle_core::Player;
 use std::collections::HashMap;
 use std::fs;
 use std::sync::MutexGuard;
+use std::time::{Duration, SystemTime};
 use unic_langid::LanguageIdentifier;
-use winit::event_loop::EventLoopProxy;
 
 static_loader! {
     static TEXTS = {
@@ -56,7 +63,6 @@ pub fn available_languages() -> Vec<&'static LanguageIdentifier> {
     result
 }
 
-#[allow(dead_code)]
 pub fn text_with_args<'a, T: AsRef<str>>(
     locale: &LanguageIdentifier,
     id: &'a str,
@@ -78,26 +84,42 @@ pub const MENU_HEIGHT: u32 = 24;
 
 /// The main controller for the Ruffle GUI.
 pub struct RuffleGui {
-    event_loop: EventLoopProxy<RuffleEvent>,
+    event_loop: RuffleEventProxy,
     context_menu: Option<ContextMenu>,
+    toast: Option<Toast>,
     dialogs: Dialogs,
     menu_bar: MenuBar,
 
     was_suspended_before_debug: bool,
+    /// Is the movie paused until the user clicks on it, as autoplay is off
+    awaiting_click_to_play: bool,
+    /// Shown while the movie loads, until its first frame is ready.
+    splash: Option<Splash>,
+    /// Captions shown over the movie, as given with `--captions`.
+    captions: Option<Captions>,
     preferences: GlobalPreferences,
 }
 
 impl RuffleGui {
     fn new(
-        event_loop: EventLoopProxy<RuffleEvent>,
+        event_loop: RuffleEventProxy,
         default_path: Option<Url>,
         default_launch_options: LaunchOptions,
         preferences: GlobalPreferences,
     ) -> Self {
+        let captions = preferences.cli.captions.as_deref().and_then(|path| {
+            Captions::load(path)
+                .inspect_err(|e| tracing::error!("{e:#}"))
+                .ok()
+        });
         Self {
             was_suspended_before_debug: false,
+            awaiting_click_to_play: false,
+            splash: None,
+            captions,
 
             context_menu: None,
+            toast: None,
             dialogs: Dialogs::new(
                 preferences.clone(),
                 default_launch_options.clone(),
@@ -132,6 +154,23 @@ impl RuffleGui {
 
         self.dialogs.show(&locale, egui_ctx, player.as_deref_mut());
 
+        if let Some(player) = player.as_deref() {
+            if player.current_frame().unwrap_or_default() > 0 {
+                self.splash = None;
+            }
+            if let Some(splash) = &self.splash {
+                splash.show(egui_ctx);
+            }
+
+            self.show_captions(egui_ctx, player, show_menu);
+
+            // The movie may have been started some other way, such as from the menu.
+            self.awaiting_click_to_play &= !player.is_playing();
+            if self.awaiting_click_to_play {
+                self.show_click_to_play(&locale, egui_ctx);
+            }
+        }
+
         if let Some(player) = player {
             let was_suspended = player.debug_ui().should_suspend_player();
             player.show_debug_ui(egui_ctx, menu_height_offset);
@@ -165,6 +204,71 @@ impl RuffleGui {
                 self.context_menu = None;
             }
         }
+
+        if let Some(toast) = &self.toast {
+            if !toast.show(egui_ctx) {
+                self.toast = None;
+            }
+        }
+    }
+
+    /// Shows the caption for the current time of the main timeline, if there is one.
+    /// As the time follows the current frame, the captions start over whenever the movie loops.
+    fn show_captions(&self, egui_ctx: &egui::Context, player: &Player, show_menu: bool) {
+        let Some(captions) = &self.captions else {
+            return;
+        };
+        let frame_rate = player.frame_rate();
+        let Some(frame) = player.current_frame().filter(|_| frame_rate > 0.0) else {
+            return;
+        };
+        let time = Duration::from_secs_f64(f64::from(frame.saturating_sub(1)) / frame_rate);
+        let Some(text) = captions.text_at(time) else {
+            return;
+        };
+
+        let cli = &self.preferences.cli;
+        let (anchor, offset) = match cli.captions_position {
+            CaptionsPosition::Top if show_menu => (Align2::CENTER_TOP, MENU_HEIGHT as f32 + 16.0),
+            CaptionsPosition::Top => (Align2::CENTER_TOP, 16.0),
+            CaptionsPosition::Bottom => (Align2::CENTER_BOTTOM, -16.0),
+        };
+        Area::new(Id::new("captions"))
+            .anchor(anchor, [0.0, offset])
+            .interactable(false)
+            .show(egui_ctx, |ui| {
+                Frame::none()
+                    .fill(Color32::from_black_alpha(180))
+                    .inner_margin(8.0)
+                    .rounding(4.0)
+                    .show(ui, |ui| {
+                        ui.label(
+                            RichText::new(text)
+                                .size(cli.captions_size)
+                                .color(Color32::WHITE),
+                        );
+                    });
+            });
+    }
+
+    /// Shows a prompt over the movie while it's waiting to be clicked on to start.
+    fn show_click_to_play(&self, locale: &LanguageIdentifier, egui_ctx: &egui::Context) {
+        Area::new(Id::new("click_to_play"))
+            .anchor(Align2::CENTER_CENTER, [0.0, 0.0])
+            .interactable(false)
+            .show(egui_ctx, |ui| {
+                Frame::none()
+                    .fill(Color32::from_black_alpha(180))
+                    .inner_margin(16.0)
+                    .rounding(8.0)
+                    .show(ui, |ui| {
+                        ui.label(
+                            RichText::new(text(locale, "click-to-play"))
+                                .size(24.0)
+                                .color(Color32::WHITE),
+                        );
+                    });
+            });
     }
 
     pub fn show_context_menu(&mut self, menu: Vec<ruffle_core::ContextMenuItem>) {
@@ -177,30 +281,103 @@ impl RuffleGui {
         self.context_menu.is_some()
     }
 
+    /// Notifies the GUI that the stored recents have changed.
+    pub fn on_recents_changed(&mut self) {
+        self.menu_bar.invalidate_recents();
+    }
+
+    /// Briefly shows a message to the user, replacing any message already shown.
+    pub fn show_toast(&mut self, message: String) {
+        self.toast = Some(Toast::new(message));
+    }
+
     /// Notifies the GUI that a new player was created.
     fn on_player_created(
         &mut self,
         opt: LaunchOptions,
         movie_url: Url,
+        name: Option<String>,
+        network_policy: NetworkPolicy,
+        splash: Option<Splash>,
         mut player: MutexGuard<Player>,
     ) {
+        self.splash = splash;
         self.menu_bar.currently_opened = Some((movie_url.clone(), opt.clone()));
+        self.menu_bar.network_policy = network_policy;
         let recent_limit = self.preferences.recent_limit();
-        if let Err(e) = self.preferences.write_recents(|writer| {
+        // Hashing reads the file, so only do it when asked to.
+        let content_hash = movie_url
+            .to_file_path()
+            .ok()
+            .filter(|_| self.preferences.recent_match_content())
+            .and_then(|path| content_hash(&path).ok());
+        self.preferences.update_recents(&self.event_loop, |writer| {
             writer.push(
                 Recent {
                     url: movie_url.clone(),
+                    name,
+                    pinned: false,
+                    content_hash,
+                    last_opened: SystemTime::now()
+                        .duration_since(SystemTime::UNIX_EPOCH)
+                        .ok()
+                        .map(|time| time.as_secs()),
+                    presentation: Default::default(),
                 },
                 recent_limit,
             )
-        }) {
-            tracing::warn!("Couldn't update recents: {e}");
-        }
+        });
+
+        self.awaiting_click_to_play = !opt.autoplay;
 
         // Update dialog state to reflect the newly-opened movie's options.
         self.dialogs
             .recreate_open_dialog(opt, Some(movie_url), self.event_loop.clone());
 
-        player.set_volume(self.dialogs.volume_controls.get_volume());
+        let volume = self.dialogs.interaction_mute.start(
+            self.preferences.cli.mute_on_start_until_click,
+            self.dialogs.volume_controls.get_volume(),
+        );
+        player.set_volume(volume);
+    }
+
+    /// Notifies the GUI that the preferences were reloaded from disk.
+    pub fn on_preferences_reloaded(&mut self, player: Option<&mut Player>) {
+        self.dialogs.on_preferences_reloaded();
+        if let Some(player) = player {
+            let volume = self.dialogs.volume_controls.get_volume();
+            self.set_volume(player, volume);
+        }
+        let recent_limit = self.preferences.recent_limit();
+        self.preferences.update_recents(&self.event_loop, |writer| {
+            writer.enforce_limit(recent_limit)
+        });
+    }
+
+    /// Whether the movie is paused until the user clicks on it.
+    pub fn is_awaiting_click_to_play(&self) -> bool {
+        self.awaiting_click_to_play
+    }
+
+    /// Starts the movie that was waiting for a click, as autoplay is off.
+    pub fn click_to_play(&mut self, player: &mut Player) {
+        if std::mem::take(&mut self.awaiting_click_to_play) {
+            player.set_is_playing(true);
+        }
+    }
+
+    /// Notifies the GUI that the user clicked on or typed into the movie.
+    pub fn on_user_interaction(&mut self, player: &mut Player) {
+        if let Some(volume) = self.dialogs.interaction_mute.on_interaction() {
+            player.set_volume(volume);
+        }
+    }
+
+    /// Changes the volume of the movie, unless it's muted until the user interacts with it,
+    /// in which case the volume is used once they do.
+    pub fn set_volume(&mut self, player: &mut Player, volume: f32) {
+        if let Some(volume) = self.dialogs.interaction_mute.set_volume(volume) {
+            player.set_volume(volume);
+        }
     }
 }
@@ -32,16 +32,49 @@ fn vs_main(
 
 @group(0) @binding(0) var r_tex_color: texture_2d<f32>;
 @group(0) @binding(1) var r_tex_sampler: sampler;
+/// Rows of the color filter matrix, see `ColorFilter::matrix`.
+@group(0) @binding(2) var<uniform> r_color_matrix: mat4x4<f32>;
+
+struct Lut {
+    /// Scales a color to the coordinates of the LUT texture, see `ColorLut::texture_transform`.
+    scale: vec3<f32>,
+    /// Whether a LUT is applied at all, as 0.0 or 1.0.
+    enabled: f32,
+    offset: vec3<f32>,
+};
+
+@group(0) @binding(3) var r_lut: texture_3d<f32>;
+@group(0) @binding(4) var<uniform> r_lut_transform: Lut;
+@group(0) @binding(5) var r_lut_sampler: sampler;
+
+/// Corrects the (premultiplied) color with the LUT, if there is one.
+fn apply_lut(color: vec4<f32>) -> vec4<f32> {
+    if( r_lut_transform.enabled == 0.0 || color.a <= 0.0 ) {
+        return color;
+    }
+    let coord = (color.rgb / color.a) * r_lut_transform.scale + r_lut_transform.offset;
+    // Sampling at an explicit level, as this isn't done by every fragment.
+    let corrected = textureSampleLevel(r_lut, r_lut_sampler, coord, 0.0).rgb;
+    return vec4<f32>(corrected * color.a, color.a);
+}
+
+/// Samples the movie with the color filter and LUT applied.
+fn sample_filtered(tex_coord: vec2<f32>) -> vec4<f32> {
+    let tex = textureSample(r_tex_color, r_tex_sampler, tex_coord);
+    // Each column of the uniform holds a row of the filter, so this multiplies the color by the filter.
+    let filtered = tex * r_color_matrix;
+    return apply_lut(vec4<f32>(clamp(filtered.rgb, vec3<f32>(0.0), vec3<f32>(filtered.a)), filtered.a));
+}
 
 @fragment
 fn fs_main_linear_framebuffer(in: VertexOutput) -> @location(0) vec4<f32> {
     // We always have a linear texture at the moment.
-    return textureSample(r_tex_color, r_tex_sampler, in.tex_coord);
+    return sample_filtered(in.tex_coord);
 }
 
 @fragment
 fn fs_main_srgb_framebuffer(in: VertexOutput) -> @location(0) vec4<f32> {
     // We always have a linear texture at the moment.
-    let tex = textureSample(r_tex_color, r_tex_sampler, in.tex_coord);
+    let tex = sample_filtered(in.tex_coord);
     return srgb_to_linear(tex);
 }
@@ -1,10 +1,9 @@
-use crate::custom_event::RuffleEvent;
+use crate::custom_event::{RuffleEvent, RuffleEventProxy};
 use egui::{
     vec2, Align, Area, Button, Checkbox, Color32, Frame, Id, Key, Layout, Modifiers, Order, Pos2,
     Stroke, Style, Widget,
 };
 use ruffle_core::ContextMenuItem;
-use winit::event_loop::EventLoopProxy;
 
 pub struct ContextMenu {
     items: Vec<ContextMenuItem>,
@@ -19,11 +18,7 @@ impl ContextMenu {
         }
     }
 
-    pub fn show(
-        &mut self,
-        egui_ctx: &egui::Context,
-        event_loop: &EventLoopProxy<RuffleEvent>,
-    ) -> bool {
+    pub fn show(&mut self, egui_ctx: &egui::Context, event_loop: &RuffleEventProxy) -> bool {
         let mut item_clicked = false;
         self.position = self.position.or(egui_ctx.pointer_latest_pos());
 
@@ -1,16 +1,21 @@
 use crate::backends::DesktopUiBackend;
-use crate::custom_event::RuffleEvent;
-use crate::gui::movie::{MovieView, MovieViewRenderer};
+use crate::cli::UpscaleFilter;
+use crate::custom_event::RuffleEventProxy;
+use crate::gui::movie::{MovieView, MovieViewRenderer, MovieZoom};
 use crate::gui::{RuffleGui, MENU_HEIGHT};
-use crate::player::{LaunchOptions, PlayerController};
+use crate::player::{ContentKind, LaunchOptions, PlayerController};
 use crate::preferences::GlobalPreferences;
 use anyhow::anyhow;
 use egui::{Context, ViewportId};
 use fontdb::{Database, Family, Query, Source};
+use rfd::{MessageButtons, MessageDialog, MessageLevel};
 use ruffle_core::Player;
-use ruffle_render_wgpu::backend::{request_adapter_and_device, WgpuRenderBackend};
+use ruffle_render_wgpu::backend::{
+    request_adapter_and_device, request_fallback_adapter_and_device, WgpuRenderBackend,
+};
 use ruffle_render_wgpu::descriptors::Descriptors;
 use ruffle_render_wgpu::utils::{format_list, get_backend_names};
+use std::path::Path;
 use std::rc::Rc;
 use std::sync::{Arc, MutexGuard};
 use std::time::{Duration, Instant};
@@ -18,7 +23,6 @@ use unic_langid::LanguageIdentifier;
 use url::Url;
 use winit::dpi::PhysicalSize;
 use winit::event::WindowEvent;
-use winit::event_loop::EventLoop;
 use winit::keyboard::{Key, NamedKey};
 use winit::window::{Theme, Window};
 
@@ -33,7 +37,11 @@ pub struct GuiController {
     repaint_after: Duration,
     surface: wgpu::Surface<'static>,
     surface_format: wgpu::TextureFormat,
+    /// How the window is composited with what's behind it, which is only see-through with `--transparent`.
+    surface_alpha_mode: wgpu::CompositeAlphaMode,
     movie_view_renderer: Arc<MovieViewRenderer>,
+    /// The zoom into the presented movie, which the movie itself is unaware of.
+    movie_zoom: MovieZoom,
     // Note that `window.get_inner_size` can change at any point on x11, even between two lines of code.
     // Use this instead.
     size: PhysicalSize<u32>,
@@ -42,42 +50,87 @@ pub struct GuiController {
 }
 
 impl GuiController {
+    /// Creates the GUI of a window. With `descriptors`, it draws with the same graphics device
+    /// as another window, otherwise a new device is set up for it.
     pub fn new(
         window: Rc<Window>,
-        event_loop: &EventLoop<RuffleEvent>,
+        event_loop: RuffleEventProxy,
+        descriptors: Option<Arc<Descriptors>>,
         preferences: GlobalPreferences,
         font_database: &Database,
         initial_movie_url: Option<Url>,
         no_gui: bool,
     ) -> anyhow::Result<Self> {
-        let (instance, backend) = create_wgpu_instance(preferences.graphics_backends().into())?;
-        let surface = unsafe {
-            instance.create_surface_unsafe(wgpu::SurfaceTargetUnsafe::from_window(window.as_ref())?)
-        }?;
-        let (adapter, device, queue) = futures::executor::block_on(request_adapter_and_device(
-            backend,
-            &instance,
-            Some(&surface),
-            preferences.graphics_power_preference().into(),
-            preferences.cli.trace_path(),
-        ))
-        .map_err(|e| anyhow!(e.to_string()))?;
-        let adapter_info = adapter.get_info();
-        tracing::info!(
-            "Using graphics API {} on {} (type: {:?})",
-            adapter_info.backend.to_str(),
-            adapter_info.name,
-            adapter_info.device_type
-        );
-        let surface_format = surface
-            .get_capabilities(&adapter)
+        let (descriptors, surface) = match descriptors {
+            Some(descriptors) => {
+                let surface = create_surface(&descriptors.wgpu_instance, &window)?;
+                (descriptors, surface)
+            }
+            None => {
+                let (instance, backend) =
+                    create_wgpu_instance(preferences.graphics_backends().into())?;
+                let surface = create_surface(&instance, &window)?;
+                let (adapter, device, queue) = futures::executor::block_on(async {
+                    if preferences.cli.safe_mode {
+                        match request_fallback_adapter_and_device(
+                            &instance,
+                            Some(&surface),
+                            preferences.cli.trace_path(),
+                        )
+                        .await
+                        {
+                            Ok(result) => return Ok(result),
+                            Err(e) => tracing::warn!(
+                                "Couldn't use the fallback graphics device, using the default one instead: {e}"
+                            ),
+                        }
+                    }
+                    request_adapter_and_device(
+                        backend,
+                        &instance,
+                        Some(&surface),
+                        preferences.graphics_power_preference().into(),
+                        preferences.cli.trace_path(),
+                    )
+                    .await
+                })
+                .map_err(|e| anyhow!(e.to_string()))?;
+                let adapter_info = adapter.get_info();
+                tracing::info!(
+                    "Using graphics API {} on {} (type: {:?})",
+                    adapter_info.backend.to_str(),
+                    adapter_info.name,
+                    adapter_info.device_type
+                );
+                let descriptors = Descriptors::new(instance, adapter, device, queue);
+                (Arc::new(descriptors), surface)
+            }
+        };
+        let capabilities = surface.get_capabilities(&descriptors.adapter);
+        let surface_format = capabilities
             .formats
             .first()
             .cloned()
-            .expect("At least one format should be supported");
+            .ok_or_else(|| anyhow!("The graphics device can't draw to this window"))?;
+        let surface_alpha_mode = if preferences.cli.transparent {
+            // The movie is drawn with premultiplied alpha, so that's preferred when it's available.
+            [
+                wgpu::CompositeAlphaMode::PreMultiplied,
+                wgpu::CompositeAlphaMode::PostMultiplied,
+                wgpu::CompositeAlphaMode::Inherit,
+            ]
+            .into_iter()
+            .find(|mode| capabilities.alpha_modes.contains(mode))
+            .unwrap_or_else(|| {
+                tracing::warn!("This window can't be made transparent on this system");
+                wgpu::CompositeAlphaMode::Auto
+            })
+        } else {
+            wgpu::CompositeAlphaMode::Auto
+        };
         let size = window.inner_size();
         surface.configure(
-            &device,
+            &descriptors.device,
             &wgpu::SurfaceConfiguration {
                 usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
                 format: surface_format,
@@ -85,11 +138,10 @@ impl GuiController {
                 height: size.height,
                 present_mode: Default::default(),
                 desired_maximum_frame_latency: 2,
-                alpha_mode: Default::default(),
+                alpha_mode: surface_alpha_mode,
                 view_formats: Default::default(),
             },
         );
-        let descriptors = Descriptors::new(instance, adapter, device, queue);
         let egui_ctx = Context::default();
         if let Some(Theme::Light) = window.theme() {
             egui_ctx.set_visuals(egui::Visuals::light());
@@ -101,14 +153,19 @@ impl GuiController {
 
         let movie_view_renderer = Arc::new(MovieViewRenderer::new(
             &descriptors.device,
+            &descriptors.queue,
             surface_format,
             window.fullscreen().is_none() && !no_gui,
             size.height,
             window.scale_factor(),
+            preferences.cli.color_lut.as_deref(),
+            match preferences.cli.upscale_filter {
+                UpscaleFilter::Linear => wgpu::FilterMode::Linear,
+                UpscaleFilter::Nearest => wgpu::FilterMode::Nearest,
+            },
         ));
+        movie_view_renderer.set_color_filter(&descriptors, preferences.color_filter());
         let egui_renderer = egui_wgpu::Renderer::new(&descriptors.device, surface_format, None, 1);
-        let event_loop = event_loop.create_proxy();
-        let descriptors = Arc::new(descriptors);
         let gui = RuffleGui::new(
             event_loop,
             initial_movie_url.clone(),
@@ -131,7 +188,9 @@ impl GuiController {
             repaint_after: Duration::ZERO,
             surface,
             surface_format,
+            surface_alpha_mode,
             movie_view_renderer,
+            movie_zoom: MovieZoom::default(),
             size,
             no_gui,
         })
@@ -152,17 +211,35 @@ impl GuiController {
                     height: size.height,
                     present_mode: Default::default(),
                     desired_maximum_frame_latency: 2,
-                    alpha_mode: Default::default(),
+                    alpha_mode: self.surface_alpha_mode,
                     view_formats: Default::default(),
                 },
             );
-            self.movie_view_renderer.update_resolution(
-                &self.descriptors,
-                self.window.fullscreen().is_none() && !self.no_gui,
-                size.height,
-                self.window.scale_factor(),
-            );
             self.size = size;
+            self.update_movie_view();
+        }
+    }
+
+    fn update_movie_view(&self) {
+        self.movie_view_renderer.update_resolution(
+            &self.descriptors,
+            self.window.fullscreen().is_none() && !self.no_gui,
+            self.size.height,
+            self.window.scale_factor(),
+            self.movie_zoom,
+        );
+    }
+
+    pub fn movie_zoom(&self) -> MovieZoom {
+        self.movie_zoom
+    }
+
+    /// Changes which part of the movie is shown, without the movie being aware of it.
+    pub fn set_movie_zoom(&mut self, zoom: MovieZoom) {
+        if self.movie_zoom != zoom {
+            self.movie_zoom = zoom;
+            self.update_movie_view();
+            self.window.request_redraw();
         }
     }
 
@@ -213,10 +290,69 @@ impl GuiController {
             self.size.width,
             self.size.height,
         );
-        player.create(&opt, &movie_url, movie_view);
+        if let Err(e) = player.create(&opt, &movie_url, movie_view) {
+            tracing::error!("Couldn't play {movie_url}: {e:#}");
+            let _guard = player.blocking_dialog_guard();
+            MessageDialog::new()
+                .set_level(MessageLevel::Error)
+                .set_title("Ruffle - Load failed")
+                .set_description(e.to_string())
+                .set_buttons(MessageButtons::Ok)
+                .show();
+            return;
+        }
+        self.on_player_created(player, opt, movie_url);
+    }
+
+    /// Plays another SWF of the bundle being played, as the movie navigated to it.
+    pub fn navigate_within_bundle(&mut self, player: &mut PlayerController, swf_url: Url) {
+        if player.content_kind() != Some(ContentKind::Bundle) {
+            return;
+        }
+        let (Some(opt), Some(movie_url)) =
+            (player.launch_options().cloned(), player.url().cloned())
+        else {
+            return;
+        };
+        let movie_view = MovieView::new(
+            self.movie_view_renderer.clone(),
+            &self.descriptors.device,
+            self.size.width,
+            self.size.height,
+        );
+        if player.navigate_within_bundle(&swf_url, movie_view) {
+            self.on_player_created(player, opt, movie_url);
+        }
+    }
+
+    /// Plays the SWF at `path` in place of the root SWF of the content being played.
+    pub fn swap_root_swf(&mut self, player: &mut PlayerController, path: &Path) {
+        let Some(opt) = player.launch_options().cloned() else {
+            return;
+        };
+        let movie_view = MovieView::new(
+            self.movie_view_renderer.clone(),
+            &self.descriptors.device,
+            self.size.width,
+            self.size.height,
+        );
+        if let Err(e) = player.swap_root_swf(path, movie_view) {
+            tracing::error!("Couldn't swap the root SWF: {e:#}");
+            return;
+        }
+        if let Some(movie_url) = player.url().cloned() {
+            self.on_player_created(player, opt, movie_url);
+        }
+    }
+
+    fn on_player_created(&mut self, player: &PlayerController, opt: LaunchOptions, movie_url: Url) {
+        self.set_movie_zoom(MovieZoom::default());
         self.gui.on_player_created(
             opt,
             movie_url,
+            player.name().map(str::to_owned),
+            player.network_policy(),
+            player.splash(),
             player
                 .get()
                 .expect("Player must exist after being created."),
@@ -242,6 +378,21 @@ impl GuiController {
                     0.0
                 },
             );
+
+            // Draw the content's own cursor image in place of the arrow, when it has one
+            if let Some(player) = player.as_deref() {
+                let custom_cursor = player
+                    .ui()
+                    .downcast_ref::<DesktopUiBackend>()
+                    .and_then(|ui| ui.custom_cursor());
+                if let (Some(cursor), Some(pos), false) = (
+                    custom_cursor,
+                    context.pointer_hover_pos(),
+                    context.wants_pointer_input(),
+                ) {
+                    cursor.paint(context, pos);
+                }
+            }
         });
         self.repaint_after = full_output
             .viewport_output
@@ -315,7 +466,13 @@ impl GuiController {
                     view: &surface_view,
                     resolve_target: None,
                     ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                        load: wgpu::LoadOp::Clear(
+                            if self.surface_alpha_mode == wgpu::CompositeAlphaMode::Auto {
+                                wgpu::Color::BLACK
+                            } else {
+                                wgpu::Color::TRANSPARENT
+                            },
+                        ),
                         store: wgpu::StoreOp::Store,
                     },
                 })],
@@ -348,6 +505,53 @@ impl GuiController {
         self.gui.is_context_menu_visible()
     }
 
+    pub fn on_recents_changed(&mut self) {
+        self.gui.on_recents_changed();
+        self.window.request_redraw();
+    }
+
+    pub fn on_preferences_reloaded(&mut self, player: Option<&mut Player>) {
+        self.gui.on_preferences_reloaded(player);
+        self.update_color_filter();
+    }
+
+    /// Applies the color filter from the preferences to the presented movie.
+    pub fn update_color_filter(&self) {
+        self.movie_view_renderer
+            .set_color_filter(&self.descriptors, self.gui.preferences.color_filter());
+        self.window.request_redraw();
+    }
+
+    pub fn on_user_interaction(&mut self, player: &mut Player) {
+        self.gui.on_user_interaction(player);
+    }
+
+    pub fn set_volume(&mut self, player: &mut Player, volume: f32) {
+        self.gui.set_volume(player, volume);
+    }
+
+    pub fn is_awaiting_click_to_play(&self) -> bool {
+        self.gui.is_awaiting_click_to_play()
+    }
+
+    pub fn click_to_play(&mut self, player: &mut Player) {
+        self.gui.click_to_play(player);
+        self.window.request_redraw();
+    }
+
+    /// Copies the text to the system clipboard, once the next frame is drawn.
+    pub fn copy_to_clipboard(&mut self, text: String) {
+        self.egui_winit
+            .egui_ctx()
+            .output_mut(|output| output.copied_text = text);
+        self.window.request_redraw();
+    }
+
+    pub fn show_toast(&mut self, message: String) {
+        self.gui.show_toast(message);
+        self.window.request_redraw();
+    }
+
     pub fn needs_render(&self) -> bool {
         Instant::now().duration_since(self.last_update) >= self.repaint_after
     }
@@ -357,7 +561,16 @@ impl GuiController {
     }
 }
 
-fn create_wgpu_instance(
+fn create_surface(
+    instance: &wgpu::Instance,
+    window: &Window,
+) -> anyhow::Result<wgpu::Surface<'static>> {
+    let surface =
+        unsafe { instance.create_surface_unsafe(wgpu::SurfaceTargetUnsafe::from_window(window)?) }?;
+    Ok(surface)
+}
+
+pub fn create_wgpu_instance(
     preferred_backends: wgpu::Backends,
 ) -> anyhow::Result<(wgpu::Instance, wgpu::Backends)> {
     for backend in preferred_backends.iter() {
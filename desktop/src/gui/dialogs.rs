@@ -4,8 +4,8 @@ mod open_dialog;
 mod preferences_dialog;
 mod volume_controls;
 
-use crate::custom_event::RuffleEvent;
-use crate::player::LaunchOptions;
+use crate::custom_event::RuffleEventProxy;
+use crate::player::{InteractionMute, LaunchOptions};
 use crate::preferences::GlobalPreferences;
 use bookmarks_dialog::{BookmarkAddDialog, BookmarksDialog};
 use open_dialog::OpenDialog;
@@ -14,7 +14,6 @@ use ruffle_core::Player;
 use unic_langid::LanguageIdentifier;
 use url::Url;
 use volume_controls::VolumeControls;
-use winit::event_loop::EventLoopProxy;
 
 pub struct Dialogs {
     preferences_dialog: Option<PreferencesDialog>,
@@ -25,11 +24,15 @@ pub struct Dialogs {
     is_open_dialog_visible: bool,
 
     pub volume_controls: VolumeControls,
+    /// Holds the movie's volume back until the user interacts with it, if asked to.
+    /// This outlives the volume controls, which are recreated when the preferences are reloaded.
+    pub interaction_mute: InteractionMute,
     is_volume_visible: bool,
 
     is_about_visible: bool,
 
     preferences: GlobalPreferences,
+    event_loop: RuffleEventProxy,
 }
 
 impl Dialogs {
@@ -37,22 +40,29 @@ impl Dialogs {
         preferences: GlobalPreferences,
         player_options: LaunchOptions,
         default_path: Option<Url>,
-        event_loop: EventLoopProxy<RuffleEvent>,
+        event_loop: RuffleEventProxy,
     ) -> Self {
         Self {
             preferences_dialog: None,
             bookmarks_dialog: None,
             bookmark_add_dialog: None,
 
-            open_dialog: OpenDialog::new(player_options, default_path, event_loop),
+            open_dialog: OpenDialog::new(
+                player_options,
+                default_path,
+                event_loop.clone(),
+                preferences.clone(),
+            ),
             is_open_dialog_visible: false,
 
             volume_controls: VolumeControls::new(&preferences),
+            interaction_mute: InteractionMute::default(),
             is_volume_visible: false,
 
             is_about_visible: false,
 
             preferences,
+            event_loop,
         }
     }
 
@@ -60,10 +70,10 @@ impl Dialogs {
         &mut self,
         opt: LaunchOptions,
         url: Option<Url>,
-        event_loop: EventLoopProxy<RuffleEvent>,
+        event_loop: RuffleEventProxy,
     ) {
         self.is_open_dialog_visible = false;
-        self.open_dialog = OpenDialog::new(opt, url, event_loop);
+        self.open_dialog = OpenDialog::new(opt, url, event_loop, self.preferences.clone());
     }
 
     pub fn open_file_advanced(&mut self) {
@@ -71,7 +81,10 @@ impl Dialogs {
     }
 
     pub fn open_preferences(&mut self) {
-        self.preferences_dialog = Some(PreferencesDialog::new(self.preferences.clone()));
+        self.preferences_dialog = Some(PreferencesDialog::new(
+            self.preferences.clone(),
+            self.event_loop.clone(),
+        ));
     }
 
     pub fn open_bookmarks(&mut self) {
@@ -85,6 +98,14 @@ impl Dialogs {
         ))
     }
 
+    /// Refreshes any dialogs showing preferences, after they were changed outside of Ruffle.
+    pub fn on_preferences_reloaded(&mut self) {
+        self.volume_controls = VolumeControls::new(&self.preferences);
+        if self.preferences_dialog.is_some() {
+            self.open_preferences();
+        }
+    }
+
     pub fn open_volume_controls(&mut self) {
         self.is_volume_visible = true;
     }
@@ -154,9 +175,13 @@ impl Dialogs {
         player: Option<&mut Player>,
     ) {
         if self.is_volume_visible {
-            let keep_open = self
-                .volume_controls
-                .show(locale, egui_ctx, player, &self.preferences);
+            let keep_open = self.volume_controls.show(
+                locale,
+                egui_ctx,
+                player,
+                &mut self.interaction_mute,
+                &self.preferences,
+            );
             self.is_volume_visible = keep_open;
         }
     }
@@ -1,7 +1,8 @@
-use crate::custom_event::RuffleEvent;
+use crate::custom_event::{RuffleEvent, RuffleEventProxy};
 use crate::gui::text;
 use crate::gui::widgets::PathOrUrlField;
 use crate::player::LaunchOptions;
+use crate::preferences::GlobalPreferences;
 use egui::{
     emath, Align2, Button, Checkbox, ComboBox, Grid, Layout, Slider, TextEdit, Ui, Widget, Window,
 };
@@ -14,16 +15,18 @@ use std::ops::RangeInclusive;
 use std::time::Duration;
 use unic_langid::LanguageIdentifier;
 use url::Url;
-use winit::event_loop::EventLoopProxy;
 
 pub struct OpenDialog {
     options: LaunchOptions,
-    event_loop: EventLoopProxy<RuffleEvent>,
+    event_loop: RuffleEventProxy,
+    preferences: GlobalPreferences,
 
     // These are outside of PlayerOptions as it can be an invalid value (ie URL) during typing,
     // and we don't want to clear the value if the user, ie, toggles the checkbox.
     spoof_url: OptionalField<UrlField>,
     base_url: OptionalField<UrlField>,
+    /// Whether to save the base URL, so that it's used whenever the movie is opened.
+    remember_base_url: bool,
     proxy_url: OptionalField<UrlField>,
     path: PathOrUrlField,
 
@@ -47,7 +50,8 @@ impl OpenDialog {
     pub fn new(
         defaults: LaunchOptions,
         default_url: Option<Url>,
-        event_loop: EventLoopProxy<RuffleEvent>,
+        event_loop: RuffleEventProxy,
+        preferences: GlobalPreferences,
     ) -> Self {
         let spoof_url = OptionalField::new(
             defaults.player.spoof_url.as_ref().map(Url::to_string),
@@ -241,8 +245,10 @@ impl OpenDialog {
         Self {
             options: defaults,
             event_loop,
+            preferences,
             spoof_url,
             base_url,
+            remember_base_url: false,
             proxy_url,
             path,
             framerate: 30.0,
@@ -268,6 +274,15 @@ impl OpenDialog {
             self.options.player.frame_rate = None;
         }
         if let Some(url) = self.path.value() {
+            if self.remember_base_url {
+                let base = self.options.player.base.clone();
+                if let Err(e) = self
+                    .preferences
+                    .write_preferences(|writer| writer.set_movie_base(url, base))
+                {
+                    tracing::error!("Could not save the base URL of {url}: {e}");
+                }
+            }
             if self
                 .event_loop
                 .send_event(RuffleEvent::OpenURL(
@@ -347,6 +362,13 @@ impl OpenDialog {
                     .is_valid();
                 ui.end_row();
 
+                ui.label("");
+                ui.checkbox(
+                    &mut self.remember_base_url,
+                    text(locale, "custom-base-url-remember"),
+                );
+                ui.end_row();
+
                 ui.label(text(locale, "spoof-swf-url"));
                 is_valid &= self
                     .spoof_url
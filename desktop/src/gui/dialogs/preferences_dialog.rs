@@ -1,8 +1,12 @@
+use crate::backends::FullscreenRequests;
+use crate::custom_event::RuffleEventProxy;
 use crate::gui::{available_languages, optional_text, text};
 use crate::log::FilenamePattern;
-use crate::preferences::{storage::StorageBackend, GlobalPreferences};
+use crate::preferences::{storage::StorageBackend, GlobalPreferences, MouseWheelDirection};
+use crate::touch::TouchEmulation;
 use cpal::traits::{DeviceTrait, HostTrait};
 use egui::{Align2, Button, ComboBox, DragValue, Grid, Ui, Widget, Window};
+use ruffle_core::events::KeyCode;
 use ruffle_render_wgpu::clap::{GraphicsBackend, PowerPreference};
 use std::borrow::Cow;
 use unic_langid::LanguageIdentifier;
@@ -10,6 +14,7 @@ use unic_langid::LanguageIdentifier;
 pub struct PreferencesDialog {
     available_backends: wgpu::Backends,
     preferences: GlobalPreferences,
+    event_loop: RuffleEventProxy,
 
     graphics_backend: GraphicsBackend,
     graphics_backend_readonly: bool,
@@ -29,6 +34,41 @@ pub struct PreferencesDialog {
     recent_limit: usize,
     recent_limit_changed: bool,
 
+    recent_match_content: bool,
+    recent_match_content_changed: bool,
+
+    auto_hide_cursor: bool,
+    auto_hide_cursor_changed: bool,
+
+    autoplay: bool,
+    autoplay_readonly: bool,
+    autoplay_changed: bool,
+
+    keep_awake: bool,
+    keep_awake_readonly: bool,
+    keep_awake_changed: bool,
+
+    play_stats: bool,
+    play_stats_readonly: bool,
+    play_stats_changed: bool,
+
+    fullscreen_requests: FullscreenRequests,
+    fullscreen_requests_changed: bool,
+
+    open_url_requests: Option<bool>,
+    open_url_requests_changed: bool,
+    have_open_url_hosts: bool,
+    forget_open_url_hosts: bool,
+
+    mouse_wheel_up: Option<KeyCode>,
+    mouse_wheel_up_changed: bool,
+
+    mouse_wheel_down: Option<KeyCode>,
+    mouse_wheel_down_changed: bool,
+
+    touch_emulation: TouchEmulation,
+    touch_emulation_changed: bool,
+
     log_filename_pattern: FilenamePattern,
     log_filename_pattern_changed: bool,
 
@@ -38,7 +78,7 @@ pub struct PreferencesDialog {
 }
 
 impl PreferencesDialog {
-    pub fn new(preferences: GlobalPreferences) -> Self {
+    pub fn new(preferences: GlobalPreferences, event_loop: RuffleEventProxy) -> Self {
         let available_backends = find_available_graphics_backends();
 
         let audio_host = cpal::default_host();
@@ -71,6 +111,40 @@ impl PreferencesDialog {
             recent_limit: preferences.recent_limit(),
             recent_limit_changed: false,
 
+            recent_match_content: preferences.recent_match_content(),
+            recent_match_content_changed: false,
+
+            auto_hide_cursor: preferences.auto_hide_cursor(),
+            auto_hide_cursor_changed: false,
+
+            autoplay: preferences.autoplay(),
+            autoplay_readonly: preferences.cli.no_autoplay,
+            autoplay_changed: false,
+            keep_awake: preferences.keep_awake(),
+            keep_awake_readonly: preferences.cli.keep_awake.is_some(),
+            keep_awake_changed: false,
+
+            play_stats: preferences.play_stats_enabled(),
+            play_stats_readonly: preferences.cli.play_stats.is_some(),
+            play_stats_changed: false,
+
+            fullscreen_requests: preferences.fullscreen_requests(),
+            fullscreen_requests_changed: false,
+
+            open_url_requests: preferences.open_url_requests(),
+            open_url_requests_changed: false,
+            have_open_url_hosts: preferences.have_remembered_open_url_hosts(),
+            forget_open_url_hosts: false,
+
+            mouse_wheel_up: preferences.mouse_wheel_key(MouseWheelDirection::Up),
+            mouse_wheel_up_changed: false,
+
+            mouse_wheel_down: preferences.mouse_wheel_key(MouseWheelDirection::Down),
+            mouse_wheel_down_changed: false,
+
+            touch_emulation: preferences.touch_emulation(),
+            touch_emulation_changed: false,
+
             log_filename_pattern: preferences.log_filename_pattern(),
             log_filename_pattern_changed: false,
 
@@ -79,6 +153,7 @@ impl PreferencesDialog {
             storage_backend_changed: false,
 
             preferences,
+            event_loop,
         }
     }
 
@@ -333,15 +408,157 @@ impl PreferencesDialog {
             }
 
             if ui.button(text(locale, "recent-clear")).clicked() {
-                if let Err(e) = self.preferences.write_recents(|writer| {
-                    writer.clear();
-                }) {
-                    tracing::warn!("Couldn't update recents: {e}");
+                self.preferences
+                    .update_recents(&self.event_loop, |writer| writer.clear());
+            }
+        });
+
+        ui.end_row();
+
+        ui.label(text(locale, "recent-match-content"));
+        if ui
+            .checkbox(
+                &mut self.recent_match_content,
+                text(locale, "recent-match-content-check"),
+            )
+            .changed()
+        {
+            self.recent_match_content_changed = true;
+        }
+        ui.end_row();
+
+        ui.label(text(locale, "auto-hide-cursor"));
+        if ui
+            .checkbox(
+                &mut self.auto_hide_cursor,
+                text(locale, "auto-hide-cursor-check"),
+            )
+            .changed()
+        {
+            self.auto_hide_cursor_changed = true;
+        }
+        ui.end_row();
+
+        ui.label(text(locale, "autoplay"));
+        ui.add_enabled_ui(!self.autoplay_readonly, |ui| {
+            if ui
+                .checkbox(&mut self.autoplay, text(locale, "autoplay-check"))
+                .on_disabled_hover_text(text(locale, "preference-locked-by-cli"))
+                .changed()
+            {
+                self.autoplay_changed = true;
+            }
+        });
+        ui.end_row();
+
+        ui.label(text(locale, "keep-awake"));
+        ui.add_enabled_ui(!self.keep_awake_readonly, |ui| {
+            if ui
+                .checkbox(&mut self.keep_awake, text(locale, "keep-awake-check"))
+                .on_disabled_hover_text(text(locale, "preference-locked-by-cli"))
+                .changed()
+            {
+                self.keep_awake_changed = true;
+            }
+        });
+        ui.end_row();
+
+        ui.label(text(locale, "play-stats"));
+        ui.add_enabled_ui(!self.play_stats_readonly, |ui| {
+            if ui
+                .checkbox(&mut self.play_stats, text(locale, "play-stats-check"))
+                .on_disabled_hover_text(text(locale, "preference-locked-by-cli"))
+                .changed()
+            {
+                self.play_stats_changed = true;
+            }
+        });
+        ui.end_row();
+
+        ui.label(text(locale, "fullscreen-requests"));
+        let previous = self.fullscreen_requests;
+        ComboBox::from_id_source("fullscreen-requests")
+            .selected_text(fullscreen_requests_name(locale, self.fullscreen_requests))
+            .show_ui(ui, |ui| {
+                for requests in [
+                    FullscreenRequests::Allow,
+                    FullscreenRequests::Deny,
+                    FullscreenRequests::Ask,
+                ] {
+                    ui.selectable_value(
+                        &mut self.fullscreen_requests,
+                        requests,
+                        fullscreen_requests_name(locale, requests),
+                    );
                 }
+            });
+        if self.fullscreen_requests != previous {
+            self.fullscreen_requests_changed = true;
+        }
+        ui.end_row();
+
+        ui.label(text(locale, "open-url-requests"));
+        ui.horizontal(|ui| {
+            let previous = self.open_url_requests;
+            ComboBox::from_id_source("open-url-requests")
+                .selected_text(open_url_requests_name(locale, self.open_url_requests))
+                .show_ui(ui, |ui| {
+                    for requests in [Some(true), Some(false), None] {
+                        ui.selectable_value(
+                            &mut self.open_url_requests,
+                            requests,
+                            open_url_requests_name(locale, requests),
+                        );
+                    }
+                });
+            if self.open_url_requests != previous {
+                self.open_url_requests_changed = true;
+            }
+            if ui
+                .add_enabled(
+                    self.have_open_url_hosts && !self.forget_open_url_hosts,
+                    Button::new(text(locale, "open-url-requests-forget")),
+                )
+                .clicked()
+            {
+                self.forget_open_url_hosts = true;
             }
         });
+        ui.end_row();
+
+        ui.label(text(locale, "mouse-wheel-up"));
+        if mouse_wheel_key_combo(ui, locale, "mouse-wheel-up", &mut self.mouse_wheel_up) {
+            self.mouse_wheel_up_changed = true;
+        }
+        ui.end_row();
 
-        ui.end_row()
+        ui.label(text(locale, "mouse-wheel-down"));
+        if mouse_wheel_key_combo(ui, locale, "mouse-wheel-down", &mut self.mouse_wheel_down) {
+            self.mouse_wheel_down_changed = true;
+        }
+        ui.end_row();
+
+        ui.label(text(locale, "touch-emulation"));
+        let previous = self.touch_emulation;
+        ComboBox::from_id_source("touch-emulation")
+            .selected_text(touch_emulation_name(locale, self.touch_emulation))
+            .show_ui(ui, |ui| {
+                for emulation in [
+                    TouchEmulation::Off,
+                    TouchEmulation::FirstTouch,
+                    TouchEmulation::LatestTouch,
+                ] {
+                    ui.selectable_value(
+                        &mut self.touch_emulation,
+                        emulation,
+                        touch_emulation_name(locale, emulation),
+                    );
+                }
+            });
+        if self.touch_emulation != previous {
+            self.touch_emulation_changed = true;
+        }
+        ui.end_row();
     }
 
     fn save(&mut self) {
@@ -368,10 +585,50 @@ impl PreferencesDialog {
             if self.recent_limit_changed {
                 preferences.set_recent_limit(self.recent_limit);
             }
+            if self.recent_match_content_changed {
+                preferences.set_recent_match_content(self.recent_match_content);
+            }
+            if self.auto_hide_cursor_changed {
+                preferences.set_auto_hide_cursor(self.auto_hide_cursor);
+            }
+            if self.autoplay_changed {
+                preferences.set_autoplay(self.autoplay);
+            }
+            if self.keep_awake_changed {
+                preferences.set_keep_awake(self.keep_awake);
+            }
+            if self.play_stats_changed {
+                preferences.set_play_stats(self.play_stats);
+            }
+            if self.fullscreen_requests_changed {
+                preferences.set_fullscreen_requests(self.fullscreen_requests);
+            }
+            if self.open_url_requests_changed {
+                preferences.set_open_url_requests(self.open_url_requests);
+            }
+            if self.forget_open_url_hosts {
+                preferences.forget_open_url_hosts();
+            }
+            if self.mouse_wheel_up_changed {
+                preferences.set_mouse_wheel_key(MouseWheelDirection::Up, self.mouse_wheel_up);
+            }
+            if self.mouse_wheel_down_changed {
+                preferences.set_mouse_wheel_key(MouseWheelDirection::Down, self.mouse_wheel_down);
+            }
+            if self.touch_emulation_changed {
+                preferences.set_touch_emulation(self.touch_emulation);
+            }
         }) {
             // [NA] TODO: Better error handling... everywhere in desktop, really
             tracing::error!("Could not save preferences: {e}");
         }
+
+        if self.recent_limit_changed {
+            // Trim the existing recents right away, rather than on the next push.
+            let limit = self.recent_limit;
+            self.preferences
+                .update_recents(&self.event_loop, |writer| writer.enforce_limit(limit));
+        }
     }
 }
 
@@ -412,6 +669,56 @@ fn storage_backend_name(locale: &LanguageIdentifier, backend: StorageBackend) ->
     }
 }
 
+fn fullscreen_requests_name(
+    locale: &LanguageIdentifier,
+    fullscreen_requests: FullscreenRequests,
+) -> Cow<str> {
+    match fullscreen_requests {
+        FullscreenRequests::Allow => text(locale, "fullscreen-requests-allow"),
+        FullscreenRequests::Deny => text(locale, "fullscreen-requests-deny"),
+        FullscreenRequests::Ask => text(locale, "fullscreen-requests-ask"),
+    }
+}
+
+fn touch_emulation_name(locale: &LanguageIdentifier, touch_emulation: TouchEmulation) -> Cow<str> {
+    match touch_emulation {
+        TouchEmulation::Off => text(locale, "touch-emulation-off"),
+        TouchEmulation::FirstTouch => text(locale, "touch-emulation-first-touch"),
+        TouchEmulation::LatestTouch => text(locale, "touch-emulation-latest-touch"),
+    }
+}
+
+/// Shows the keys that the mouse wheel can be mapped to, returning whether the choice changed.
+fn mouse_wheel_key_combo(
+    ui: &mut Ui,
+    locale: &LanguageIdentifier,
+    id: &str,
+    key: &mut Option<KeyCode>,
+) -> bool {
+    let key_name = |key: Option<KeyCode>| match key {
+        Some(key) => Cow::Owned(format!("{key:?}")),
+        None => text(locale, "mouse-wheel-key-none"),
+    };
+    let previous = *key;
+    ComboBox::from_id_source(id)
+        .selected_text(key_name(*key))
+        .show_ui(ui, |ui| {
+            for choice in [
+                None,
+                Some(KeyCode::Up),
+                Some(KeyCode::Down),
+                Some(KeyCode::Left),
+                Some(KeyCode::Right),
+                Some(KeyCode::PgUp),
+                Some(KeyCode::PgDown),
+                Some(KeyCode::Space),
+            ] {
+                ui.selectable_value(key, choice, key_name(choice));
+            }
+        });
+    *key != previous
+}
+
 fn backend_availability(instance: &wgpu::Instance, backend: wgpu::Backends) -> wgpu::Backends {
     if instance.enumerate_adapters(backend).is_empty() {
         wgpu::Backends::empty()
@@ -438,3 +745,14 @@ fn find_available_graphics_backends() -> wgpu::Backends {
 
     available_backends
 }
+
+fn open_url_requests_name(
+    locale: &LanguageIdentifier,
+    open_url_requests: Option<bool>,
+) -> Cow<str> {
+    match open_url_requests {
+        Some(true) => text(locale, "open-url-requests-allow"),
+        Some(false) => text(locale, "open-url-requests-deny"),
+        None => text(locale, "open-url-requests-ask"),
+    }
+}
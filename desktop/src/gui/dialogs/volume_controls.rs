@@ -1,4 +1,5 @@
 use crate::gui::text;
+use crate::player::InteractionMute;
 use crate::preferences::GlobalPreferences;
 use egui::{Align2, Slider};
 use ruffle_core::Player;
@@ -23,6 +24,7 @@ impl VolumeControls {
         locale: &LanguageIdentifier,
         egui_ctx: &egui::Context,
         player: Option<&mut Player>,
+        interaction_mute: &mut InteractionMute,
         preferences: &GlobalPreferences,
     ) -> bool {
         let mut keep_open = true;
@@ -48,8 +50,10 @@ impl VolumeControls {
                 });
 
                 if changed_checkbox || changed_slider {
-                    if let Some(player) = player {
-                        player.set_volume(self.get_volume());
+                    if let Some(volume) = interaction_mute.set_volume(self.get_volume()) {
+                        if let Some(player) = player {
+                            player.set_volume(volume);
+                        }
                     }
                     // Don't update persisted volume if the CLI set it
                     if preferences.cli.volume.is_none() {
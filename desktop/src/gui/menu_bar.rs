@@ -1,27 +1,34 @@
-use crate::custom_event::RuffleEvent;
+use crate::custom_event::{RuffleEvent, RuffleEventProxy};
 use crate::gui::dialogs::Dialogs;
-use crate::gui::{text, DebugMessage};
+use crate::gui::{text, text_with_args, ColorFilter, DebugMessage, MovieView, MovieZoom};
 use crate::player::LaunchOptions;
 use crate::preferences::GlobalPreferences;
-use egui::{menu, Button, Key, KeyboardShortcut, Modifiers, Widget};
-use ruffle_core::Player;
+use egui::{menu, Align, Button, Key, KeyboardShortcut, Layout, Modifiers, RichText, Widget};
+use ruffle_core::config::Letterbox;
+use ruffle_core::{Player, StageScaleMode};
+use ruffle_frontend_utils::backends::navigator::NetworkPolicy;
 use ruffle_frontend_utils::recents::Recent;
+use ruffle_render::quality::StageQuality;
+use ruffle_render_wgpu::backend::WgpuRenderBackend;
+use std::collections::HashMap;
 use unic_langid::LanguageIdentifier;
 use url::Url;
-use winit::event_loop::EventLoopProxy;
 
 pub struct MenuBar {
-    event_loop: EventLoopProxy<RuffleEvent>,
+    event_loop: RuffleEventProxy,
     default_launch_options: LaunchOptions,
     preferences: GlobalPreferences,
 
-    cached_recents: Option<Vec<Recent>>,
+    /// Recent entries to show, along with whether they're still available.
+    cached_recents: Option<Vec<(Recent, bool)>>,
     pub currently_opened: Option<(Url, LaunchOptions)>,
+    /// The network restrictions of the movie currently opened, shown as an indicator.
+    pub network_policy: NetworkPolicy,
 }
 
 impl MenuBar {
     pub fn new(
-        event_loop: EventLoopProxy<RuffleEvent>,
+        event_loop: RuffleEventProxy,
         default_launch_options: LaunchOptions,
         preferences: GlobalPreferences,
     ) -> Self {
@@ -30,6 +37,7 @@ impl MenuBar {
             default_launch_options,
             cached_recents: None,
             currently_opened: None,
+            network_policy: NetworkPolicy::Unrestricted,
             preferences,
         }
     }
@@ -53,11 +61,64 @@ impl MenuBar {
             }) {
                 dialogs.open_file_advanced();
             }
+            if ui.ctx().input_mut(|input| {
+                input.consume_shortcut(&KeyboardShortcut::new(Modifiers::COMMAND, Key::N))
+            }) {
+                self.open_file_in_new_window(ui);
+            }
+            if ui.ctx().input_mut(|input| {
+                input.consume_shortcut(&KeyboardShortcut::new(Modifiers::COMMAND | Modifiers::SHIFT, Key::T))
+            }) {
+                let _ = self.event_loop.send_event(RuffleEvent::ReopenClosedFile);
+            }
             if ui.ctx().input_mut(|input| {
                 input.consume_shortcut(&KeyboardShortcut::new(Modifiers::COMMAND, Key::Q))
             }) {
                 self.request_exit(ui);
             }
+            if ui.ctx().input_mut(|input| {
+                input.consume_shortcut(&KeyboardShortcut::new(Modifiers::COMMAND | Modifiers::SHIFT, Key::R))
+            }) {
+                let _ = self.event_loop.send_event(RuffleEvent::ReloadPreferences);
+            }
+            if ui.ctx().input_mut(|input| {
+                input.consume_shortcut(&KeyboardShortcut::new(Modifiers::COMMAND | Modifiers::SHIFT, Key::C))
+            }) && player.is_some() {
+                let _ = self.event_loop.send_event(RuffleEvent::CopyMovieUrl);
+            }
+            if ui.ctx().input_mut(|input| {
+                input.consume_shortcut(&KeyboardShortcut::new(Modifiers::COMMAND | Modifiers::SHIFT, Key::K))
+            }) {
+                if let Some(player) = &mut player {
+                    player.debug_ui().queue_message(DebugMessage::ToggleCallstack);
+                }
+            }
+            if ui.ctx().input_mut(|input| {
+                input.consume_shortcut(&KeyboardShortcut::new(Modifiers::COMMAND | Modifiers::SHIFT, Key::L))
+            }) && player.is_some() {
+                let _ = self.event_loop.send_event(RuffleEvent::DumpDisplayList);
+            }
+            if ui.ctx().input_mut(|input| {
+                input.consume_shortcut(&KeyboardShortcut::new(Modifiers::COMMAND, Key::Equals))
+                    || input.consume_shortcut(&KeyboardShortcut::new(Modifiers::COMMAND, Key::Plus))
+            }) && player.is_some() {
+                let _ = self.event_loop.send_event(RuffleEvent::ZoomMovie(MovieZoom::STEP));
+            }
+            if ui.ctx().input_mut(|input| {
+                input.consume_shortcut(&KeyboardShortcut::new(Modifiers::COMMAND, Key::Minus))
+            }) && player.is_some() {
+                let _ = self.event_loop.send_event(RuffleEvent::ZoomMovie(1.0 / MovieZoom::STEP));
+            }
+            if ui.ctx().input_mut(|input| {
+                input.consume_shortcut(&KeyboardShortcut::new(Modifiers::COMMAND, Key::Num0))
+            }) && player.is_some() {
+                let _ = self.event_loop.send_event(RuffleEvent::ResetMovieZoom);
+            }
+            if ui.ctx().input_mut(|input| {
+                input.consume_shortcut(&KeyboardShortcut::new(Modifiers::COMMAND | Modifiers::SHIFT, Key::B))
+            }) && player.is_some() {
+                let _ = self.event_loop.send_event(RuffleEvent::CycleBitmapSmoothing);
+            }
             if ui.ctx().input_mut(|input| {
                 input.consume_shortcut(&KeyboardShortcut::new(Modifiers::COMMAND, Key::P))
             }) {
@@ -84,6 +145,40 @@ impl MenuBar {
                         dialogs.open_volume_controls();
                         ui.close_menu();
                     }
+                    ui.separator();
+                    ui.add_enabled_ui(player.is_some(), |ui| {
+                        let zoom_in_shortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::Equals);
+                        if Button::new(text(locale, "controls-menu-zoom-in")).shortcut_text(ui.ctx().format_shortcut(&zoom_in_shortcut)).ui(ui).clicked() {
+                            ui.close_menu();
+                            let _ = self.event_loop.send_event(RuffleEvent::ZoomMovie(MovieZoom::STEP));
+                        }
+                        let zoom_out_shortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::Minus);
+                        if Button::new(text(locale, "controls-menu-zoom-out")).shortcut_text(ui.ctx().format_shortcut(&zoom_out_shortcut)).ui(ui).clicked() {
+                            ui.close_menu();
+                            let _ = self.event_loop.send_event(RuffleEvent::ZoomMovie(1.0 / MovieZoom::STEP));
+                        }
+                        let reset_zoom_shortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::Num0);
+                        if Button::new(text(locale, "controls-menu-reset-zoom")).shortcut_text(ui.ctx().format_shortcut(&reset_zoom_shortcut)).ui(ui).clicked() {
+                            ui.close_menu();
+                            let _ = self.event_loop.send_event(RuffleEvent::ResetMovieZoom);
+                        }
+                    });
+                    ui.menu_button(text(locale, "controls-menu-color-filter"), |ui| {
+                        let current = self.preferences.color_filter();
+                        for filter in ColorFilter::ALL {
+                            if ui.radio(current == filter, text(locale, color_filter_text_id(filter))).clicked() {
+                                ui.close_menu();
+                                if let Err(e) = self.preferences.write_preferences(|writer| writer.set_color_filter(filter)) {
+                                    tracing::warn!("Couldn't update color filter preference: {e}");
+                                }
+                                let _ = self.event_loop.send_event(RuffleEvent::ColorFilterChanged);
+                            }
+                        }
+                    });
+                    if let Some(player) = player.as_deref_mut() {
+                        ui.separator();
+                        self.presentation_menus(locale, ui, player);
+                    }
                 });
                 menu::menu_button(ui, text(locale, "bookmarks-menu"), |ui| {
                     if Button::new(text(locale, "bookmarks-menu-add")).ui(ui).clicked() {
@@ -143,6 +238,34 @@ impl MenuBar {
                                 player.debug_ui().queue_message(DebugMessage::SearchForDisplayObject);
                             }
                         }
+                        let callstack_shortcut = KeyboardShortcut::new(Modifiers::COMMAND | Modifiers::SHIFT, Key::K);
+                        if Button::new(text(locale, "debug-menu-toggle-callstack")).shortcut_text(ui.ctx().format_shortcut(&callstack_shortcut)).ui(ui).clicked() {
+                            ui.close_menu();
+                            if let Some(player) = &mut player {
+                                player.debug_ui().queue_message(DebugMessage::ToggleCallstack);
+                            }
+                        }
+                        let dump_shortcut = KeyboardShortcut::new(Modifiers::COMMAND | Modifiers::SHIFT, Key::L);
+                        if Button::new(text(locale, "debug-menu-dump-display-list")).shortcut_text(ui.ctx().format_shortcut(&dump_shortcut)).ui(ui).clicked() {
+                            ui.close_menu();
+                            let _ = self.event_loop.send_event(RuffleEvent::DumpDisplayList);
+                        }
+                        if Button::new(text(locale, "debug-menu-show-asset-stats")).ui(ui).clicked() {
+                            ui.close_menu();
+                            let _ = self.event_loop.send_event(RuffleEvent::ShowAssetStats);
+                        }
+                        if Button::new(text(locale, "debug-menu-clear-asset-cache")).ui(ui).clicked() {
+                            ui.close_menu();
+                            let _ = self.event_loop.send_event(RuffleEvent::ClearAssetCache);
+                        }
+                        if Button::new(text(locale, "debug-menu-collect-garbage")).ui(ui).clicked() {
+                            ui.close_menu();
+                            let _ = self.event_loop.send_event(RuffleEvent::CollectGarbage);
+                        }
+                        if Button::new(text(locale, "debug-menu-swap-root-swf")).ui(ui).clicked() {
+                            ui.close_menu();
+                            let _ = self.event_loop.send_event(RuffleEvent::BrowseAndSwapRootSwf);
+                        }
                     });
                 });
                 menu::menu_button(ui, text(locale, "help-menu"), |ui| {
@@ -164,6 +287,8 @@ impl MenuBar {
                         ui.close_menu();
                     }
                 });
+
+                self.network_policy_indicator(locale, ui);
             });
         });
     }
@@ -197,6 +322,15 @@ impl MenuBar {
                 dialogs.open_file_advanced();
             }
 
+            shortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::N);
+            if Button::new(text(locale, "file-menu-open-new-window"))
+                .shortcut_text(ui.ctx().format_shortcut(&shortcut))
+                .ui(ui)
+                .clicked()
+            {
+                self.open_file_in_new_window(ui);
+            }
+
             if ui
                 .add_enabled(player_exists, Button::new(text(locale, "file-menu-reload")))
                 .clicked()
@@ -210,6 +344,51 @@ impl MenuBar {
             {
                 self.close_movie(ui);
             }
+
+            shortcut = KeyboardShortcut::new(Modifiers::COMMAND | Modifiers::SHIFT, Key::T);
+            if Button::new(text(locale, "file-menu-reopen-closed"))
+                .shortcut_text(ui.ctx().format_shortcut(&shortcut))
+                .ui(ui)
+                .clicked()
+            {
+                ui.close_menu();
+                let _ = self.event_loop.send_event(RuffleEvent::ReopenClosedFile);
+            }
+
+            if ui
+                .add_enabled(
+                    player_exists,
+                    Button::new(text(locale, "file-menu-export-svg")),
+                )
+                .clicked()
+            {
+                ui.close_menu();
+                let _ = self.event_loop.send_event(RuffleEvent::ExportFrameSvg);
+            }
+
+            shortcut = KeyboardShortcut::new(Modifiers::COMMAND | Modifiers::SHIFT, Key::C);
+            if ui
+                .add_enabled(
+                    player_exists,
+                    Button::new(text(locale, "file-menu-copy-url"))
+                        .shortcut_text(ui.ctx().format_shortcut(&shortcut)),
+                )
+                .clicked()
+            {
+                ui.close_menu();
+                let _ = self.event_loop.send_event(RuffleEvent::CopyMovieUrl);
+            }
+
+            if ui
+                .add_enabled(
+                    player_exists,
+                    Button::new(text(locale, "file-menu-show-in-folder")),
+                )
+                .clicked()
+            {
+                ui.close_menu();
+                let _ = self.event_loop.send_event(RuffleEvent::ShowMovieInFolder);
+            }
             ui.separator();
 
             let recent_menu_response = ui
@@ -223,17 +402,63 @@ impl MenuBar {
                         ui.label(text(locale, "file-menu-recents-empty"));
                     }
 
+                    let mut toggle_pin = None;
                     if let Some(recents) = &self.cached_recents {
-                        for recent in recents {
-                            if ui.button(recent.url.as_str()).clicked() {
+                        for (recent, available) in recents {
+                            let available = *available;
+                            let mut name = recent.display_name().into_owned();
+                            if !available {
+                                let args = HashMap::from([("name", name.into())]);
+                                name = text_with_args(locale, "file-menu-recents-missing", &args)
+                                    .into_owned();
+                            }
+                            if recent.pinned {
+                                name = format!("📌 {name}");
+                            }
+                            let mut label = RichText::new(name);
+                            if !available {
+                                label = label.strikethrough();
+                            }
+                            let response = ui
+                                .add_enabled(available, Button::new(label))
+                                .on_hover_text(recent.url.as_str())
+                                .on_disabled_hover_text(recent.url.as_str());
+                            if response.clicked() {
                                 ui.close_menu();
                                 let _ = self.event_loop.send_event(RuffleEvent::OpenURL(
                                     recent.url.clone(),
                                     Box::new(self.default_launch_options.clone()),
                                 ));
                             }
+                            response.context_menu(|ui| {
+                                let key = if recent.pinned {
+                                    "file-menu-recents-unpin"
+                                } else {
+                                    "file-menu-recents-pin"
+                                };
+                                if ui.button(text(locale, key)).clicked() {
+                                    ui.close_menu();
+                                    toggle_pin = Some((recent.url.clone(), !recent.pinned));
+                                }
+                            });
+                        }
+
+                        if !recents.is_empty() {
+                            ui.separator();
+                            if ui.button(text(locale, "file-menu-recents-clear")).clicked() {
+                                ui.close_menu();
+                                self.preferences
+                                    .update_recents(&self.event_loop, |writer| writer.clear());
+                                let _ = self.event_loop.send_event(RuffleEvent::ForgetClosedFile);
+                            }
                         }
                     };
+
+                    if let Some((url, pinned)) = toggle_pin {
+                        self.preferences.update_recents(&self.event_loop, |writer| {
+                            writer.set_pinned(&url, pinned)
+                        });
+                    }
                 })
                 .inner;
 
@@ -241,12 +466,15 @@ impl MenuBar {
                 // recreate the cache on the first draw.
                 Some(_) if self.cached_recents.is_none() => {
                     self.cached_recents = Some(self.preferences.recents(|recents| {
-                        recents
+                        let mut recents = recents
                             .iter()
                             .rev()
-                            .filter(|x| !x.is_invalid() && x.is_available())
-                            .cloned()
-                            .collect::<Vec<_>>()
+                            .filter(|x| !x.is_invalid())
+                            .map(|x| (x.clone(), x.is_available()))
+                            .collect::<Vec<_>>();
+                        // Pinned entries go first, otherwise keep the newest first.
+                        recents.sort_by_key(|(recent, _)| !recent.pinned);
+                        recents
                     }))
                 }
                 // clear cache, since menu was closed.
@@ -262,6 +490,24 @@ impl MenuBar {
                 ui.close_menu();
                 dialogs.open_preferences();
             }
+
+            shortcut = KeyboardShortcut::new(Modifiers::COMMAND | Modifiers::SHIFT, Key::R);
+            if Button::new(text(locale, "file-menu-reload-preferences"))
+                .shortcut_text(ui.ctx().format_shortcut(&shortcut))
+                .ui(ui)
+                .clicked()
+            {
+                ui.close_menu();
+                let _ = self.event_loop.send_event(RuffleEvent::ReloadPreferences);
+            }
+
+            if Button::new(text(locale, "file-menu-reset-preferences"))
+                .ui(ui)
+                .clicked()
+            {
+                ui.close_menu();
+                let _ = self.event_loop.send_event(RuffleEvent::ResetPreferences);
+            }
             ui.separator();
 
             shortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::Q);
@@ -275,6 +521,130 @@ impl MenuBar {
         });
     }
 
+    /// Makes any recents shown in the menu be reloaded the next time it's drawn.
+    pub fn invalidate_recents(&mut self) {
+        self.cached_recents = None;
+    }
+
+    /// Submenus to change how the movie is presented, which is remembered for its recent entry
+    /// so that it's presented the same way when opened again.
+    fn presentation_menus(
+        &self,
+        locale: &LanguageIdentifier,
+        ui: &mut egui::Ui,
+        player: &mut Player,
+    ) {
+        let url = self.currently_opened.as_ref().map(|(url, _)| url);
+        ui.menu_button(text(locale, "quality"), |ui| {
+            let current = player.quality();
+            for quality in [
+                StageQuality::Low,
+                StageQuality::Medium,
+                StageQuality::High,
+                StageQuality::Best,
+                StageQuality::High8x8,
+                StageQuality::High8x8Linear,
+                StageQuality::High16x16,
+                StageQuality::High16x16Linear,
+            ] {
+                if ui
+                    .radio(current == quality, text(locale, quality_text_id(quality)))
+                    .clicked()
+                {
+                    ui.close_menu();
+                    player.set_quality(quality);
+                    if let Some(url) = url {
+                        self.preferences.update_recents(&self.event_loop, |writer| {
+                            writer.set_quality(url, quality)
+                        });
+                    }
+                }
+            }
+        });
+        ui.menu_button(text(locale, "scale-mode"), |ui| {
+            let current = player.scale_mode();
+            for scale in [
+                StageScaleMode::ShowAll,
+                StageScaleMode::ExactFit,
+                StageScaleMode::NoBorder,
+                StageScaleMode::NoScale,
+            ] {
+                if ui
+                    .radio(current == scale, text(locale, scale_mode_text_id(scale)))
+                    .clicked()
+                {
+                    ui.close_menu();
+                    player.set_scale_mode(scale);
+                    if let Some(url) = url {
+                        self.preferences.update_recents(&self.event_loop, |writer| {
+                            writer.set_scale(url, scale)
+                        });
+                    }
+                }
+            }
+        });
+        ui.menu_button(text(locale, "letterbox"), |ui| {
+            let current = player.letterbox();
+            for letterbox in [Letterbox::On, Letterbox::Fullscreen, Letterbox::Off] {
+                if ui
+                    .radio(
+                        current == letterbox,
+                        text(locale, letterbox_text_id(letterbox)),
+                    )
+                    .clicked()
+                {
+                    ui.close_menu();
+                    player.set_letterbox(letterbox);
+                    if let Some(url) = url {
+                        self.preferences.update_recents(&self.event_loop, |writer| {
+                            writer.set_letterbox(url, letterbox)
+                        });
+                    }
+                }
+            }
+        });
+        ui.menu_button(text(locale, "controls-menu-bitmap-smoothing"), |ui| {
+            let current = player
+                .renderer_mut()
+                .downcast_mut::<WgpuRenderBackend<MovieView>>()
+                .and_then(|renderer| renderer.bitmap_smoothing());
+            let shortcut = KeyboardShortcut::new(Modifiers::COMMAND | Modifiers::SHIFT, Key::B);
+            ui.label(
+                RichText::new(text_with_args(
+                    locale,
+                    "controls-menu-bitmap-smoothing-shortcut",
+                    &HashMap::from([("shortcut", ui.ctx().format_shortcut(&shortcut).into())]),
+                ))
+                .weak(),
+            );
+            for (smoothing, id) in [
+                (None, "controls-menu-bitmap-smoothing-movie"),
+                (Some(true), "controls-menu-bitmap-smoothing-on"),
+                (Some(false), "controls-menu-bitmap-smoothing-off"),
+            ] {
+                if ui.radio(current == smoothing, text(locale, id)).clicked() {
+                    ui.close_menu();
+                    let _ = self
+                        .event_loop
+                        .send_event(RuffleEvent::SetBitmapSmoothing(smoothing));
+                }
+            }
+        });
+        let mut text_anti_aliasing = player.text_anti_aliasing();
+        if ui
+            .checkbox(
+                &mut text_anti_aliasing,
+                text(locale, "controls-menu-text-anti-aliasing"),
+            )
+            .clicked()
+        {
+            ui.close_menu();
+            let _ = self
+                .event_loop
+                .send_event(RuffleEvent::SetTextAntiAliasing(text_anti_aliasing));
+        }
+    }
+
     fn open_file(&mut self, ui: &mut egui::Ui) {
         ui.close_menu();
 
@@ -285,9 +655,40 @@ impl MenuBar {
             )));
     }
 
+    fn open_file_in_new_window(&mut self, ui: &mut egui::Ui) {
+        ui.close_menu();
+
+        let _ = self
+            .event_loop
+            .send_event(RuffleEvent::BrowseAndOpenInNewWindow(Box::new(
+                self.default_launch_options.clone(),
+            )));
+    }
+
+    fn network_policy_indicator(&self, locale: &LanguageIdentifier, ui: &mut egui::Ui) {
+        let (label, tooltip) = match &self.network_policy {
+            NetworkPolicy::Unrestricted => return,
+            NetworkPolicy::AllowedHosts(hosts) => {
+                let args = HashMap::from([("hosts", hosts.join(", ").into())]);
+                (
+                    text(locale, "network-policy-restricted"),
+                    text_with_args(locale, "network-policy-restricted-tooltip", &args),
+                )
+            }
+            NetworkPolicy::Offline => (
+                text(locale, "network-policy-offline"),
+                text(locale, "network-policy-offline-tooltip"),
+            ),
+        };
+        ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+            ui.label(label).on_hover_text(tooltip);
+        });
+    }
+
     fn close_movie(&mut self, ui: &mut egui::Ui) {
         let _ = self.event_loop.send_event(RuffleEvent::CloseFile);
         self.currently_opened = None;
+        self.network_policy = NetworkPolicy::Unrestricted;
         ui.close_menu();
     }
 
@@ -311,3 +712,44 @@ impl MenuBar {
         ui.close_menu();
     }
 }
+
+fn color_filter_text_id(filter: ColorFilter) -> &'static str {
+    match filter {
+        ColorFilter::None => "color-filter-none",
+        ColorFilter::Grayscale => "color-filter-grayscale",
+        ColorFilter::HighContrast => "color-filter-high-contrast",
+        ColorFilter::Protanopia => "color-filter-protanopia",
+        ColorFilter::Deuteranopia => "color-filter-deuteranopia",
+        ColorFilter::Tritanopia => "color-filter-tritanopia",
+    }
+}
+
+fn quality_text_id(quality: StageQuality) -> &'static str {
+    match quality {
+        StageQuality::Low => "quality-low",
+        StageQuality::Medium => "quality-medium",
+        StageQuality::High => "quality-high",
+        StageQuality::Best => "quality-best",
+        StageQuality::High8x8 => "quality-high8x8",
+        StageQuality::High8x8Linear => "quality-high8x8linear",
+        StageQuality::High16x16 => "quality-high16x16",
+        StageQuality::High16x16Linear => "quality-high16x16linear",
+    }
+}
+
+fn scale_mode_text_id(scale: StageScaleMode) -> &'static str {
+    match scale {
+        StageScaleMode::ExactFit => "scale-mode-exactfit",
+        StageScaleMode::NoBorder => "scale-mode-noborder",
+        StageScaleMode::NoScale => "scale-mode-noscale",
+        StageScaleMode::ShowAll => "scale-mode-showall",
+    }
+}
+
+fn letterbox_text_id(letterbox: Letterbox) -> &'static str {
+    match letterbox {
+        Letterbox::On => "letterbox-on",
+        Letterbox::Fullscreen => "letterbox-fullscreen",
+        Letterbox::Off => "letterbox-off",
+    }
+}
@@ -1,7 +1,10 @@
 use crate::gui::MENU_HEIGHT;
+use crate::lut::ColorLut;
 use ruffle_render_wgpu::descriptors::Descriptors;
 use ruffle_render_wgpu::target::{RenderTarget, RenderTargetFrame};
+use ruffle_render_wgpu::utils::{buffer_to_image, BufferDimensions};
 use std::borrow::Cow;
+use std::str::FromStr;
 use std::sync::Arc;
 use wgpu::util::DeviceExt;
 
@@ -11,33 +14,256 @@ pub struct MovieViewRenderer {
     pipeline: wgpu::RenderPipeline,
     sampler: wgpu::Sampler,
     vertices: wgpu::Buffer,
+    color_matrix: wgpu::Buffer,
+    /// The color-correction LUT applied after the color filter, which does nothing unless one was given.
+    lut_view: wgpu::TextureView,
+    lut_transform: wgpu::Buffer,
+    lut_sampler: wgpu::Sampler,
 }
 
-fn get_vertices(has_menu: bool, height: u32, scale_factor: f64) -> [[f32; 4]; 6] {
+/// A filter applied to the colors of the presented movie, such as for accessibility.
+/// The movie itself is unaware of this.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorFilter {
+    #[default]
+    None,
+    Grayscale,
+    HighContrast,
+    /// Simulates how red-blind people see the movie.
+    Protanopia,
+    /// Simulates how green-blind people see the movie.
+    Deuteranopia,
+    /// Simulates how blue-blind people see the movie.
+    Tritanopia,
+}
+
+impl FromStr for ColorFilter {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(ColorFilter::None),
+            "grayscale" => Ok(ColorFilter::Grayscale),
+            "high_contrast" => Ok(ColorFilter::HighContrast),
+            "protanopia" => Ok(ColorFilter::Protanopia),
+            "deuteranopia" => Ok(ColorFilter::Deuteranopia),
+            "tritanopia" => Ok(ColorFilter::Tritanopia),
+            _ => Err(()),
+        }
+    }
+}
+
+impl ColorFilter {
+    pub const ALL: [ColorFilter; 6] = [
+        ColorFilter::None,
+        ColorFilter::Grayscale,
+        ColorFilter::HighContrast,
+        ColorFilter::Protanopia,
+        ColorFilter::Deuteranopia,
+        ColorFilter::Tritanopia,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ColorFilter::None => "none",
+            ColorFilter::Grayscale => "grayscale",
+            ColorFilter::HighContrast => "high_contrast",
+            ColorFilter::Protanopia => "protanopia",
+            ColorFilter::Deuteranopia => "deuteranopia",
+            ColorFilter::Tritanopia => "tritanopia",
+        }
+    }
+
+    /// The rows of the matrix that each (premultiplied) RGBA color is multiplied by.
+    /// The last column is an offset, scaled by alpha.
+    fn matrix(&self) -> [[f32; 4]; 4] {
+        const ALPHA: [f32; 4] = [0.0, 0.0, 0.0, 1.0];
+        // Luminance, as weighted by Rec. 709.
+        const GRAY: [f32; 4] = [0.2126, 0.7152, 0.0722, 0.0];
+        // The color blindness simulations are from Machado, Oliveira and Fernandes (2009), at full severity.
+        match self {
+            ColorFilter::None => [
+                [1.0, 0.0, 0.0, 0.0],
+                [0.0, 1.0, 0.0, 0.0],
+                [0.0, 0.0, 1.0, 0.0],
+                ALPHA,
+            ],
+            ColorFilter::Grayscale => [GRAY, GRAY, GRAY, ALPHA],
+            ColorFilter::HighContrast => [
+                [2.0, 0.0, 0.0, -0.5],
+                [0.0, 2.0, 0.0, -0.5],
+                [0.0, 0.0, 2.0, -0.5],
+                ALPHA,
+            ],
+            ColorFilter::Protanopia => [
+                [0.152286, 1.052583, -0.204868, 0.0],
+                [0.114503, 0.786281, 0.099216, 0.0],
+                [-0.003882, -0.048116, 1.051998, 0.0],
+                ALPHA,
+            ],
+            ColorFilter::Deuteranopia => [
+                [0.367322, 0.860646, -0.227968, 0.0],
+                [0.280085, 0.672501, 0.047413, 0.0],
+                [-0.011820, 0.042940, 0.968881, 0.0],
+                ALPHA,
+            ],
+            ColorFilter::Tritanopia => [
+                [1.255528, -0.076749, -0.178779, 0.0],
+                [-0.078411, 0.930809, 0.147602, 0.0],
+                [0.004733, 0.691367, 0.303900, 0.0],
+                ALPHA,
+            ],
+        }
+    }
+}
+
+/// A zoom into the presented movie, independent of the movie's own scaling.
+/// The movie is unaware of this, as it only changes which part of the rendered stage is shown.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MovieZoom {
+    /// How much the stage is magnified, where 1.0 shows all of it.
+    scale: f64,
+    /// The point of the stage shown in the middle of the view,
+    /// from (0, 0) at the top left to (1, 1) at the bottom right.
+    center: (f64, f64),
+}
+
+impl Default for MovieZoom {
+    fn default() -> Self {
+        Self {
+            scale: 1.0,
+            center: (0.5, 0.5),
+        }
+    }
+}
+
+impl MovieZoom {
+    const MAX_SCALE: f64 = 16.0;
+
+    /// How much a single step of zooming in magnifies the stage.
+    pub const STEP: f64 = 1.25;
+
+    pub fn is_zoomed(&self) -> bool {
+        self.scale > 1.0
+    }
+
+    /// Zooms in by `factor` (or out, if it's less than 1), keeping the point of the stage
+    /// at `anchor` in place. The anchor is a fraction of the view, such as (0.5, 0.5) for its middle.
+    pub fn zoom_by(&mut self, factor: f64, anchor: (f64, f64)) {
+        let (x, y) = self.view_to_stage_fraction(anchor);
+        self.scale = (self.scale * factor).clamp(1.0, Self::MAX_SCALE);
+        self.center = (
+            x - (anchor.0 - 0.5) / self.scale,
+            y - (anchor.1 - 0.5) / self.scale,
+        );
+        self.clamp_center();
+    }
+
+    /// Moves the stage along with a drag of `delta`, as a fraction of the view.
+    pub fn pan_by(&mut self, delta: (f64, f64)) {
+        self.center.0 -= delta.0 / self.scale;
+        self.center.1 -= delta.1 / self.scale;
+        self.clamp_center();
+    }
+
+    /// Converts a position in a view of the given size to the position on the stage shown there.
+    pub fn view_to_stage(&self, x: f64, y: f64, width: f64, height: f64) -> (f64, f64) {
+        if !self.is_zoomed() || width <= 0.0 || height <= 0.0 {
+            return (x, y);
+        }
+        let (x, y) = self.view_to_stage_fraction((x / width, y / height));
+        (x * width, y * height)
+    }
+
+    fn view_to_stage_fraction(&self, (x, y): (f64, f64)) -> (f64, f64) {
+        (
+            self.center.0 + (x - 0.5) / self.scale,
+            self.center.1 + (y - 0.5) / self.scale,
+        )
+    }
+
+    /// Keeps the view within the stage, so nothing past its edges is shown.
+    fn clamp_center(&mut self) {
+        let extent = 0.5 / self.scale;
+        self.center.0 = self.center.0.clamp(extent, 1.0 - extent);
+        self.center.1 = self.center.1.clamp(extent, 1.0 - extent);
+    }
+}
+
+fn get_vertices(has_menu: bool, height: u32, scale_factor: f64, zoom: MovieZoom) -> [[f32; 4]; 6] {
     let top = if has_menu {
         let menu_height = MENU_HEIGHT as f64 * scale_factor;
         1.0 - ((menu_height / height as f64) * 2.0) as f32
     } else {
         1.0
     };
+    let (u0, v0) = zoom.view_to_stage_fraction((0.0, 0.0));
+    let (u1, v1) = zoom.view_to_stage_fraction((1.0, 1.0));
+    let (u0, v0, u1, v1) = (u0 as f32, v0 as f32, u1 as f32, v1 as f32);
     // x y u v
     [
-        [-1.0, top, 0.0, 0.0],  // tl
-        [1.0, top, 1.0, 0.0],   // tr
-        [1.0, -1.0, 1.0, 1.0],  // br
-        [1.0, -1.0, 1.0, 1.0],  // br
-        [-1.0, -1.0, 0.0, 1.0], // bl
-        [-1.0, top, 0.0, 0.0],  // tl
+        [-1.0, top, u0, v0],  // tl
+        [1.0, top, u1, v0],   // tr
+        [1.0, -1.0, u1, v1],  // br
+        [1.0, -1.0, u1, v1],  // br
+        [-1.0, -1.0, u0, v1], // bl
+        [-1.0, top, u0, v0],  // tl
     ]
 }
 
+/// Uploads a color-correction LUT as a 3D texture, along with the scale and offset from colors to
+/// its texture coordinates. Without a LUT, the texture is a placeholder and the LUT is disabled.
+fn create_lut(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    lut: Option<&ColorLut>,
+) -> (wgpu::TextureView, wgpu::Buffer) {
+    let size = lut.map_or(1, ColorLut::size);
+    let texture = device.create_texture_with_data(
+        queue,
+        &wgpu::TextureDescriptor {
+            label: Some("Color LUT"),
+            size: wgpu::Extent3d {
+                width: size,
+                height: size,
+                depth_or_array_layers: size,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D3,
+            format: wgpu::TextureFormat::Rgba16Float,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        },
+        wgpu::util::TextureDataOrder::LayerMajor,
+        &lut.map_or_else(|| vec![0; 8], ColorLut::to_rgba16f),
+    );
+    // Laid out as `Lut` in blit.wgsl: the scale, whether it's enabled, then the offset.
+    let mut transform = [0.0f32; 8];
+    if let Some(lut) = lut {
+        let (scale, offset) = lut.texture_transform();
+        transform[0..3].copy_from_slice(&scale);
+        transform[3] = 1.0;
+        transform[4..7].copy_from_slice(&offset);
+    }
+    let buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: Some("Color LUT transform"),
+        contents: bytemuck::cast_slice(&transform),
+        usage: wgpu::BufferUsages::UNIFORM,
+    });
+    (texture.create_view(&Default::default()), buffer)
+}
+
 impl MovieViewRenderer {
     pub fn new(
         device: &wgpu::Device,
+        queue: &wgpu::Queue,
         surface_format: wgpu::TextureFormat,
         has_menu: bool,
         height: u32,
         scale_factor: f64,
+        lut: Option<&ColorLut>,
+        upscale_filter: wgpu::FilterMode,
     ) -> Self {
         let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
             label: None,
@@ -62,9 +288,51 @@ impl MovieViewRenderer {
                     ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
                     count: None,
                 },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 3,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        multisampled: false,
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D3,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 4,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 5,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
             ],
         });
         let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            mag_filter: upscale_filter,
+            min_filter: upscale_filter,
+            ..Default::default()
+        });
+        // The LUT is always interpolated, as only its entries are known.
+        let lut_sampler = device.create_sampler(&wgpu::SamplerDescriptor {
             mag_filter: wgpu::FilterMode::Linear,
             min_filter: wgpu::FilterMode::Linear,
             ..Default::default()
@@ -121,29 +389,53 @@ impl MovieViewRenderer {
         });
         let vertices = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
             label: None,
-            contents: bytemuck::cast_slice(&get_vertices(has_menu, height, scale_factor)),
+            contents: bytemuck::cast_slice(&get_vertices(
+                has_menu,
+                height,
+                scale_factor,
+                MovieZoom::default(),
+            )),
             usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
         });
+        let color_matrix = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: None,
+            contents: bytemuck::cast_slice(&ColorFilter::None.matrix()),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+        let (lut_view, lut_transform) = create_lut(device, queue, lut);
 
         Self {
             bind_group_layout,
             pipeline,
             sampler,
             vertices,
+            color_matrix,
+            lut_view,
+            lut_transform,
+            lut_sampler,
         }
     }
 
+    pub fn set_color_filter(&self, descriptors: &Descriptors, filter: ColorFilter) {
+        descriptors.queue.write_buffer(
+            &self.color_matrix,
+            0,
+            bytemuck::cast_slice(&filter.matrix()),
+        );
+    }
+
     pub fn update_resolution(
         &self,
         descriptors: &Descriptors,
         has_menu: bool,
         height: u32,
         scale_factor: f64,
+        zoom: MovieZoom,
     ) {
         descriptors.queue.write_buffer(
             &self.vertices,
             0,
-            bytemuck::cast_slice(&get_vertices(has_menu, height, scale_factor)),
+            bytemuck::cast_slice(&get_vertices(has_menu, height, scale_factor, zoom)),
         );
     }
 }
@@ -173,7 +465,9 @@ impl MovieView {
             sample_count: 1,
             dimension: wgpu::TextureDimension::D2,
             format: wgpu::TextureFormat::Rgba8Unorm,
-            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                | wgpu::TextureUsages::TEXTURE_BINDING
+                | wgpu::TextureUsages::COPY_SRC,
             view_formats: &[],
         });
         let view = texture.create_view(&Default::default());
@@ -189,6 +483,22 @@ impl MovieView {
                     binding: 1,
                     resource: wgpu::BindingResource::Sampler(&renderer.sampler),
                 },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: renderer.color_matrix.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: wgpu::BindingResource::TextureView(&renderer.lut_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 4,
+                    resource: renderer.lut_transform.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 5,
+                    resource: wgpu::BindingResource::Sampler(&renderer.lut_sampler),
+                },
             ],
         });
         Self {
@@ -208,6 +518,42 @@ impl MovieView {
         render_pass.set_vertex_buffer(0, renderer.vertices.slice(..));
         render_pass.draw(0..6, 0..1);
     }
+
+    /// Reads back what was last drawn of the movie, before any color filter, LUT or zoom is applied.
+    pub fn capture(&self, descriptors: &Descriptors) -> image::RgbaImage {
+        let size = self.texture.size();
+        let dimensions = BufferDimensions::new(
+            size.width as usize,
+            size.height as usize,
+            self.texture.format(),
+        );
+        let buffer = descriptors.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Movie view capture"),
+            size: dimensions.size(),
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+        let mut encoder =
+            descriptors
+                .device
+                .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                    label: Some("Movie view capture"),
+                });
+        encoder.copy_texture_to_buffer(
+            self.texture.as_image_copy(),
+            wgpu::ImageCopyBuffer {
+                buffer: &buffer,
+                layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(dimensions.padded_bytes_per_row),
+                    rows_per_image: None,
+                },
+            },
+            size,
+        );
+        let index = descriptors.queue.submit(Some(encoder.finish()));
+        buffer_to_image(&descriptors.device, &buffer, &dimensions, Some(index), size)
+    }
 }
 
 impl RenderTarget for MovieView {
@@ -258,3 +604,43 @@ impl RenderTargetFrame for MovieViewFrame {
         &self.0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::gui::movie::MovieZoom;
+
+    #[test]
+    fn unzoomed_maps_directly() {
+        let zoom = MovieZoom::default();
+        assert!(!zoom.is_zoomed());
+        assert_eq!(zoom.view_to_stage(120.0, 30.0, 400.0, 300.0), (120.0, 30.0));
+    }
+
+    #[test]
+    fn zoom_keeps_anchor_in_place() {
+        let mut zoom = MovieZoom::default();
+        zoom.zoom_by(2.0, (0.25, 0.25));
+        assert!(zoom.is_zoomed());
+        assert_eq!(zoom.view_to_stage(100.0, 75.0, 400.0, 300.0), (100.0, 75.0));
+        assert_eq!(zoom.view_to_stage(0.0, 0.0, 400.0, 300.0), (50.0, 37.5));
+    }
+
+    #[test]
+    fn zoom_is_limited() {
+        let mut zoom = MovieZoom::default();
+        zoom.zoom_by(0.5, (0.5, 0.5));
+        assert_eq!(zoom, MovieZoom::default());
+        zoom.zoom_by(1000.0, (0.5, 0.5));
+        assert_eq!(zoom.scale, MovieZoom::MAX_SCALE);
+    }
+
+    #[test]
+    fn pan_stays_within_stage() {
+        let mut zoom = MovieZoom::default();
+        zoom.zoom_by(2.0, (0.5, 0.5));
+        zoom.pan_by((0.1, 0.0));
+        assert_eq!(zoom.view_to_stage(0.0, 0.0, 100.0, 100.0), (20.0, 25.0));
+        zoom.pan_by((10.0, -10.0));
+        assert_eq!(zoom.view_to_stage(0.0, 0.0, 100.0, 100.0), (0.0, 50.0));
+    }
+}
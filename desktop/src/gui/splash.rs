@@ -0,0 +1,49 @@
+use anyhow::Error;
+use egui::load::SizedTexture;
+use egui::{Align2, Area, ColorImage, Context, Id, Image, Spinner, TextureHandle};
+use std::cell::OnceCell;
+
+/// Shown in the middle of the window while the root movie loads, until its first frame is ready.
+pub struct Splash {
+    /// The image to show, or `None` for a spinner.
+    image: Option<ColorImage>,
+    texture: OnceCell<TextureHandle>,
+}
+
+impl Splash {
+    pub fn spinner() -> Self {
+        Self {
+            image: None,
+            texture: OnceCell::new(),
+        }
+    }
+
+    pub fn from_image_data(data: &[u8]) -> Result<Self, Error> {
+        let image = image::load_from_memory(data)?.to_rgba8();
+        let size = [image.width() as usize, image.height() as usize];
+        Ok(Self {
+            image: Some(ColorImage::from_rgba_unmultiplied(size, image.as_raw())),
+            texture: OnceCell::new(),
+        })
+    }
+
+    pub fn show(&self, egui_ctx: &Context) {
+        let max_size = egui_ctx.screen_rect().size() * 0.8;
+        Area::new(Id::new("splash"))
+            .anchor(Align2::CENTER_CENTER, [0.0, 0.0])
+            .interactable(false)
+            .show(egui_ctx, |ui| match &self.image {
+                Some(image) => {
+                    let texture = self.texture.get_or_init(|| {
+                        egui_ctx.load_texture("splash", image.clone(), Default::default())
+                    });
+                    ui.add(
+                        Image::from_texture(SizedTexture::from_handle(texture)).max_size(max_size),
+                    );
+                }
+                None => {
+                    ui.add(Spinner::new().size(48.0));
+                }
+            });
+    }
+}
@@ -0,0 +1,39 @@
+use egui::{Align2, Area, Context, Frame, Id};
+use std::time::{Duration, Instant};
+
+/// How long a toast stays on screen.
+const TOAST_DURATION: Duration = Duration::from_secs(4);
+
+/// A short message shown at the bottom of the window, which disappears on its own.
+pub struct Toast {
+    message: String,
+    expires_at: Instant,
+}
+
+impl Toast {
+    pub fn new(message: String) -> Self {
+        Self {
+            message,
+            expires_at: Instant::now() + TOAST_DURATION,
+        }
+    }
+
+    /// Shows the toast, returning false once it has expired and should be removed.
+    pub fn show(&self, egui_ctx: &Context) -> bool {
+        let now = Instant::now();
+        if now >= self.expires_at {
+            return false;
+        }
+
+        Area::new(Id::new("toast"))
+            .anchor(Align2::CENTER_BOTTOM, [0.0, -16.0])
+            .interactable(false)
+            .show(egui_ctx, |ui| {
+                Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.label(&self.message);
+                });
+            });
+        egui_ctx.request_repaint_after(self.expires_at - now);
+        true
+    }
+}
@@ -0,0 +1,148 @@
+//! Keeping the screen from going to sleep while a movie plays.
+
+use std::io;
+
+/// Keeps the screen awake for as long as it's inhibited, using whatever the platform offers.
+#[derive(Default)]
+pub struct KeepAwake {
+    inhibitor: Option<Inhibitor>,
+    /// Set once the screen couldn't be kept awake, so that it isn't tried again every frame.
+    unavailable: bool,
+}
+
+impl KeepAwake {
+    /// Starts or stops keeping the screen awake.
+    pub fn set_inhibited(&mut self, inhibited: bool) {
+        if inhibited == self.inhibitor.is_some() {
+            return;
+        }
+        if !inhibited {
+            self.inhibitor = None;
+            return;
+        }
+        if self.unavailable {
+            return;
+        }
+        match Inhibitor::acquire() {
+            Ok(inhibitor) => self.inhibitor = Some(inhibitor),
+            Err(e) => {
+                tracing::warn!("Couldn't keep the screen awake: {e}");
+                self.unavailable = true;
+            }
+        }
+    }
+}
+
+/// Stops the screen from sleeping until dropped.
+#[cfg(windows)]
+struct Inhibitor;
+
+#[cfg(windows)]
+impl Inhibitor {
+    fn acquire() -> io::Result<Self> {
+        use winapi::um::winbase::SetThreadExecutionState;
+        use winapi::um::winnt::{ES_CONTINUOUS, ES_DISPLAY_REQUIRED, ES_SYSTEM_REQUIRED};
+
+        // SAFETY: This only changes flags of the calling thread.
+        let previous = unsafe {
+            SetThreadExecutionState(ES_CONTINUOUS | ES_DISPLAY_REQUIRED | ES_SYSTEM_REQUIRED)
+        };
+        if previous == 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(Self)
+    }
+}
+
+#[cfg(windows)]
+impl Drop for Inhibitor {
+    fn drop(&mut self) {
+        use winapi::um::winbase::SetThreadExecutionState;
+        use winapi::um::winnt::ES_CONTINUOUS;
+
+        // SAFETY: This only changes flags of the calling thread.
+        unsafe {
+            SetThreadExecutionState(ES_CONTINUOUS);
+        }
+    }
+}
+
+/// Stops the screen from sleeping until dropped, through the freedesktop screensaver service.
+#[cfg(target_os = "linux")]
+struct Inhibitor {
+    connection: zbus::blocking::Connection,
+    cookie: u32,
+}
+
+#[cfg(target_os = "linux")]
+impl Inhibitor {
+    const DESTINATION: &'static str = "org.freedesktop.ScreenSaver";
+    const PATH: &'static str = "/org/freedesktop/ScreenSaver";
+
+    fn acquire() -> io::Result<Self> {
+        let connection = zbus::blocking::Connection::session().map_err(io::Error::other)?;
+        let reply = connection
+            .call_method(
+                Some(Self::DESTINATION),
+                Self::PATH,
+                Some(Self::DESTINATION),
+                "Inhibit",
+                &("Ruffle", "Playing a movie"),
+            )
+            .map_err(io::Error::other)?;
+        let cookie = reply.body().deserialize().map_err(io::Error::other)?;
+        Ok(Self { connection, cookie })
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl Drop for Inhibitor {
+    fn drop(&mut self) {
+        // The inhibition also ends with the connection, if this fails.
+        let _ = self.connection.call_method(
+            Some(Self::DESTINATION),
+            Self::PATH,
+            Some(Self::DESTINATION),
+            "UnInhibit",
+            &(self.cookie,),
+        );
+    }
+}
+
+/// Stops the display from sleeping until dropped, by running `caffeinate` alongside Ruffle.
+#[cfg(target_os = "macos")]
+struct Inhibitor(std::process::Child);
+
+#[cfg(target_os = "macos")]
+impl Inhibitor {
+    fn acquire() -> io::Result<Self> {
+        // `-w` makes it exit along with Ruffle, even if Ruffle doesn't get to stop it.
+        std::process::Command::new("caffeinate")
+            .arg("-d")
+            .arg("-w")
+            .arg(std::process::id().to_string())
+            .spawn()
+            .map(Self)
+    }
+}
+
+#[cfg(target_os = "macos")]
+impl Drop for Inhibitor {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+#[cfg(not(any(windows, target_os = "linux", target_os = "macos")))]
+struct Inhibitor;
+
+#[cfg(not(any(windows, target_os = "linux", target_os = "macos")))]
+impl Inhibitor {
+    fn acquire() -> io::Result<Self> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "not supported on this platform",
+        ))
+    }
+}
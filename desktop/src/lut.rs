@@ -0,0 +1,235 @@
+//! Color-correction lookup tables, to present movies as they looked on a particular display.
+//!
+//! Tables are read from the `.cube` format used by most color grading tools.
+
+use anyhow::{anyhow, Context, Error};
+use std::path::Path;
+
+/// The sizes of 3D LUTs which are accepted, in entries per side.
+const SIZES: std::ops::RangeInclusive<u32> = 2..=256;
+
+/// A 3D lookup table mapping each RGB color to a corrected one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColorLut {
+    /// How many entries the table has along each side.
+    size: u32,
+    /// The colors mapped to the first and last entries along each side.
+    domain_min: [f32; 3],
+    domain_max: [f32; 3],
+    /// The corrected colors, with red changing fastest and blue slowest.
+    table: Vec<[f32; 3]>,
+}
+
+impl ColorLut {
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Couldn't read {}", path.display()))?;
+        Self::parse(&text).with_context(|| format!("{} isn't a valid .cube LUT", path.display()))
+    }
+
+    pub fn parse(text: &str) -> Result<Self, Error> {
+        let mut size = None;
+        let mut domain_min = [0.0; 3];
+        let mut domain_max = [1.0; 3];
+        let mut table = Vec::new();
+        for (index, line) in text.lines().enumerate() {
+            let line_number = index + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (keyword, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+            let rest = rest.trim();
+            let result = match keyword {
+                "TITLE" => Ok(()),
+                "LUT_3D_SIZE" => parse_size(rest).map(|value| size = Some(value)),
+                "DOMAIN_MIN" => parse_color(rest).map(|value| domain_min = value),
+                "DOMAIN_MAX" => parse_color(rest).map(|value| domain_max = value),
+                "LUT_3D_INPUT_RANGE" => parse_range(rest).map(|(min, max)| {
+                    domain_min = [min; 3];
+                    domain_max = [max; 3];
+                }),
+                "LUT_1D_SIZE" | "LUT_1D_INPUT_RANGE" => {
+                    Err(anyhow!("only 3D LUTs are supported, not 1D ones"))
+                }
+                _ if keyword.starts_with(|c: char| c.is_ascii_alphabetic()) => {
+                    Err(anyhow!("unknown keyword {keyword}"))
+                }
+                _ if size.is_none() => Err(anyhow!("LUT_3D_SIZE must come before the table")),
+                _ => parse_color(line).map(|value| table.push(value)),
+            };
+            result.with_context(|| format!("Line {line_number}"))?;
+        }
+
+        let size = size.ok_or_else(|| anyhow!("LUT_3D_SIZE is missing"))?;
+        let expected = (size as usize).pow(3);
+        if table.len() != expected {
+            return Err(anyhow!(
+                "A LUT of size {size} needs {expected} entries, but it has {}",
+                table.len()
+            ));
+        }
+        if (0..3).any(|channel| domain_min[channel] >= domain_max[channel]) {
+            return Err(anyhow!("DOMAIN_MIN must be less than DOMAIN_MAX"));
+        }
+        Ok(Self {
+            size,
+            domain_min,
+            domain_max,
+            table,
+        })
+    }
+
+    pub fn size(&self) -> u32 {
+        self.size
+    }
+
+    /// The table as the bytes of `Rgba16Float` texels of a 3D texture, with red along its
+    /// width and blue along its depth. Half floats keep gradients free of banding.
+    pub fn to_rgba16f(&self) -> Vec<u8> {
+        self.table
+            .iter()
+            .flat_map(|&[r, g, b]| [r, g, b, 1.0])
+            .flat_map(|channel| to_f16_bits(channel).to_le_bytes())
+            .collect()
+    }
+
+    /// The scale and offset that turn a color into the coordinates of the texture made by
+    /// [`ColorLut::to_rgba16f`] that its corrected color is sampled from.
+    pub fn texture_transform(&self) -> ([f32; 3], [f32; 3]) {
+        let size = self.size as f32;
+        let mut scale = [0.0; 3];
+        let mut offset = [0.0; 3];
+        for channel in 0..3 {
+            // The first and last entries are at the centers of the texels at the edges.
+            scale[channel] =
+                (size - 1.0) / (size * (self.domain_max[channel] - self.domain_min[channel]));
+            offset[channel] = 0.5 / size - self.domain_min[channel] * scale[channel];
+        }
+        (scale, offset)
+    }
+}
+
+/// Converts a color channel, clamped between 0 and 1, to the bits of the nearest half float.
+fn to_f16_bits(value: f32) -> u16 {
+    let value = value.clamp(0.0, 1.0);
+    if value < 2f32.powi(-14) {
+        // Subnormal half floats count in steps of 2^-24.
+        return (value * 2f32.powi(24)).round() as u16;
+    }
+    let bits = value.to_bits();
+    let exponent = ((bits >> 23) & 0xff) + 15 - 127;
+    let mantissa = bits & 0x7f_ffff;
+    // Rounding up may carry into the exponent, which still gives the right number.
+    let half = (exponent << 10) | (mantissa >> 13);
+    (half + ((mantissa >> 12) & 1)) as u16
+}
+
+fn parse_size(value: &str) -> Result<u32, Error> {
+    let size = value
+        .parse::<u32>()
+        .map_err(|_| anyhow!("{value} is not a size"))?;
+    if !SIZES.contains(&size) {
+        return Err(anyhow!(
+            "a size of {size} is not between {} and {}",
+            SIZES.start(),
+            SIZES.end()
+        ));
+    }
+    Ok(size)
+}
+
+fn parse_color(value: &str) -> Result<[f32; 3], Error> {
+    let channels = value
+        .split_whitespace()
+        .map(|channel| {
+            channel
+                .parse::<f32>()
+                .ok()
+                .filter(|channel| channel.is_finite())
+                .ok_or_else(|| anyhow!("{channel} is not a number"))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    channels
+        .try_into()
+        .map_err(|_| anyhow!("expected 3 numbers, but found \"{value}\""))
+}
+
+fn parse_range(value: &str) -> Result<(f32, f32), Error> {
+    match value.split_whitespace().collect::<Vec<_>>()[..] {
+        [min, max] => match (min.parse::<f32>(), max.parse::<f32>()) {
+            (Ok(min), Ok(max)) if min.is_finite() && max.is_finite() => Ok((min, max)),
+            _ => Err(anyhow!("\"{value}\" is not a range")),
+        },
+        _ => Err(anyhow!("expected 2 numbers, but found \"{value}\"")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const IDENTITY: &str = "TITLE \"Identity\"
+# Red changes fastest.
+LUT_3D_SIZE 2
+0 0 0
+1 0 0
+0 1 0
+1 1 0
+0 0 1
+1 0 1
+0 1 1
+1 1 1
+";
+
+    #[test]
+    fn parses_cube_files() {
+        let lut = ColorLut::parse(IDENTITY).unwrap();
+        assert_eq!(lut.size(), 2);
+        assert_eq!(&lut.to_rgba16f()[8..16], &[0, 0x3c, 0, 0, 0, 0, 0, 0x3c]);
+        assert_eq!(lut.texture_transform(), ([0.5; 3], [0.25; 3]));
+
+        let with_domain = format!("DOMAIN_MIN 0 0 0\nDOMAIN_MAX 2 2 2\n{IDENTITY}");
+        let lut = ColorLut::parse(&with_domain).unwrap();
+        assert_eq!(lut.texture_transform(), ([0.25; 3], [0.25; 3]));
+    }
+
+    #[test]
+    fn converts_to_half_floats() {
+        assert_eq!(to_f16_bits(0.0), 0);
+        assert_eq!(to_f16_bits(1.0), 0x3c00);
+        assert_eq!(to_f16_bits(0.5), 0x3800);
+        assert_eq!(to_f16_bits(0.1), 0x2e66);
+        assert_eq!(to_f16_bits(2f32.powi(-24)), 1);
+        assert_eq!(to_f16_bits(2.0), 0x3c00);
+    }
+
+    #[test]
+    fn rejects_invalid_files() {
+        let error = |text: &str| format!("{:#}", ColorLut::parse(text).unwrap_err());
+        assert_eq!(
+            error("0 0 0\n"),
+            "Line 1: LUT_3D_SIZE must come before the table"
+        );
+        assert_eq!(
+            error("LUT_1D_SIZE 4\n"),
+            "Line 1: only 3D LUTs are supported, not 1D ones"
+        );
+        assert_eq!(
+            error("LUT_3D_SIZE 2\n0 0\n"),
+            "Line 2: expected 3 numbers, but found \"0 0\""
+        );
+        assert_eq!(
+            error("LUT_3D_SIZE 2\n0 0 0\n"),
+            "A LUT of size 2 needs 8 entries, but it has 1"
+        );
+        assert_eq!(
+            error(&format!("DOMAIN_MIN 1 1 1\n{IDENTITY}")),
+            "DOMAIN_MIN must be less than DOMAIN_MAX"
+        );
+        assert_eq!(
+            error(&format!("LUT_3D_INPUT_RANGE 0 inf\n{IDENTITY}")),
+            "Line 1: \"0 inf\" is not a range"
+        );
+    }
+}
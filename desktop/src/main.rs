@@ -8,28 +8,53 @@
 
 mod app;
 mod backends;
+mod captions;
 mod cli;
+mod compat_check;
 mod custom_event;
+mod export;
+mod feature_report;
+mod frame_skip;
 mod gui;
+mod keep_awake;
 mod log;
+mod lut;
 mod player;
 mod preferences;
+mod recording;
+mod script;
+mod stems;
+mod touch;
 mod util;
 
+use crate::feature_report::FeatureReport;
+use crate::gui::create_wgpu_instance;
+use crate::log::{avm_error_layer, RotatingLogFile};
 use crate::preferences::GlobalPreferences;
-use anyhow::Error;
+use anyhow::{anyhow, Context, Error};
 use app::App;
+use chrono::Utc;
 use clap::Parser;
-use cli::Opt;
+use cli::{Opt, ReportFormat};
+use cpal::traits::{DeviceTrait, HostTrait};
 use rfd::MessageDialogResult;
+use ruffle_core::tag_utils::SwfMovie;
 use ruffle_core::StaticCallstack;
+use ruffle_frontend_utils::bundle::create::create_bundle;
+use ruffle_frontend_utils::bundle::Bundle;
+use ruffle_frontend_utils::play_stats::PlayStatsExt;
+use ruffle_frontend_utils::recents::RecentsExt;
 use std::cell::RefCell;
 use std::env;
 use std::fs::File;
+use std::io::{BufWriter, Write};
 use std::panic::PanicInfo;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
 use tracing_subscriber::fmt::Layer;
 use tracing_subscriber::layer::SubscriberExt;
 use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Layer as _};
 
 use url::Url;
 
@@ -39,6 +64,9 @@ thread_local! {
     static SWF_INFO: RefCell<Option<String>> = RefCell::default();
 }
 
+/// Where crash reports are saved, set once the configuration directory is known.
+static CRASH_REPORT_DIRECTORY: OnceLock<PathBuf> = OnceLock::new();
+
 #[cfg(feature = "tracy")]
 #[global_allocator]
 static GLOBAL: tracing_tracy::client::ProfiledAllocator<std::alloc::System> =
@@ -72,6 +100,58 @@ fn init() {
     }));
 }
 
+/// Saves everything we know about a crash to a file, so that it can be attached to a bug report.
+///
+/// This writes straight to the file rather than building up the report in memory,
+/// and doesn't rely on any state that may have been left borrowed by the panic.
+fn write_crash_report(info: &PanicInfo) -> std::io::Result<PathBuf> {
+    let directory = CRASH_REPORT_DIRECTORY
+        .get()
+        .cloned()
+        .unwrap_or_else(env::temp_dir);
+    std::fs::create_dir_all(&directory)?;
+    let path = directory.join(
+        Utc::now()
+            .format("ruffle_crash_%F_%H-%M-%S.txt")
+            .to_string(),
+    );
+    let mut file = BufWriter::new(File::create(&path)?);
+
+    writeln!(file, "Ruffle version: {RUFFLE_VERSION}")?;
+    writeln!(file, "Operating system: {}", os_info::get())?;
+    SWF_INFO.with(|i| match i.try_borrow().as_deref() {
+        Ok(Some(swf_name)) => writeln!(file, "Filename: {swf_name}"),
+        _ => Ok(()),
+    })?;
+
+    writeln!(file, "\n## Panic\n{info}")?;
+    writeln!(
+        file,
+        "\n## Backtrace\n{}",
+        std::backtrace::Backtrace::force_capture()
+    )?;
+
+    let mut result = Ok(());
+    CALLSTACK.with(|callstack| {
+        if let Ok(callstack) = callstack.try_borrow() {
+            if let Some(callstack) = &*callstack {
+                callstack.avm2(|callstack| {
+                    result = writeln!(file, "\n## AVM2 Callstack\n{callstack}");
+                });
+            }
+        }
+    });
+    result?;
+
+    RENDER_INFO.with(|i| match i.try_borrow().as_deref() {
+        Ok(Some(render_info)) => writeln!(file, "\n## Render Info\n{render_info}"),
+        _ => Ok(()),
+    })?;
+
+    file.flush()?;
+    Ok(path)
+}
+
 fn panic_hook(info: &PanicInfo) {
     CALLSTACK.with(|callstack| {
         if let Some(callstack) = &*callstack.borrow() {
@@ -79,6 +159,20 @@ fn panic_hook(info: &PanicInfo) {
         }
     });
 
+    let report = match write_crash_report(info) {
+        Ok(path) => {
+            eprintln!("Saved a crash report to {}", path.display());
+            format!(
+                "A report with more details was saved to:\n{}\n\n",
+                path.display()
+            )
+        }
+        Err(e) => {
+            eprintln!("Couldn't save a crash report: {e}");
+            String::new()
+        }
+    };
+
     // [NA] Let me just point out that PanicInfo::message() exists but isn't stable and that sucks.
     let panic_text = info.to_string();
     let message = if let Some(text) = panic_text.strip_prefix("panicked at '") {
@@ -97,6 +191,7 @@ fn panic_hook(info: &PanicInfo) {
         .set_description(format!(
             "Ruffle has encountered a fatal error, this is a bug.\n\n\
             {message}\n\n\
+            {report}\
             Please report this to us so that we can fix it. Thank you!\n\
             Pressing Yes will open a browser window."
         ))
@@ -146,12 +241,239 @@ fn shutdown() {
     }
 }
 
+/// Prints any problems with the bundle at `path`, failing if it can't be played.
+fn validate_bundle(path: &Path) -> Result<(), Error> {
+    let bundle = Bundle::from_path(path)
+        .with_context(|| format!("{} isn't a valid bundle", path.display()))?;
+    println!("Name: {}", bundle.information().name);
+    println!("URL: {}", bundle.information().url);
+    for warning in bundle.warnings() {
+        println!("Warning: {warning}");
+    }
+
+    let errors = bundle.validate();
+    for error in &errors {
+        println!("Error: {error}");
+    }
+    if !errors.is_empty() {
+        return Err(anyhow!(
+            "{} has {} problem(s) that stop it from playing",
+            path.display(),
+            errors.len()
+        ));
+    }
+    println!("{} is valid", path.display());
+    Ok(())
+}
+
+/// Prints the name of every audio output device, as used to pick one in the preferences.
+fn print_audio_devices() -> Result<(), Error> {
+    let host = cpal::default_host();
+    let default_name = host
+        .default_output_device()
+        .and_then(|device| device.name().ok());
+    let devices = host
+        .output_devices()
+        .context("Couldn't list the audio output devices")?;
+    for name in devices.filter_map(|device| device.name().ok()) {
+        if Some(&name) == default_name.as_ref() {
+            println!("{name} (default)");
+        } else {
+            println!("{name}");
+        }
+    }
+    Ok(())
+}
+
+/// Prints every graphics adapter, along with the backend it's used through.
+fn print_gpus(preferences: &GlobalPreferences) {
+    // The default is whichever adapter a window would be given with the current preferences.
+    let default_adapter = create_wgpu_instance(preferences.graphics_backends().into())
+        .ok()
+        .and_then(|(instance, _)| {
+            futures::executor::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+                power_preference: preferences.graphics_power_preference().into(),
+                ..Default::default()
+            }))
+        })
+        .map(|adapter| adapter.get_info());
+
+    let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
+        backends: wgpu::Backends::all(),
+        flags: wgpu::InstanceFlags::default().with_env(),
+        ..Default::default()
+    });
+    for adapter in instance.enumerate_adapters(wgpu::Backends::all()) {
+        let info = adapter.get_info();
+        println!(
+            "{} ({}, {:?}){}",
+            info.name,
+            info.backend.to_str(),
+            info.device_type,
+            if default_adapter.as_ref() == Some(&info) {
+                " (default)"
+            } else {
+                ""
+            }
+        );
+    }
+}
+
+/// Prints what can be learned about the movie given on the command line, without playing it.
+fn print_movie_info(opt: &Opt) -> Result<(), Error> {
+    let movie_url = opt
+        .movie_url
+        .as_ref()
+        .ok_or_else(|| anyhow!("A movie is required to print its information"))?;
+    let movie_path = movie_url
+        .to_file_path()
+        .map_err(|_| anyhow!("Only local movies can be inspected, not {movie_url}"))?;
+    let data = std::fs::read(&movie_path)
+        .with_context(|| format!("Couldn't read {}", movie_path.display()))?;
+
+    // The compression is known even when the movie can't be decompressed.
+    let compression = ruffle_core::swf::read::read_compression_type(&data[..])
+        .map_err(|e| anyhow!("{} isn't a SWF: {e}", movie_path.display()))?;
+    println!("Compression: {compression}");
+
+    let movie = SwfMovie::from_data(&data, movie_url.to_string(), None)?;
+    println!("SWF version: {}", movie.version());
+    println!(
+        "Size: {}x{}",
+        movie.width().to_pixels(),
+        movie.height().to_pixels()
+    );
+    println!("Frame rate: {}", movie.frame_rate());
+    println!("Frames: {}", movie.num_frames());
+    println!(
+        "ActionScript version: {}",
+        if movie.is_action_script_3() { 3 } else { 2 }
+    );
+    Ok(())
+}
+
+/// Prints which features the movie given on the command line uses, without playing it.
+fn print_feature_report(opt: &Opt, format: ReportFormat) -> Result<(), Error> {
+    let movie_url = opt
+        .movie_url
+        .as_ref()
+        .ok_or_else(|| anyhow!("A movie is required to report its features"))?;
+    let movie_path = movie_url
+        .to_file_path()
+        .map_err(|_| anyhow!("Only local movies can be inspected, not {movie_url}"))?;
+    let data = std::fs::read(&movie_path)
+        .with_context(|| format!("Couldn't read {}", movie_path.display()))?;
+
+    let report = FeatureReport::from_data(&data)
+        .with_context(|| format!("{} isn't a valid SWF", movie_path.display()))?;
+    match format {
+        ReportFormat::Text => println!("{}", report.to_text()),
+        ReportFormat::Json => println!("{}", report.to_json()),
+    }
+    Ok(())
+}
+
 #[tokio::main]
 async fn main() -> Result<(), Error> {
     init();
 
-    let opt = Opt::parse();
+    let mut opt = Opt::parse();
+    if opt.safe_mode {
+        for change in opt.apply_safe_mode() {
+            println!("Safe mode: {change}");
+        }
+    }
     let preferences = GlobalPreferences::load(opt.clone())?;
+    let _ = CRASH_REPORT_DIRECTORY.set(preferences.cli.config.join("crash_reports"));
+
+    if preferences.cli.dump_recents {
+        println!("{}", preferences.recents(|recents| recents.to_json()));
+        shutdown();
+        return Ok(());
+    }
+
+    if preferences.cli.dump_stats {
+        println!(
+            "{}",
+            preferences.play_stats(|play_stats| play_stats.to_json())
+        );
+        shutdown();
+        return Ok(());
+    }
+
+    if preferences.cli.list_audio_devices {
+        let result = print_audio_devices();
+        shutdown();
+        return result;
+    }
+
+    if preferences.cli.list_gpus {
+        print_gpus(&preferences);
+        shutdown();
+        return Ok(());
+    }
+
+    if preferences.cli.reset_preferences {
+        let had_preferences = preferences.cli.config.join("preferences.toml").exists();
+        preferences.reset()?;
+        if had_preferences {
+            println!(
+                "Preferences have been reset, the previous ones were saved to {}",
+                preferences.preferences_backup_path().display()
+            );
+        } else {
+            println!("Preferences have been reset");
+        }
+        shutdown();
+        return Ok(());
+    }
+
+    if let (true, Some(swf), Some(out)) = (
+        preferences.cli.create_bundle,
+        &preferences.cli.swf,
+        &preferences.cli.out,
+    ) {
+        let result = create_bundle(swf, out, preferences.cli.bundle_name.clone())
+            .with_context(|| format!("Couldn't create a bundle at {}", out.display()))
+            .map(|information| {
+                println!(
+                    "Created bundle \"{}\" at {}",
+                    information.name,
+                    out.display()
+                );
+            });
+        shutdown();
+        return result;
+    }
+
+    if let Some(path) = &preferences.cli.validate_bundle {
+        let result = validate_bundle(path);
+        shutdown();
+        return result;
+    }
+
+    if preferences.cli.info {
+        let result = print_movie_info(&preferences.cli);
+        shutdown();
+        return result;
+    }
+
+    if let Some(format) = preferences.cli.feature_report {
+        let result = print_feature_report(&preferences.cli, format);
+        shutdown();
+        return result;
+    }
+
+    if let Some(format) = preferences.cli.compat_check {
+        let report = compat_check::check_compatibility(&preferences);
+        shutdown();
+        let report = report?;
+        match format {
+            ReportFormat::Text => println!("{}", report.to_text()),
+            ReportFormat::Json => println!("{}", report.to_json()),
+        }
+        std::process::exit(report.severity().exit_code());
+    }
 
     // [NA] `_guard` cannot be `_` or it'll immediately drop
     // https://docs.rs/tracing-appender/latest/tracing_appender/non_blocking/index.html
@@ -161,26 +483,58 @@ async fn main() -> Result<(), Error> {
     let (non_blocking_file, _file_guard) = tracing_appender::non_blocking(File::create(log_path)?);
     let (non_blocking_stdout, _stdout_guard) = tracing_appender::non_blocking(std::io::stdout());
 
-    let env_filter = tracing_subscriber::EnvFilter::builder().parse_lossy(
-        env::var("RUST_LOG")
-            .as_deref()
-            .unwrap_or("warn,ruffle=info,avm_trace=info"),
-    );
+    let directives =
+        env::var("RUST_LOG").unwrap_or_else(|_| preferences.cli.log_filter().to_string());
+    let env_filter = || EnvFilter::builder().parse_lossy(&directives);
+
+    let (log_file_layer, _log_file_guard) = match &preferences.cli.log_file {
+        Some(path) => {
+            let file =
+                RotatingLogFile::new(path.clone(), preferences.cli.log_file_max_size as u64)?;
+            let (non_blocking, guard) = tracing_appender::non_blocking(file);
+            let filter = match &preferences.cli.log_file_filter {
+                Some(filter) => EnvFilter::builder().parse_lossy(filter),
+                None => env_filter(),
+            };
+            let layer = Layer::new()
+                .with_writer(non_blocking)
+                .with_ansi(false)
+                .with_filter(filter);
+            (Some(layer), Some(guard))
+        }
+        None => (None, None),
+    };
 
     let subscriber = tracing_subscriber::registry()
-        .with(env_filter)
-        .with(Layer::new().with_writer(non_blocking_stdout))
-        .with(Layer::new().with_writer(non_blocking_file).with_ansi(false));
+        .with(
+            Layer::new()
+                .with_writer(non_blocking_stdout)
+                .with_filter(env_filter()),
+        )
+        .with(
+            Layer::new()
+                .with_writer(non_blocking_file)
+                .with_ansi(false)
+                .with_filter(env_filter()),
+        )
+        .with(log_file_layer)
+        .with(preferences.cli.break_on_error.then(avm_error_layer));
 
     #[cfg(feature = "tracy")]
     let subscriber = {
-        let tracy_subscriber = tracing_tracy::TracyLayer::new();
+        let tracy_subscriber = tracing_tracy::TracyLayer::new().with_filter(env_filter());
         subscriber.with(tracy_subscriber)
     };
 
     subscriber.init();
 
-    let result = App::new(preferences).and_then(|app| app.run());
+    let result = if let Some(output) = preferences.cli.export_gif.clone() {
+        export::export_gif(&preferences, &output)
+    } else if let Some(output) = preferences.cli.screenshot.clone() {
+        export::export_screenshot(&preferences, &output)
+    } else {
+        App::new(preferences).and_then(|app| app.run())
+    };
 
     #[cfg(windows)]
     if let Err(error) = &result {
@@ -1,34 +1,47 @@
 use crate::backends::{
-    CpalAudioBackend, DesktopExternalInterfaceProvider, DesktopFSCommandProvider, DesktopUiBackend,
-    RfdNavigatorInterface,
+    BlockedTime, CpalAudioBackend, CustomCursor, DesktopExternalInterfaceProvider,
+    DesktopFSCommandProvider, DesktopUiBackend, ExternalInterfaceValues, ModalDialogGuard,
+    ModalDialogs, RfdNavigatorInterface,
 };
-use crate::custom_event::RuffleEvent;
-use crate::gui::MovieView;
-use crate::preferences::GlobalPreferences;
+use crate::cli::{AvmVersion, VideoBackendMode};
+use crate::custom_event::{RuffleEvent, RuffleEventProxy};
+use crate::gui::{MovieView, Splash};
+use crate::preferences::{system_locale, GlobalPreferences};
+use crate::recording::{InputRecorder, InputReplay};
+use crate::script::{Script, ScriptCommand};
 use crate::{CALLSTACK, RENDER_INFO, SWF_INFO};
-use anyhow::anyhow;
+use anyhow::{anyhow, Context, Error};
+use chrono::{DateTime, Utc};
 use ruffle_core::backend::navigator::{OpenURLMode, SocketMode};
-use ruffle_core::config::Letterbox;
+use ruffle_core::config::{Letterbox, LetterboxStyle, LoopMode};
 use ruffle_core::events::{GamepadButton, KeyCode};
-use ruffle_core::{DefaultFont, LoadBehavior, Player, PlayerBuilder, PlayerEvent};
+use ruffle_core::tag_utils::SwfMovie;
+use ruffle_core::{
+    AssetStats, DefaultFont, LoadBehavior, Player, PlayerBuilder, PlayerEvent, StageAlign,
+    StageScaleMode,
+};
 use ruffle_frontend_utils::backends::executor::{AsyncExecutor, PollRequester};
-use ruffle_frontend_utils::backends::navigator::ExternalNavigatorBackend;
+use ruffle_frontend_utils::backends::navigator::{
+    ExternalNavigatorBackend, FileRoot, NavigatorOptions, NetworkPolicy, NetworkStats,
+};
 use ruffle_frontend_utils::bundle::source::BundleSourceError;
 use ruffle_frontend_utils::bundle::{Bundle, BundleError};
 use ruffle_frontend_utils::content::PlayingContent;
 use ruffle_frontend_utils::player_options::PlayerOptions;
-use ruffle_render::backend::RenderBackend;
+use ruffle_render::backend::{RenderBackend, ViewportDimensions};
 use ruffle_render::quality::StageQuality;
 use ruffle_render_wgpu::backend::WgpuRenderBackend;
 use ruffle_render_wgpu::descriptors::Descriptors;
+use std::cell::RefCell;
 use std::collections::{HashMap, HashSet};
 use std::fmt::Debug;
-use std::path::PathBuf;
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
 use std::sync::{Arc, Mutex, MutexGuard};
-use std::time::Duration;
+use std::time::{Duration, Instant};
+use unic_langid::LanguageIdentifier;
 use url::Url;
-use winit::event_loop::EventLoopProxy;
 use winit::window::Window;
 
 /// Options used when creating a Player (& passed through to a PlayerBuilder).
@@ -39,11 +52,62 @@ pub struct LaunchOptions {
     pub proxy: Option<Url>,
     pub socket_allowed: HashSet<String>,
     pub tcp_connections: Option<SocketMode>,
+    /// Whether to ignore the network policy of a bundle.
+    pub ignore_bundle_network_policy: bool,
+    /// The network policy to play with instead of the content's own, such as the one
+    /// of a bundle that navigated to a movie outside of it.
+    pub network_policy: Option<NetworkPolicy>,
+    pub log_requests: bool,
+    /// Whether the movie is played without its background color.
+    pub transparent: bool,
+    /// How many requests may be made over the network at once, if limited.
+    pub max_connections: Option<NonZeroUsize>,
+    /// The size in bytes above which the user is asked before a file is loaded, if any.
+    pub confirm_larger_than: Option<u64>,
+    pub retry_busy_files: bool,
+    /// The directory local files may only be loaded from, if confined to one.
+    pub file_root: Option<FileRoot>,
+    pub network_stats: bool,
+    /// The locale reported to the movie as the language of the system.
+    pub locale: LanguageIdentifier,
+    pub fake_time: Option<DateTime<Utc>>,
+    pub fake_time_rate: f64,
+    pub record_input: Option<PathBuf>,
+    pub record_audio_stems: Option<PathBuf>,
+    pub replay: Option<PathBuf>,
+    pub script: Option<PathBuf>,
+    pub video_backend: VideoBackendMode,
+    /// The values returned by the dummy External Interface, if it's used.
+    pub external_interface_values: ExternalInterfaceValues,
+    /// Whether calls to the dummy External Interface are logged.
+    pub log_external_interface: bool,
+    /// The only methods of the dummy External Interface the movie may call, if limited.
+    pub allowed_external_interface_methods: Option<HashSet<String>>,
     pub fullscreen: bool,
+    /// The preferred width and height of the window, where the movie's own size is used for any that are missing.
+    pub window_size: (Option<f64>, Option<f64>),
     pub save_directory: PathBuf,
     pub open_url_mode: OpenURLMode,
+    /// Whether SWFs the movie opens in its own window are played in place of it.
+    pub open_swfs_in_place: bool,
     pub gamepad_button_mapping: HashMap<GamepadButton, KeyCode>,
     pub avm2_optimizer_enabled: bool,
+    pub avm2_optimizer_denylist: Vec<String>,
+    pub avm2_call_trace: Option<Vec<String>>,
+    pub loop_mode: LoopMode,
+    pub letterbox_style: LetterboxStyle,
+    pub max_memory: Option<usize>,
+    /// How often a full garbage collection is run, if periodically.
+    pub auto_gc: Option<Duration>,
+    /// How long the movie may go without showing its first frame once downloaded, if limited.
+    pub first_frame_timeout: Option<Duration>,
+    pub break_on_error: bool,
+    /// The only ActionScript virtual machine movies may use, if it's forced.
+    pub require_avm: Option<AvmVersion>,
+    /// Whether the movie starts playing right away, rather than waiting for a click.
+    pub autoplay: bool,
+    /// Shows a splash while the movie loads, with the given image or otherwise a spinner.
+    pub splash: Option<Option<PathBuf>>,
 }
 
 impl From<&GlobalPreferences> for LaunchOptions {
@@ -77,7 +141,11 @@ impl From<&GlobalPreferences> for LaunchOptions {
                 player_version: value.cli.player_version,
                 player_runtime: value.cli.player_runtime,
                 frame_rate: value.cli.frame_rate,
-                dummy_external_interface: if value.cli.dummy_external_interface {
+                dummy_external_interface: if value.cli.dummy_external_interface
+                    || value.cli.external_interface_values.is_some()
+                    || value.cli.log_external_interface
+                    || value.cli.allowed_external_interface_methods().is_some()
+                {
                     Some(true)
                 } else {
                     None
@@ -85,18 +153,186 @@ impl From<&GlobalPreferences> for LaunchOptions {
             },
             proxy: value.cli.proxy.clone(),
             fullscreen: value.cli.fullscreen,
+            window_size: value.cli.window_size(),
             save_directory: value.cli.save_directory.clone(),
             open_url_mode: value.cli.open_url_mode,
+            open_swfs_in_place: value.cli.open_swfs_in_place,
             socket_allowed: HashSet::from_iter(value.cli.socket_allow.iter().cloned()),
             tcp_connections: value.cli.tcp_connections,
+            ignore_bundle_network_policy: value.cli.ignore_bundle_network_policy,
+            network_policy: None,
+            log_requests: value.cli.log_requests,
+            transparent: value.cli.transparent,
+            max_connections: value.cli.max_connections,
+            confirm_larger_than: value.cli.confirm_larger_than,
+            retry_busy_files: value.cli.retry_busy_files,
+            file_root: value.cli.file_root.clone(),
+            network_stats: value.cli.network_stats,
+            locale: value.cli.locale.clone().unwrap_or_else(system_locale),
+            fake_time: value.cli.fake_time,
+            fake_time_rate: value.cli.fake_time_rate,
+            record_input: value.cli.record_input.clone(),
+            record_audio_stems: value.cli.record_audio_stems.clone(),
+            replay: value.cli.replay.clone(),
+            script: value.cli.script.clone(),
+            video_backend: value.cli.video_backend,
+            external_interface_values: value
+                .cli
+                .external_interface_values
+                .clone()
+                .unwrap_or_default(),
+            log_external_interface: value.cli.log_external_interface,
+            allowed_external_interface_methods: value.cli.allowed_external_interface_methods(),
             gamepad_button_mapping: HashMap::from_iter(value.cli.gamepad_button.iter().cloned()),
             avm2_optimizer_enabled: !value.cli.no_avm2_optimizer,
+            avm2_optimizer_denylist: value.cli.avm2_optimizer_denylist(),
+            avm2_call_trace: value.cli.avm2_call_trace(),
+            loop_mode: value.cli.loop_mode,
+            letterbox_style: LetterboxStyle {
+                color: value.cli.letterbox_color,
+                pattern: value.cli.letterbox_pattern,
+            },
+            max_memory: value.cli.max_memory,
+            auto_gc: value.cli.auto_gc.filter(|interval| !interval.is_zero()),
+            first_frame_timeout: Some(value.cli.first_frame_timeout)
+                .filter(|timeout| !timeout.is_zero()),
+            break_on_error: value.cli.break_on_error,
+            require_avm: value.cli.require_avm,
+            autoplay: value.autoplay(),
+            splash: value.cli.splash.clone(),
+        }
+    }
+}
+
+impl LaunchOptions {
+    /// The options of the navigator that loads the movie, where anything the content
+    /// may change, like the network policy of a bundle, is left to the caller.
+    pub fn navigator_options(&self) -> NavigatorOptions {
+        NavigatorOptions {
+            proxy: self.proxy.clone(),
+            upgrade_to_https: self.player.upgrade_to_https.unwrap_or_default(),
+            open_url_mode: self.open_url_mode,
+            socket_allowed: self.socket_allowed.clone(),
+            socket_mode: self.tcp_connections.unwrap_or(SocketMode::Ask),
+            network_policy: NetworkPolicy::Unrestricted,
+            log_requests: self.log_requests,
+            max_connections: self.max_connections,
+            confirm_larger_than: self.confirm_larger_than,
+            retry_busy_files: self.retry_busy_files,
+            file_root: self.file_root.clone(),
+            open_swfs_in_place: self.open_swfs_in_place,
         }
     }
 }
 
+/// Saves what was last drawn of the movie as a PNG at `path`.
+fn save_screenshot(player: &mut Player, path: &Path) -> Result<(), Error> {
+    let renderer = player
+        .renderer_mut()
+        .downcast_mut::<WgpuRenderBackend<MovieView>>()
+        .ok_or_else(|| anyhow!("The movie isn't drawn by the expected renderer"))?;
+    let image = renderer.target().capture(renderer.descriptors());
+    image
+        .save(path)
+        .with_context(|| format!("Couldn't save {}", path.display()))?;
+    Ok(())
+}
+
+/// Refuses to play the file at `path` if it's outside of the `--file-root`, when there is one.
+fn check_file_root(file_root: Option<&FileRoot>, path: &Path) -> Result<(), Error> {
+    match file_root {
+        Some(root) if !root.contains(path) => Err(anyhow!(
+            "{} is outside of the file root {}, so it can't be played.\n\nOnly files within the directory given to --file-root may be opened.",
+            path.display(),
+            root.path().display()
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Refuses a movie that doesn't use the `required` ActionScript virtual machine.
+fn check_required_avm(required: AvmVersion, is_action_script_3: bool) -> Result<(), Error> {
+    let (uses, required_number) = match (is_action_script_3, required) {
+        (true, AvmVersion::Avm1) => ("ActionScript 3 (AVM2)", 1),
+        (false, AvmVersion::Avm2) => ("ActionScript 1 or 2 (AVM1)", 2),
+        _ => return Ok(()),
+    };
+    Err(anyhow!(
+        "This movie is written in {uses}, so it can't be played with --require-avm {required_number}."
+    ))
+}
+
+/// Opens the content at the given URL, which is either a bundle or a movie.
+fn open_content(movie_url: &Url) -> PlayingContent {
+    let mut content = PlayingContent::DirectFile(movie_url.clone());
+    if movie_url.scheme() == "file" {
+        if let Ok(path) = movie_url.to_file_path() {
+            match Bundle::from_path(&path) {
+                Ok(bundle) => {
+                    if bundle.warnings().is_empty() {
+                        tracing::info!("Opening bundle at {path:?}");
+                    } else {
+                        // TODO: Show warnings to user (toast?)
+                        tracing::warn!("Opening bundle at {path:?} with warnings");
+                        for warning in bundle.warnings() {
+                            tracing::warn!("{warning}");
+                        }
+                    }
+                    content = PlayingContent::Bundle(movie_url.clone(), bundle);
+                }
+                Err(BundleError::BundleDoesntExist)
+                | Err(BundleError::InvalidSource(BundleSourceError::UnknownSource)) => {
+                    // Do nothing and carry on opening it as a swf - this likely isn't a bundle at all
+                }
+                Err(e) => {
+                    // TODO: Visible popup when a bundle (or regular file) fails to open
+                    tracing::error!("Couldn't open bundle at {path:?}: {e}");
+                }
+            }
+        }
+    }
+    content
+}
+
+/// The settings of the stage which movies can change while playing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StageSettings {
+    pub quality: StageQuality,
+    pub scale_mode: StageScaleMode,
+    pub align: StageAlign,
+}
+
+impl StageSettings {
+    fn of(player: &mut Player) -> Self {
+        Self {
+            quality: player.quality(),
+            scale_mode: player.scale_mode(),
+            align: player.align(),
+        }
+    }
+}
+
+/// Which SWF of the content an [`ActivePlayer`] plays.
+enum RootSwf {
+    /// The SWF the content starts with.
+    Initial,
+    /// Another SWF of the content, such as one of a bundle that was navigated to.
+    Url(Url),
+    /// A SWF read from elsewhere, played in place of the one the content starts with.
+    Replacement(SwfMovie),
+}
+
+/// Where the content being played came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentKind {
+    /// A bundle, which may have other SWFs and files of its own.
+    Bundle,
+    /// A movie opened on its own.
+    DirectFile,
+}
+
 #[derive(Clone)]
-struct WinitWaker(EventLoopProxy<RuffleEvent>);
+struct WinitWaker(RuffleEventProxy);
 
 impl PollRequester for WinitWaker {
     fn request_poll(&self) {
@@ -111,14 +347,54 @@ impl PollRequester for WinitWaker {
 struct ActivePlayer {
     player: Arc<Mutex<Player>>,
     executor: Arc<AsyncExecutor<WinitWaker>>,
+    /// Human readable name of the content being played.
+    name: String,
+    /// The URL of the content being played, such as the path of a bundle.
+    url: Url,
+    /// Maximum number of bytes the player may allocate before it's halted.
+    max_memory: Option<usize>,
+    /// Set once the player has been halted for exceeding `max_memory`.
+    memory_limit_exceeded: bool,
+    /// When the next full garbage collection is due, if they're run periodically.
+    next_auto_gc: Option<Instant>,
+    /// Set once the root timeline has played to the end without looping.
+    playback_complete: bool,
+    /// Set once the player has been paused for an error, as requested by `--break-on-error`.
+    broke_on_error: bool,
+    /// When the movie is considered stuck if it hasn't shown its first frame yet.
+    /// This is only set once the movie has been downloaded, so a slow download isn't mistaken for it.
+    first_frame_deadline: Option<Instant>,
+    /// The preferred width and height of the window for this movie.
+    window_size: (Option<f64>, Option<f64>),
+    /// The network restrictions the content is played with.
+    network_policy: NetworkPolicy,
+    /// The modal dialogs currently shown over the movie.
+    modal_dialogs: ModalDialogs,
+    /// How long dialogs blocked the movie, which it shouldn't catch up on.
+    blocked_time: BlockedTime,
+    /// The content being played, which is kept open to play its other SWFs.
+    content: Rc<PlayingContent>,
+    /// The options the content was opened with.
+    options: LaunchOptions,
+    /// Where the input given to the movie is being recorded to, if anywhere.
+    recorder: RefCell<Option<InputRecorder>>,
+    /// Recorded input being given to the movie in place of the user's, if any.
+    replay: Option<InputReplay>,
+    /// The script being run against the movie, if any.
+    script: Option<Script>,
+    /// What the movie loaded, to be summarized once it's closed with `--network-stats`.
+    network_stats: Option<NetworkStats>,
+    /// The stage settings last seen, to tell when the movie changes them.
+    stage_settings: StageSettings,
 }
 
 impl ActivePlayer {
     #[allow(clippy::too_many_arguments)]
     pub fn new(
         opt: &LaunchOptions,
-        event_loop: EventLoopProxy<RuffleEvent>,
-        movie_url: &Url,
+        event_loop: RuffleEventProxy,
+        content: Rc<PlayingContent>,
+        root_swf: RootSwf,
         window: Rc<Window>,
         descriptors: Arc<Descriptors>,
         movie_view: MovieView,
@@ -128,73 +404,133 @@ impl ActivePlayer {
         let mut builder = PlayerBuilder::new();
 
         match CpalAudioBackend::new(&preferences) {
-            Ok(audio) => {
+            Ok(mut audio) => {
+                if let Some(directory) = &opt.record_audio_stems {
+                    if let Err(e) = audio.record_stems(directory) {
+                        tracing::error!("Couldn't record the audio stems: {e:#}");
+                    }
+                }
                 builder = builder.with_audio(audio);
             }
             Err(e) => {
                 tracing::error!("Unable to create audio device: {}", e);
+                if opt.record_audio_stems.is_some() {
+                    tracing::warn!("Audio stems won't be recorded without an audio device");
+                }
             }
         };
 
-        let mut content = PlayingContent::DirectFile(movie_url.clone());
-        if movie_url.scheme() == "file" {
-            if let Ok(path) = movie_url.to_file_path() {
-                match Bundle::from_path(&path) {
-                    Ok(bundle) => {
-                        if bundle.warnings().is_empty() {
-                            tracing::info!("Opening bundle at {path:?}");
-                        } else {
-                            // TODO: Show warnings to user (toast?)
-                            tracing::warn!("Opening bundle at {path:?} with warnings");
-                            for warning in bundle.warnings() {
-                                tracing::warn!("{warning}");
-                            }
-                        }
-                        content = PlayingContent::Bundle(movie_url.clone(), bundle);
-                    }
-                    Err(BundleError::BundleDoesntExist)
-                    | Err(BundleError::InvalidSource(BundleSourceError::UnknownSource)) => {
-                        // Do nothing and carry on opening it as a swf - this likely isn't a bundle at all
-                    }
-                    Err(e) => {
-                        // TODO: Visible popup when a bundle (or regular file) fails to open
-                        tracing::error!("Couldn't open bundle at {path:?}: {e}");
-                    }
+        let custom_cursor = match &*content {
+            PlayingContent::Bundle(_, bundle) => CustomCursor::from_bundle(bundle),
+            _ => None,
+        };
+
+        // The presentation the user picked while last playing this content is used again,
+        // unless they set something else explicitly this time.
+        let presentation = preferences.recent_presentation(content.url());
+        let user_options = PlayerOptions {
+            quality: opt.player.quality.or(presentation.quality),
+            scale: opt.player.scale.or(presentation.scale),
+            letterbox: opt.player.letterbox.or(presentation.letterbox),
+            ..opt.player.clone()
+        };
+
+        // Options the user set explicitly always win over the defaults a bundle asks for.
+        let mut player_options = match &*content {
+            PlayingContent::Bundle(_, bundle) => {
+                let bundle_options = &bundle.information().player;
+                for option in user_options.conflicting_options(bundle_options) {
+                    tracing::warn!(
+                        "Bundle requested a different {option} than the one set by the user, which will be used instead"
+                    );
                 }
+                user_options.or(bundle_options)
             }
+            _ => user_options,
+        };
+
+        let network_policy = match (&opt.network_policy, &*content) {
+            (Some(network_policy), _) => network_policy.clone(),
+            (None, PlayingContent::Bundle(_, bundle)) if !opt.ignore_bundle_network_policy => {
+                bundle.information().network.clone()
+            }
+            (None, _) => NetworkPolicy::Unrestricted,
+        };
+        if network_policy.is_restricted() {
+            tracing::info!("Restricting network access: {network_policy:?}");
         }
 
+        let blocked_time = BlockedTime::default();
         let (executor, future_spawner) = AsyncExecutor::new(WinitWaker(event_loop.clone()));
-        let movie_url = content.initial_swf_url().clone();
+        let content_url = content.url().clone();
+        let (movie_url, replacement) = match root_swf {
+            RootSwf::Initial => (content.initial_swf_url().clone(), None),
+            RootSwf::Url(url) => (url, None),
+            RootSwf::Replacement(movie) => (content.initial_swf_url().clone(), Some(movie)),
+        };
         let readable_name = content.name();
+
+        // The movie gets the parameters in the query string of its URL, but that's the spoofed
+        // URL if there is one, so the real URL's parameters are passed along with the others.
+        // Later parameters win, so the ones given on the command line still take precedence.
+        if player_options.spoof_url.is_some() {
+            let mut parameters = movie_url
+                .query_pairs()
+                .map(|(key, value)| (key.into_owned(), value.into_owned()))
+                .collect::<Vec<_>>();
+            parameters.append(&mut player_options.parameters);
+            player_options.parameters = parameters;
+        }
+
+        // A base given explicitly or by the bundle wins, and otherwise the one remembered
+        // for this movie is used, so that it needn't be given every time.
+        let base = player_options
+            .base
+            .to_owned()
+            .or_else(|| preferences.movie_base(&content_url))
+            .unwrap_or_else(|| movie_url.clone());
         let navigator = ExternalNavigatorBackend::new(
-            opt.player
-                .base
-                .to_owned()
-                .unwrap_or_else(|| movie_url.clone()),
+            base,
             future_spawner,
-            opt.proxy.clone(),
-            opt.player.upgrade_to_https.unwrap_or_default(),
-            opt.open_url_mode,
-            opt.socket_allowed.clone(),
-            opt.tcp_connections.unwrap_or(SocketMode::Ask),
-            Rc::new(content),
-            RfdNavigatorInterface,
+            NavigatorOptions {
+                upgrade_to_https: player_options.upgrade_to_https.unwrap_or_default(),
+                network_policy: network_policy.clone(),
+                ..opt.navigator_options()
+            },
+            content.clone(),
+            RfdNavigatorInterface::new(
+                event_loop.clone(),
+                blocked_time.clone(),
+                preferences.clone(),
+            ),
         );
+        let network_stats = opt.network_stats.then(|| navigator.network_stats());
 
-        if cfg!(feature = "software_video") {
-            builder =
-                builder.with_video(ruffle_video_software::backend::SoftwareVideoBackend::new());
-        }
+        let video_backend =
+            if opt.video_backend != VideoBackendMode::None && cfg!(feature = "software_video") {
+                builder =
+                    builder.with_video(ruffle_video_software::backend::SoftwareVideoBackend::new());
+                "Software"
+            } else {
+                "None"
+            };
 
         let renderer = WgpuRenderBackend::new(descriptors, movie_view)
             .map_err(|e| anyhow!(e.to_string()))
             .expect("Couldn't create wgpu rendering backend");
-        RENDER_INFO.with(|i| *i.borrow_mut() = Some(renderer.debug_info().to_string()));
+        RENDER_INFO.with(|i| {
+            *i.borrow_mut() = Some(format!(
+                "{}\nVideo backend: {video_backend}",
+                renderer.debug_info()
+            ))
+        });
 
-        if opt.player.dummy_external_interface.unwrap_or_default() {
+        if player_options.dummy_external_interface.unwrap_or_default() {
             builder = builder.with_external_interface(Box::new(DesktopExternalInterfaceProvider {
-                spoof_url: opt.player.spoof_url.clone(),
+                spoof_url: player_options.spoof_url.clone(),
+                values: opt.external_interface_values.clone(),
+                log_calls: opt.log_external_interface,
+                allowed_methods: opt.allowed_external_interface_methods.clone(),
             }));
         }
 
@@ -209,56 +545,87 @@ impl ActivePlayer {
             .with_fs_commands(Box::new(DesktopFSCommandProvider {
                 event_loop: event_loop.clone(),
                 window: window.clone(),
+                fullscreen_monitor: preferences.cli.fullscreen_monitor,
+                quit_mode: preferences.cli.fscommand_quit,
             }))
             .with_ui(
                 DesktopUiBackend::new(
                     window.clone(),
+                    event_loop.clone(),
+                    blocked_time.clone(),
                     opt.open_url_mode,
                     font_database,
                     preferences,
+                    custom_cursor,
                 )
                 .expect("Couldn't create ui backend"),
             )
-            .with_autoplay(true)
-            .with_letterbox(opt.player.letterbox.unwrap_or(Letterbox::On))
-            .with_max_execution_duration(opt.player.max_execution_duration.unwrap_or(Duration::MAX))
-            .with_quality(opt.player.quality.unwrap_or(StageQuality::High))
+            .with_autoplay(opt.autoplay)
+            .with_letterbox(player_options.letterbox.unwrap_or(Letterbox::On))
+            .with_letterbox_style(opt.letterbox_style)
+            .with_max_execution_duration(
+                player_options
+                    .max_execution_duration
+                    .unwrap_or(Duration::MAX),
+            )
+            .with_quality(player_options.quality.unwrap_or(StageQuality::High))
             .with_align(
-                opt.player.align.unwrap_or_default(),
-                opt.player.force_align.unwrap_or_default(),
+                player_options.align.unwrap_or_default(),
+                player_options.force_align.unwrap_or_default(),
             )
             .with_scale_mode(
-                opt.player.scale.unwrap_or_default(),
-                opt.player.force_scale.unwrap_or_default(),
+                player_options.scale.unwrap_or_default(),
+                player_options.force_scale.unwrap_or_default(),
             )
             .with_fullscreen(opt.fullscreen)
-            .with_load_behavior(opt.player.load_behavior.unwrap_or(LoadBehavior::Streaming))
-            .with_spoofed_url(opt.player.spoof_url.clone().map(|url| url.to_string()))
-            .with_page_url(opt.player.spoof_url.clone().map(|url| url.to_string()))
-            .with_player_version(opt.player.player_version)
-            .with_player_runtime(opt.player.player_runtime.unwrap_or_default())
-            .with_frame_rate(opt.player.frame_rate)
-            .with_avm2_optimizer_enabled(opt.avm2_optimizer_enabled);
+            .with_load_behavior(
+                player_options
+                    .load_behavior
+                    .unwrap_or(LoadBehavior::Streaming),
+            )
+            .with_spoofed_url(player_options.spoof_url.clone().map(|url| url.to_string()))
+            .with_page_url(player_options.spoof_url.clone().map(|url| url.to_string()))
+            .with_player_version(player_options.player_version)
+            .with_language(opt.locale.clone())
+            .with_fake_time(opt.fake_time, opt.fake_time_rate)
+            .with_player_runtime(player_options.player_runtime.unwrap_or_default())
+            .with_frame_rate(player_options.frame_rate)
+            .with_avm2_optimizer_enabled(opt.avm2_optimizer_enabled)
+            .with_avm2_optimizer_denylist(opt.avm2_optimizer_denylist.clone())
+            .with_avm2_call_trace(opt.avm2_call_trace.clone())
+            .with_loop_mode(opt.loop_mode);
+        // A replacement was already read, so it's given to the player instead of being fetched.
+        let replacement_header = replacement.as_ref().map(|movie| movie.header().clone());
+        if let Some(mut movie) = replacement {
+            movie.append_parameters(player_options.parameters.to_owned());
+            builder = builder.with_movie(movie);
+        }
         let player = builder.build();
 
         window.set_title(&format!("Ruffle - {readable_name}"));
 
-        SWF_INFO.with(|i| *i.borrow_mut() = Some(readable_name));
+        SWF_INFO.with(|i| *i.borrow_mut() = Some(readable_name.clone()));
 
         let on_metadata = move |swf_header: &ruffle_core::swf::HeaderExt| {
             let _ = event_loop.send_event(RuffleEvent::OnMetadata(swf_header.clone()));
         };
 
-        {
+        let stage_settings = {
             let mut player_lock = player.lock().expect("Player lock must be available");
+            if opt.transparent {
+                player_lock.set_window_mode("transparent");
+            }
             CALLSTACK.with(|callstack| {
                 *callstack.borrow_mut() = Some(player_lock.callstack());
             });
-            player_lock.fetch_root_movie(
-                movie_url.to_string(),
-                opt.player.parameters.to_owned(),
-                Box::new(on_metadata),
-            );
+            match replacement_header {
+                Some(header) => on_metadata(&header),
+                None => player_lock.fetch_root_movie(
+                    movie_url.to_string(),
+                    player_options.parameters.to_owned(),
+                    Box::new(on_metadata),
+                ),
+            }
 
             player_lock.set_default_font(
                 DefaultFont::Serif,
@@ -314,9 +681,78 @@ impl ActivePlayer {
                     "Arial Unicode MS".into(),    // Mac fallback
                 ],
             );
+            StageSettings::of(&mut player_lock)
+        };
+
+        Self {
+            player,
+            executor,
+            name: readable_name,
+            url: content_url,
+            max_memory: opt.max_memory,
+            memory_limit_exceeded: false,
+            next_auto_gc: opt.auto_gc.map(|interval| Instant::now() + interval),
+            playback_complete: false,
+            broke_on_error: false,
+            first_frame_deadline: None,
+            window_size: opt.window_size,
+            network_policy,
+            modal_dialogs: ModalDialogs::default(),
+            blocked_time,
+            content,
+            options: opt.clone(),
+            recorder: RefCell::default(),
+            replay: None,
+            script: None,
+            network_stats,
+            stage_settings,
+        }
+    }
+}
+
+impl Drop for ActivePlayer {
+    fn drop(&mut self) {
+        if let Some(stats) = &self.network_stats {
+            tracing::info!("Network use of {}: {stats}", self.name);
+        }
+    }
+}
+
+/// Keeps a movie silent until the user first clicks on or types into it, like the autoplay
+/// policy of a browser. Volume changes made in the meantime are held back until then.
+#[derive(Debug, Default)]
+pub struct InteractionMute {
+    /// The volume to play at once the user interacts, while the movie is kept silent.
+    pending_volume: Option<f32>,
+}
+
+impl InteractionMute {
+    /// Starts a new movie, kept silent if `mute` is set,
+    /// returning the volume it should play at right away.
+    pub fn start(&mut self, mute: bool, volume: f32) -> f32 {
+        self.pending_volume = mute.then_some(volume);
+        if mute {
+            0.0
+        } else {
+            volume
+        }
+    }
+
+    /// Changes the volume, returning it if it should be applied right away,
+    /// or `None` if it's held back until the user interacts.
+    pub fn set_volume(&mut self, volume: f32) -> Option<f32> {
+        match &mut self.pending_volume {
+            Some(pending_volume) => {
+                *pending_volume = volume;
+                None
+            }
+            None => Some(volume),
         }
+    }
 
-        Self { player, executor }
+    /// Stops keeping the movie silent, returning the volume to play at if it was.
+    pub fn on_interaction(&mut self) -> Option<f32> {
+        self.pending_volume.take()
     }
 }
 
@@ -324,19 +760,27 @@ impl ActivePlayer {
 /// responsible for either creating, destroying or communicating with that player.
 pub struct PlayerController {
     player: Option<ActivePlayer>,
-    event_loop: EventLoopProxy<RuffleEvent>,
+    event_loop: RuffleEventProxy,
     window: Rc<Window>,
     descriptors: Arc<Descriptors>,
     font_database: Rc<fontdb::Database>,
     preferences: GlobalPreferences,
+    /// The URL and options of the content closed last, so that it can be reopened.
+    last_closed: Option<(Url, LaunchOptions)>,
+    /// Whether bitmaps are always or never smoothed, which is kept for every movie played.
+    bitmap_smoothing: Option<bool>,
+    /// Whether the edges of text are anti-aliased, which is kept for every movie played.
+    text_anti_aliasing: bool,
+    /// When the content being played was opened, if its play time is being kept track of.
+    session_start: Option<Instant>,
 }
 
 impl PlayerController {
     pub fn new(
-        event_loop: EventLoopProxy<RuffleEvent>,
+        event_loop: RuffleEventProxy,
         window: Rc<Window>,
         descriptors: Arc<Descriptors>,
-        font_database: fontdb::Database,
+        font_database: Rc<fontdb::Database>,
         preferences: GlobalPreferences,
     ) -> Self {
         Self {
@@ -344,26 +788,461 @@ impl PlayerController {
             event_loop,
             window,
             descriptors,
-            font_database: Rc::new(font_database),
+            font_database,
             preferences,
+            last_closed: None,
+            bitmap_smoothing: None,
+            text_anti_aliasing: !preferences.cli.no_text_anti_aliasing,
+            session_start: None,
         }
     }
 
-    pub fn create(&mut self, opt: &LaunchOptions, movie_url: &Url, movie_view: MovieView) {
+    /// Plays the movie at `movie_url` in place of the current one, unless it may not be played.
+    pub fn create(
+        &mut self,
+        opt: &LaunchOptions,
+        movie_url: &Url,
+        movie_view: MovieView,
+    ) -> Result<(), Error> {
+        if let Ok(path) = movie_url.to_file_path() {
+            check_file_root(opt.file_root.as_ref(), &path)?;
+        }
+
+        self.end_session();
+        self.session_start = self.preferences.play_stats_enabled().then(Instant::now);
         self.player = Some(ActivePlayer::new(
             opt,
             self.event_loop.clone(),
-            movie_url,
+            Rc::new(open_content(movie_url)),
+            RootSwf::Initial,
             self.window.clone(),
             self.descriptors.clone(),
             movie_view,
             self.font_database.clone(),
             self.preferences.clone(),
         ));
+        self.apply_display_overrides();
+
+        if let Some(path) = &opt.record_input {
+            if let Err(e) = self.start_recording(path) {
+                tracing::error!("Couldn't start recording input: {e:#}");
+            }
+        }
+        if let (Some(path), Some(active)) = (&opt.replay, &mut self.player) {
+            match InputReplay::load(path) {
+                Ok(replay) => active.replay = Some(replay),
+                Err(e) => tracing::error!("Couldn't replay input: {e:#}"),
+            }
+        }
+        if let (Some(path), Some(active)) = (&opt.script, &mut self.player) {
+            match Script::load(path) {
+                Ok(script) => active.script = Some(script),
+                Err(e) => tracing::error!("Couldn't run the script: {e:#}"),
+            }
+        }
+        Ok(())
     }
 
-    pub fn destroy(&mut self) {
+    /// Starts recording the input given to the movie to a file at `path`,
+    /// finishing any recording already in progress.
+    pub fn start_recording(&mut self, path: &Path) -> Result<(), Error> {
+        let active = self
+            .player
+            .as_mut()
+            .ok_or_else(|| anyhow!("No movie is currently open"))?;
+        let recorder = InputRecorder::create(path)?;
+        if let Some(previous) = active.recorder.get_mut().replace(recorder) {
+            previous.finish()?;
+        }
+        tracing::info!("Recording input to {}", path.display());
+        Ok(())
+    }
+
+    /// Stops recording the input given to the movie, writing out what's left of the recording.
+    pub fn stop_recording(&mut self) -> Result<(), Error> {
+        match self
+            .player
+            .as_mut()
+            .and_then(|active| active.recorder.get_mut().take())
+        {
+            Some(recorder) => recorder.finish(),
+            None => Ok(()),
+        }
+    }
+
+    /// Gives the movie any recorded input that's due by the number of frames it has run,
+    /// when replaying a recording.
+    pub fn replay_input(&mut self) {
+        let Some(active) = &mut self.player else {
+            return;
+        };
+        let Some(replay) = &mut active.replay else {
+            return;
+        };
+
+        let mut player = active
+            .player
+            .try_lock()
+            .expect("Player lock must be available");
+        for event in replay.events_until(player.frames_run()) {
+            player.handle_event(event);
+        }
+        drop(player);
+
+        if replay.is_finished() {
+            tracing::info!("Finished replaying input");
+            active.replay = None;
+        }
+    }
+
+    /// Runs the commands of the script that are due by the number of frames the movie has run,
+    /// when running a script.
+    pub fn run_script(&mut self) {
+        let Some(active) = &mut self.player else {
+            return;
+        };
+        let Some(script) = &mut active.script else {
+            return;
+        };
+
+        let frames_run = active
+            .player
+            .try_lock()
+            .expect("Player lock must be available")
+            .frames_run();
+        let commands = script.commands_until(frames_run);
+        let finished = script.is_finished();
+        if finished {
+            active.script = None;
+        }
+
+        for command in commands {
+            // Resizing locks the player itself, so it mustn't be locked here yet.
+            if let ScriptCommand::Resize(width, height) = command {
+                let previous = self.stage_size();
+                match self.set_stage_size(width, height) {
+                    Ok(()) => match previous {
+                        Some((previous_width, previous_height)) => tracing::info!(
+                            "Resized the stage from {previous_width}x{previous_height} to {width}x{height}"
+                        ),
+                        None => tracing::info!("Resized the stage to {width}x{height}"),
+                    },
+                    Err(e) => tracing::error!("Couldn't resize the stage: {e:#}"),
+                }
+                continue;
+            }
+
+            let Some(mut player) = self.get() else {
+                return;
+            };
+            match command {
+                ScriptCommand::Event(event) => {
+                    player.handle_event(event);
+                }
+                ScriptCommand::Volume(volume) => {
+                    // The GUI holds the volume back if the movie is muted until the user interacts.
+                    let _ = self.event_loop.send_event(RuffleEvent::SetVolume(volume));
+                }
+                ScriptCommand::Screenshot(path) => match save_screenshot(&mut player, &path) {
+                    Ok(()) => tracing::info!("Saved a screenshot to {}", path.display()),
+                    Err(e) => tracing::error!("Couldn't save a screenshot: {e:#}"),
+                },
+                ScriptCommand::Quit => {
+                    let _ = self.event_loop.send_event(RuffleEvent::ExitRequested);
+                }
+                ScriptCommand::Resize(..) => unreachable!("Resizes are handled above"),
+            }
+        }
+
+        if finished {
+            tracing::info!("Finished running the script");
+        }
+    }
+
+    /// Replaces the player with one playing another SWF of the same bundle,
+    /// reusing the bundle that's already open.
+    ///
+    /// Returns false if no bundle is being played.
+    pub fn navigate_within_bundle(&mut self, swf_url: &Url, movie_view: MovieView) -> bool {
+        let Some(active) = self.player.take() else {
+            return false;
+        };
+        if !matches!(*active.content, PlayingContent::Bundle(..)) {
+            self.player = Some(active);
+            return false;
+        }
+
+        tracing::info!("Navigating to {swf_url} within the bundle");
+        let content = active.content.clone();
+        let options = active.options.clone();
+        // The previous movie must be gone before the next one starts, as they'd share the audio device.
+        drop(active);
+        self.player = Some(ActivePlayer::new(
+            &options,
+            self.event_loop.clone(),
+            content,
+            RootSwf::Url(swf_url.clone()),
+            self.window.clone(),
+            self.descriptors.clone(),
+            movie_view,
+            self.font_database.clone(),
+            self.preferences.clone(),
+        ));
+        self.apply_display_overrides();
+        true
+    }
+
+    /// Plays the SWF at `path` in place of the root SWF of the bundle being played, keeping the
+    /// bundle's other files and the options it was opened with, such as to try out a new build
+    /// of the SWF. Anything that isn't a bundle is replaced by the SWF entirely.
+    pub fn swap_root_swf(&mut self, path: &Path, movie_view: MovieView) -> Result<(), Error> {
+        let Some(active) = &self.player else {
+            return Err(anyhow!("No movie is currently open"));
+        };
+        check_file_root(active.options.file_root.as_ref(), path)?;
+        let (content, root_swf) = match &*active.content {
+            PlayingContent::Bundle(..) => {
+                let data = std::fs::read(path)
+                    .with_context(|| format!("Couldn't read {}", path.display()))?;
+                let movie =
+                    SwfMovie::from_data(&data, active.content.initial_swf_url().to_string(), None)
+                        .map_err(|e| anyhow!("{} isn't a valid SWF: {e}", path.display()))?;
+                (active.content.clone(), RootSwf::Replacement(movie))
+            }
+            PlayingContent::DirectFile(_) => {
+                let url = Url::from_file_path(path)
+                    .map_err(|_| anyhow!("{} isn't a valid path", path.display()))?;
+                (Rc::new(open_content(&url)), RootSwf::Initial)
+            }
+        };
+
+        tracing::info!("Swapping the root SWF for {}", path.display());
+        let options = active.options.clone();
+        // The time spent so far belongs to the previous movie, which may have had another URL.
+        self.end_session();
+        self.session_start = self.preferences.play_stats_enabled().then(Instant::now);
+        // The previous movie must be gone before the next one starts, as they'd share the audio device.
         self.player = None;
+        self.player = Some(ActivePlayer::new(
+            &options,
+            self.event_loop.clone(),
+            content,
+            root_swf,
+            self.window.clone(),
+            self.descriptors.clone(),
+            movie_view,
+            self.font_database.clone(),
+            self.preferences.clone(),
+        ));
+        self.apply_display_overrides();
+        Ok(())
+    }
+
+    /// Whether bitmaps are forced to be smoothed or not, or follow the movie with `None`.
+    pub fn bitmap_smoothing(&self) -> Option<bool> {
+        self.bitmap_smoothing
+    }
+
+    /// Forces bitmaps to be smoothed or not when they're drawn, or leaves it to the movie with `None`.
+    /// This is kept for the movies played after this one.
+    pub fn set_bitmap_smoothing(&mut self, smoothing: Option<bool>) {
+        self.bitmap_smoothing = smoothing;
+        self.apply_display_overrides();
+    }
+
+    /// Whether the edges of text are anti-aliased.
+    pub fn text_anti_aliasing(&self) -> bool {
+        self.text_anti_aliasing
+    }
+
+    /// Turns anti-aliasing of text on or off, separately from shapes.
+    /// This is kept for the movies played after this one.
+    pub fn set_text_anti_aliasing(&mut self, value: bool) {
+        self.text_anti_aliasing = value;
+        self.apply_display_overrides();
+    }
+
+    /// Applies the display settings chosen at runtime to the movie being played.
+    fn apply_display_overrides(&self) {
+        if let Some(mut player) = self.get() {
+            if let Some(renderer) = player
+                .renderer_mut()
+                .downcast_mut::<WgpuRenderBackend<MovieView>>()
+            {
+                renderer.set_bitmap_smoothing(self.bitmap_smoothing);
+            }
+            player.set_text_anti_aliasing(self.text_anti_aliasing);
+        }
+    }
+
+    /// The options the content being played was opened with.
+    pub fn launch_options(&self) -> Option<&LaunchOptions> {
+        self.player.as_ref().map(|player| &player.options)
+    }
+
+    /// Closes the content being played, remembering it so that it can be reopened.
+    pub fn destroy(&mut self) {
+        self.end_session();
+        if let Some(player) = self.player.take() {
+            self.last_closed = Some((player.url.clone(), player.options.clone()));
+        }
+    }
+
+    /// Adds the time since the content being played was opened to its play statistics,
+    /// if they're being kept. Moving around within a bundle doesn't start a new session.
+    pub fn end_session(&mut self) {
+        let (Some(start), Some(active)) = (self.session_start.take(), &self.player) else {
+            return;
+        };
+        let play_time = start.elapsed();
+        if let Err(e) = self
+            .preferences
+            .write_play_stats(|writer| writer.add_session(&active.url, play_time))
+        {
+            tracing::warn!("Couldn't save play statistics: {e}");
+        }
+    }
+
+    /// Takes the URL and options of the content closed last, if it wasn't reopened yet.
+    pub fn take_last_closed(&mut self) -> Option<(Url, LaunchOptions)> {
+        self.last_closed.take()
+    }
+
+    pub fn forget_last_closed(&mut self) {
+        self.last_closed = None;
+    }
+
+    /// The human readable name of the content being played, such as the name of a bundle.
+    pub fn name(&self) -> Option<&str> {
+        self.player.as_ref().map(|player| player.name.as_str())
+    }
+
+    /// The preferred width and height of the window for the content being played, if any.
+    pub fn window_size(&self) -> (Option<f64>, Option<f64>) {
+        self.player
+            .as_ref()
+            .map_or((None, None), |player| player.window_size)
+    }
+
+    /// The URL of the content being played, as it was opened.
+    pub fn url(&self) -> Option<&Url> {
+        self.player.as_ref().map(|player| &player.url)
+    }
+
+    /// Whether the content being played is a bundle or a movie on its own.
+    pub fn content_kind(&self) -> Option<ContentKind> {
+        self.player.as_ref().map(|player| match &*player.content {
+            PlayingContent::Bundle(..) => ContentKind::Bundle,
+            PlayingContent::DirectFile(_) => ContentKind::DirectFile,
+        })
+    }
+
+    /// The splash to show while the content loads, as asked for with `--splash` or by a bundle.
+    ///
+    /// An image given by the user is preferred over the one of a bundle,
+    /// which in turn is preferred over a spinner.
+    pub fn splash(&self) -> Option<Splash> {
+        let player = self.player.as_ref()?;
+        let bundle_splash = match &*player.content {
+            PlayingContent::Bundle(_, bundle) => bundle
+                .information()
+                .splash
+                .as_deref()
+                .map(|image| (bundle, image)),
+            _ => None,
+        };
+        let data = match (&player.options.splash, bundle_splash) {
+            (Some(Some(path)), _) => std::fs::read(path).map_err(Error::from),
+            (_, Some((bundle, image))) => bundle.source().read_file(image).map_err(Error::from),
+            (Some(None), None) => return Some(Splash::spinner()),
+            (None, None) => return None,
+        };
+        match data.and_then(|data| Splash::from_image_data(&data)) {
+            Ok(splash) => Some(splash),
+            Err(e) => {
+                tracing::warn!("Couldn't load the splash image: {e}");
+                None
+            }
+        }
+    }
+
+    /// The options to play another movie with in place of the current one, keeping the
+    /// network policy and player options the current content is played with, such as a bundle's.
+    pub fn in_place_launch_options(&self) -> Option<LaunchOptions> {
+        let active = self.player.as_ref()?;
+        let mut options = active.options.clone();
+        if let PlayingContent::Bundle(_, bundle) = &*active.content {
+            options.player = options.player.or(&bundle.information().player);
+        }
+        if active.network_policy.is_restricted() {
+            options.network_policy = Some(active.network_policy.clone());
+        }
+        // The base of the previous movie most likely doesn't apply to the next one.
+        options.player.base = None;
+        Some(options)
+    }
+
+    /// The network restrictions the content being played is under.
+    pub fn network_policy(&self) -> NetworkPolicy {
+        self.player
+            .as_ref()
+            .map(|player| player.network_policy.clone())
+            .unwrap_or_default()
+    }
+
+    /// The width and height of the player's stage, in pixels.
+    pub fn stage_size(&self) -> Option<(u32, u32)> {
+        let dimensions = self.get()?.viewport_dimensions();
+        Some((dimensions.width, dimensions.height))
+    }
+
+    /// Resizes the player's stage, keeping its scale factor,
+    /// such as to follow the size of the UI it's embedded into or for a script.
+    pub fn set_stage_size(&self, width: u32, height: u32) -> Result<(), Error> {
+        let Some(scale_factor) = self
+            .get()
+            .map(|mut player| player.viewport_dimensions().scale_factor)
+        else {
+            return Err(anyhow!("No movie is currently open"));
+        };
+        self.set_viewport_dimensions(ViewportDimensions {
+            width,
+            height,
+            scale_factor,
+        })
+    }
+
+    /// Resizes the player's stage and the surface it's rendered to,
+    /// letting the movie react to the new size as its scale mode asks.
+    ///
+    /// This does nothing if no movie is open.
+    pub fn set_viewport_dimensions(&self, dimensions: ViewportDimensions) -> Result<(), Error> {
+        let max_size = self.descriptors.limits.max_texture_dimension_2d;
+        if dimensions.width == 0 || dimensions.height == 0 {
+            return Err(anyhow!(
+                "The stage can't be {}x{}, as it would be empty",
+                dimensions.width,
+                dimensions.height
+            ));
+        }
+        if dimensions.width > max_size || dimensions.height > max_size {
+            return Err(anyhow!(
+                "The stage can't be {}x{}, as it would be larger than the maximum of {max_size}x{max_size}",
+                dimensions.width,
+                dimensions.height
+            ));
+        }
+        if !(dimensions.scale_factor.is_finite() && dimensions.scale_factor > 0.0) {
+            return Err(anyhow!(
+                "The stage can't have a scale factor of {}",
+                dimensions.scale_factor
+            ));
+        }
+
+        if let Some(mut player) = self.get() {
+            player.set_viewport_dimensions(dimensions);
+        }
+        Ok(())
     }
 
     pub fn get(&self) -> Option<MutexGuard<Player>> {
@@ -379,17 +1258,357 @@ impl PlayerController {
         }
     }
 
+    /// Toggles fullscreen on behalf of the user, which is allowed even when the movie isn't.
+    pub fn toggle_fullscreen(&self) {
+        let Some(mut player) = self.get() else {
+            return;
+        };
+        let set_user_request = |player: &mut Player, value: bool| {
+            if let Some(ui) = player.ui_mut().downcast_mut::<DesktopUiBackend>() {
+                ui.set_user_fullscreen_request(value);
+            }
+        };
+        set_user_request(&mut *player, true);
+        player.update(|uc| {
+            uc.stage.toggle_display_state(uc);
+        });
+        set_user_request(&mut *player, false);
+    }
+
+    /// Saves the current frame of the movie as an SVG document at the given path.
+    pub fn export_frame_svg(&self, path: &Path) -> Result<(), Error> {
+        let svg = self
+            .get()
+            .ok_or_else(|| anyhow!("No movie is currently open"))?
+            .export_svg();
+        std::fs::write(path, svg)?;
+        Ok(())
+    }
+
+    /// Checks that the movie uses the ActionScript virtual machine required by `--require-avm`,
+    /// as its header is only known once it's loaded.
+    pub fn check_required_avm(
+        &self,
+        swf_header: &ruffle_core::swf::HeaderExt,
+    ) -> Result<(), Error> {
+        match self.launch_options().and_then(|opt| opt.require_avm) {
+            Some(required) => check_required_avm(required, swf_header.is_action_script_3()),
+            None => Ok(()),
+        }
+    }
+
+    /// Saves the display list of the current movie to a JSON file.
+    pub fn save_display_list(&self, path: &Path) -> Result<(), Error> {
+        let json = self
+            .get()
+            .ok_or_else(|| anyhow!("No movie is currently open"))?
+            .dump_display_list();
+        std::fs::write(path, json)?;
+        Ok(())
+    }
+
+    /// Counts the assets loaded by the movie being played, and how much memory they use.
+    pub fn loaded_asset_stats(&self) -> Option<AssetStats> {
+        self.get().map(|mut player| player.loaded_asset_stats())
+    }
+
+    /// Releases the cached assets of the movie being played which are loaded again when
+    /// they're next used. Returns how many were released.
+    pub fn clear_asset_cache(&self) -> usize {
+        self.get()
+            .map(|mut player| player.clear_asset_cache())
+            .unwrap_or_default()
+    }
+
+    /// Runs a full garbage collection cycle right away, returning how many bytes it reclaimed.
+    pub fn collect_garbage(&self) -> usize {
+        self.get()
+            .map(|mut player| player.collect_garbage())
+            .unwrap_or_default()
+    }
+
+    /// Runs a full garbage collection once it's due, as requested by `--auto-gc`.
+    pub fn check_auto_gc(&mut self) {
+        let Some(active) = &mut self.player else {
+            return;
+        };
+        let (Some(next_auto_gc), Some(interval)) = (active.next_auto_gc, active.options.auto_gc)
+        else {
+            return;
+        };
+        let now = Instant::now();
+        if now < next_auto_gc {
+            return;
+        }
+        active.next_auto_gc = Some(now + interval);
+
+        let reclaimed = active
+            .player
+            .try_lock()
+            .expect("Player lock must be available")
+            .collect_garbage();
+        tracing::debug!(
+            "Collected garbage in {:?}, reclaiming {reclaimed} bytes",
+            now.elapsed()
+        );
+    }
+
     pub fn handle_event(&self, event: PlayerEvent) {
+        let Some(active) = &self.player else {
+            return;
+        };
+        // The movie only gets the recorded input while it's being replayed.
+        if active.replay.is_some() {
+            return;
+        }
         if let Some(mut player) = self.get() {
             if player.is_playing() {
+                if let Some(recorder) = active.recorder.borrow_mut().as_mut() {
+                    if let Err(e) = recorder.record(player.frames_run(), &event) {
+                        tracing::error!("Couldn't record input: {e}");
+                    }
+                }
                 player.handle_event(event);
             }
         }
     }
 
+    /// Halts the player if it has allocated more memory than it's allowed to.
+    ///
+    /// A `RuffleEvent::MemoryLimitExceeded` is sent the first time this happens,
+    /// so that the movie can be closed before it exhausts the memory of the host.
+    pub fn check_memory_limit(&mut self) {
+        let Some(active) = &mut self.player else {
+            return;
+        };
+        let Some(max_memory) = active.max_memory else {
+            return;
+        };
+        if active.memory_limit_exceeded {
+            return;
+        }
+
+        let mut player = active
+            .player
+            .try_lock()
+            .expect("Player lock must be available");
+        let allocated = player.total_allocation();
+        if allocated > max_memory {
+            tracing::error!(
+                "Movie exceeded the memory limit ({allocated} bytes > {max_memory} bytes), halting"
+            );
+            player.set_is_playing(false);
+            active.memory_limit_exceeded = true;
+            let _ = self.event_loop.send_event(RuffleEvent::MemoryLimitExceeded);
+        }
+    }
+
+    /// Sends a `RuffleEvent::PlaybackComplete` the first time the movie plays to the end
+    /// without looping, as requested by `--loop never`.
+    pub fn check_playback_complete(&mut self) {
+        let Some(active) = &mut self.player else {
+            return;
+        };
+        if active.playback_complete {
+            return;
+        }
+
+        let player = active
+            .player
+            .try_lock()
+            .expect("Player lock must be available");
+        if player.playback_complete() {
+            active.playback_complete = true;
+            let _ = self.event_loop.send_event(RuffleEvent::PlaybackComplete);
+        }
+    }
+
+    /// The current settings of the stage, as changed by the movie or the user.
+    pub fn stage_settings(&self) -> Option<StageSettings> {
+        self.get().map(|mut player| StageSettings::of(&mut player))
+    }
+
+    /// Sends a `RuffleEvent::StageSettingsChanged` when the stage settings changed since they
+    /// were last checked, such as when the movie set its own quality, so that the GUI shows them.
+    pub fn check_stage_settings(&mut self) {
+        let Some(settings) = self.stage_settings() else {
+            return;
+        };
+        let Some(active) = &mut self.player else {
+            return;
+        };
+        if settings != active.stage_settings {
+            active.stage_settings = settings;
+            let _ = self
+                .event_loop
+                .send_event(RuffleEvent::StageSettingsChanged {
+                    quality: settings.quality,
+                    scale_mode: settings.scale_mode,
+                    align: settings.align,
+                });
+        }
+    }
+
+    /// Starts waiting for the movie to show its first frame, now that it has been downloaded,
+    /// as limited by `--first-frame-timeout`.
+    pub fn wait_for_first_frame(&mut self) {
+        let Some(active) = &mut self.player else {
+            return;
+        };
+        active.first_frame_deadline = active
+            .options
+            .first_frame_timeout
+            .map(|timeout| Instant::now() + timeout);
+    }
+
+    /// Sends a `RuffleEvent::FirstFrameTimeout` if the movie still hasn't shown its first frame
+    /// by the end of the `--first-frame-timeout`.
+    pub fn check_first_frame(&mut self) {
+        let Some(active) = &mut self.player else {
+            return;
+        };
+        let Some(deadline) = active.first_frame_deadline else {
+            return;
+        };
+
+        let player = active
+            .player
+            .try_lock()
+            .expect("Player lock must be available");
+        if player.current_frame().unwrap_or_default() > 0 {
+            active.first_frame_deadline = None;
+        } else if Instant::now() >= deadline {
+            tracing::warn!("Movie hasn't shown its first frame in time");
+            active.first_frame_deadline = None;
+            let _ = self.event_loop.send_event(RuffleEvent::FirstFrameTimeout);
+        }
+    }
+
+    /// Pauses the player the first time ActionScript reports an error, as requested by
+    /// `--break-on-error`, and sends a `RuffleEvent::BrokeOnError` with the error.
+    pub fn check_avm_error(&mut self) {
+        let error = crate::log::take_avm_error();
+        let Some(active) = &mut self.player else {
+            return;
+        };
+        let Some(error) = error.filter(|_| active.options.break_on_error) else {
+            return;
+        };
+        if active.broke_on_error {
+            return;
+        }
+
+        let mut player = active
+            .player
+            .try_lock()
+            .expect("Player lock must be available");
+        tracing::info!("Pausing on ActionScript error: {error}");
+        player.set_is_playing(false);
+        active.broke_on_error = true;
+        let _ = self.event_loop.send_event(RuffleEvent::BrokeOnError(error));
+    }
+
+    /// Pauses the player while a modal dialog is shown, unless it was already paused.
+    pub fn on_modal_dialog_opened(&mut self) {
+        let Some(active) = &mut self.player else {
+            return;
+        };
+        let mut player = active
+            .player
+            .try_lock()
+            .expect("Player lock must be available");
+        if active.modal_dialogs.opened(player.is_playing()) {
+            player.set_is_playing(false);
+        }
+    }
+
+    /// Resumes the player once every modal dialog is closed, if it was paused for them.
+    pub fn on_modal_dialog_closed(&mut self) {
+        let Some(active) = &mut self.player else {
+            return;
+        };
+        if active.modal_dialogs.closed() && !active.memory_limit_exceeded {
+            let mut player = active
+                .player
+                .try_lock()
+                .expect("Player lock must be available");
+            player.set_is_playing(true);
+        }
+    }
+
+    /// Takes how long dialogs blocked the movie since this was last called,
+    /// which the frame timer should skip over.
+    pub fn take_blocked_time(&self) -> Duration {
+        self.player
+            .as_ref()
+            .map(|player| player.blocked_time.take())
+            .unwrap_or_default()
+    }
+
+    /// Guards a dialog that blocks the event loop while it's shown over the movie, if any,
+    /// so that the movie doesn't catch up on the time it was shown for.
+    pub fn blocking_dialog_guard(&self) -> ModalDialogGuard {
+        let blocked_time = self
+            .player
+            .as_ref()
+            .map(|player| player.blocked_time.clone())
+            .unwrap_or_default();
+        ModalDialogGuard::blocking(&self.event_loop, &blocked_time)
+    }
+
     pub fn poll(&self) {
         if let Some(player) = &self.player {
             player.executor.poll_all()
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_files_in_the_file_root_are_played() {
+        let directory = tempfile::tempdir().unwrap();
+        let root_path = directory.path().join("root");
+        std::fs::create_dir_all(&root_path).unwrap();
+        std::fs::write(root_path.join("game.swf"), b"FWS").unwrap();
+        std::fs::write(directory.path().join("other.swf"), b"FWS").unwrap();
+        let root = FileRoot::new(&root_path).unwrap();
+
+        assert!(check_file_root(Some(&root), &root_path.join("game.swf")).is_ok());
+        assert!(check_file_root(Some(&root), &directory.path().join("other.swf")).is_err());
+        assert!(check_file_root(Some(&root), &root_path.join("..").join("other.swf")).is_err());
+        assert!(check_file_root(None, &directory.path().join("other.swf")).is_ok());
+    }
+
+    #[test]
+    fn movies_for_the_other_avm_are_refused() {
+        assert!(check_required_avm(AvmVersion::Avm1, false).is_ok());
+        assert!(check_required_avm(AvmVersion::Avm2, true).is_ok());
+        let error = check_required_avm(AvmVersion::Avm1, true).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "This movie is written in ActionScript 3 (AVM2), so it can't be played with --require-avm 1."
+        );
+        assert!(check_required_avm(AvmVersion::Avm2, false).is_err());
+    }
+
+    #[test]
+    fn volume_is_held_back_until_interaction() {
+        let mut mute = InteractionMute::default();
+        assert_eq!(mute.start(true, 0.5), 0.0);
+        assert_eq!(mute.set_volume(0.8), None);
+        assert_eq!(mute.on_interaction(), Some(0.8));
+        assert_eq!(mute.on_interaction(), None);
+        assert_eq!(mute.set_volume(0.3), Some(0.3));
+    }
+
+    #[test]
+    fn volume_applies_right_away_without_muting() {
+        let mut mute = InteractionMute::default();
+        assert_eq!(mute.start(false, 0.5), 0.5);
+        assert_eq!(mute.set_volume(0.8), Some(0.8));
+        assert_eq!(mute.on_interaction(), None);
+    }
+}
@@ -1,21 +1,33 @@
+mod migrate;
 mod read;
 mod write;
 
 pub mod storage;
 
+use crate::backends::FullscreenRequests;
 use crate::cli::Opt;
+use crate::custom_event::{RuffleEvent, RuffleEventProxy};
+use crate::gui::{text, ColorFilter};
 use crate::log::FilenamePattern;
+use crate::preferences::migrate::migrate_preferences;
 use crate::preferences::read::read_preferences;
 use crate::preferences::write::PreferencesWriter;
+use crate::touch::TouchEmulation;
 use anyhow::{Context, Error};
 use ruffle_core::backend::ui::US_ENGLISH;
+use ruffle_core::events::KeyCode;
 use ruffle_frontend_utils::bookmarks::{read_bookmarks, Bookmarks, BookmarksWriter};
 use ruffle_frontend_utils::parse::DocumentHolder;
-use ruffle_frontend_utils::recents::{read_recents, Recents, RecentsWriter};
+use ruffle_frontend_utils::play_stats::{read_play_stats, PlayStats, PlayStatsWriter};
+use ruffle_frontend_utils::recents::{read_recents, RecentPresentation, Recents, RecentsWriter};
 use ruffle_render_wgpu::clap::{GraphicsBackend, PowerPreference};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use sys_locale::get_locale;
+use toml_edit::DocumentMut;
 use unic_langid::LanguageIdentifier;
+use url::Url;
 
 /// The preferences that relate to the application itself.
 ///
@@ -42,24 +54,20 @@ pub struct GlobalPreferences {
     bookmarks: Arc<Mutex<DocumentHolder<Bookmarks>>>,
 
     recents: Arc<Mutex<DocumentHolder<Recents>>>,
+
+    /// How long each movie was played, which is only kept while [`Self::play_stats_enabled`].
+    play_stats: Arc<Mutex<DocumentHolder<PlayStats>>>,
 }
 
 impl GlobalPreferences {
-    pub fn load(cli: Opt) -> Result<Self, Error> {
+    pub fn load(mut cli: Opt) -> Result<Self, Error> {
+        if let Some(profile) = &cli.profile {
+            // Profiles live next to the default one, so that their files can't be mixed up.
+            cli.config = cli.config.join("profiles").join(profile);
+            cli.save_directory = cli.save_directory.join("profiles").join(profile);
+        }
         std::fs::create_dir_all(&cli.config).context("Failed to create configuration directory")?;
-        let preferences_path = cli.config.join("preferences.toml");
-        let preferences = if preferences_path.exists() {
-            let contents = std::fs::read_to_string(&preferences_path)
-                .context("Failed to read saved preferences")?;
-            let result = read_preferences(&contents);
-            for warning in result.warnings {
-                // TODO: A way to display warnings to users, generally
-                tracing::warn!("{warning}");
-            }
-            result.result
-        } else {
-            Default::default()
-        };
+        let preferences = load_preferences(&cli.config.join("preferences.toml"))?;
 
         let bookmarks_path = cli.config.join("bookmarks.toml");
         let bookmarks = if bookmarks_path.exists() {
@@ -87,14 +95,93 @@ impl GlobalPreferences {
             Default::default()
         };
 
+        let play_stats_path = cli.config.join("play_stats.toml");
+        let play_stats = if play_stats_path.exists() {
+            let contents = std::fs::read_to_string(&play_stats_path)
+                .context("Failed to read saved play statistics")?;
+            let result = read_play_stats(&contents);
+            for warning in result.warnings {
+                tracing::warn!("{warning}");
+            }
+            result.result
+        } else {
+            Default::default()
+        };
+
         Ok(Self {
             cli,
             preferences: Arc::new(Mutex::new(preferences)),
             bookmarks: Arc::new(Mutex::new(bookmarks)),
             recents: Arc::new(Mutex::new(recents)),
+            play_stats: Arc::new(Mutex::new(play_stats)),
         })
     }
 
+    /// Reads the preferences from disk again, picking up any changes made outside of Ruffle.
+    ///
+    /// Most preferences are read whenever they're used, so they apply right away.
+    /// Returns the text ids naming the changed preferences that only take effect after a restart,
+    /// see [`restart_needed_names`].
+    pub fn reload(&self) -> Result<Vec<&'static str>, Error> {
+        let reloaded = load_preferences(&self.preferences_path())?;
+        Ok(self.replace(reloaded))
+    }
+
+    /// Resets all preferences to their defaults, after copying the preferences file to
+    /// [`Self::preferences_backup_path`]. Recents, bookmarks and saves are kept.
+    ///
+    /// Returns the text ids naming the changed preferences that only take effect after a restart,
+    /// see [`restart_needed_names`].
+    pub fn reset(&self) -> Result<Vec<&'static str>, Error> {
+        let path = self.preferences_path();
+        if path.exists() {
+            std::fs::copy(&path, self.preferences_backup_path())
+                .context("Could not back up preferences")?;
+        }
+        let defaults = DocumentHolder::default();
+        std::fs::write(&path, defaults.serialize())
+            .context("Could not write preferences to disk")?;
+        Ok(self.replace(defaults))
+    }
+
+    /// Where the preferences file is copied to before resetting the preferences.
+    pub fn preferences_backup_path(&self) -> PathBuf {
+        self.cli.config.join("preferences.toml.bak")
+    }
+
+    fn preferences_path(&self) -> PathBuf {
+        self.cli.config.join("preferences.toml")
+    }
+
+    /// Replaces the preferences in use, returning the text ids naming the changed preferences
+    /// that only take effect after a restart. These are the labels of the preferences dialog.
+    fn replace(&self, new: DocumentHolder<SavedGlobalPreferences>) -> Vec<&'static str> {
+        let mut preferences = self
+            .preferences
+            .lock()
+            .expect("Preferences is not reentrant");
+
+        let mut needs_restart = vec![];
+        if new.graphics_backend != preferences.graphics_backend {
+            needs_restart.push("graphics-backend");
+        }
+        if new.graphics_power_preference != preferences.graphics_power_preference {
+            needs_restart.push("graphics-power");
+        }
+        if new.output_device != preferences.output_device {
+            needs_restart.push("audio-output-device");
+        }
+        if new.log != preferences.log {
+            needs_restart.push("log-filename-pattern");
+        }
+        if new.storage != preferences.storage {
+            needs_restart.push("storage-backend");
+        }
+
+        *preferences = new;
+        needs_restart
+    }
+
     pub fn graphics_backends(&self) -> GraphicsBackend {
         self.cli.graphics.unwrap_or_else(|| {
             self.preferences
@@ -136,6 +223,80 @@ impl GlobalPreferences {
             .mute
     }
 
+    /// Whether the system cursor should be hidden while the movie draws its own cursor.
+    pub fn auto_hide_cursor(&self) -> bool {
+        self.preferences
+            .lock()
+            .expect("Preferences is not reentrant")
+            .auto_hide_cursor
+    }
+
+    /// Whether movies start playing as soon as they're opened, rather than on the first click.
+    pub fn autoplay(&self) -> bool {
+        !self.cli.no_autoplay
+            && self
+                .preferences
+                .lock()
+                .expect("Preferences is not reentrant")
+                .autoplay
+    }
+
+    /// Whether the screen is kept from going to sleep while a movie plays in a focused window.
+    pub fn keep_awake(&self) -> bool {
+        self.cli.keep_awake.unwrap_or_else(|| {
+            self.preferences
+                .lock()
+                .expect("Preferences is not reentrant")
+                .keep_awake
+        })
+    }
+
+    /// Whether to keep track of how long each movie is played.
+    pub fn play_stats_enabled(&self) -> bool {
+        self.cli.play_stats.unwrap_or_else(|| {
+            self.preferences
+                .lock()
+                .expect("Preferences is not reentrant")
+                .play_stats
+        })
+    }
+
+    /// How to respond when a movie asks to go fullscreen.
+    pub fn fullscreen_requests(&self) -> FullscreenRequests {
+        self.preferences
+            .lock()
+            .expect("Preferences is not reentrant")
+            .fullscreen_requests
+    }
+
+    /// Which touches move the mouse pointer, when Ruffle emulates it itself.
+    pub fn touch_emulation(&self) -> TouchEmulation {
+        self.preferences
+            .lock()
+            .expect("Preferences is not reentrant")
+            .touch_emulation
+    }
+
+    /// The filter applied to the colors of the presented movie.
+    pub fn color_filter(&self) -> ColorFilter {
+        self.preferences
+            .lock()
+            .expect("Preferences is not reentrant")
+            .color_filter
+    }
+
+    /// The key pressed in place of scrolling the mouse wheel in the given direction, if any.
+    pub fn mouse_wheel_key(&self, direction: MouseWheelDirection) -> Option<KeyCode> {
+        let preferences = self
+            .preferences
+            .lock()
+            .expect("Preferences is not reentrant");
+        match direction {
+            MouseWheelDirection::Up => preferences.mouse_wheel.up,
+            MouseWheelDirection::Down => preferences.mouse_wheel.down,
+        }
+    }
+
     pub fn preferred_volume(&self) -> f32 {
         self.cli.volume.unwrap_or_else(|| {
             self.preferences
@@ -153,6 +314,58 @@ impl GlobalPreferences {
             .filename_pattern
     }
 
+    /// The base URL remembered for the movie at the given URL, if any.
+    pub fn movie_base(&self, movie_url: &Url) -> Option<Url> {
+        self.preferences
+            .lock()
+            .expect("Preferences is not reentrant")
+            .movies
+            .get(movie_url)
+            .and_then(|movie| movie.base.clone())
+    }
+
+    /// Whether any website may be opened without asking, or `None` to ask.
+    pub fn open_url_requests(&self) -> Option<bool> {
+        self.preferences
+            .lock()
+            .expect("Preferences is not reentrant")
+            .open_url
+            .all
+    }
+
+    /// Whether the website at the given URL may be opened without asking, or `None` to ask.
+    pub fn remembered_open_url(&self, url: &Url) -> Option<bool> {
+        let preferences = self
+            .preferences
+            .lock()
+            .expect("Preferences is not reentrant");
+        url.host_str()
+            .and_then(|host| preferences.open_url.hosts.get(host).copied())
+            .or(preferences.open_url.all)
+    }
+
+    /// Whether a choice was remembered for the websites of any host.
+    pub fn have_remembered_open_url_hosts(&self) -> bool {
+        !self
+            .preferences
+            .lock()
+            .expect("Preferences is not reentrant")
+            .open_url
+            .hosts
+            .is_empty()
+    }
+
+    /// The presentation last picked for the recent entry with the given URL, if there is one.
+    pub fn recent_presentation(&self, url: &Url) -> RecentPresentation {
+        self.recents(|recents| {
+            recents
+                .iter()
+                .find(|recent| &recent.url == url)
+                .map(|recent| recent.presentation)
+                .unwrap_or_default()
+        })
+    }
+
     pub fn bookmarks(&self, fun: impl FnOnce(&Bookmarks)) {
         fun(&self.bookmarks.lock().expect("Bookmarks is not reentrant"))
     }
@@ -180,10 +393,21 @@ impl GlobalPreferences {
             .recent_limit
     }
 
+    pub fn recent_match_content(&self) -> bool {
+        self.preferences
+            .lock()
+            .expect("Preferences is not reentrant")
+            .recent_match_content
+    }
+
     pub fn recents<R>(&self, fun: impl FnOnce(&Recents) -> R) -> R {
         fun(&self.recents.lock().expect("Recents is not reentrant"))
     }
 
+    pub fn play_stats<R>(&self, fun: impl FnOnce(&PlayStats) -> R) -> R {
+        fun(&self.play_stats.lock().expect("Play stats is not reentrant"))
+    }
+
     pub fn write_preferences(&self, fun: impl FnOnce(&mut PreferencesWriter)) -> Result<(), Error> {
         let mut preferences = self
             .preferences
@@ -194,7 +418,7 @@ impl GlobalPreferences {
         fun(&mut writer);
 
         let serialized = preferences.serialize();
-        std::fs::write(self.cli.config.join("preferences.toml"), serialized)
+        std::fs::write(self.preferences_path(), serialized)
             .context("Could not write preferences to disk")
     }
 
@@ -219,6 +443,64 @@ impl GlobalPreferences {
         std::fs::write(self.cli.config.join("recents.toml"), serialized)
             .context("Could not write recents to disk")
     }
+
+    /// Changes the recents like [`Self::write_recents`], and lets every window know so that
+    /// they show the new list. A failure to save them is only logged.
+    pub fn update_recents(
+        &self,
+        event_loop: &RuffleEventProxy,
+        fun: impl FnOnce(&mut RecentsWriter),
+    ) {
+        match self.write_recents(fun) {
+            Ok(()) => {
+                let _ = event_loop.send_event(RuffleEvent::RecentsChanged);
+            }
+            Err(e) => tracing::warn!("Couldn't update recents: {e}"),
+        }
+    }
+
+    pub fn write_play_stats(&self, fun: impl FnOnce(&mut PlayStatsWriter)) -> Result<(), Error> {
+        let mut play_stats = self.play_stats.lock().expect("Play stats is not reentrant");
+
+        let mut writer = PlayStatsWriter::new(&mut play_stats);
+        fun(&mut writer);
+
+        let serialized = play_stats.serialize();
+        std::fs::write(self.cli.config.join("play_stats.toml"), serialized)
+            .context("Could not write play statistics to disk")
+    }
+}
+
+/// Lists the preferences named by text ids, as returned by [`GlobalPreferences::reload`],
+/// in the user's language.
+pub fn restart_needed_names(locale: &LanguageIdentifier, ids: &[&str]) -> String {
+    ids.iter()
+        .map(|id| text(locale, id))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn load_preferences(path: &Path) -> Result<DocumentHolder<SavedGlobalPreferences>, Error> {
+    if !path.exists() {
+        return Ok(Default::default());
+    }
+
+    let mut contents = std::fs::read_to_string(path).context("Failed to read saved preferences")?;
+    // Invalid documents are left for `read_preferences` to warn about.
+    if let Ok(mut document) = contents.parse::<DocumentMut>() {
+        if migrate_preferences(&mut document) {
+            contents = document.to_string();
+            if let Err(e) = std::fs::write(path, &contents) {
+                tracing::warn!("Couldn't save upgraded preferences: {e}");
+            }
+        }
+    }
+    let result = read_preferences(&contents);
+    for warning in result.warnings {
+        // TODO: A way to display warnings to users, generally
+        tracing::warn!("{warning}");
+    }
+    Ok(result.result)
 }
 
 #[derive(PartialEq, Debug)]
@@ -229,27 +511,53 @@ pub struct SavedGlobalPreferences {
     pub output_device: Option<String>,
     pub mute: bool,
     pub volume: f32,
+    pub auto_hide_cursor: bool,
+    pub autoplay: bool,
+    pub keep_awake: bool,
+    pub play_stats: bool,
+    pub fullscreen_requests: FullscreenRequests,
+    pub touch_emulation: TouchEmulation,
+    pub color_filter: ColorFilter,
     pub recent_limit: usize,
+    pub recent_match_content: bool,
     pub log: LogPreferences,
     pub storage: StoragePreferences,
+    pub mouse_wheel: MouseWheelPreferences,
+    pub open_url: OpenUrlPreferences,
+    /// Settings remembered for individual movies, by their URL.
+    pub movies: BTreeMap<Url, MoviePreferences>,
+}
+
+/// The locale the system is set to, or US English if it can't be told.
+pub fn system_locale() -> LanguageIdentifier {
+    get_locale()
+        .and_then(|l| l.parse().ok())
+        .unwrap_or_else(|| US_ENGLISH.clone())
 }
 
 impl Default for SavedGlobalPreferences {
     fn default() -> Self {
-        let preferred_locale = get_locale();
-        let locale = preferred_locale
-            .and_then(|l| l.parse().ok())
-            .unwrap_or_else(|| US_ENGLISH.clone());
         Self {
             graphics_backend: Default::default(),
             graphics_power_preference: Default::default(),
-            language: locale,
+            language: system_locale(),
             output_device: None,
             mute: false,
             volume: 1.0,
+            auto_hide_cursor: true,
+            autoplay: true,
+            keep_awake: true,
+            play_stats: false,
+            fullscreen_requests: FullscreenRequests::Allow,
+            touch_emulation: TouchEmulation::Off,
+            color_filter: ColorFilter::None,
             recent_limit: 10,
+            recent_match_content: false,
             log: Default::default(),
             storage: Default::default(),
+            mouse_wheel: Default::default(),
+            open_url: Default::default(),
+            movies: Default::default(),
         }
     }
 }
@@ -263,3 +571,72 @@ pub struct LogPreferences {
 pub struct StoragePreferences {
     pub backend: storage::StorageBackend,
 }
+
+#[derive(PartialEq, Debug, Default)]
+pub struct MoviePreferences {
+    /// The base URL used to resolve relative paths in the movie, instead of the movie's own URL.
+    pub base: Option<Url>,
+}
+
+/// Keys pressed in place of scrolling the mouse wheel, for input devices which can't scroll
+/// or movies which can only be controlled with the keyboard.
+#[derive(PartialEq, Debug, Default)]
+pub struct MouseWheelPreferences {
+    pub up: Option<KeyCode>,
+    pub down: Option<KeyCode>,
+}
+
+/// Whether movies may open websites without asking, when `--open-url-mode confirm` is used.
+#[derive(PartialEq, Debug, Default)]
+pub struct OpenUrlPreferences {
+    /// Whether any website may be opened, or `None` to ask.
+    pub all: Option<bool>,
+    /// Whether the websites of a host may be opened, as remembered when the user was asked.
+    /// These take precedence over `all`.
+    pub hosts: BTreeMap<String, bool>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MouseWheelDirection {
+    Up,
+    Down,
+}
+
+impl MouseWheelDirection {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MouseWheelDirection::Up => "up",
+            MouseWheelDirection::Down => "down",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+    use std::ffi::OsStr;
+
+    #[test]
+    fn restart_needed_preferences_are_named_like_the_dialog() {
+        let directory = tempfile::tempdir().expect("Must be able to create a temporary directory");
+        let opt = Opt::parse_from([
+            OsStr::new("ruffle"),
+            OsStr::new("--config"),
+            directory.path().as_os_str(),
+        ]);
+        let preferences = GlobalPreferences::load(opt).expect("Preferences should load");
+        std::fs::write(
+            directory.path().join("preferences.toml"),
+            "graphics_backend = \"vulkan\"\n[storage]\nbackend = \"memory\"\n",
+        )
+        .expect("Preferences should be written");
+
+        let needs_restart = preferences.reload().expect("Preferences should reload");
+        assert_eq!(needs_restart, ["graphics-backend", "storage-backend"]);
+        assert_eq!(
+            restart_needed_names(&US_ENGLISH, &needs_restart),
+            "Graphics Backend, Save-File Location"
+        );
+    }
+}
@@ -0,0 +1,98 @@
+//! Upgrades preferences written by older versions of Ruffle to the current format.
+
+use toml_edit::{value, DocumentMut, Item};
+
+/// The version of the preferences format written by this version of Ruffle.
+pub const CURRENT_VERSION: i64 = 1;
+
+/// The migration at each index upgrades a document from that version to the next one.
+const MIGRATIONS: [fn(&mut DocumentMut); CURRENT_VERSION as usize] = [migrate_to_v1];
+
+/// Upgrades the document to the current version in place, returning whether it was changed.
+///
+/// Documents without a version predate versioning and are treated as version 0.
+/// Only the keys that need to change are touched, so comments and unknown keys are kept.
+/// Documents from newer versions of Ruffle are left alone, as they can't be downgraded.
+pub fn migrate_preferences(document: &mut DocumentMut) -> bool {
+    let version = document
+        .get("version")
+        .and_then(Item::as_integer)
+        .unwrap_or(0);
+    if version > CURRENT_VERSION {
+        tracing::warn!(
+            "Preferences are from a newer version of Ruffle (version {version}), some may be ignored"
+        );
+        return false;
+    }
+    if version == CURRENT_VERSION {
+        return false;
+    }
+
+    for migration in &MIGRATIONS[version.max(0) as usize..] {
+        migration(document);
+    }
+    document["version"] = value(CURRENT_VERSION);
+    true
+}
+
+/// Version 1 reads `volume` as a float only, so whole numbers such as `volume = 1` are converted.
+fn migrate_to_v1(document: &mut DocumentMut) {
+    if let Some(volume) = document.get("volume").and_then(Item::as_integer) {
+        document["volume"] = value(volume as f64);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn migrate(input: &str) -> (bool, String) {
+        let mut document = input.parse::<DocumentMut>().unwrap();
+        let changed = migrate_preferences(&mut document);
+        (changed, document.to_string())
+    }
+
+    #[test]
+    fn unversioned_is_stamped() {
+        assert_eq!(
+            (true, "mute = true\nversion = 1\n".to_string()),
+            migrate("mute = true\n")
+        );
+    }
+
+    #[test]
+    fn current_is_untouched() {
+        assert_eq!(
+            (false, "version = 1\nvolume = 1\n".to_string()),
+            migrate("version = 1\nvolume = 1\n")
+        );
+    }
+
+    #[test]
+    fn newer_is_untouched() {
+        assert_eq!(
+            (false, "version = 99\nvolume = 1\n".to_string()),
+            migrate("version = 99\nvolume = 1\n")
+        );
+    }
+
+    #[test]
+    fn integer_volume_becomes_float() {
+        assert_eq!(
+            (true, "volume = 1.0\nversion = 1\n".to_string()),
+            migrate("volume = 1\n")
+        );
+    }
+
+    #[test]
+    fn comments_and_unknown_keys_are_kept() {
+        assert_eq!(
+            (
+                true,
+                "# My settings\nfuture_setting = \"yes\"\nversion = 1\n\n[log]\nfilename_pattern = \"single_file\"\n"
+                    .to_string()
+            ),
+            migrate("# My settings\nfuture_setting = \"yes\"\n\n[log]\nfilename_pattern = \"single_file\"\n")
+        );
+    }
+}
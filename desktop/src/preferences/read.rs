@@ -1,8 +1,11 @@
 use crate::preferences::SavedGlobalPreferences;
+use clap::ValueEnum;
+use ruffle_core::events::KeyCode;
 use ruffle_frontend_utils::parse::{
-    DocumentHolder, ParseContext, ParseDetails, ParseWarning, ReadExt,
+    DocumentHolder, ItemExt, ParseContext, ParseDetails, ParseWarning, ReadExt,
 };
-use toml_edit::DocumentMut;
+use toml_edit::{DocumentMut, TableLike};
+use url::Url;
 
 /// Read the given preferences into a **guaranteed valid** `SavedGlobalPreferences`,
 /// recording any possible warnings encountered along the way.
@@ -51,10 +54,42 @@ pub fn read_preferences(input: &str) -> ParseDetails<SavedGlobalPreferences> {
         result.mute = value;
     };
 
+    if let Some(value) = document.get_bool(&mut cx, "auto_hide_cursor") {
+        result.auto_hide_cursor = value;
+    };
+
+    if let Some(value) = document.get_bool(&mut cx, "autoplay") {
+        result.autoplay = value;
+    };
+
+    if let Some(value) = document.get_bool(&mut cx, "keep_awake") {
+        result.keep_awake = value;
+    };
+
+    if let Some(value) = document.get_bool(&mut cx, "play_stats") {
+        result.play_stats = value;
+    };
+
+    if let Some(value) = document.parse_from_str(&mut cx, "fullscreen_requests") {
+        result.fullscreen_requests = value;
+    };
+
+    if let Some(value) = document.parse_from_str(&mut cx, "touch_emulation") {
+        result.touch_emulation = value;
+    };
+
+    if let Some(value) = document.parse_from_str(&mut cx, "color_filter") {
+        result.color_filter = value;
+    };
+
     if let Some(value) = document.get_integer(&mut cx, "recent_limit") {
         result.recent_limit = value as usize;
     }
 
+    if let Some(value) = document.get_bool(&mut cx, "recent_match_content") {
+        result.recent_match_content = value;
+    };
+
     document.get_table_like(&mut cx, "log", |cx, log| {
         if let Some(value) = log.parse_from_str(cx, "filename_pattern") {
             result.log.filename_pattern = value;
@@ -67,17 +102,79 @@ pub fn read_preferences(input: &str) -> ParseDetails<SavedGlobalPreferences> {
         }
     });
 
+    document.get_table_like(&mut cx, "movies", |cx, movies| {
+        for (movie_url, movie) in movies.iter() {
+            cx.push_key(movie_url);
+            if let Ok(movie_url) = Url::parse(movie_url) {
+                if let Some(movie) = movie.as_table_like() {
+                    let preferences = result.movies.entry(movie_url).or_default();
+                    preferences.base = movie.parse_from_str(cx, "base");
+                } else {
+                    cx.unexpected_type("table", movie.type_name());
+                }
+            } else {
+                cx.unsupported_value(movie_url.to_owned());
+            }
+            cx.pop_key();
+        }
+    });
+
+    document.get_table_like(&mut cx, "mouse_wheel", |cx, mouse_wheel| {
+        result.mouse_wheel.up = get_key_code(cx, mouse_wheel, "up");
+        result.mouse_wheel.down = get_key_code(cx, mouse_wheel, "down");
+    });
+
+    document.get_table_like(&mut cx, "open_url", |cx, open_url| {
+        result.open_url.all = open_url.get_bool(cx, "all");
+        open_url.get_table_like(cx, "hosts", |cx, hosts| {
+            for (host, allow) in hosts.iter() {
+                cx.push_key(host);
+                if let Some(allow) = allow.as_bool_or_warn(cx) {
+                    result.open_url.hosts.insert(host.to_owned(), allow);
+                }
+                cx.pop_key();
+            }
+        });
+    });
+
     ParseDetails {
         warnings: cx.warnings,
         result: DocumentHolder::new(result, document),
     }
 }
 
+/// Reads a key by the same name as used by `--gamepad-button`, such as `pg-up`.
+fn get_key_code(
+    cx: &mut ParseContext,
+    table: &dyn TableLike,
+    key: &'static str,
+) -> Option<KeyCode> {
+    let mut result = None;
+
+    cx.push_key(key);
+    if let Some(str) = table.get(key).and_then(|item| item.as_str_or_warn(cx)) {
+        if let Ok(value) = KeyCode::from_str(str, true) {
+            result = Some(value);
+        } else {
+            cx.unsupported_value(str.to_owned());
+        }
+    }
+    cx.pop_key();
+
+    result
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::backends::FullscreenRequests;
+    use crate::gui::ColorFilter;
     use crate::log::FilenamePattern;
-    use crate::preferences::{storage::StorageBackend, LogPreferences, StoragePreferences};
+    use crate::preferences::{
+        storage::StorageBackend, LogPreferences, MouseWheelPreferences, MoviePreferences,
+        OpenUrlPreferences, StoragePreferences,
+    };
+    use crate::touch::TouchEmulation;
     use fluent_templates::loader::langid;
     use ruffle_render_wgpu::clap::{GraphicsBackend, PowerPreference};
 
@@ -287,6 +384,186 @@ mod tests {
         assert_eq!(Vec::<ParseWarning>::new(), result.warnings);
     }
 
+    #[test]
+    fn auto_hide_cursor() {
+        let result = read_preferences("auto_hide_cursor = 0");
+        assert_eq!(
+            &SavedGlobalPreferences {
+                auto_hide_cursor: true,
+                ..Default::default()
+            },
+            result.values()
+        );
+        assert_eq!(
+            vec![ParseWarning::UnexpectedType {
+                expected: "boolean",
+                actual: "integer",
+                path: "auto_hide_cursor".to_string()
+            }],
+            result.warnings
+        );
+
+        let result = read_preferences("auto_hide_cursor = false");
+        assert_eq!(
+            &SavedGlobalPreferences {
+                auto_hide_cursor: false,
+                ..Default::default()
+            },
+            result.values()
+        );
+        assert_eq!(Vec::<ParseWarning>::new(), result.warnings);
+    }
+
+    #[test]
+    fn keep_awake() {
+        let result = read_preferences("keep_awake = false");
+        assert_eq!(
+            &SavedGlobalPreferences {
+                keep_awake: false,
+                ..Default::default()
+            },
+            result.values()
+        );
+        assert_eq!(Vec::<ParseWarning>::new(), result.warnings);
+    }
+
+    #[test]
+    fn play_stats() {
+        let result = read_preferences("play_stats = true");
+        assert_eq!(
+            &SavedGlobalPreferences {
+                play_stats: true,
+                ..Default::default()
+            },
+            result.values()
+        );
+        assert_eq!(Vec::<ParseWarning>::new(), result.warnings);
+    }
+
+    #[test]
+    fn autoplay() {
+        let result = read_preferences("autoplay = \"no\"");
+        assert_eq!(&SavedGlobalPreferences::default(), result.values());
+        assert_eq!(
+            vec![ParseWarning::UnexpectedType {
+                expected: "boolean",
+                actual: "string",
+                path: "autoplay".to_string()
+            }],
+            result.warnings
+        );
+
+        let result = read_preferences("autoplay = false");
+        assert_eq!(
+            &SavedGlobalPreferences {
+                autoplay: false,
+                ..Default::default()
+            },
+            result.values()
+        );
+        assert_eq!(Vec::<ParseWarning>::new(), result.warnings);
+    }
+
+    #[test]
+    fn fullscreen_requests() {
+        let result = read_preferences("fullscreen_requests = \"sometimes\"");
+        assert_eq!(&SavedGlobalPreferences::default(), result.values());
+        assert_eq!(
+            vec![ParseWarning::UnsupportedValue {
+                value: "sometimes".to_string(),
+                path: "fullscreen_requests".to_string()
+            }],
+            result.warnings
+        );
+
+        let result = read_preferences("fullscreen_requests = \"deny\"");
+        assert_eq!(
+            &SavedGlobalPreferences {
+                fullscreen_requests: FullscreenRequests::Deny,
+                ..Default::default()
+            },
+            result.values()
+        );
+        assert_eq!(Vec::<ParseWarning>::new(), result.warnings);
+    }
+
+    #[test]
+    fn touch_emulation() {
+        let result = read_preferences("touch_emulation = true");
+        assert_eq!(&SavedGlobalPreferences::default(), result.values());
+        assert_eq!(
+            vec![ParseWarning::UnexpectedType {
+                expected: "string",
+                actual: "boolean",
+                path: "touch_emulation".to_string()
+            }],
+            result.warnings
+        );
+
+        let result = read_preferences("touch_emulation = \"latest_touch\"");
+        assert_eq!(
+            &SavedGlobalPreferences {
+                touch_emulation: TouchEmulation::LatestTouch,
+                ..Default::default()
+            },
+            result.values()
+        );
+        assert_eq!(Vec::<ParseWarning>::new(), result.warnings);
+    }
+
+    #[test]
+    fn color_filter() {
+        let result = read_preferences("color_filter = \"sepia\"");
+        assert_eq!(&SavedGlobalPreferences::default(), result.values());
+        assert_eq!(
+            vec![ParseWarning::UnsupportedValue {
+                value: "sepia".to_string(),
+                path: "color_filter".to_string()
+            }],
+            result.warnings
+        );
+
+        let result = read_preferences("color_filter = \"deuteranopia\"");
+        assert_eq!(
+            &SavedGlobalPreferences {
+                color_filter: ColorFilter::Deuteranopia,
+                ..Default::default()
+            },
+            result.values()
+        );
+        assert_eq!(Vec::<ParseWarning>::new(), result.warnings);
+    }
+
+    #[test]
+    fn recent_match_content() {
+        let result = read_preferences("recent_match_content = \"yes\"");
+        assert_eq!(
+            &SavedGlobalPreferences {
+                recent_match_content: false,
+                ..Default::default()
+            },
+            result.values()
+        );
+        assert_eq!(
+            vec![ParseWarning::UnexpectedType {
+                expected: "boolean",
+                actual: "string",
+                path: "recent_match_content".to_string()
+            }],
+            result.warnings
+        );
+
+        let result = read_preferences("recent_match_content = true");
+        assert_eq!(
+            &SavedGlobalPreferences {
+                recent_match_content: true,
+                ..Default::default()
+            },
+            result.values()
+        );
+        assert_eq!(Vec::<ParseWarning>::new(), result.warnings);
+    }
+
     #[test]
     fn volume() {
         let result = read_preferences("volume = \"0.5\"");
@@ -481,4 +758,96 @@ mod tests {
         );
         assert_eq!(Vec::<ParseWarning>::new(), result.warnings);
     }
+
+    #[test]
+    fn mouse_wheel() {
+        let result = read_preferences("[mouse_wheel]\nup = \"up\"\ndown = \"pg-down\"");
+        assert_eq!(
+            &SavedGlobalPreferences {
+                mouse_wheel: MouseWheelPreferences {
+                    up: Some(KeyCode::Up),
+                    down: Some(KeyCode::PgDown),
+                },
+                ..Default::default()
+            },
+            result.values()
+        );
+        assert_eq!(Vec::<ParseWarning>::new(), result.warnings);
+
+        let result = read_preferences("mouse_wheel = { up = \"sideways\", down = 5 }");
+        assert_eq!(&SavedGlobalPreferences::default(), result.values());
+        assert_eq!(
+            vec![
+                ParseWarning::UnsupportedValue {
+                    value: "sideways".to_string(),
+                    path: "mouse_wheel.up".to_string()
+                },
+                ParseWarning::UnexpectedType {
+                    expected: "string",
+                    actual: "integer",
+                    path: "mouse_wheel.down".to_string()
+                },
+            ],
+            result.warnings
+        );
+    }
+
+    #[test]
+    fn movies() {
+        let result = read_preferences(
+            "[movies]\n\"file:///games/game.swf\" = { base = \"file:///games/assets/\" }\n\"not a url\" = {}\n\"file:///games/other.swf\" = 5",
+        );
+        assert_eq!(
+            &SavedGlobalPreferences {
+                movies: [(
+                    Url::parse("file:///games/game.swf").unwrap(),
+                    MoviePreferences {
+                        base: Some(Url::parse("file:///games/assets/").unwrap()),
+                    }
+                )]
+                .into(),
+                ..Default::default()
+            },
+            result.values()
+        );
+        assert_eq!(
+            vec![
+                ParseWarning::UnsupportedValue {
+                    value: "not a url".to_string(),
+                    path: "movies.not a url".to_string()
+                },
+                ParseWarning::UnexpectedType {
+                    expected: "table",
+                    actual: "integer",
+                    path: "movies.file:///games/other.swf".to_string()
+                },
+            ],
+            result.warnings
+        );
+    }
+
+    #[test]
+    fn open_url() {
+        let result = read_preferences(
+            "[open_url]\nall = false\nhosts = { \"example.com\" = true, \"ads.example.com\" = \"no\" }",
+        );
+        assert_eq!(
+            &SavedGlobalPreferences {
+                open_url: OpenUrlPreferences {
+                    all: Some(false),
+                    hosts: [("example.com".to_string(), true)].into(),
+                },
+                ..Default::default()
+            },
+            result.values()
+        );
+        assert_eq!(
+            vec![ParseWarning::UnexpectedType {
+                expected: "boolean",
+                actual: "string",
+                path: "open_url.hosts.ads.example.com".to_string()
+            },],
+            result.warnings
+        );
+    }
 }
@@ -1,10 +1,16 @@
+use crate::backends::FullscreenRequests;
+use crate::gui::ColorFilter;
 use crate::log::FilenamePattern;
 use crate::preferences::storage::StorageBackend;
-use crate::preferences::SavedGlobalPreferences;
+use crate::preferences::{MouseWheelDirection, SavedGlobalPreferences};
+use crate::touch::TouchEmulation;
+use clap::ValueEnum;
+use ruffle_core::events::KeyCode;
 use ruffle_frontend_utils::parse::DocumentHolder;
 use ruffle_render_wgpu::clap::{GraphicsBackend, PowerPreference};
-use toml_edit::value;
+use toml_edit::{table, value};
 use unic_langid::LanguageIdentifier;
+use url::Url;
 
 pub struct PreferencesWriter<'a>(&'a mut DocumentHolder<SavedGlobalPreferences>);
 
@@ -52,6 +58,62 @@ impl<'a> PreferencesWriter<'a> {
         })
     }
 
+    pub fn set_auto_hide_cursor(&mut self, auto_hide_cursor: bool) {
+        self.0.edit(|values, toml_document| {
+            toml_document["auto_hide_cursor"] = value(auto_hide_cursor);
+            values.auto_hide_cursor = auto_hide_cursor;
+        })
+    }
+
+    pub fn set_autoplay(&mut self, autoplay: bool) {
+        self.0.edit(|values, toml_document| {
+            toml_document["autoplay"] = value(autoplay);
+            values.autoplay = autoplay;
+        })
+    }
+
+    pub fn set_keep_awake(&mut self, keep_awake: bool) {
+        self.0.edit(|values, toml_document| {
+            toml_document["keep_awake"] = value(keep_awake);
+            values.keep_awake = keep_awake;
+        })
+    }
+
+    pub fn set_play_stats(&mut self, play_stats: bool) {
+        self.0.edit(|values, toml_document| {
+            toml_document["play_stats"] = value(play_stats);
+            values.play_stats = play_stats;
+        })
+    }
+
+    pub fn set_fullscreen_requests(&mut self, fullscreen_requests: FullscreenRequests) {
+        self.0.edit(|values, toml_document| {
+            toml_document["fullscreen_requests"] = value(fullscreen_requests.as_str());
+            values.fullscreen_requests = fullscreen_requests;
+        })
+    }
+
+    pub fn set_touch_emulation(&mut self, touch_emulation: TouchEmulation) {
+        self.0.edit(|values, toml_document| {
+            toml_document["touch_emulation"] = value(touch_emulation.as_str());
+            values.touch_emulation = touch_emulation;
+        })
+    }
+
+    pub fn set_color_filter(&mut self, filter: ColorFilter) {
+        self.0.edit(|values, toml_document| {
+            toml_document["color_filter"] = value(filter.as_str());
+            values.color_filter = filter;
+        })
+    }
+
+    pub fn set_recent_match_content(&mut self, recent_match_content: bool) {
+        self.0.edit(|values, toml_document| {
+            toml_document["recent_match_content"] = value(recent_match_content);
+            values.recent_match_content = recent_match_content;
+        })
+    }
+
     pub fn set_volume(&mut self, volume: f32) {
         self.0.edit(|values, toml_document| {
             toml_document["volume"] = value(volume as f64);
@@ -73,6 +135,102 @@ impl<'a> PreferencesWriter<'a> {
         })
     }
 
+    pub fn set_mouse_wheel_key(&mut self, direction: MouseWheelDirection, key: Option<KeyCode>) {
+        self.0.edit(|values, toml_document| {
+            if let Some(key) = key {
+                let name = key
+                    .to_possible_value()
+                    .expect("Must have a PossibleValue")
+                    .get_name()
+                    .to_owned();
+                toml_document["mouse_wheel"][direction.as_str()] = value(name);
+            } else if let Some(mouse_wheel) = toml_document
+                .get_mut("mouse_wheel")
+                .and_then(|item| item.as_table_like_mut())
+            {
+                mouse_wheel.remove(direction.as_str());
+            }
+            match direction {
+                MouseWheelDirection::Up => values.mouse_wheel.up = key,
+                MouseWheelDirection::Down => values.mouse_wheel.down = key,
+            }
+        })
+    }
+
+    pub fn set_movie_base(&mut self, movie_url: &Url, base: Option<Url>) {
+        self.0.edit(|values, toml_document| {
+            if let Some(base) = &base {
+                toml_document.entry("movies").or_insert(table());
+                toml_document["movies"][movie_url.as_str()]["base"] = value(base.as_str());
+            } else if let Some(movies) = toml_document
+                .get_mut("movies")
+                .and_then(|item| item.as_table_like_mut())
+            {
+                if let Some(movie) = movies
+                    .get_mut(movie_url.as_str())
+                    .and_then(|item| item.as_table_like_mut())
+                {
+                    movie.remove("base");
+                    if movie.is_empty() {
+                        movies.remove(movie_url.as_str());
+                    }
+                }
+                if movies.is_empty() {
+                    toml_document.remove("movies");
+                }
+            }
+            let movie = values.movies.entry(movie_url.clone()).or_default();
+            movie.base = base;
+            if movie.base.is_none() {
+                values.movies.remove(movie_url);
+            }
+        })
+    }
+
+    /// Sets whether any website may be opened without asking, or `None` to ask.
+    pub fn set_open_url_requests(&mut self, all: Option<bool>) {
+        self.0.edit(|values, toml_document| {
+            if let Some(all) = all {
+                toml_document.entry("open_url").or_insert(table());
+                toml_document["open_url"]["all"] = value(all);
+            } else if let Some(open_url) = toml_document
+                .get_mut("open_url")
+                .and_then(|item| item.as_table_like_mut())
+            {
+                open_url.remove("all");
+                if open_url.is_empty() {
+                    toml_document.remove("open_url");
+                }
+            }
+            values.open_url.all = all;
+        })
+    }
+
+    /// Remembers whether the websites of `host` may be opened without asking.
+    pub fn remember_open_url_host(&mut self, host: &str, allow: bool) {
+        self.0.edit(|values, toml_document| {
+            toml_document.entry("open_url").or_insert(table());
+            toml_document["open_url"]["hosts"][host] = value(allow);
+            values.open_url.hosts.insert(host.to_owned(), allow);
+        })
+    }
+
+    /// Forgets the choices remembered for the websites of every host, so that the user is asked again.
+    pub fn forget_open_url_hosts(&mut self) {
+        self.0.edit(|values, toml_document| {
+            if let Some(open_url) = toml_document
+                .get_mut("open_url")
+                .and_then(|item| item.as_table_like_mut())
+            {
+                open_url.remove("hosts");
+                if open_url.is_empty() {
+                    toml_document.remove("open_url");
+                }
+            }
+            values.open_url.hosts.clear();
+        })
+    }
+
     pub fn set_recent_limit(&mut self, limit: usize) {
         self.0.edit(|values, toml_document| {
             toml_document["recent_limit"] = value(limit as i64);
@@ -168,6 +326,62 @@ mod tests {
         );
     }
 
+    #[test]
+    fn set_auto_hide_cursor() {
+        test(
+            "",
+            |writer| writer.set_auto_hide_cursor(false),
+            "auto_hide_cursor = false\n",
+        );
+        test(
+            "auto_hide_cursor = false",
+            |writer| writer.set_auto_hide_cursor(true),
+            "auto_hide_cursor = true\n",
+        );
+    }
+
+    #[test]
+    fn set_autoplay() {
+        test(
+            "",
+            |writer| writer.set_autoplay(false),
+            "autoplay = false\n",
+        );
+        test(
+            "autoplay = false",
+            |writer| writer.set_autoplay(true),
+            "autoplay = true\n",
+        );
+    }
+
+    #[test]
+    fn set_keep_awake() {
+        test(
+            "",
+            |writer| writer.set_keep_awake(false),
+            "keep_awake = false\n",
+        );
+        test(
+            "keep_awake = false",
+            |writer| writer.set_keep_awake(true),
+            "keep_awake = true\n",
+        );
+    }
+
+    #[test]
+    fn set_play_stats() {
+        test(
+            "",
+            |writer| writer.set_play_stats(true),
+            "play_stats = true\n",
+        );
+        test(
+            "play_stats = true",
+            |writer| writer.set_play_stats(false),
+            "play_stats = false\n",
+        );
+    }
+
     #[test]
     fn set_log_filename_pattern() {
         test(
@@ -206,6 +420,62 @@ mod tests {
         );
     }
 
+    #[test]
+    fn set_fullscreen_requests() {
+        test(
+            "",
+            |writer| writer.set_fullscreen_requests(FullscreenRequests::Ask),
+            "fullscreen_requests = \"ask\"\n",
+        );
+        test(
+            "fullscreen_requests = \"ask\"",
+            |writer| writer.set_fullscreen_requests(FullscreenRequests::Deny),
+            "fullscreen_requests = \"deny\"\n",
+        );
+    }
+
+    #[test]
+    fn set_touch_emulation() {
+        test(
+            "",
+            |writer| writer.set_touch_emulation(TouchEmulation::FirstTouch),
+            "touch_emulation = \"first_touch\"\n",
+        );
+        test(
+            "touch_emulation = \"first_touch\"",
+            |writer| writer.set_touch_emulation(TouchEmulation::Off),
+            "touch_emulation = \"off\"\n",
+        );
+    }
+
+    #[test]
+    fn set_color_filter() {
+        test(
+            "",
+            |writer| writer.set_color_filter(ColorFilter::Grayscale),
+            "color_filter = \"grayscale\"\n",
+        );
+        test(
+            "color_filter = \"grayscale\"",
+            |writer| writer.set_color_filter(ColorFilter::None),
+            "color_filter = \"none\"\n",
+        );
+    }
+
+    #[test]
+    fn set_recent_match_content() {
+        test(
+            "",
+            |writer| writer.set_recent_match_content(true),
+            "recent_match_content = true\n",
+        );
+        test(
+            "recent_match_content = true",
+            |writer| writer.set_recent_match_content(false),
+            "recent_match_content = false\n",
+        );
+    }
+
     #[test]
     fn set_recent_limit() {
         test(
@@ -219,4 +489,71 @@ mod tests {
             "recent_limit = 15\n",
         );
     }
+
+    #[test]
+    fn set_mouse_wheel_key() {
+        test(
+            "",
+            |writer| writer.set_mouse_wheel_key(MouseWheelDirection::Up, Some(KeyCode::PgUp)),
+            "mouse_wheel = { up = \"pg-up\" }\n",
+        );
+        test(
+            "[mouse_wheel]\nup = \"up\"\ndown = \"down\"\n",
+            |writer| writer.set_mouse_wheel_key(MouseWheelDirection::Down, None),
+            "[mouse_wheel]\nup = \"up\"\n",
+        );
+        test(
+            "",
+            |writer| writer.set_mouse_wheel_key(MouseWheelDirection::Down, None),
+            "",
+        );
+    }
+
+    #[test]
+    fn set_movie_base() {
+        let movie_url = Url::parse("file:///games/game.swf").unwrap();
+        test(
+            "",
+            |writer| {
+                writer.set_movie_base(
+                    &movie_url,
+                    Some(Url::parse("file:///games/assets/").unwrap()),
+                )
+            },
+            "[movies]\n\"file:///games/game.swf\" = { base = \"file:///games/assets/\" }\n",
+        );
+        test(
+            "[movies]\n\"file:///games/game.swf\" = { base = \"file:///games/assets/\" }\n",
+            |writer| writer.set_movie_base(&movie_url, None),
+            "",
+        );
+    }
+
+    #[test]
+    fn set_open_url_requests() {
+        test(
+            "",
+            |writer| writer.set_open_url_requests(Some(false)),
+            "[open_url]\nall = false\n",
+        );
+        test(
+            "[open_url]\nall = true\n",
+            |writer| writer.set_open_url_requests(None),
+            "",
+        );
+    }
+
+    #[test]
+    fn remember_open_url_host() {
+        test(
+            "",
+            |writer| writer.remember_open_url_host("example.com", true),
+            "[open_url]\nhosts = { \"example.com\" = true }\n",
+        );
+        test(
+            "[open_url]\nall = false\nhosts = { \"example.com\" = true }\n",
+            |writer| writer.forget_open_url_hosts(),
+            "[open_url]\nall = false\n",
+        );
+    }
 }
@@ -0,0 +1,316 @@
+//! Recording of the input given to a movie, so that it can be replayed later to reproduce a problem.
+//!
+//! A recording is a text file starting with a version line, followed by one line per event:
+//! the number of frames the player had run when it happened, and the event itself.
+//!
+//! ```text
+//! ruffle-input-recording 1
+//! 12 mouse_move 120.5 80
+//! 12 mouse_down 120.5 80 left
+//! 30 key_down 65 97
+//! ```
+
+use anyhow::{anyhow, Context, Error};
+use clap::ValueEnum;
+use ruffle_core::events::{GamepadButton, KeyCode, MouseButton, MouseWheelDelta, TextControlCode};
+use ruffle_core::PlayerEvent;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+/// The first line of every recording, which changes whenever the format does.
+const HEADER: &str = "ruffle-input-recording 1";
+
+/// Writes the events given to a movie to a recording as they happen.
+pub struct InputRecorder {
+    writer: BufWriter<File>,
+}
+
+impl InputRecorder {
+    pub fn create(path: &Path) -> Result<Self, Error> {
+        let file = File::create(path)
+            .with_context(|| format!("Couldn't create the recording {}", path.display()))?;
+        let mut writer = BufWriter::new(file);
+        writeln!(writer, "{HEADER}")?;
+        Ok(Self { writer })
+    }
+
+    pub fn record(&mut self, frame: u64, event: &PlayerEvent) -> Result<(), Error> {
+        writeln!(self.writer, "{frame} {}", format_event(event))?;
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> Result<(), Error> {
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+/// The events of a recording, given back to a movie at the frames they were recorded at.
+#[derive(Debug, Default)]
+pub struct InputReplay {
+    events: VecDeque<(u64, PlayerEvent)>,
+}
+
+impl InputReplay {
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Couldn't read the recording {}", path.display()))?;
+        Self::parse(&contents)
+            .with_context(|| format!("{} isn't a valid recording", path.display()))
+    }
+
+    pub fn parse(input: &str) -> Result<Self, Error> {
+        let mut lines = input.lines();
+        match lines.next() {
+            Some(header) if header.trim_end() == HEADER => {}
+            Some(header) if header.starts_with("ruffle-input-recording ") => {
+                return Err(anyhow!("Unsupported recording version `{header}`"));
+            }
+            _ => return Err(anyhow!("Missing `{HEADER}` header")),
+        }
+
+        let mut events = VecDeque::new();
+        for (index, line) in lines.enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let event = line
+                .split_once(' ')
+                .ok_or_else(|| anyhow!("expected a frame and an event"))
+                .and_then(|(frame, event)| Ok((frame.parse()?, parse_event(event)?)))
+                .with_context(|| format!("Invalid event on line {}", index + 2))?;
+            events.push_back(event);
+        }
+        Ok(Self { events })
+    }
+
+    /// Takes the events which happened once `frame` frames had been run.
+    pub fn events_until(&mut self, frame: u64) -> Vec<PlayerEvent> {
+        let count = self
+            .events
+            .iter()
+            .take_while(|(event_frame, _)| *event_frame <= frame)
+            .count();
+        self.events.drain(..count).map(|(_, event)| event).collect()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.events.is_empty()
+    }
+}
+
+fn format_event(event: &PlayerEvent) -> String {
+    let format_char = |c: Option<char>| c.map_or("-".to_string(), |c| (c as u32).to_string());
+    match *event {
+        PlayerEvent::KeyDown { key_code, key_char } => {
+            format!("key_down {} {}", key_code as u8, format_char(key_char))
+        }
+        PlayerEvent::KeyUp { key_code, key_char } => {
+            format!("key_up {} {}", key_code as u8, format_char(key_char))
+        }
+        PlayerEvent::MouseMove { x, y } => format!("mouse_move {x} {y}"),
+        PlayerEvent::MouseDown { x, y, button } => {
+            format!("mouse_down {x} {y} {}", mouse_button_name(button))
+        }
+        PlayerEvent::MouseUp { x, y, button } => {
+            format!("mouse_up {x} {y} {}", mouse_button_name(button))
+        }
+        PlayerEvent::MouseLeave => "mouse_leave".to_string(),
+        PlayerEvent::MouseWheel {
+            delta: MouseWheelDelta::Lines(delta),
+        } => format!("mouse_wheel lines {delta}"),
+        PlayerEvent::MouseWheel {
+            delta: MouseWheelDelta::Pixels(delta),
+        } => format!("mouse_wheel pixels {delta}"),
+        PlayerEvent::GamepadButtonDown { button } => {
+            format!("gamepad_down {}", gamepad_button_name(button))
+        }
+        PlayerEvent::GamepadButtonUp { button } => {
+            format!("gamepad_up {}", gamepad_button_name(button))
+        }
+        PlayerEvent::TextInput { codepoint } => format!("text_input {}", codepoint as u32),
+        PlayerEvent::TextControl { code } => format!("text_control {code:?}"),
+    }
+}
+
+fn parse_event(line: &str) -> Result<PlayerEvent, Error> {
+    let mut parts = line.split_whitespace();
+    let name = parts.next().unwrap_or_default();
+    let mut next = || {
+        parts
+            .next()
+            .ok_or_else(|| anyhow!("`{name}` is missing a value"))
+    };
+    let event = match name {
+        "key_down" => PlayerEvent::KeyDown {
+            key_code: parse_key_code(next()?)?,
+            key_char: parse_key_char(next()?)?,
+        },
+        "key_up" => PlayerEvent::KeyUp {
+            key_code: parse_key_code(next()?)?,
+            key_char: parse_key_char(next()?)?,
+        },
+        "mouse_move" => PlayerEvent::MouseMove {
+            x: next()?.parse()?,
+            y: next()?.parse()?,
+        },
+        "mouse_down" => PlayerEvent::MouseDown {
+            x: next()?.parse()?,
+            y: next()?.parse()?,
+            button: parse_mouse_button(next()?)?,
+        },
+        "mouse_up" => PlayerEvent::MouseUp {
+            x: next()?.parse()?,
+            y: next()?.parse()?,
+            button: parse_mouse_button(next()?)?,
+        },
+        "mouse_leave" => PlayerEvent::MouseLeave,
+        "mouse_wheel" => {
+            let unit = next()?;
+            let delta = next()?.parse()?;
+            PlayerEvent::MouseWheel {
+                delta: match unit {
+                    "lines" => MouseWheelDelta::Lines(delta),
+                    "pixels" => MouseWheelDelta::Pixels(delta),
+                    _ => return Err(anyhow!("unknown mouse wheel unit `{unit}`")),
+                },
+            }
+        }
+        "gamepad_down" => PlayerEvent::GamepadButtonDown {
+            button: parse_gamepad_button(next()?)?,
+        },
+        "gamepad_up" => PlayerEvent::GamepadButtonUp {
+            button: parse_gamepad_button(next()?)?,
+        },
+        "text_input" => PlayerEvent::TextInput {
+            codepoint: parse_char(next()?)?,
+        },
+        "text_control" => PlayerEvent::TextControl {
+            code: parse_text_control_code(next()?)?,
+        },
+        _ => return Err(anyhow!("unknown event `{name}`")),
+    };
+    Ok(event)
+}
+
+fn parse_key_code(value: &str) -> Result<KeyCode, Error> {
+    KeyCode::from_u8(value.parse()?).ok_or_else(|| anyhow!("unknown key code `{value}`"))
+}
+
+fn parse_key_char(value: &str) -> Result<Option<char>, Error> {
+    if value == "-" {
+        Ok(None)
+    } else {
+        parse_char(value).map(Some)
+    }
+}
+
+fn parse_char(value: &str) -> Result<char, Error> {
+    char::from_u32(value.parse()?).ok_or_else(|| anyhow!("invalid character code `{value}`"))
+}
+
+fn mouse_button_name(button: MouseButton) -> &'static str {
+    match button {
+        MouseButton::Unknown => "unknown",
+        MouseButton::Left => "left",
+        MouseButton::Right => "right",
+        MouseButton::Middle => "middle",
+    }
+}
+
+fn parse_mouse_button(value: &str) -> Result<MouseButton, Error> {
+    match value {
+        "unknown" => Ok(MouseButton::Unknown),
+        "left" => Ok(MouseButton::Left),
+        "right" => Ok(MouseButton::Right),
+        "middle" => Ok(MouseButton::Middle),
+        _ => Err(anyhow!("unknown mouse button `{value}`")),
+    }
+}
+
+fn gamepad_button_name(button: GamepadButton) -> String {
+    button
+        .to_possible_value()
+        .map(|value| value.get_name().to_string())
+        .unwrap_or_default()
+}
+
+fn parse_gamepad_button(value: &str) -> Result<GamepadButton, Error> {
+    GamepadButton::from_str(value, true).map_err(|_| anyhow!("unknown gamepad button `{value}`"))
+}
+
+fn parse_text_control_code(value: &str) -> Result<TextControlCode, Error> {
+    serde_json::from_value(serde_json::Value::String(value.to_string()))
+        .map_err(|_| anyhow!("unknown text control `{value}`"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let events = [
+            PlayerEvent::KeyDown {
+                key_code: KeyCode::A,
+                key_char: Some('a'),
+            },
+            PlayerEvent::KeyUp {
+                key_code: KeyCode::Shift,
+                key_char: None,
+            },
+            PlayerEvent::MouseDown {
+                x: 120.5,
+                y: 80.0,
+                button: MouseButton::Left,
+            },
+            PlayerEvent::MouseWheel {
+                delta: MouseWheelDelta::Pixels(-3.0),
+            },
+            PlayerEvent::GamepadButtonDown {
+                button: GamepadButton::DPadLeft,
+            },
+            PlayerEvent::TextInput { codepoint: 'é' },
+            PlayerEvent::TextControl {
+                code: TextControlCode::SelectAll,
+            },
+            PlayerEvent::MouseLeave,
+        ];
+        let mut recording = format!("{HEADER}\n");
+        for (frame, event) in events.iter().enumerate() {
+            recording += &format!("{frame} {}\n", format_event(event));
+        }
+
+        let mut replay = InputReplay::parse(&recording).expect("Recording should be valid");
+        let replayed = replay.events_until(u64::MAX);
+        assert!(replay.is_finished());
+        assert_eq!(
+            format!("{replayed:?}"),
+            format!("{:?}", events.to_vec()),
+            "Replayed events should match the recorded ones"
+        );
+    }
+
+    #[test]
+    fn events_until_frame() {
+        let mut replay = InputReplay::parse(&format!(
+            "{HEADER}\n1 mouse_move 1 2\n1 mouse_leave\n\n5 text_input 65\n"
+        ))
+        .expect("Recording should be valid");
+        assert!(replay.events_until(0).is_empty());
+        assert_eq!(replay.events_until(3).len(), 2);
+        assert!(!replay.is_finished());
+        assert_eq!(replay.events_until(5).len(), 1);
+        assert!(replay.is_finished());
+    }
+
+    #[test]
+    fn invalid_recordings() {
+        assert!(InputReplay::parse("1 mouse_leave\n").is_err());
+        assert!(InputReplay::parse("ruffle-input-recording 2\n").is_err());
+        assert!(InputReplay::parse(&format!("{HEADER}\n1 mouse_move 1\n")).is_err());
+        assert!(InputReplay::parse(&format!("{HEADER}\nx mouse_leave\n")).is_err());
+    }
+}
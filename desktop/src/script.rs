@@ -0,0 +1,206 @@
+//! Scripts of timed commands run against a movie, to automate demos and tests.
+//!
+//! A script is a text file with one command per line. Commands run at the frame the movie
+//! has reached when they're due, which only advances with `wait`. Empty lines and lines
+//! starting with `#` are ignored.
+//!
+//! ```text
+//! # Start the game and take a picture of the first level.
+//! wait 30
+//! click 120.5 80
+//! wait 10
+//! press SPACE
+//! type hello
+//! volume 0.5
+//! resize 800 600
+//! screenshot level1.png
+//! quit
+//! ```
+
+use anyhow::{anyhow, Context, Error};
+use clap::ValueEnum;
+use ruffle_core::events::{KeyCode, MouseButton};
+use ruffle_core::PlayerEvent;
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+
+/// Something a script does to the movie.
+#[derive(Debug)]
+pub enum ScriptCommand {
+    /// Gives an event to the movie, as if the user did it.
+    Event(PlayerEvent),
+    /// Saves what's shown of the movie as a PNG.
+    Screenshot(PathBuf),
+    /// Changes the volume, from 0 to 1.
+    Volume(f32),
+    /// Resizes the stage to a width and height, in pixels.
+    Resize(u32, u32),
+    /// Exits Ruffle.
+    Quit,
+}
+
+/// The commands of a script, run at the frames they're scheduled at.
+#[derive(Debug, Default)]
+pub struct Script {
+    commands: VecDeque<(u64, ScriptCommand)>,
+}
+
+impl Script {
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Couldn't read the script {}", path.display()))?;
+        Self::parse(&contents).with_context(|| format!("{} isn't a valid script", path.display()))
+    }
+
+    pub fn parse(input: &str) -> Result<Self, Error> {
+        let mut commands = VecDeque::new();
+        let mut frame = 0;
+        for (index, line) in input.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            parse_line(line, &mut frame, &mut commands)
+                .with_context(|| format!("Invalid command on line {}", index + 1))?;
+        }
+        Ok(Self { commands })
+    }
+
+    /// Takes the commands which are due once `frame` frames had been run.
+    pub fn commands_until(&mut self, frame: u64) -> Vec<ScriptCommand> {
+        let count = self
+            .commands
+            .iter()
+            .take_while(|(command_frame, _)| *command_frame <= frame)
+            .count();
+        self.commands
+            .drain(..count)
+            .map(|(_, command)| command)
+            .collect()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.commands.is_empty()
+    }
+}
+
+fn parse_line(
+    line: &str,
+    frame: &mut u64,
+    commands: &mut VecDeque<(u64, ScriptCommand)>,
+) -> Result<(), Error> {
+    let (name, arguments) = line.split_once(' ').unwrap_or((line, ""));
+    let arguments = arguments.trim();
+    let mut parts = arguments.split_whitespace();
+    let mut next = || {
+        parts
+            .next()
+            .ok_or_else(|| anyhow!("`{name}` is missing a value"))
+    };
+    let mut push = |command| commands.push_back((*frame, command));
+    match name {
+        "wait" => *frame += next()?.parse::<u64>()?,
+        "move" => push(ScriptCommand::Event(PlayerEvent::MouseMove {
+            x: next()?.parse()?,
+            y: next()?.parse()?,
+        })),
+        "click" => {
+            let (x, y) = (next()?.parse()?, next()?.parse()?);
+            let button = MouseButton::Left;
+            push(ScriptCommand::Event(PlayerEvent::MouseMove { x, y }));
+            push(ScriptCommand::Event(PlayerEvent::MouseDown {
+                x,
+                y,
+                button,
+            }));
+            push(ScriptCommand::Event(PlayerEvent::MouseUp { x, y, button }));
+        }
+        "press" => {
+            let key = next()?;
+            let key_code =
+                KeyCode::from_str(key, true).map_err(|_| anyhow!("unknown key `{key}`"))?;
+            push(ScriptCommand::Event(PlayerEvent::KeyDown {
+                key_code,
+                key_char: None,
+            }));
+            push(ScriptCommand::Event(PlayerEvent::KeyUp {
+                key_code,
+                key_char: None,
+            }));
+        }
+        "type" => {
+            if arguments.is_empty() {
+                return Err(anyhow!("`type` is missing the text to type"));
+            }
+            for codepoint in arguments.chars() {
+                push(ScriptCommand::Event(PlayerEvent::TextInput { codepoint }));
+            }
+        }
+        "screenshot" => {
+            if arguments.is_empty() {
+                return Err(anyhow!("`screenshot` is missing the path to save it to"));
+            }
+            push(ScriptCommand::Screenshot(PathBuf::from(arguments)));
+        }
+        "volume" => {
+            let volume: f32 = next()?.parse()?;
+            if !(0.0..=1.0).contains(&volume) {
+                return Err(anyhow!("volume must be between 0 and 1, not {volume}"));
+            }
+            push(ScriptCommand::Volume(volume));
+        }
+        "resize" => {
+            let (width, height): (u32, u32) = (next()?.parse()?, next()?.parse()?);
+            if width == 0 || height == 0 {
+                return Err(anyhow!("the stage can't be resized to {width}x{height}"));
+            }
+            push(ScriptCommand::Resize(width, height));
+        }
+        "quit" => push(ScriptCommand::Quit),
+        _ => return Err(anyhow!("unknown command `{name}`")),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn commands_run_at_their_frame() {
+        let mut script = Script::parse(
+            "# A comment\n\nclick 10 20\nwait 5\npress SPACE\ntype ab\nwait 2\nscreenshot shot one.png\nquit\n",
+        )
+        .expect("Script should be valid");
+        assert_eq!(script.commands_until(0).len(), 3);
+        assert!(script.commands_until(4).is_empty());
+        assert_eq!(script.commands_until(5).len(), 4);
+        let last = script.commands_until(7);
+        assert!(matches!(
+            &last[..],
+            [ScriptCommand::Screenshot(path), ScriptCommand::Quit] if path == Path::new("shot one.png")
+        ));
+        assert!(script.is_finished());
+    }
+
+    #[test]
+    fn errors_have_line_numbers() {
+        let error = Script::parse("wait 1\n\njump 3\n").expect_err("`jump` isn't a command");
+        assert_eq!(error.to_string(), "Invalid command on line 3");
+        assert!(Script::parse("press NOT_A_KEY\n").is_err());
+        assert!(Script::parse("volume 2\n").is_err());
+        assert!(Script::parse("wait\n").is_err());
+        assert!(Script::parse("screenshot\n").is_err());
+        assert!(Script::parse("resize 800\n").is_err());
+        assert!(Script::parse("resize 0 600\n").is_err());
+    }
+
+    #[test]
+    fn resize_takes_a_width_and_height() {
+        let mut script = Script::parse("resize 800 600\n").expect("Script should be valid");
+        assert!(matches!(
+            &script.commands_until(0)[..],
+            [ScriptCommand::Resize(800, 600)]
+        ));
+    }
+}
@@ -0,0 +1,146 @@
+//! Recording of a movie's audio as separate stems, so that captured audio can be remixed.
+//!
+//! Event sounds, stream sounds and everything mixed together are each written to their own
+//! 32-bit float stereo WAV file as they're played.
+
+use anyhow::{Context, Error};
+use ruffle_core::backend::audio::StemSink;
+use std::fs::File;
+use std::io::{self, BufWriter, Seek, SeekFrom, Write};
+use std::path::Path;
+
+/// The size of the header of the WAV files written, before the samples.
+const HEADER_LEN: u32 = 44;
+
+/// Writes the stems of a movie's audio to `events.wav`, `streams.wav` and `mixed.wav` in a
+/// directory.
+pub struct WavStems {
+    events: WavWriter,
+    streams: WavWriter,
+    mixed: WavWriter,
+    failed: bool,
+}
+
+impl WavStems {
+    pub fn create(directory: &Path, sample_rate: u32) -> Result<Self, Error> {
+        std::fs::create_dir_all(directory)
+            .with_context(|| format!("Couldn't create the directory {}", directory.display()))?;
+        let create = |name: &str| {
+            let path = directory.join(name);
+            WavWriter::create(&path, sample_rate)
+                .with_context(|| format!("Couldn't create {}", path.display()))
+        };
+        Ok(Self {
+            events: create("events.wav")?,
+            streams: create("streams.wav")?,
+            mixed: create("mixed.wav")?,
+            failed: false,
+        })
+    }
+
+    fn write(&mut self, events: [f32; 2], streams: [f32; 2]) -> io::Result<()> {
+        self.events.write(events)?;
+        self.streams.write(streams)?;
+        self.mixed
+            .write([events[0] + streams[0], events[1] + streams[1]])
+    }
+}
+
+impl StemSink for WavStems {
+    fn push(&mut self, events: [f32; 2], streams: [f32; 2]) {
+        if self.failed {
+            return;
+        }
+        if let Err(e) = self.write(events, streams) {
+            tracing::error!("Couldn't write the audio stems, no longer recording them: {e}");
+            self.failed = true;
+        }
+    }
+}
+
+/// Writes stereo samples to a WAV file, keeping its header up to date every second so that
+/// it's still playable if Ruffle doesn't exit cleanly.
+struct WavWriter {
+    writer: BufWriter<File>,
+    sample_rate: u32,
+    frames: u32,
+}
+
+impl WavWriter {
+    fn create(path: &Path, sample_rate: u32) -> io::Result<Self> {
+        let mut writer = Self {
+            writer: BufWriter::new(File::create(path)?),
+            sample_rate,
+            frames: 0,
+        };
+        writer.write_header()?;
+        Ok(writer)
+    }
+
+    fn write(&mut self, frame: [f32; 2]) -> io::Result<()> {
+        for sample in frame {
+            self.writer.write_all(&sample.to_le_bytes())?;
+        }
+        self.frames = self.frames.saturating_add(1);
+        if self.frames % self.sample_rate == 0 {
+            self.write_header()?;
+        }
+        Ok(())
+    }
+
+    fn write_header(&mut self) -> io::Result<()> {
+        let data_len = self.frames.saturating_mul(8).min(u32::MAX - HEADER_LEN);
+        let position = self.writer.stream_position()?;
+        self.writer.seek(SeekFrom::Start(0))?;
+        let writer = &mut self.writer;
+        writer.write_all(b"RIFF")?;
+        writer.write_all(&(HEADER_LEN - 8 + data_len).to_le_bytes())?;
+        writer.write_all(b"WAVEfmt ")?;
+        writer.write_all(&16u32.to_le_bytes())?;
+        // IEEE float samples, in 2 channels.
+        writer.write_all(&3u16.to_le_bytes())?;
+        writer.write_all(&2u16.to_le_bytes())?;
+        writer.write_all(&self.sample_rate.to_le_bytes())?;
+        writer.write_all(&(self.sample_rate * 8).to_le_bytes())?;
+        writer.write_all(&8u16.to_le_bytes())?;
+        writer.write_all(&32u16.to_le_bytes())?;
+        writer.write_all(b"data")?;
+        writer.write_all(&data_len.to_le_bytes())?;
+        writer.seek(SeekFrom::Start(position.max(HEADER_LEN.into())))?;
+        Ok(())
+    }
+}
+
+impl Drop for WavWriter {
+    fn drop(&mut self) {
+        if let Err(e) = self.write_header().and_then(|_| self.writer.flush()) {
+            tracing::error!("Couldn't finish writing an audio stem: {e}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stems_are_written_as_wav() {
+        let directory = tempfile::tempdir().unwrap();
+        let mut stems = WavStems::create(directory.path(), 44100).unwrap();
+        stems.push([0.25, 0.5], [0.5, 0.0]);
+        stems.push([0.0, 0.0], [-1.0, 1.0]);
+        drop(stems);
+
+        let mixed = std::fs::read(directory.path().join("mixed.wav")).unwrap();
+        assert_eq!(mixed.len(), HEADER_LEN as usize + 2 * 8);
+        assert_eq!(&mixed[0..4], b"RIFF");
+        assert_eq!(&mixed[40..44], &16u32.to_le_bytes());
+        let samples: Vec<f32> = mixed[44..]
+            .chunks_exact(4)
+            .map(|bytes| f32::from_le_bytes(bytes.try_into().unwrap()))
+            .collect();
+        assert_eq!(samples, [0.75, 0.5, -1.0, 1.0]);
+        let events = std::fs::read(directory.path().join("events.wav")).unwrap();
+        assert_eq!(events.len(), mixed.len());
+    }
+}
@@ -0,0 +1,177 @@
+//! Emulation of the mouse with a touchscreen, for setups where touches don't already reach
+//! the movie as mouse events.
+
+use std::str::FromStr;
+use winit::dpi::PhysicalPosition;
+use winit::event::TouchPhase;
+
+/// Which touches move the emulated mouse pointer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TouchEmulation {
+    /// Touches are left to the system, which often turns them into mouse events already.
+    #[default]
+    Off,
+    /// The first finger on the screen controls the pointer, and any others are ignored.
+    FirstTouch,
+    /// Every new finger on the screen takes over the pointer from the previous one.
+    LatestTouch,
+}
+
+impl FromStr for TouchEmulation {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "off" => Ok(TouchEmulation::Off),
+            "first_touch" => Ok(TouchEmulation::FirstTouch),
+            "latest_touch" => Ok(TouchEmulation::LatestTouch),
+            _ => Err(()),
+        }
+    }
+}
+
+impl TouchEmulation {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TouchEmulation::Off => "off",
+            TouchEmulation::FirstTouch => "first_touch",
+            TouchEmulation::LatestTouch => "latest_touch",
+        }
+    }
+}
+
+/// What the emulated mouse does in response to a touch.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PointerAction {
+    Move(PhysicalPosition<f64>),
+    Press,
+    Release,
+}
+
+/// Follows the touch currently controlling the pointer.
+#[derive(Debug, Default)]
+pub struct TouchEmulator {
+    /// The ID and latest position of the touch controlling the pointer.
+    active: Option<(u64, PhysicalPosition<f64>)>,
+}
+
+impl TouchEmulator {
+    /// Turns a touch into the mouse actions it stands for.
+    ///
+    /// A tap becomes a move, press and release at the same spot, while a drag moves
+    /// the pointer with the button held. A touch cancelled by the system, such as one
+    /// which left the window, releases the button where the pointer was last moved to.
+    pub fn handle_touch(
+        &mut self,
+        emulation: TouchEmulation,
+        id: u64,
+        phase: TouchPhase,
+        location: PhysicalPosition<f64>,
+    ) -> Vec<PointerAction> {
+        if emulation == TouchEmulation::Off {
+            self.active = None;
+            return vec![];
+        }
+
+        let mut actions = vec![];
+        match (phase, self.active) {
+            (TouchPhase::Started, Some((active_id, _)))
+                if active_id != id && emulation == TouchEmulation::FirstTouch => {}
+            (TouchPhase::Started, active) => {
+                if active.is_some() {
+                    actions.push(PointerAction::Release);
+                }
+                self.active = Some((id, location));
+                actions.push(PointerAction::Move(location));
+                actions.push(PointerAction::Press);
+            }
+            (TouchPhase::Moved, Some((active_id, _))) if active_id == id => {
+                self.active = Some((id, location));
+                actions.push(PointerAction::Move(location));
+            }
+            (TouchPhase::Ended, Some((active_id, last_location))) if active_id == id => {
+                self.active = None;
+                if location != last_location {
+                    actions.push(PointerAction::Move(location));
+                }
+                actions.push(PointerAction::Release);
+            }
+            (TouchPhase::Cancelled, Some((active_id, _))) if active_id == id => {
+                self.active = None;
+                actions.push(PointerAction::Release);
+            }
+            _ => {}
+        }
+        actions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(x: f64, y: f64) -> PhysicalPosition<f64> {
+        PhysicalPosition::new(x, y)
+    }
+
+    #[test]
+    fn tap_and_drag() {
+        let mut emulator = TouchEmulator::default();
+        let mode = TouchEmulation::FirstTouch;
+        assert_eq!(
+            emulator.handle_touch(mode, 1, TouchPhase::Started, at(10.0, 20.0)),
+            vec![PointerAction::Move(at(10.0, 20.0)), PointerAction::Press]
+        );
+        assert_eq!(
+            emulator.handle_touch(mode, 1, TouchPhase::Moved, at(15.0, 25.0)),
+            vec![PointerAction::Move(at(15.0, 25.0))]
+        );
+        assert_eq!(
+            emulator.handle_touch(mode, 1, TouchPhase::Ended, at(15.0, 25.0)),
+            vec![PointerAction::Release]
+        );
+    }
+
+    #[test]
+    fn first_touch_ignores_other_fingers() {
+        let mut emulator = TouchEmulator::default();
+        let mode = TouchEmulation::FirstTouch;
+        emulator.handle_touch(mode, 1, TouchPhase::Started, at(0.0, 0.0));
+        assert!(emulator
+            .handle_touch(mode, 2, TouchPhase::Started, at(5.0, 5.0))
+            .is_empty());
+        assert!(emulator
+            .handle_touch(mode, 2, TouchPhase::Moved, at(6.0, 6.0))
+            .is_empty());
+        assert_eq!(
+            emulator.handle_touch(mode, 1, TouchPhase::Cancelled, at(-1.0, -1.0)),
+            vec![PointerAction::Release]
+        );
+    }
+
+    #[test]
+    fn latest_touch_takes_over() {
+        let mut emulator = TouchEmulator::default();
+        let mode = TouchEmulation::LatestTouch;
+        emulator.handle_touch(mode, 1, TouchPhase::Started, at(0.0, 0.0));
+        assert_eq!(
+            emulator.handle_touch(mode, 2, TouchPhase::Started, at(5.0, 5.0)),
+            vec![
+                PointerAction::Release,
+                PointerAction::Move(at(5.0, 5.0)),
+                PointerAction::Press
+            ]
+        );
+        assert!(emulator
+            .handle_touch(mode, 1, TouchPhase::Ended, at(0.0, 0.0))
+            .is_empty());
+    }
+
+    #[test]
+    fn off_does_nothing() {
+        let mut emulator = TouchEmulator::default();
+        assert!(emulator
+            .handle_touch(TouchEmulation::Off, 1, TouchPhase::Started, at(0.0, 0.0))
+            .is_empty());
+    }
+}
@@ -1,14 +1,19 @@
-use crate::custom_event::RuffleEvent;
+use crate::custom_event::WindowedRuffleEvent;
 use anyhow::{anyhow, Error};
 use gilrs::Button;
 use rfd::FileDialog;
 use ruffle_core::events::{GamepadButton, KeyCode, TextControlCode};
+use ruffle_frontend_utils::bundle::Bundle;
+use std::fs::File;
+use std::io::Read;
 use std::path::{Path, PathBuf};
 use url::Url;
-use winit::dpi::PhysicalSize;
+use winit::dpi::{PhysicalPosition, PhysicalSize};
 use winit::event::{KeyEvent, Modifiers};
 use winit::event_loop::EventLoop;
 use winit::keyboard::{Key, KeyLocation, NamedKey};
+use winit::monitor::MonitorHandle;
+use winit::window::{Fullscreen, Window};
 
 /// Converts a winit event to a Ruffle `TextControlCode`.
 /// Returns `None` if there is no match.
@@ -195,7 +200,7 @@ pub fn gilrs_button_to_gamepad_button(button: Button) -> Option<GamepadButton> {
     }
 }
 
-pub fn get_screen_size(event_loop: &EventLoop<RuffleEvent>) -> PhysicalSize<u32> {
+pub fn get_screen_size(event_loop: &EventLoop<WindowedRuffleEvent>) -> PhysicalSize<u32> {
     let mut min_x = 0;
     let mut min_y = 0;
     let mut max_x = 0;
@@ -220,11 +225,103 @@ pub fn get_screen_size(event_loop: &EventLoop<RuffleEvent>) -> PhysicalSize<u32>
     (width, height).into()
 }
 
+/// Moves the window to `position`, or to the middle of its monitor if there's none.
+///
+/// The window is kept within a monitor, so that it can't end up out of sight.
+pub fn place_window(window: &Window, position: Option<PhysicalPosition<i32>>) {
+    let monitor = match position {
+        Some(position) => window
+            .available_monitors()
+            .find(|monitor| monitor_contains(monitor, position)),
+        None => window.current_monitor(),
+    }
+    .or_else(|| window.primary_monitor())
+    .or_else(|| window.available_monitors().next());
+    let Some(monitor) = monitor else {
+        if let Some(position) = position {
+            window.set_outer_position(position);
+        }
+        return;
+    };
+
+    let monitor_position = monitor.position();
+    let monitor_size = monitor.size();
+    let window_size = window.outer_size();
+    let max_x = monitor_position.x + monitor_size.width as i32 - window_size.width as i32;
+    let max_y = monitor_position.y + monitor_size.height as i32 - window_size.height as i32;
+    let position = position.unwrap_or_else(|| {
+        PhysicalPosition::new(
+            monitor_position.x + (max_x - monitor_position.x) / 2,
+            monitor_position.y + (max_y - monitor_position.y) / 2,
+        )
+    });
+
+    // Windows larger than the monitor are kept at its top left corner.
+    window.set_outer_position(PhysicalPosition::new(
+        position.x.min(max_x).max(monitor_position.x),
+        position.y.min(max_y).max(monitor_position.y),
+    ));
+}
+
+/// Borderless fullscreen on the monitor at `index` in winit's list of monitors, as chosen with
+/// `--fullscreen-monitor`, or on the window's current monitor if there's no such monitor.
+pub fn borderless_fullscreen(window: &Window, index: Option<usize>) -> Fullscreen {
+    let monitor = index.and_then(|index| {
+        let monitor = window.available_monitors().nth(index);
+        if monitor.is_none() {
+            tracing::warn!("There's no monitor {index}, going fullscreen on the current one");
+        }
+        monitor
+    });
+    Fullscreen::Borderless(monitor)
+}
+
+fn monitor_contains(monitor: &MonitorHandle, position: PhysicalPosition<i32>) -> bool {
+    let monitor_position = monitor.position();
+    let monitor_size = monitor.size();
+    (monitor_position.x..monitor_position.x + monitor_size.width as i32).contains(&position.x)
+        && (monitor_position.y..monitor_position.y + monitor_size.height as i32)
+            .contains(&position.y)
+}
+
+/// Opens the folder containing the file in the system file manager, selecting the file where supported.
+pub fn show_in_folder(path: &Path) -> std::io::Result<()> {
+    #[cfg(target_os = "windows")]
+    let mut command = {
+        let mut command = std::process::Command::new("explorer");
+        command.arg(format!("/select,{}", path.display()));
+        command
+    };
+    #[cfg(target_os = "macos")]
+    let mut command = {
+        let mut command = std::process::Command::new("open");
+        command.arg("-R").arg(path);
+        command
+    };
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let mut command = {
+        let mut command = std::process::Command::new("xdg-open");
+        command.arg(path.parent().unwrap_or(path));
+        command
+    };
+    // Don't wait for the file manager, which may keep running.
+    command.spawn().map(|_| ())
+}
+
 pub fn parse_url(path: &Path) -> Result<Url, Error> {
     if path.exists() {
         let absolute_path = path.canonicalize().unwrap_or_else(|_| path.to_owned());
         Url::from_file_path(absolute_path)
             .map_err(|_| anyhow!("Path must be absolute and cannot be a URL"))
+    } else if let Some((file, query)) = path
+        .to_str()
+        .and_then(|path| path.split_once('?'))
+        .filter(|(file, _)| Path::new(file).exists())
+    {
+        // Like in a browser, `movie.swf?key=value` gives the movie parameters.
+        let mut url = parse_url(Path::new(file))?;
+        url.set_query(Some(query));
+        Ok(url)
     } else {
         Url::parse(path.to_str().unwrap_or_default())
             .ok()
@@ -233,6 +330,18 @@ pub fn parse_url(path: &Path) -> Result<Url, Error> {
     }
 }
 
+/// Checks whether a local file is something we can open, either a SWF or a bundle.
+pub fn is_movie_or_bundle(path: &Path) -> bool {
+    if Bundle::from_path(path).is_ok() {
+        return true;
+    }
+    let mut signature = [0; 3];
+    File::open(path)
+        .and_then(|mut file| file.read_exact(&mut signature))
+        .is_ok()
+        && matches!(&signature, b"FWS" | b"CWS" | b"ZWS")
+}
+
 fn actually_pick_file(dir: Option<PathBuf>) -> Option<PathBuf> {
     let mut dialog = FileDialog::new()
         .add_filter("Flash Files", &["swf", "spl", "ruf"])
@@ -246,6 +355,14 @@ fn actually_pick_file(dir: Option<PathBuf>) -> Option<PathBuf> {
     dialog.pick_file()
 }
 
+/// Asks the user where to save a file of the given type.
+pub fn pick_save_file(filter_name: &str, extensions: &[&str]) -> Option<PathBuf> {
+    FileDialog::new()
+        .add_filter(filter_name, extensions)
+        .add_filter("All Files", &["*"])
+        .save_file()
+}
+
 // [NA] Horrible hacky workaround for https://github.com/rust-windowing/winit/issues/2291
 // We only need the workaround from within UI code, not when executing custom events
 // The workaround causes Ruffle to show as "not responding" on windows, so we don't use it if we don't need to
@@ -306,3 +423,39 @@ pub fn plot_stats_in_tracy(instance: &wgpu::Instance) {
 
     tracy.frame_mark();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_url_file_with_and_without_query() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("movie.swf");
+        std::fs::write(&path, b"FWS").unwrap();
+        let expected = Url::from_file_path(path.canonicalize().unwrap()).unwrap();
+
+        let url = parse_url(&path).unwrap();
+        assert_eq!(url, expected);
+        assert_eq!(url.query(), None);
+
+        let with_query = format!("{}?level=2&name=a%20b", path.display());
+        let url = parse_url(Path::new(&with_query)).unwrap();
+        assert_eq!(url.path(), expected.path());
+        assert_eq!(url.query(), Some("level=2&name=a%20b"));
+    }
+
+    #[test]
+    fn parse_url_missing_file_with_query() {
+        let dir = tempfile::tempdir().unwrap();
+        let missing = format!("{}?level=2", dir.path().join("missing.swf").display());
+        assert!(parse_url(Path::new(&missing)).is_err());
+    }
+
+    #[test]
+    fn parse_url_web_url_keeps_query() {
+        let url = parse_url(Path::new("https://example.com/movie.swf?level=2")).unwrap();
+        assert_eq!(url.host_str(), Some("example.com"));
+        assert_eq!(url.query(), Some("level=2"));
+    }
+}
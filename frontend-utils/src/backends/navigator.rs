@@ -1,7 +1,12 @@
+mod busy_file;
 mod fetch;
+mod file_root;
+mod policy;
+mod request_log;
 
 use crate::backends::executor::{spawn_tokio, FutureSpawner};
-use crate::backends::navigator::fetch::{Response, ResponseBody};
+use crate::backends::navigator::fetch::{ConnectionLimit, NetworkStats, Response, ResponseBody};
+use crate::backends::navigator::request_log::RequestLog;
 use crate::content::PlayingContent;
 use async_channel::{Receiver, Sender, TryRecvError};
 use async_io::Timer;
@@ -9,10 +14,10 @@ use async_net::TcpStream;
 use futures::future::select;
 use futures::{AsyncReadExt, AsyncWriteExt};
 use futures_lite::FutureExt;
-use reqwest::Proxy;
+use reqwest::{redirect, Proxy};
 use ruffle_core::backend::navigator::{
-    async_return, create_fetch_error, ErrorResponse, NavigationMethod, NavigatorBackend,
-    OpenURLMode, OwnedFuture, Request, SocketMode, SuccessResponse,
+    async_return, create_fetch_error, create_specific_fetch_error, ErrorResponse, NavigationMethod,
+    NavigatorBackend, OpenURLMode, OwnedFuture, Request, SocketMode, SuccessResponse,
 };
 use ruffle_core::indexmap::IndexMap;
 use ruffle_core::loader::Error;
@@ -21,6 +26,7 @@ use std::collections::HashSet;
 use std::fs::File;
 use std::io;
 use std::io::ErrorKind;
+use std::num::NonZeroUsize;
 use std::path::Path;
 use std::rc::Rc;
 use std::sync::{Arc, Mutex};
@@ -28,6 +34,10 @@ use std::time::Duration;
 use tracing::warn;
 use url::{ParseError, Url};
 
+pub use fetch::{InFlightRequests, NetworkStats};
+pub use file_root::FileRoot;
+pub use policy::NetworkPolicy;
+
 pub trait NavigatorInterface: Clone + 'static {
     fn confirm_website_navigation(&self, url: &Url) -> bool;
 
@@ -38,6 +48,24 @@ pub trait NavigatorInterface: Clone + 'static {
         host: &str,
         port: u16,
     ) -> impl std::future::Future<Output = bool> + Send;
+
+    /// Asks whether a file of `size` bytes should be loaded, as it's larger than the navigator
+    /// was told to load without asking.
+    fn confirm_large_file(
+        &self,
+        url: &Url,
+        size: u64,
+    ) -> impl std::future::Future<Output = bool> + Send;
+
+    /// Plays another SWF of the bundle being played, instead of the current one.
+    ///
+    /// A browser can't open files inside of a bundle, so this is used when the movie
+    /// navigates to one of them.
+    fn navigate_within_bundle(&self, url: &Url);
+
+    /// Plays the SWF at `url` instead of the current movie, as the movie navigated to it
+    /// in its own window.
+    fn navigate_in_place(&self, url: &Url);
 }
 
 /// Implementation of `NavigatorBackend` for non-web environments that can call
@@ -56,32 +84,127 @@ pub struct ExternalNavigatorBackend<F: FutureSpawner, I: NavigatorInterface> {
 
     socket_mode: SocketMode,
 
+    /// Which hosts the content may reach, regardless of the other settings.
+    network_policy: NetworkPolicy,
+
     upgrade_to_https: bool,
 
     open_url_mode: OpenURLMode,
 
+    /// The requests made through `fetch` that haven't finished yet.
+    in_flight_requests: InFlightRequests,
+
+    network_stats: NetworkStats,
+
+    /// Where every request made through `fetch` is logged, if requested.
+    request_log: Option<RequestLog>,
+
+    /// How many requests may be made over the network at once, if limited.
+    connection_limit: Option<ConnectionLimit>,
+
+    /// The size in bytes above which the user is asked before a file is loaded, if any.
+    /// Files of unknown size are always loaded.
+    confirm_larger_than: Option<u64>,
+
+    /// Whether local files that seem to still be written are read again after a short while.
+    retry_busy_files: bool,
+
+    /// The directory local files may only be loaded from, if confined to one.
+    /// Files inside of a bundle are always allowed.
+    file_root: Option<FileRoot>,
+
+    /// Whether SWFs opened in the movie's own window are played in place of the movie,
+    /// instead of being opened in a browser.
+    open_swfs_in_place: bool,
+
     content: Rc<PlayingContent>,
 
     interface: I,
 }
 
+/// How a navigator loads content, and what it lets the content open.
+#[derive(Debug, Clone)]
+pub struct NavigatorOptions {
+    pub proxy: Option<Url>,
+
+    pub upgrade_to_https: bool,
+
+    pub open_url_mode: OpenURLMode,
+
+    pub socket_allowed: HashSet<String>,
+
+    pub socket_mode: SocketMode,
+
+    /// Which hosts the content may reach, regardless of the other settings.
+    pub network_policy: NetworkPolicy,
+
+    /// Whether every request made through `fetch` is logged.
+    pub log_requests: bool,
+
+    /// How many requests may be made over the network at once, if limited.
+    pub max_connections: Option<NonZeroUsize>,
+
+    /// The size in bytes above which the user is asked before a file is loaded, if any.
+    pub confirm_larger_than: Option<u64>,
+
+    /// Whether local files that seem to still be written are read again after a short while.
+    pub retry_busy_files: bool,
+
+    /// The directory local files may only be loaded from, if confined to one.
+    pub file_root: Option<FileRoot>,
+
+    /// Whether SWFs opened in the movie's own window are played in place of the movie.
+    pub open_swfs_in_place: bool,
+}
+
+impl Default for NavigatorOptions {
+    /// Options that don't let the content open anything outside of the player.
+    fn default() -> Self {
+        Self {
+            proxy: None,
+            upgrade_to_https: false,
+            open_url_mode: OpenURLMode::Deny,
+            socket_allowed: HashSet::new(),
+            socket_mode: SocketMode::Deny,
+            network_policy: NetworkPolicy::default(),
+            log_requests: false,
+            max_connections: None,
+            confirm_larger_than: None,
+            retry_busy_files: false,
+            file_root: None,
+            open_swfs_in_place: false,
+        }
+    }
+}
+
 impl<F: FutureSpawner, I: NavigatorInterface> ExternalNavigatorBackend<F, I> {
     /// Construct a navigator backend with fetch and async capability.
-    #[allow(clippy::too_many_arguments)]
     pub fn new(
         mut base_url: Url,
         future_spawner: F,
-        proxy: Option<Url>,
-        upgrade_to_https: bool,
-        open_url_mode: OpenURLMode,
-        socket_allowed: HashSet<String>,
-        socket_mode: SocketMode,
+        options: NavigatorOptions,
         content: Rc<PlayingContent>,
         interface: I,
     ) -> Self {
-        let mut builder = reqwest::ClientBuilder::new().cookie_store(true);
+        // Redirects are followed by the client, so they're checked against the network policy
+        // there, as the content could otherwise be sent anywhere by a host it's allowed to reach.
+        let network_policy = options.network_policy.clone();
+        let redirect_policy = redirect::Policy::custom(move |attempt| {
+            if network_policy.allows_url(attempt.url()) {
+                redirect::Policy::default().redirect(attempt)
+            } else {
+                tracing::warn!(
+                    "Blocked redirect to {} because of the network policy",
+                    attempt.url()
+                );
+                attempt.error("Blocked by network policy")
+            }
+        });
+        let mut builder = reqwest::ClientBuilder::new()
+            .cookie_store(true)
+            .redirect(redirect_policy);
 
-        if let Some(proxy) = proxy {
+        if let Some(proxy) = options.proxy {
             match Proxy::all(proxy.clone()) {
                 Ok(proxy) => {
                     builder = builder.proxy(proxy);
@@ -104,21 +227,89 @@ impl<F: FutureSpawner, I: NavigatorInterface> ExternalNavigatorBackend<F, I> {
             future_spawner,
             client,
             base_url,
-            upgrade_to_https,
-            open_url_mode,
-            socket_allowed,
-            socket_mode,
+            upgrade_to_https: options.upgrade_to_https,
+            open_url_mode: options.open_url_mode,
+            socket_allowed: options.socket_allowed,
+            socket_mode: options.socket_mode,
+            network_policy: options.network_policy,
+            in_flight_requests: InFlightRequests::default(),
+            network_stats: NetworkStats::default(),
+            request_log: options.log_requests.then(RequestLog::default),
+            connection_limit: options.max_connections.map(ConnectionLimit::new),
+            confirm_larger_than: options.confirm_larger_than,
+            retry_busy_files: options.retry_busy_files,
+            file_root: options.file_root,
+            open_swfs_in_place: options.open_swfs_in_place,
             content,
             interface,
         }
     }
+
+    /// A handle to the number of requests that haven't finished yet,
+    /// which stays up to date after this navigator is given to a player.
+    pub fn in_flight_requests(&self) -> InFlightRequests {
+        self.in_flight_requests.clone()
+    }
+
+    /// Whether the file at `url` may be loaded, which is when it's within the file root if there is one.
+    /// Anything that isn't a local file is always allowed.
+    fn allows_local_file(&self, url: &Url) -> bool {
+        let Some(root) = &self.file_root else {
+            return true;
+        };
+        if url.scheme() != "file" || matches!(&*self.content, PlayingContent::Bundle(..)) {
+            return true;
+        }
+        url.to_file_path().is_ok_and(|path| root.contains(&path))
+    }
+
+    /// A handle to the counts of what this navigator loaded,
+    /// which stays up to date after this navigator is given to a player.
+    pub fn network_stats(&self) -> NetworkStats {
+        self.network_stats.clone()
+    }
+}
+
+/// Whether a file of `size` bytes may be loaded, asking the user if it's larger than `threshold`.
+async fn confirm_size<I: NavigatorInterface>(
+    interface: &I,
+    threshold: Option<u64>,
+    url: &Url,
+    size: Option<u64>,
+) -> bool {
+    match (threshold, size) {
+        (Some(threshold), Some(size)) if size > threshold => {
+            interface.confirm_large_file(url, size).await
+        }
+        _ => true,
+    }
+}
+
+fn is_swf_url(url: &Url) -> bool {
+    url.path().to_ascii_lowercase().ends_with(".swf")
+}
+
+/// Whether a navigation to `target` replaces the page the movie is in, rather than opening another window.
+fn is_same_window(target: &str) -> bool {
+    matches!(
+        target.to_ascii_lowercase().as_str(),
+        "" | "_self" | "_parent" | "_top"
+    )
+}
+
+fn declined_error(url: &Url) -> ErrorResponse {
+    tracing::warn!("Not loading {url}, as the user declined to load such a large file");
+    ErrorResponse {
+        url: url.to_string(),
+        error: Error::FetchError("The user declined to load this file".to_string()),
+    }
 }
 
 impl<F: FutureSpawner, I: NavigatorInterface> NavigatorBackend for ExternalNavigatorBackend<F, I> {
     fn navigate_to_url(
         &self,
         url: &str,
-        _target: &str,
+        target: &str,
         vars_method: Option<(NavigationMethod, IndexMap<String, String>)>,
     ) {
         //TODO: Should we return a result for failed opens? Does Flash care?
@@ -160,6 +351,41 @@ impl<F: FutureSpawner, I: NavigatorInterface> NavigatorBackend for ExternalNavig
             return;
         }
 
+        // Pages of the bundle may stand in for websites, so they're played even when offline.
+        if let Some(swf_url) = self.content.page_for(&modified_url) {
+            self.interface.navigate_within_bundle(&swf_url);
+            return;
+        }
+
+        if !self.network_policy.allows_url(&modified_url) {
+            tracing::warn!(
+                "SWF tried to open {modified_url}, but the network policy doesn't allow it"
+            );
+            return;
+        }
+
+        if self.content.is_bundled(&modified_url) {
+            if is_swf_url(&modified_url) {
+                self.interface.navigate_within_bundle(&modified_url);
+            } else {
+                tracing::warn!(
+                    "SWF tried to open {modified_url} from its bundle, but only SWFs can be opened"
+                );
+            }
+            return;
+        }
+
+        if self.open_swfs_in_place && is_same_window(target) && is_swf_url(&modified_url) {
+            if self.allows_local_file(&modified_url) {
+                self.interface.navigate_in_place(&modified_url);
+            } else {
+                tracing::warn!(
+                    "SWF tried to open {modified_url}, which is outside of the file root"
+                );
+            }
+            return;
+        }
+
         if self.open_url_mode == OpenURLMode::Confirm {
             if !self.interface.confirm_website_navigation(&modified_url) {
                 tracing::info!("SWF tried to open a website, but the user declined the request");
@@ -191,12 +417,49 @@ impl<F: FutureSpawner, I: NavigatorInterface> NavigatorBackend for ExternalNavig
             }
         };
 
+        if !self.network_policy.allows_url(&processed_url) {
+            tracing::warn!("Blocked request to {processed_url} because of the network policy");
+            return async_return(create_specific_fetch_error(
+                "Blocked by network policy",
+                processed_url.as_str(),
+                "",
+            ));
+        }
+
+        if !self.allows_local_file(&processed_url) {
+            tracing::warn!("Blocked loading {processed_url}, as it's outside of the file root");
+            return async_return(create_specific_fetch_error(
+                "Outside of the file root",
+                processed_url.as_str(),
+                "",
+            ));
+        }
+
         let client = self.client.clone();
+        let connection_limit = self.connection_limit.clone();
+        let confirm_larger_than = self.confirm_larger_than;
+        let retry_busy_files = self.retry_busy_files;
+        let interface = self.interface.clone();
+        let in_flight = self.in_flight_requests.start();
+        let method = match request.method() {
+            NavigationMethod::Get => "GET",
+            NavigationMethod::Post => "POST",
+        };
+        let stats = self.network_stats.clone();
+        let request_log = self.request_log.clone();
+        let requested = request.url().to_string();
+        let base_url = self.base_url.clone();
+        let log = move |resolved: &Url, outcome: Result<(u16, Option<u64>), String>| {
+            stats.add_request(&outcome);
+            if let Some(log) = &request_log {
+                log.log(method, &requested, &base_url, resolved, outcome);
+            }
+        };
+        let stats = self.network_stats.clone();
 
         match processed_url.scheme() {
             "file" => {
                 let content = self.content.clone();
-                let interface = self.interface.clone();
                 Box::pin(async move {
                     // We send the original url (including query parameters)
                     // back to ruffle_core in the `Response`
@@ -206,14 +469,40 @@ impl<F: FutureSpawner, I: NavigatorInterface> NavigatorBackend for ExternalNavig
                     // when we actually load a filesystem url, strip them out.
                     processed_url.set_query(None);
 
-                    let contents =
-                        content.get_local_file(&processed_url, |path| interface.open_file(path));
+                    // Files inside of a bundle aren't on the filesystem, and are trusted as much as the bundle.
+                    let size = match &*content {
+                        PlayingContent::DirectFile(_) => processed_url
+                            .to_file_path()
+                            .ok()
+                            .and_then(|path| std::fs::metadata(path).ok())
+                            .map(|metadata| metadata.len()),
+                        PlayingContent::Bundle(..) => None,
+                    };
+                    if !confirm_size(&interface, confirm_larger_than, &response_url, size).await {
+                        return Err(declined_error(&response_url));
+                    }
+
+                    let read =
+                        || content.get_local_file(&processed_url, |path| interface.open_file(path));
+                    let contents = match (&*content, processed_url.to_file_path()) {
+                        (PlayingContent::DirectFile(_), Ok(path)) if retry_busy_files => {
+                            busy_file::read_when_written(&path, read).await
+                        }
+                        _ => read(),
+                    };
+                    let outcome = match &contents {
+                        Ok(contents) => Ok((0, Some(contents.len() as u64))),
+                        Err(e) => Err(e.to_string()),
+                    };
+                    log(&response_url, outcome);
 
                     let response: Box<dyn SuccessResponse> = Box::new(Response {
                         url: response_url.to_string(),
                         response_body: ResponseBody::File(contents),
                         status: 0,
                         redirected: false,
+                        in_flight,
+                        stats,
                     });
 
                     Ok(response)
@@ -237,7 +526,18 @@ impl<F: FutureSpawner, I: NavigatorInterface> NavigatorBackend for ExternalNavig
 
                 request_builder = request_builder.body(body_data);
 
-                let response = spawn_tokio(request_builder.send()).await.map_err(|e| {
+                let connection = match &connection_limit {
+                    Some(limit) => Some(limit.acquire().await),
+                    None => None,
+                };
+                let response = spawn_tokio(request_builder.send()).await;
+                drop(connection);
+                let outcome = match &response {
+                    Ok(response) => Ok((response.status().as_u16(), response.content_length())),
+                    Err(e) => Err(e.to_string()),
+                };
+                log(&processed_url, outcome);
+                let response = response.map_err(|e| {
                     let inner = if e.is_connect() {
                         Error::InvalidDomain(processed_url.to_string())
                     } else {
@@ -249,6 +549,17 @@ impl<F: FutureSpawner, I: NavigatorInterface> NavigatorBackend for ExternalNavig
                     }
                 })?;
 
+                if !confirm_size(
+                    &interface,
+                    confirm_larger_than,
+                    &processed_url,
+                    response.content_length(),
+                )
+                .await
+                {
+                    return Err(declined_error(&processed_url));
+                }
+
                 let url = response.url().to_string();
 
                 let status = response.status().as_u16();
@@ -268,6 +579,8 @@ impl<F: FutureSpawner, I: NavigatorInterface> NavigatorBackend for ExternalNavig
                     response_body: ResponseBody::Network(Arc::new(Mutex::new(Some(response)))),
                     status,
                     redirected,
+                    in_flight,
+                    stats,
                 });
                 Ok(response)
             }),
@@ -301,10 +614,21 @@ impl<F: FutureSpawner, I: NavigatorInterface> NavigatorBackend for ExternalNavig
         receiver: Receiver<Vec<u8>>,
         sender: Sender<SocketAction>,
     ) {
+        if !self.network_policy.allows_host(&host) {
+            tracing::warn!(
+                "SWF tried to open a socket to {host}:{port}, but the network policy doesn't allow it"
+            );
+            sender
+                .try_send(SocketAction::Connect(handle, ConnectionState::Failed))
+                .expect("working channel send");
+            return;
+        }
+
         let addr = format!("{}:{}", host, port);
         let is_allowed = self.socket_allowed.contains(&addr);
         let socket_mode = self.socket_mode;
         let interface = self.interface.clone();
+        let stats = self.network_stats.clone();
 
         let future = Box::pin(async move {
             match (is_allowed, socket_mode) {
@@ -351,6 +675,7 @@ impl<F: FutureSpawner, I: NavigatorInterface> NavigatorBackend for ExternalNavig
                     return Ok(());
                 }
                 Ok(stream) => {
+                    stats.add_socket();
                     sender
                         .try_send(SocketAction::Connect(handle, ConnectionState::Connected))
                         .expect("working channel send");
@@ -467,6 +792,7 @@ mod tests {
     use tokio::task;
 
     use super::*;
+    use crate::bundle::Bundle;
 
     impl NavigatorInterface for () {
         fn confirm_website_navigation(&self, _url: &Url) -> bool {
@@ -480,6 +806,106 @@ mod tests {
         async fn confirm_socket(&self, _host: &str, _port: u16) -> bool {
             true
         }
+
+        async fn confirm_large_file(&self, _url: &Url, _size: u64) -> bool {
+            true
+        }
+
+        fn navigate_within_bundle(&self, _url: &Url) {}
+
+        fn navigate_in_place(&self, _url: &Url) {}
+    }
+
+    /// Remembers where the movie navigated to within its bundle.
+    #[derive(Clone, Default)]
+    struct BundleNavigationInterface(Arc<Mutex<Vec<Url>>>);
+
+    impl NavigatorInterface for BundleNavigationInterface {
+        fn confirm_website_navigation(&self, _url: &Url) -> bool {
+            panic!("Bundled SWFs shouldn't be opened in a browser")
+        }
+
+        fn open_file(&self, _path: &Path) -> io::Result<File> {
+            panic!("Bundled files shouldn't be read from the filesystem")
+        }
+
+        async fn confirm_socket(&self, _host: &str, _port: u16) -> bool {
+            false
+        }
+
+        async fn confirm_large_file(&self, _url: &Url, _size: u64) -> bool {
+            false
+        }
+
+        fn navigate_within_bundle(&self, url: &Url) {
+            self.0.lock().unwrap().push(url.clone());
+        }
+
+        fn navigate_in_place(&self, _url: &Url) {
+            panic!("Bundled SWFs should be played from the bundle")
+        }
+    }
+
+    /// Remembers which SWFs the movie played in place of itself.
+    #[derive(Clone, Default)]
+    struct InPlaceNavigationInterface(Arc<Mutex<Vec<Url>>>);
+
+    impl NavigatorInterface for InPlaceNavigationInterface {
+        fn confirm_website_navigation(&self, _url: &Url) -> bool {
+            false
+        }
+
+        fn open_file(&self, path: &Path) -> io::Result<File> {
+            File::open(path)
+        }
+
+        async fn confirm_socket(&self, _host: &str, _port: u16) -> bool {
+            false
+        }
+
+        async fn confirm_large_file(&self, _url: &Url, _size: u64) -> bool {
+            false
+        }
+
+        fn navigate_within_bundle(&self, _url: &Url) {
+            panic!("There's no bundle to navigate within")
+        }
+
+        fn navigate_in_place(&self, url: &Url) {
+            self.0.lock().unwrap().push(url.clone());
+        }
+    }
+
+    /// Remembers the sizes of the files it was asked about, and answers all of them the same.
+    #[derive(Clone, Default)]
+    struct LargeFileInterface {
+        allow: bool,
+        asked: Arc<Mutex<Vec<u64>>>,
+    }
+
+    impl NavigatorInterface for LargeFileInterface {
+        fn confirm_website_navigation(&self, _url: &Url) -> bool {
+            false
+        }
+
+        fn open_file(&self, path: &Path) -> io::Result<File> {
+            File::open(path)
+        }
+
+        async fn confirm_socket(&self, _host: &str, _port: u16) -> bool {
+            false
+        }
+
+        async fn confirm_large_file(&self, _url: &Url, size: u64) -> bool {
+            self.asked.lock().unwrap().push(size);
+            self.allow
+        }
+
+        fn navigate_within_bundle(&self, _url: &Url) {
+            panic!("There's no bundle to navigate within")
+        }
+
+        fn navigate_in_place(&self, _url: &Url) {}
     }
 
     const TIMEOUT_ZERO: Duration = Duration::ZERO;
@@ -536,14 +962,14 @@ mod tests {
         ExternalNavigatorBackend::new(
             url.clone(),
             TestFutureSpawner,
-            None,
-            false,
-            OpenURLMode::Allow,
-            Default::default(),
-            if socket_allow {
-                SocketMode::Allow
-            } else {
-                SocketMode::Deny
+            NavigatorOptions {
+                open_url_mode: OpenURLMode::Allow,
+                socket_mode: if socket_allow {
+                    SocketMode::Allow
+                } else {
+                    SocketMode::Deny
+                },
+                ..Default::default()
             },
             Rc::new(PlayingContent::DirectFile(url)),
             (),
@@ -645,6 +1071,29 @@ mod tests {
         );
     }
 
+    #[macro_rules_attribute::apply(async_test)]
+    async fn test_socket_network_policy() {
+        let (_accept_task, addr) = start_test_server().await;
+        let mut backend = new_test_backend(true);
+        backend.network_policy = NetworkPolicy::Offline;
+
+        let (_client_write, receiver) = async_channel::unbounded();
+        let (sender, client_read) = async_channel::unbounded();
+        backend.connect_socket(
+            addr.ip().to_string(),
+            addr.port(),
+            TIMEOUT,
+            dummy_handle!(),
+            receiver,
+            sender,
+        );
+
+        assert_next_socket_actions!(
+            client_read;
+            Connect(dummy_handle!(), ConnectionState::Failed),
+        );
+    }
+
     #[macro_rules_attribute::apply(async_test)]
     async fn test_socket_fail() {
         let addr = SocketAddr::from_str("[100::]:42").expect("black hole address");
@@ -739,4 +1188,272 @@ mod tests {
 
         assert_eq!(read_server(&mut server_socket).await, "Sending some data");
     }
+
+    #[macro_rules_attribute::apply(async_test)]
+    async fn test_in_flight_requests() {
+        let backend = new_test_backend(false);
+        let in_flight = backend.in_flight_requests();
+        let url = Url::from_file_path(concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml")).unwrap();
+
+        let response = backend.fetch(Request::get(url.to_string()));
+        assert_eq!(in_flight.count(), 1);
+
+        let response = response.await.expect("local fetch");
+        assert_eq!(in_flight.count(), 1);
+
+        let body = response.body().await.expect("local body");
+        assert!(!body.is_empty());
+        assert_eq!(in_flight.count(), 0);
+    }
+
+    /// Answers requests for `/inside` with a redirect to itself, `/outside` with a redirect
+    /// to `localhost`, and anything else with the path that was requested.
+    fn start_redirect_server() -> u16 {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut request = [0; 1024];
+                let length = std::io::Read::read(&mut stream, &mut request).unwrap();
+                let request = String::from_utf8_lossy(&request[..length]);
+                let path = request.split(' ').nth(1).unwrap_or_default();
+                let response = match path {
+                    "/inside" => format!(
+                        "HTTP/1.1 302 Found\r\nLocation: http://127.0.0.1:{port}/target\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                    ),
+                    "/outside" => format!(
+                        "HTTP/1.1 302 Found\r\nLocation: http://localhost:{port}/target\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                    ),
+                    _ => format!(
+                        "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{path}",
+                        path.len()
+                    ),
+                };
+                std::io::Write::write_all(&mut stream, response.as_bytes()).unwrap();
+            }
+        });
+        port
+    }
+
+    #[macro_rules_attribute::apply(async_test)]
+    async fn test_redirect_network_policy() {
+        let port = start_redirect_server();
+        let url = Url::parse(&format!("http://127.0.0.1:{port}/game.swf")).unwrap();
+        let backend = ExternalNavigatorBackend::new(
+            url.clone(),
+            TestFutureSpawner,
+            NavigatorOptions {
+                network_policy: NetworkPolicy::AllowedHosts(vec!["127.0.0.1".to_string()]),
+                ..Default::default()
+            },
+            Rc::new(PlayingContent::DirectFile(url)),
+            (),
+        );
+
+        let response = backend
+            .fetch(Request::get("inside".to_string()))
+            .await
+            .expect("redirect within the network policy");
+        assert_eq!(response.url(), format!("http://127.0.0.1:{port}/target"));
+        assert_eq!(response.body().await.expect("redirected body"), b"/target");
+
+        assert!(backend
+            .fetch(Request::get("outside".to_string()))
+            .await
+            .is_err());
+    }
+
+    #[macro_rules_attribute::apply(async_test)]
+    async fn test_fetch_outside_file_root() {
+        let directory = tempfile::tempdir().unwrap();
+        let root_path = directory.path().join("root");
+        std::fs::create_dir_all(&root_path).unwrap();
+        std::fs::write(root_path.join("level.swf"), "level").unwrap();
+        std::fs::write(directory.path().join("secret.txt"), "secret").unwrap();
+        let url = Url::from_file_path(root_path.join("game.swf")).unwrap();
+        let backend = ExternalNavigatorBackend::new(
+            url.clone(),
+            TestFutureSpawner,
+            NavigatorOptions {
+                file_root: Some(FileRoot::new(&root_path).unwrap()),
+                ..Default::default()
+            },
+            Rc::new(PlayingContent::DirectFile(url)),
+            (),
+        );
+
+        let response = backend
+            .fetch(Request::get("level.swf".to_string()))
+            .await
+            .expect("fetch within the file root");
+        assert_eq!(response.body().await.expect("local body"), b"level");
+
+        let outside = Url::from_file_path(directory.path().join("secret.txt")).unwrap();
+        assert!(backend
+            .fetch(Request::get(outside.to_string()))
+            .await
+            .is_err());
+        assert!(backend
+            .fetch(Request::get("../secret.txt".to_string()))
+            .await
+            .is_err());
+    }
+
+    #[macro_rules_attribute::apply(async_test)]
+    async fn test_fetch_confirms_large_files() {
+        let directory = tempfile::tempdir().unwrap();
+        std::fs::write(directory.path().join("small.swf"), [0; 1024]).unwrap();
+        std::fs::write(directory.path().join("large.swf"), [0; 1025]).unwrap();
+        let url = Url::from_file_path(directory.path().join("game.swf")).unwrap();
+        let new_backend = |interface| {
+            ExternalNavigatorBackend::new(
+                url.clone(),
+                TestFutureSpawner,
+                NavigatorOptions {
+                    confirm_larger_than: Some(1024),
+                    ..Default::default()
+                },
+                Rc::new(PlayingContent::DirectFile(url.clone())),
+                interface,
+            )
+        };
+
+        let declining = LargeFileInterface::default();
+        let backend = new_backend(declining.clone());
+        backend
+            .fetch(Request::get("small.swf".to_string()))
+            .await
+            .expect("small files are loaded without asking");
+        assert!(backend
+            .fetch(Request::get("large.swf".to_string()))
+            .await
+            .is_err());
+        assert_eq!(*declining.asked.lock().unwrap(), vec![1025]);
+
+        let allowing = LargeFileInterface {
+            allow: true,
+            ..Default::default()
+        };
+        let response = new_backend(allowing.clone())
+            .fetch(Request::get("large.swf".to_string()))
+            .await
+            .expect("large file the user allowed");
+        assert_eq!(response.body().await.expect("large body").len(), 1025);
+        assert_eq!(*allowing.asked.lock().unwrap(), vec![1025]);
+    }
+
+    /// Writes a bundle with SWFs that navigate between each other, and plays it.
+    /// The bundle information is followed by `extra_information`, to add more sections to it.
+    fn new_bundle_backend(
+        directory: &Path,
+        extra_information: &str,
+        interface: BundleNavigationInterface,
+    ) -> ExternalNavigatorBackend<TestFutureSpawner, BundleNavigationInterface> {
+        std::fs::write(
+            directory.join("ruffle-bundle.toml"),
+            format!(
+                "[bundle]\nname = \"Cool Game!\"\nurl = \"file:///game.swf\"\n{extra_information}"
+            ),
+        )
+        .unwrap();
+        std::fs::create_dir_all(directory.join("content/levels")).unwrap();
+        std::fs::write(directory.join("content/game.swf"), "game").unwrap();
+        std::fs::write(directory.join("content/levels/two.swf"), "level two").unwrap();
+
+        let bundle = Bundle::from_path(directory).unwrap();
+        let base_url = bundle.information().url.clone();
+        let bundle_url = Url::from_directory_path(directory).unwrap();
+        ExternalNavigatorBackend::new(
+            base_url,
+            TestFutureSpawner,
+            NavigatorOptions {
+                open_url_mode: OpenURLMode::Confirm,
+                network_policy: NetworkPolicy::Offline,
+                ..Default::default()
+            },
+            Rc::new(PlayingContent::Bundle(bundle_url, bundle)),
+            interface,
+        )
+    }
+
+    #[macro_rules_attribute::apply(async_test)]
+    async fn test_bundle_loads_swf_from_bundle() {
+        let directory = tempfile::tempdir().unwrap();
+        let backend = new_bundle_backend(directory.path(), "", Default::default());
+
+        let response = backend
+            .fetch(Request::get("levels/two.swf".to_string()))
+            .await
+            .expect("bundled fetch");
+        assert_eq!(response.url(), "file:///levels/two.swf");
+        assert_eq!(response.body().await.expect("bundled body"), b"level two");
+    }
+
+    #[test]
+    fn test_bundle_navigates_within_bundle() {
+        let directory = tempfile::tempdir().unwrap();
+        let interface = BundleNavigationInterface::default();
+        let backend = new_bundle_backend(directory.path(), "", interface.clone());
+
+        backend.navigate_to_url("levels/two.swf", "_self", None);
+        // Only SWFs can be played, there's nothing to open anything else with.
+        backend.navigate_to_url("readme.txt", "_blank", None);
+        assert_eq!(
+            *interface.0.lock().unwrap(),
+            vec![Url::parse("file:///levels/two.swf").unwrap()]
+        );
+    }
+
+    #[test]
+    fn test_navigates_to_swfs_in_place() {
+        let url = Url::parse("https://example.com/site/index.swf").unwrap();
+        let interface = InPlaceNavigationInterface::default();
+        let backend = ExternalNavigatorBackend::new(
+            url.clone(),
+            TestFutureSpawner,
+            NavigatorOptions {
+                open_swfs_in_place: true,
+                ..Default::default()
+            },
+            Rc::new(PlayingContent::DirectFile(url)),
+            interface.clone(),
+        );
+
+        backend.navigate_to_url("about.swf", "_self", None);
+        backend.navigate_to_url("/games/GAME.SWF", "", None);
+        // Other windows and anything but SWFs are left to the browser.
+        backend.navigate_to_url("popup.swf", "_blank", None);
+        backend.navigate_to_url("contact.html", "_self", None);
+        assert_eq!(
+            *interface.0.lock().unwrap(),
+            vec![
+                Url::parse("https://example.com/site/about.swf").unwrap(),
+                Url::parse("https://example.com/games/GAME.SWF").unwrap(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_bundle_navigates_to_pages() {
+        let directory = tempfile::tempdir().unwrap();
+        let interface = BundleNavigationInterface::default();
+        let backend = new_bundle_backend(
+            directory.path(),
+            "[pages]\n\"news.html\" = \"levels/two.swf\"\n\"https://example.com/\" = \"game.swf\"\n",
+            interface.clone(),
+        );
+
+        backend.navigate_to_url("news.html?id=3", "_self", None);
+        // The bundle is offline, but the website is played from the bundle instead.
+        backend.navigate_to_url("https://example.com/#top", "_self", None);
+        backend.navigate_to_url("https://example.com/contact.html", "_self", None);
+        assert_eq!(
+            *interface.0.lock().unwrap(),
+            vec![
+                Url::parse("file:///levels/two.swf?id=3").unwrap(),
+                Url::parse("file:///game.swf").unwrap(),
+            ]
+        );
+    }
 }
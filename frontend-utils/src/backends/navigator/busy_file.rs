@@ -0,0 +1,120 @@
+//! Reading local files that another program, such as an exporter, is still writing.
+
+use async_io::Timer;
+use std::io;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+/// How many times a file is read at most while it seems to still be written.
+const MAX_ATTEMPTS: u32 = 5;
+
+/// How long to wait before reading a busy file again, doubled after every attempt.
+const FIRST_DELAY: Duration = Duration::from_millis(100);
+
+/// Files modified more recently than this may still be written, and are checked once more.
+const RECENTLY_MODIFIED: Duration = Duration::from_secs(1);
+
+/// The size and modification time of a file, to tell whether it's still changing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FileState {
+    len: u64,
+    modified: Option<SystemTime>,
+}
+
+/// Reads the file at `path` with `read`, reading it again after a short while when it seems
+/// to still be written. That is when another program has it locked, when it changed while being
+/// read, or when it was just modified and hasn't been seen unchanged since.
+///
+/// This gives up after about a second and a half, returning the result of the last read.
+pub(super) async fn read_when_written(
+    path: &Path,
+    mut read: impl FnMut() -> io::Result<Vec<u8>>,
+) -> io::Result<Vec<u8>> {
+    let mut delay = FIRST_DELAY;
+    let mut previous = None;
+    let mut attempt = 1;
+    loop {
+        let result = read();
+        let state = std::fs::metadata(path).ok().map(|metadata| FileState {
+            len: metadata.len(),
+            modified: metadata.modified().ok(),
+        });
+        let busy = match &result {
+            Err(e) => is_locked(e),
+            Ok(contents) => match state {
+                Some(state) => {
+                    state.len != contents.len() as u64
+                        || (was_recently_modified(state) && previous != Some(state))
+                }
+                None => false,
+            },
+        };
+        if !busy || attempt == MAX_ATTEMPTS {
+            return result;
+        }
+        tracing::debug!(
+            "{} seems to still be written, reading it again in {delay:?}",
+            path.display()
+        );
+        previous = state;
+        Timer::after(delay).await;
+        delay *= 2;
+        attempt += 1;
+    }
+}
+
+fn was_recently_modified(state: FileState) -> bool {
+    state
+        .modified
+        .and_then(|modified| modified.elapsed().ok())
+        .is_some_and(|elapsed| elapsed < RECENTLY_MODIFIED)
+}
+
+/// Whether a file couldn't be opened because another program has it open, which only
+/// prevents opening it on Windows.
+fn is_locked(error: &io::Error) -> bool {
+    // ERROR_SHARING_VIOLATION and ERROR_LOCK_VIOLATION.
+    cfg!(windows) && matches!(error.raw_os_error(), Some(32 | 33))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use tempfile::NamedTempFile;
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn new_files_are_read_until_unchanged() {
+        let file = NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), b"FWS").unwrap();
+        let reads = Cell::new(0);
+        let contents = read_when_written(file.path(), || {
+            reads.set(reads.get() + 1);
+            std::fs::read(file.path())
+        })
+        .await
+        .unwrap();
+        assert_eq!(contents, b"FWS");
+        assert_eq!(reads.get(), 2);
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn changing_files_are_read_again() {
+        let file = NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), b"FWS").unwrap();
+        let reads = Cell::new(0);
+        let contents = read_when_written(file.path(), || {
+            reads.set(reads.get() + 1);
+            let contents = std::fs::read(file.path());
+            if reads.get() == 1 {
+                // The rest of the file is written after the first read.
+                std::fs::write(file.path(), b"FWS\x0a").unwrap();
+            }
+            contents
+        })
+        .await
+        .unwrap();
+        assert_eq!(contents, b"FWS\x0a");
+        assert_eq!(reads.get(), 2);
+    }
+}
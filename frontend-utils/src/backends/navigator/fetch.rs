@@ -1,8 +1,143 @@
+use async_channel::{Receiver, Sender};
 use reqwest::Response as ReqwestResponse;
 use ruffle_core::backend::navigator::{OwnedFuture, SuccessResponse};
 use ruffle_core::loader::Error;
+use std::fmt;
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
 
+/// A shared count of the requests made by a navigator that haven't finished yet.
+///
+/// A request is only finished once its response has been read or dropped,
+/// so this also covers downloading the body of a response.
+#[derive(Clone, Debug, Default)]
+pub struct InFlightRequests(Arc<AtomicUsize>);
+
+impl InFlightRequests {
+    pub fn count(&self) -> usize {
+        self.0.load(Ordering::Acquire)
+    }
+
+    /// Counts a new request, until the returned value is dropped.
+    pub(super) fn start(&self) -> InFlightRequest {
+        self.0.fetch_add(1, Ordering::AcqRel);
+        InFlightRequest(self.0.clone())
+    }
+}
+
+/// A request counted by [`InFlightRequests`], which finishes when dropped.
+pub struct InFlightRequest(Arc<AtomicUsize>);
+
+impl Drop for InFlightRequest {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+/// Counts what a navigator loaded over its whole life, to summarize a movie's network use.
+///
+/// A request failed if it couldn't be made or got an error status. Bytes are counted as the
+/// movie reads the bodies of responses, including those of local files.
+#[derive(Clone, Debug, Default)]
+pub struct NetworkStats(Arc<NetworkCounters>);
+
+#[derive(Debug, Default)]
+struct NetworkCounters {
+    requests: AtomicU64,
+    failures: AtomicU64,
+    bytes: AtomicU64,
+    sockets: AtomicU64,
+}
+
+impl NetworkStats {
+    pub fn requests(&self) -> u64 {
+        self.0.requests.load(Ordering::Relaxed)
+    }
+
+    pub fn failures(&self) -> u64 {
+        self.0.failures.load(Ordering::Relaxed)
+    }
+
+    pub fn bytes(&self) -> u64 {
+        self.0.bytes.load(Ordering::Relaxed)
+    }
+
+    /// How many sockets were connected.
+    pub fn sockets(&self) -> u64 {
+        self.0.sockets.load(Ordering::Relaxed)
+    }
+
+    /// Counts a finished request, with the same outcome as given to the request log.
+    pub(super) fn add_request(&self, outcome: &Result<(u16, Option<u64>), String>) {
+        self.0.requests.fetch_add(1, Ordering::Relaxed);
+        // Local files have a status of 0.
+        let failed = match outcome {
+            Ok((status, _)) => *status != 0 && !(200..300).contains(status),
+            Err(_) => true,
+        };
+        if failed {
+            self.0.failures.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    fn add_bytes(&self, bytes: usize) {
+        self.0.bytes.fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+
+    pub(super) fn add_socket(&self) {
+        self.0.sockets.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+impl fmt::Display for NetworkStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} requests ({} failed), {} bytes loaded, {} sockets connected",
+            self.requests(),
+            self.failures(),
+            self.bytes(),
+            self.sockets()
+        )
+    }
+}
+
+/// Limits how many requests a navigator makes over the network at once, queuing the others.
+///
+/// A connection is only taken until the response arrives, not while its body is read.
+/// A movie may keep a response around while it waits for another request to finish,
+/// which would never happen if the first one still held a connection.
+#[derive(Clone, Debug)]
+pub(super) struct ConnectionLimit {
+    /// Holds a message for every connection in use, so that sending waits while they're all taken.
+    taken: Sender<()>,
+    released: Receiver<()>,
+}
+
+impl ConnectionLimit {
+    pub(super) fn new(max_connections: NonZeroUsize) -> Self {
+        let (taken, released) = async_channel::bounded(max_connections.get());
+        Self { taken, released }
+    }
+
+    /// Waits for a free connection, which is taken until the returned value is dropped.
+    pub(super) async fn acquire(&self) -> Connection {
+        // The channel can't be closed, as both of its ends are owned here.
+        let _ = self.taken.send(()).await;
+        Connection(self.released.clone())
+    }
+}
+
+/// A connection taken from a [`ConnectionLimit`], which is freed when dropped.
+pub(super) struct Connection(Receiver<()>);
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        let _ = self.0.try_recv();
+    }
+}
+
 pub enum ResponseBody {
     /// The response's body comes from a file.
     File(Result<Vec<u8>, std::io::Error>),
@@ -20,6 +155,8 @@ pub struct Response {
     pub response_body: ResponseBody,
     pub status: u16,
     pub redirected: bool,
+    pub in_flight: InFlightRequest,
+    pub stats: NetworkStats,
 }
 
 impl SuccessResponse for Response {
@@ -29,12 +166,17 @@ impl SuccessResponse for Response {
 
     #[allow(clippy::await_holding_lock)]
     fn body(self: Box<Self>) -> OwnedFuture<Vec<u8>, Error> {
+        let in_flight = self.in_flight;
+        let stats = self.stats;
         match self.response_body {
-            ResponseBody::File(file) => {
-                Box::pin(async move { file.map_err(|e| Error::FetchError(e.to_string())) })
-            }
+            ResponseBody::File(file) => Box::pin(async move {
+                let file = file.map_err(|e| Error::FetchError(e.to_string()))?;
+                stats.add_bytes(file.len());
+                Ok(file)
+            }),
             ResponseBody::Network(response) => Box::pin(async move {
-                Ok(response
+                let _in_flight = in_flight;
+                let body = response
                     .lock()
                     .expect("working lock during fetch body read")
                     .take()
@@ -42,7 +184,9 @@ impl SuccessResponse for Response {
                     .bytes()
                     .await
                     .map_err(|e| Error::FetchError(e.to_string()))?
-                    .to_vec())
+                    .to_vec();
+                stats.add_bytes(body.len());
+                Ok(body)
             }),
         }
     }
@@ -63,6 +207,9 @@ impl SuccessResponse for Response {
                     .as_mut()
                     .map(std::mem::take)
                     .map_err(|e| Error::FetchError(e.to_string()));
+                if let Ok(bytes) = &res {
+                    self.stats.add_bytes(bytes.len());
+                }
 
                 Box::pin(async move {
                     match res {
@@ -74,6 +221,7 @@ impl SuccessResponse for Response {
             }
             ResponseBody::Network(response) => {
                 let response = response.clone();
+                let stats = self.stats.clone();
                 Box::pin(async move {
                     let lock = response.try_lock();
                     if matches!(lock, Err(std::sync::TryLockError::WouldBlock)) {
@@ -91,7 +239,10 @@ impl SuccessResponse for Response {
                         .await;
 
                     match result {
-                        Ok(Some(bytes)) => Ok(Some(bytes.to_vec())),
+                        Ok(Some(bytes)) => {
+                            stats.add_bytes(bytes.len());
+                            Ok(Some(bytes.to_vec()))
+                        }
                         Ok(None) => Ok(None),
                         Err(e) => Err(Error::FetchError(e.to_string())),
                     }
@@ -111,3 +262,27 @@ impl SuccessResponse for Response {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::FutureExt;
+
+    #[test]
+    fn connection_limit_queues_requests() {
+        let limit = ConnectionLimit::new(NonZeroUsize::new(2).expect("2 isn't zero"));
+        let first = limit
+            .acquire()
+            .now_or_never()
+            .expect("First connection is free");
+        let _second = limit
+            .acquire()
+            .now_or_never()
+            .expect("Second connection is free");
+
+        let mut third = Box::pin(limit.acquire());
+        assert!((&mut third).now_or_never().is_none());
+        drop(first);
+        assert!(third.now_or_never().is_some());
+    }
+}
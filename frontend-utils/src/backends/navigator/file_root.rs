@@ -0,0 +1,66 @@
+//! Confining the local files content may load to a single directory.
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// A directory which content may load local files from, refusing every file outside of it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileRoot(PathBuf);
+
+impl FileRoot {
+    pub fn new(directory: &Path) -> io::Result<Self> {
+        let directory = directory.canonicalize()?;
+        if !directory.is_dir() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{} is not a directory", directory.display()),
+            ));
+        }
+        Ok(Self(directory))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.0
+    }
+
+    /// Whether the file at `path` is inside of this directory, once `..` and symbolic links
+    /// are resolved. A file that doesn't exist is judged by the directory it would be in.
+    pub fn contains(&self, path: &Path) -> bool {
+        let resolved = match path.canonicalize() {
+            Ok(path) => path,
+            Err(_) => match (path.parent().map(Path::canonicalize), path.file_name()) {
+                (Some(Ok(parent)), Some(name)) => parent.join(name),
+                _ => return false,
+            },
+        };
+        resolved.starts_with(&self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_contains_files_inside() {
+        let directory = tempfile::tempdir().unwrap();
+        let root_path = directory.path().join("root");
+        std::fs::create_dir_all(root_path.join("levels")).unwrap();
+        std::fs::write(root_path.join("game.swf"), b"FWS").unwrap();
+        std::fs::write(directory.path().join("secret.txt"), b"").unwrap();
+        let root = FileRoot::new(&root_path).unwrap();
+
+        assert!(root.contains(&root_path.join("game.swf")));
+        assert!(root.contains(&root_path.join("levels").join("missing.swf")));
+        assert!(!root.contains(&directory.path().join("secret.txt")));
+        assert!(!root.contains(&root_path.join("..").join("secret.txt")));
+        assert!(!root.contains(
+            &root_path
+                .join("missing")
+                .join("..")
+                .join("..")
+                .join("secret.txt")
+        ));
+        assert!(FileRoot::new(&root_path.join("game.swf")).is_err());
+    }
+}
@@ -0,0 +1,111 @@
+use url::Url;
+
+/// Limits which hosts content may reach over the network.
+/// Local files are never affected by this, unlike files on another host.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum NetworkPolicy {
+    /// Content may reach any host.
+    #[default]
+    Unrestricted,
+
+    /// Content may only reach the given hosts.
+    /// A host starting with `*.` also matches every subdomain, such as `*.example.com`.
+    AllowedHosts(Vec<String>),
+
+    /// Content may not use the network at all.
+    Offline,
+}
+
+impl NetworkPolicy {
+    pub fn is_restricted(&self) -> bool {
+        !matches!(self, NetworkPolicy::Unrestricted)
+    }
+
+    pub fn allows_host(&self, host: &str) -> bool {
+        match self {
+            NetworkPolicy::Unrestricted => true,
+            NetworkPolicy::AllowedHosts(hosts) => {
+                hosts.iter().any(|allowed| host_matches(allowed, host))
+            }
+            NetworkPolicy::Offline => false,
+        }
+    }
+
+    /// Whether content may reach the given url. Local files are always allowed.
+    ///
+    /// A `file:` url with a host other than `localhost` is a network share, such as a UNC path
+    /// on Windows, so it's treated like any other url of that host.
+    pub fn allows_url(&self, url: &Url) -> bool {
+        !self.is_restricted()
+            || is_local_file(url)
+            || url.host_str().is_some_and(|host| self.allows_host(host))
+    }
+}
+
+fn is_local_file(url: &Url) -> bool {
+    url.scheme() == "file"
+        && url
+            .host_str()
+            .is_none_or(|host| host.eq_ignore_ascii_case("localhost"))
+}
+
+fn host_matches(allowed: &str, host: &str) -> bool {
+    match allowed.strip_prefix("*.") {
+        Some(domain) => {
+            host.eq_ignore_ascii_case(domain)
+                || host
+                    .len()
+                    .checked_sub(domain.len() + 1)
+                    .and_then(|start| host.get(start..))
+                    .is_some_and(|suffix| {
+                        suffix.starts_with('.') && suffix[1..].eq_ignore_ascii_case(domain)
+                    })
+        }
+        None => host.eq_ignore_ascii_case(allowed),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::backends::navigator::NetworkPolicy;
+    use url::Url;
+
+    #[test]
+    fn unrestricted_allows_everything() {
+        let policy = NetworkPolicy::Unrestricted;
+        assert!(policy.allows_url(&Url::parse("https://example.com/").unwrap()));
+        assert!(policy.allows_url(&Url::parse("mailto:someone@example.com").unwrap()));
+    }
+
+    #[test]
+    fn offline_only_allows_files() {
+        let policy = NetworkPolicy::Offline;
+        assert!(policy.allows_url(&Url::parse("file:///game.swf").unwrap()));
+        assert!(policy.allows_url(&Url::parse("file://localhost/game.swf").unwrap()));
+        assert!(!policy.allows_url(&Url::parse("https://example.com/").unwrap()));
+        assert!(!policy.allows_host("localhost"));
+    }
+
+    #[test]
+    fn files_on_other_hosts_are_network_access() {
+        let share = Url::parse("file://remote-host/share/game.swf").unwrap();
+        assert!(!NetworkPolicy::Offline.allows_url(&share));
+        assert!(!NetworkPolicy::AllowedHosts(vec!["example.com".to_string()]).allows_url(&share));
+        assert!(NetworkPolicy::AllowedHosts(vec!["remote-host".to_string()]).allows_url(&share));
+        assert!(NetworkPolicy::Unrestricted.allows_url(&share));
+    }
+
+    #[test]
+    fn allowed_hosts() {
+        let policy = NetworkPolicy::AllowedHosts(vec![
+            "example.com".to_string(),
+            "*.cdn.example.org".to_string(),
+        ]);
+        assert!(policy.allows_url(&Url::parse("https://EXAMPLE.com/data.xml").unwrap()));
+        assert!(!policy.allows_url(&Url::parse("https://www.example.com/").unwrap()));
+        assert!(policy.allows_host("cdn.example.org"));
+        assert!(policy.allows_host("a.b.cdn.example.org"));
+        assert!(!policy.allows_host("evilcdn.example.org"));
+        assert!(!policy.allows_url(&Url::parse("mailto:someone@example.com").unwrap()));
+    }
+}
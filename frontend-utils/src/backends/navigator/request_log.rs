@@ -0,0 +1,103 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use url::Url;
+
+/// How many requests are logged in each `WINDOW` before the rest are only counted.
+const MAX_LOGGED_PER_WINDOW: usize = 20;
+
+const WINDOW: Duration = Duration::from_secs(1);
+
+/// Logs the requests made by a navigator, to help find out why assets fail to load.
+///
+/// Every request is logged with where it was resolved from, so that bad relative paths stand out.
+/// Movies with lots of assets would flood the log, so once too many requests were logged
+/// in a short time, the rest are only counted and summarized later.
+#[derive(Clone, Default)]
+pub struct RequestLog(Arc<Mutex<RequestLogState>>);
+
+#[derive(Default)]
+struct RequestLogState {
+    window_start: Option<Instant>,
+    logged: usize,
+    suppressed: usize,
+}
+
+impl RequestLog {
+    /// Logs a finished request. `outcome` is either the status and size of the response,
+    /// or why it failed.
+    pub fn log(
+        &self,
+        method: &str,
+        requested: &str,
+        base_url: &Url,
+        resolved: &Url,
+        outcome: Result<(u16, Option<u64>), String>,
+    ) {
+        let Some(suppressed) = self.admit(Instant::now()) else {
+            return;
+        };
+        if suppressed > 0 {
+            tracing::info!(
+                target: "ruffle_network",
+                "{suppressed} more requests were made, but weren't logged"
+            );
+        }
+
+        let resolution = if requested == resolved.as_str() {
+            String::new()
+        } else {
+            format!(" (requested as {requested:?} relative to {base_url})")
+        };
+        match outcome {
+            Ok((status, Some(size))) => tracing::info!(
+                target: "ruffle_network",
+                "{method} {resolved}{resolution}: {status}, {size} bytes"
+            ),
+            Ok((status, None)) => tracing::info!(
+                target: "ruffle_network",
+                "{method} {resolved}{resolution}: {status}, unknown size"
+            ),
+            Err(error) => tracing::warn!(
+                target: "ruffle_network",
+                "{method} {resolved}{resolution}: failed, {error}"
+            ),
+        }
+    }
+
+    /// Whether a request made at `now` should be logged. If so, this returns how many requests
+    /// weren't logged since the last one that was.
+    fn admit(&self, now: Instant) -> Option<usize> {
+        let mut state = self.0.lock().expect("Request log lock must be available");
+        let in_window = state
+            .window_start
+            .is_some_and(|start| now.duration_since(start) < WINDOW);
+        if !in_window {
+            state.window_start = Some(now);
+            state.logged = 0;
+        }
+        if state.logged >= MAX_LOGGED_PER_WINDOW {
+            state.suppressed += 1;
+            return None;
+        }
+        state.logged += 1;
+        Some(std::mem::take(&mut state.suppressed))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn requests_are_throttled() {
+        let log = RequestLog::default();
+        let start = Instant::now();
+        for _ in 0..MAX_LOGGED_PER_WINDOW {
+            assert_eq!(log.admit(start), Some(0));
+        }
+        assert_eq!(log.admit(start), None);
+        assert_eq!(log.admit(start + WINDOW / 2), None);
+        assert_eq!(log.admit(start + WINDOW), Some(2));
+        assert_eq!(log.admit(start + WINDOW), Some(0));
+    }
+}
@@ -3,8 +3,12 @@ use crate::bundle::info::{
 };
 use crate::bundle::source::BundleSource;
 use crate::parse::ParseWarning;
+use ruffle_core::tag_utils::SwfMovie;
+use std::io::ErrorKind;
 use std::path::Path;
+use url::Url;
 
+pub mod create;
 pub mod info;
 pub mod source;
 
@@ -23,6 +27,19 @@ pub enum BundleError {
     BundleDoesntExist,
 }
 
+/// A problem with a bundle that opens, but won't play as intended.
+#[derive(Debug, thiserror::Error)]
+pub enum BundleValidationError {
+    #[error("Couldn't read {0} from the bundle: {1}")]
+    MissingContent(Url, std::io::Error),
+
+    #[error("{0} isn't a valid SWF: {1}")]
+    InvalidSwf(Url, ruffle_core::tag_utils::Error),
+
+    #[error("Couldn't read cursor image {0}: {1}")]
+    MissingCursorImage(String, std::io::Error),
+}
+
 pub struct Bundle {
     source: BundleSource,
     information: BundleInformation,
@@ -61,6 +78,59 @@ impl Bundle {
     pub fn information(&self) -> &BundleInformation {
         &self.information
     }
+
+    /// Reads a file from the content directory of the bundle, given its `file:///` URL.
+    pub fn read_content_url(&self, url: &Url) -> Result<Vec<u8>, std::io::Error> {
+        if url.scheme() != "file" {
+            return Err(ErrorKind::NotFound.into());
+        }
+        let mut path = String::new();
+        if let Some(segments) = url.path_segments() {
+            for segment in segments {
+                path.push('/');
+                path.push_str(
+                    urlencoding::decode(segment)
+                        .map_err(std::io::Error::other)?
+                        .as_ref(),
+                );
+            }
+        }
+        self.source.read_content(&path)
+    }
+
+    /// Checks that the files the bundle refers to exist and can be used,
+    /// beyond what's needed to open the bundle.
+    ///
+    /// Content outside of the bundle, such as on the internet, isn't checked.
+    pub fn validate(&self) -> Vec<BundleValidationError> {
+        let mut errors = vec![];
+
+        let page_swfs = self.information.pages.iter().map(|page| &page.swf);
+        for url in std::iter::once(&self.information.url).chain(page_swfs) {
+            if url.scheme() != "file" {
+                continue;
+            }
+            match self.read_content_url(url) {
+                Ok(data) => {
+                    if let Err(e) = SwfMovie::from_data(&data, url.to_string(), None) {
+                        errors.push(BundleValidationError::InvalidSwf(url.clone(), e));
+                    }
+                }
+                Err(e) => errors.push(BundleValidationError::MissingContent(url.clone(), e)),
+            }
+        }
+
+        if let Some(cursor) = &self.information.cursor {
+            if let Err(e) = self.source.read_file(&cursor.image) {
+                errors.push(BundleValidationError::MissingCursorImage(
+                    cursor.image.clone(),
+                    e,
+                ));
+            }
+        }
+
+        errors
+    }
 }
 
 #[cfg(test)]
@@ -69,8 +139,9 @@ mod tests {
         BundleInformation, BundleInformationParseError, BUNDLE_INFORMATION_FILENAME,
     };
     use crate::bundle::source::BundleSourceError;
-    use crate::bundle::{Bundle, BundleError};
+    use crate::bundle::{Bundle, BundleError, BundleValidationError};
     use crate::parse::ParseWarning;
+    use ruffle_core::swf::{write_swf, Header};
     use tempfile::tempdir;
     use url::Url;
 
@@ -148,10 +219,84 @@ mod tests {
         assert_eq!(
             BundleInformation {
                 name: "Cool Game!".to_string(),
-                url: Url::parse("file:///game.swf").unwrap()
+                url: Url::parse("file:///game.swf").unwrap(),
+                cursor: None,
+                splash: None,
+                player: Default::default(),
+                network: Default::default(),
+                pages: vec![],
             },
             result.information
         );
         assert_eq!(Vec::<ParseWarning>::new(), result.warnings);
     }
+
+    fn write_bundle(directory: &std::path::Path, swf: Option<&[u8]>) {
+        std::fs::write(
+            directory.join(BUNDLE_INFORMATION_FILENAME),
+            r#"
+                [bundle]
+                name = "Cool Game!"
+                url = "file:///game.swf"
+                "#,
+        )
+        .unwrap();
+        if let Some(swf) = swf {
+            std::fs::create_dir(directory.join("content")).unwrap();
+            std::fs::write(directory.join("content/game.swf"), swf).unwrap();
+        }
+    }
+
+    #[test]
+    fn validate_valid() {
+        let tmp_dir = tempdir().unwrap();
+        let mut swf = vec![];
+        write_swf(&Header::default_with_swf_version(10), &[], &mut swf).unwrap();
+        write_bundle(tmp_dir.path(), Some(&swf));
+        let errors = Bundle::from_path(tmp_dir.path()).unwrap().validate();
+        drop(tmp_dir);
+        assert!(errors.is_empty(), "{errors:?}");
+    }
+
+    #[test]
+    fn validate_missing_swf() {
+        let tmp_dir = tempdir().unwrap();
+        write_bundle(tmp_dir.path(), None);
+        let errors = Bundle::from_path(tmp_dir.path()).unwrap().validate();
+        drop(tmp_dir);
+        assert!(matches!(
+            errors.as_slice(),
+            [BundleValidationError::MissingContent(..)]
+        ));
+    }
+
+    #[test]
+    fn validate_invalid_swf() {
+        let tmp_dir = tempdir().unwrap();
+        write_bundle(tmp_dir.path(), Some(b"not a swf"));
+        let errors = Bundle::from_path(tmp_dir.path()).unwrap().validate();
+        drop(tmp_dir);
+        assert!(matches!(
+            errors.as_slice(),
+            [BundleValidationError::InvalidSwf(..)]
+        ));
+    }
+
+    #[test]
+    fn validate_missing_page() {
+        let tmp_dir = tempdir().unwrap();
+        let mut swf = vec![];
+        write_swf(&Header::default_with_swf_version(10), &[], &mut swf).unwrap();
+        write_bundle(tmp_dir.path(), Some(&swf));
+        let information = tmp_dir.path().join(BUNDLE_INFORMATION_FILENAME);
+        let mut text = std::fs::read_to_string(&information).unwrap();
+        text.push_str("[pages]\n\"news.html\" = \"news.swf\"\n");
+        std::fs::write(&information, text).unwrap();
+        let errors = Bundle::from_path(tmp_dir.path()).unwrap().validate();
+        drop(tmp_dir);
+        assert!(matches!(
+            errors.as_slice(),
+            [BundleValidationError::MissingContent(url, _)] if url.as_str() == "file:///news.swf"
+        ));
+    }
 }
@@ -0,0 +1,143 @@
+use crate::bundle::info::{BundleInformation, BUNDLE_INFORMATION_FILENAME};
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use url::Url;
+
+#[derive(Debug, thiserror::Error)]
+pub enum BundleCreationError {
+    #[error("{0} already exists and isn't empty")]
+    OutputNotEmpty(PathBuf),
+
+    #[error("{0} isn't a file")]
+    InvalidSwfPath(PathBuf),
+
+    #[error("IO error creating bundle")]
+    Io(#[from] std::io::Error),
+}
+
+/// Creates a bundle directory at `output` which plays the SWF at `swf`, copying it into the content of the bundle.
+///
+/// The bundle is named after the SWF, unless a name is given.
+/// Returns the information written to the new bundle.
+pub fn create_bundle(
+    swf: &Path,
+    output: &Path,
+    name: Option<String>,
+) -> Result<BundleInformation, BundleCreationError> {
+    if output.exists() && output.read_dir()?.next().is_some() {
+        return Err(BundleCreationError::OutputNotEmpty(output.to_owned()));
+    }
+    let file_name = swf
+        .file_name()
+        .and_then(OsStr::to_str)
+        .filter(|_| swf.is_file())
+        .ok_or_else(|| BundleCreationError::InvalidSwfPath(swf.to_owned()))?;
+
+    let content = output.join("content");
+    std::fs::create_dir_all(&content)?;
+    std::fs::copy(swf, content.join(file_name))?;
+
+    // The content directory is exposed as `file:///`.
+    let mut url = Url::parse("file:///").expect("URL must be valid");
+    url.path_segments_mut()
+        .expect("file URLs have a path")
+        .pop_if_empty()
+        .push(file_name);
+    let information = BundleInformation {
+        name: name.unwrap_or_else(|| {
+            Path::new(file_name)
+                .file_stem()
+                .and_then(OsStr::to_str)
+                .unwrap_or(file_name)
+                .to_owned()
+        }),
+        url,
+        cursor: None,
+        splash: None,
+        player: Default::default(),
+        network: Default::default(),
+        pages: vec![],
+    };
+    std::fs::write(
+        output.join(BUNDLE_INFORMATION_FILENAME),
+        information.serialize(),
+    )?;
+    Ok(information)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bundle::create::{create_bundle, BundleCreationError};
+    use crate::bundle::Bundle;
+    use tempfile::tempdir;
+    use url::Url;
+
+    #[test]
+    fn create_opens_as_bundle() {
+        let tmp_dir = tempdir().unwrap();
+        let swf = tmp_dir.path().join("my game.swf");
+        std::fs::write(&swf, "swf").unwrap();
+        let output = tmp_dir.path().join("bundle");
+
+        let information = create_bundle(&swf, &output, None).unwrap();
+        let bundle = Bundle::from_path(&output).unwrap();
+        let content = bundle.read_content_url(&bundle.information().url);
+        drop(tmp_dir);
+
+        assert_eq!("my game", information.name);
+        assert_eq!(
+            Url::parse("file:///my%20game.swf").unwrap(),
+            information.url
+        );
+        assert_eq!(&information, bundle.information());
+        assert_eq!(b"swf".to_vec(), content.unwrap());
+    }
+
+    #[test]
+    fn create_with_name() {
+        let tmp_dir = tempdir().unwrap();
+        let swf = tmp_dir.path().join("game.swf");
+        std::fs::write(&swf, "swf").unwrap();
+
+        let information = create_bundle(
+            &swf,
+            &tmp_dir.path().join("bundle"),
+            Some("Cool Game!".into()),
+        );
+        drop(tmp_dir);
+
+        assert_eq!("Cool Game!", information.unwrap().name);
+    }
+
+    #[test]
+    fn create_into_non_empty_directory() {
+        let tmp_dir = tempdir().unwrap();
+        let swf = tmp_dir.path().join("game.swf");
+        std::fs::write(&swf, "swf").unwrap();
+
+        let result = create_bundle(&swf, tmp_dir.path(), None);
+        drop(tmp_dir);
+
+        assert!(matches!(
+            result,
+            Err(BundleCreationError::OutputNotEmpty(_))
+        ));
+    }
+
+    #[test]
+    fn create_from_missing_swf() {
+        let tmp_dir = tempdir().unwrap();
+
+        let result = create_bundle(
+            &tmp_dir.path().join("game.swf"),
+            &tmp_dir.path().join("bundle"),
+            None,
+        );
+        drop(tmp_dir);
+
+        assert!(matches!(
+            result,
+            Err(BundleCreationError::InvalidSwfPath(_))
+        ));
+    }
+}
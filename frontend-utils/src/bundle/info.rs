@@ -1,5 +1,8 @@
-use crate::parse::{DocumentHolder, ParseContext, ParseDetails, ReadExt};
-use toml_edit::DocumentMut;
+use crate::backends::navigator::NetworkPolicy;
+use crate::parse::{DocumentHolder, ItemExt, ParseContext, ParseDetails, ReadExt};
+use crate::player_options::PlayerOptions;
+use ruffle_core::StageAlign;
+use toml_edit::{value, Array, DocumentMut, Item, Table};
 use url::Url;
 
 pub const BUNDLE_INFORMATION_FILENAME: &str = "ruffle-bundle.toml";
@@ -23,6 +26,41 @@ pub enum BundleInformationParseError {
 pub struct BundleInformation {
     pub name: String,
     pub url: Url,
+    pub cursor: Option<BundleCursor>,
+
+    /// Path to an image shown while the content loads, relative to the root of the bundle.
+    pub splash: Option<String>,
+
+    /// Default player options requested by the bundle.
+    /// Any option the user sets explicitly takes precedence over these.
+    pub player: PlayerOptions,
+
+    /// Which hosts the content may reach, unless the user allows more.
+    pub network: NetworkPolicy,
+
+    /// The pages of a Flash website made of several SWFs, which are played in place of the
+    /// current SWF when the content links to them.
+    pub pages: Vec<BundlePage>,
+}
+
+/// A page of a Flash website, played as one of the SWFs of the bundle.
+#[derive(Debug, PartialEq)]
+pub struct BundlePage {
+    /// Where the content links to, such as `file:///news.html` or `https://example.com/news.html`.
+    /// Links to it with any query string or fragment lead to the page as well.
+    pub link: Url,
+
+    /// The SWF played for the page, within the bundle.
+    pub swf: Url,
+}
+
+/// A cursor image to use in place of the default arrow cursor.
+#[derive(Debug, PartialEq)]
+pub struct BundleCursor {
+    /// Path to the image, relative to the root of the bundle.
+    pub image: String,
+    pub hotspot_x: u32,
+    pub hotspot_y: u32,
 }
 
 impl BundleInformation {
@@ -33,7 +71,7 @@ impl BundleInformation {
 
         let mut cx = ParseContext::default();
 
-        let result = document
+        let mut result = document
             .get_table_like(&mut cx, "bundle", |cx, bundle| {
                 let Some(name) = bundle.parse_from_str(cx, "name") else {
                     return Err(BundleInformationParseError::InvalidName);
@@ -41,21 +79,246 @@ impl BundleInformation {
                 let Some(url) = bundle.parse_from_str(cx, "url") else {
                     return Err(BundleInformationParseError::InvalidUrl);
                 };
-                Ok(BundleInformation { name, url })
+                Ok(BundleInformation {
+                    name,
+                    url,
+                    cursor: None,
+                    splash: None,
+                    player: Default::default(),
+                    network: Default::default(),
+                    pages: vec![],
+                })
             })
             .unwrap_or(Err(BundleInformationParseError::InvalidBundleSection))?;
 
+        result.cursor = document
+            .get_table_like(&mut cx, "cursor", |cx, cursor| {
+                let image = cursor.parse_from_str(cx, "image")?;
+                let mut read_coordinate = |key| {
+                    cursor
+                        .get_integer(cx, key)
+                        .and_then(|value| u32::try_from(value).ok())
+                        .unwrap_or_default()
+                };
+                Some(BundleCursor {
+                    image,
+                    hotspot_x: read_coordinate("hotspot_x"),
+                    hotspot_y: read_coordinate("hotspot_y"),
+                })
+            })
+            .flatten();
+
+        result.splash = document
+            .get_table_like(&mut cx, "splash", |cx, splash| {
+                splash.parse_from_str(cx, "image")
+            })
+            .flatten();
+
+        result.player = document
+            .get_table_like(&mut cx, "player", |cx, player| PlayerOptions {
+                base: player.parse_from_str(cx, "base"),
+                quality: player.parse_from_str(cx, "quality"),
+                align: player.parse_from_str(cx, "align"),
+                force_align: player.get_bool(cx, "force_align"),
+                scale: player.parse_from_str(cx, "scale"),
+                force_scale: player.get_bool(cx, "force_scale"),
+                upgrade_to_https: player.get_bool(cx, "upgrade_to_https"),
+                spoof_url: player.parse_from_str(cx, "spoof_url"),
+                frame_rate: player.get_float(cx, "frame_rate"),
+                ..Default::default()
+            })
+            .unwrap_or_default();
+
+        result.network = document
+            .get_table_like(&mut cx, "network", |cx, network| {
+                let allowed_hosts = network
+                    .get_array(cx, "allowed_hosts", |cx, hosts| {
+                        hosts
+                            .iter()
+                            .filter_map(|host| {
+                                if host.as_str().is_none() {
+                                    cx.unexpected_type("string", host.type_name());
+                                }
+                                host.as_str().map(str::to_owned)
+                            })
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                match network.parse_from_str::<String>(cx, "policy").as_deref() {
+                    None | Some("unrestricted") => NetworkPolicy::Unrestricted,
+                    Some("allowlist") => NetworkPolicy::AllowedHosts(allowed_hosts),
+                    Some("offline") => NetworkPolicy::Offline,
+                    Some(policy) => {
+                        cx.push_key("policy");
+                        cx.unsupported_value(policy.to_owned());
+                        cx.pop_key();
+                        // Don't let the movie go online just because we don't understand
+                        // what the bundle asked for.
+                        NetworkPolicy::Offline
+                    }
+                }
+            })
+            .unwrap_or_default();
+
+        result.pages = document
+            .get_table_like(&mut cx, "pages", |cx, pages| {
+                let mut result = vec![];
+                for (link, swf) in pages.iter() {
+                    cx.push_key(link);
+                    let page = swf.as_str_or_warn(cx).and_then(|swf| {
+                        let page = resolve_content_url(link)
+                            .zip(resolve_content_url(swf).filter(|swf| swf.scheme() == "file"))
+                            .map(|(link, swf)| BundlePage { link, swf });
+                        if page.is_none() {
+                            cx.unsupported_value(swf.to_owned());
+                        }
+                        page
+                    });
+                    result.extend(page);
+                    cx.pop_key();
+                }
+                result
+            })
+            .unwrap_or_default();
+
         Ok(ParseDetails {
             warnings: cx.warnings,
             result: DocumentHolder::new(result, document),
         })
     }
+
+    /// The SWF to play when the content links to `url`, if it's one of the [pages](Self::pages)
+    /// of the bundle. The query string of the link is passed on to the SWF, unless it has its own.
+    pub fn page_for(&self, url: &Url) -> Option<Url> {
+        let page = self
+            .pages
+            .iter()
+            .find(|page| without_query(&page.link) == without_query(url))?;
+        let mut swf = page.swf.clone();
+        if swf.query().is_none() {
+            swf.set_query(url.query());
+        }
+        Some(swf)
+    }
+
+    /// Writes the information as a `ruffle-bundle.toml` document, which parses back into the same information.
+    pub fn serialize(&self) -> String {
+        let mut document = DocumentMut::new();
+
+        let mut bundle = Table::new();
+        bundle["name"] = value(&self.name);
+        bundle["url"] = value(self.url.as_str());
+        document["bundle"] = Item::Table(bundle);
+
+        if let Some(cursor) = &self.cursor {
+            let mut table = Table::new();
+            table["image"] = value(&cursor.image);
+            table["hotspot_x"] = value(i64::from(cursor.hotspot_x));
+            table["hotspot_y"] = value(i64::from(cursor.hotspot_y));
+            document["cursor"] = Item::Table(table);
+        }
+
+        if let Some(splash) = &self.splash {
+            let mut table = Table::new();
+            table["image"] = value(splash);
+            document["splash"] = Item::Table(table);
+        }
+
+        let mut player = Table::new();
+        if let Some(base) = &self.player.base {
+            player["base"] = value(base.as_str());
+        }
+        if let Some(quality) = self.player.quality {
+            player["quality"] = value(quality.to_string());
+        }
+        if let Some(align) = self.player.align {
+            player["align"] = value(align_to_string(align));
+        }
+        if let Some(force_align) = self.player.force_align {
+            player["force_align"] = value(force_align);
+        }
+        if let Some(scale) = self.player.scale {
+            player["scale"] = value(scale.to_string());
+        }
+        if let Some(force_scale) = self.player.force_scale {
+            player["force_scale"] = value(force_scale);
+        }
+        if let Some(upgrade_to_https) = self.player.upgrade_to_https {
+            player["upgrade_to_https"] = value(upgrade_to_https);
+        }
+        if let Some(spoof_url) = &self.player.spoof_url {
+            player["spoof_url"] = value(spoof_url.as_str());
+        }
+        if let Some(frame_rate) = self.player.frame_rate {
+            player["frame_rate"] = value(frame_rate);
+        }
+        if !player.is_empty() {
+            document["player"] = Item::Table(player);
+        }
+
+        match &self.network {
+            NetworkPolicy::Unrestricted => {}
+            NetworkPolicy::AllowedHosts(hosts) => {
+                let mut network = Table::new();
+                network["policy"] = value("allowlist");
+                network["allowed_hosts"] = value(Array::from_iter(hosts));
+                document["network"] = Item::Table(network);
+            }
+            NetworkPolicy::Offline => {
+                let mut network = Table::new();
+                network["policy"] = value("offline");
+                document["network"] = Item::Table(network);
+            }
+        }
+
+        if !self.pages.is_empty() {
+            let mut pages = Table::new();
+            for page in &self.pages {
+                pages[page.link.as_str()] = value(page.swf.as_str());
+            }
+            document["pages"] = Item::Table(pages);
+        }
+
+        document.to_string()
+    }
+}
+
+/// Parses a URL of the bundle, where paths are relative to its content directory at `file:///`.
+fn resolve_content_url(value: &str) -> Option<Url> {
+    Url::parse("file:///").ok()?.join(value).ok()
+}
+
+fn without_query(url: &Url) -> Url {
+    let mut url = url.clone();
+    url.set_query(None);
+    url.set_fragment(None);
+    url
+}
+
+/// Writes an alignment the way `StageAlign::from_str` reads it, such as `"TL"`.
+fn align_to_string(align: StageAlign) -> String {
+    [
+        (StageAlign::TOP, 'T'),
+        (StageAlign::BOTTOM, 'B'),
+        (StageAlign::LEFT, 'L'),
+        (StageAlign::RIGHT, 'R'),
+    ]
+    .into_iter()
+    .filter(|(flag, _)| align.contains(*flag))
+    .map(|(_, c)| c)
+    .collect()
 }
 
 #[cfg(test)]
 mod test {
-    use crate::bundle::info::{BundleInformation, BundleInformationParseError};
+    use crate::backends::navigator::NetworkPolicy;
+    use crate::bundle::info::{
+        BundleCursor, BundleInformation, BundleInformationParseError, BundlePage,
+    };
     use crate::parse::ParseWarning;
+    use crate::player_options::PlayerOptions;
+    use ruffle_core::{StageAlign, StageScaleMode};
+    use ruffle_render::quality::StageQuality;
     use url::Url;
 
     fn read(
@@ -157,9 +420,371 @@ mod test {
                 BundleInformation {
                     name: "Cool Game!".to_string(),
                     url: Url::parse("file:///game.swf").unwrap(),
+                    cursor: None,
+                    splash: None,
+                    player: Default::default(),
+                    network: Default::default(),
+                    pages: vec![],
+                },
+                vec![]
+            ))
+        )
+    }
+
+    #[test]
+    fn cursor() {
+        assert_eq!(
+            read(
+                r#"
+                [bundle]
+                name = "Cool Game!"
+                url = "file:///game.swf"
+
+                [cursor]
+                image = "cursor.png"
+                hotspot_x = 4
+                hotspot_y = 2
+                "#
+            ),
+            Ok((
+                BundleInformation {
+                    name: "Cool Game!".to_string(),
+                    url: Url::parse("file:///game.swf").unwrap(),
+                    cursor: Some(BundleCursor {
+                        image: "cursor.png".to_string(),
+                        hotspot_x: 4,
+                        hotspot_y: 2,
+                    }),
+                    splash: None,
+                    player: Default::default(),
+                    network: Default::default(),
+                    pages: vec![],
+                },
+                vec![]
+            ))
+        )
+    }
+
+    #[test]
+    fn cursor_without_image() {
+        assert_eq!(
+            read(
+                r#"
+                [bundle]
+                name = "Cool Game!"
+                url = "file:///game.swf"
+
+                [cursor]
+                hotspot_x = 4
+                "#
+            ),
+            Ok((
+                BundleInformation {
+                    name: "Cool Game!".to_string(),
+                    url: Url::parse("file:///game.swf").unwrap(),
+                    cursor: None,
+                    splash: None,
+                    player: Default::default(),
+                    network: Default::default(),
+                    pages: vec![],
+                },
+                vec![]
+            ))
+        )
+    }
+
+    #[test]
+    fn splash() {
+        assert_eq!(
+            read(
+                r#"
+                [bundle]
+                name = "Cool Game!"
+                url = "file:///game.swf"
+
+                [splash]
+                image = "loading.png"
+                "#
+            ),
+            Ok((
+                BundleInformation {
+                    name: "Cool Game!".to_string(),
+                    url: Url::parse("file:///game.swf").unwrap(),
+                    cursor: None,
+                    splash: Some("loading.png".to_string()),
+                    player: Default::default(),
+                    network: Default::default(),
+                    pages: vec![],
                 },
                 vec![]
             ))
         )
     }
+
+    #[test]
+    fn serialize_roundtrip() {
+        let information = BundleInformation {
+            name: "Cool \"Game\"!".to_string(),
+            url: Url::parse("file:///my%20game.swf").unwrap(),
+            cursor: Some(BundleCursor {
+                image: "cursor.png".to_string(),
+                hotspot_x: 4,
+                hotspot_y: 2,
+            }),
+            splash: None,
+            player: Default::default(),
+            network: Default::default(),
+            pages: vec![],
+        };
+        assert_eq!(
+            "[bundle]\nname = \"Cool \\\"Game\\\"!\"\nurl = \"file:///my%20game.swf\"\n\n[cursor]\nimage = \"cursor.png\"\nhotspot_x = 4\nhotspot_y = 2\n",
+            information.serialize()
+        );
+        assert_eq!(read(&information.serialize()), Ok((information, vec![])));
+    }
+
+    #[test]
+    fn player_options() {
+        assert_eq!(
+            read(
+                r#"
+                [bundle]
+                name = "Cool Game!"
+                url = "file:///game.swf"
+
+                [player]
+                quality = "low"
+                scale = "noScale"
+                align = "TL"
+                base = "https://example.com/game/"
+                frame_rate = 60.0
+                "#
+            ),
+            Ok((
+                BundleInformation {
+                    name: "Cool Game!".to_string(),
+                    url: Url::parse("file:///game.swf").unwrap(),
+                    cursor: None,
+                    splash: None,
+                    player: PlayerOptions {
+                        quality: Some(StageQuality::Low),
+                        scale: Some(StageScaleMode::NoScale),
+                        align: Some(StageAlign::TOP | StageAlign::LEFT),
+                        base: Some(Url::parse("https://example.com/game/").unwrap()),
+                        frame_rate: Some(60.0),
+                        ..Default::default()
+                    },
+                    network: Default::default(),
+                    pages: vec![],
+                },
+                vec![]
+            ))
+        )
+    }
+
+    #[test]
+    fn player_options_invalid_value() {
+        assert_eq!(
+            read(
+                r#"
+                [bundle]
+                name = "Cool Game!"
+                url = "file:///game.swf"
+
+                [player]
+                quality = "amazing"
+                force_scale = true
+                "#
+            ),
+            Ok((
+                BundleInformation {
+                    name: "Cool Game!".to_string(),
+                    url: Url::parse("file:///game.swf").unwrap(),
+                    cursor: None,
+                    splash: None,
+                    player: PlayerOptions {
+                        force_scale: Some(true),
+                        ..Default::default()
+                    },
+                    network: Default::default(),
+                    pages: vec![],
+                },
+                vec![ParseWarning::UnsupportedValue {
+                    value: "amazing".to_string(),
+                    path: "player.quality".to_string(),
+                }]
+            ))
+        )
+    }
+
+    #[test]
+    fn serialize_player_options_roundtrip() {
+        let information = BundleInformation {
+            name: "Cool Game!".to_string(),
+            url: Url::parse("file:///game.swf").unwrap(),
+            cursor: None,
+            splash: None,
+            player: PlayerOptions {
+                base: Some(Url::parse("https://example.com/game/").unwrap()),
+                quality: Some(StageQuality::High),
+                align: Some(StageAlign::BOTTOM | StageAlign::RIGHT),
+                force_align: Some(true),
+                scale: Some(StageScaleMode::ExactFit),
+                frame_rate: Some(24.0),
+                ..Default::default()
+            },
+            network: Default::default(),
+            pages: vec![],
+        };
+        assert_eq!(read(&information.serialize()), Ok((information, vec![])));
+    }
+    #[test]
+    fn network_policy() {
+        let (information, warnings) = read(
+            r#"
+            [bundle]
+            name = "Cool Game!"
+            url = "file:///game.swf"
+
+            [network]
+            policy = "allowlist"
+            allowed_hosts = ["example.com", 1234, "*.example.org"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            information.network,
+            NetworkPolicy::AllowedHosts(vec![
+                "example.com".to_string(),
+                "*.example.org".to_string()
+            ])
+        );
+        assert_eq!(
+            warnings,
+            vec![ParseWarning::UnexpectedType {
+                expected: "string",
+                actual: "integer",
+                path: "network.allowed_hosts".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn network_policy_invalid() {
+        let (information, warnings) = read(
+            r#"
+            [bundle]
+            name = "Cool Game!"
+            url = "file:///game.swf"
+
+            [network]
+            policy = "sometimes"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(information.network, NetworkPolicy::Offline);
+        assert_eq!(
+            warnings,
+            vec![ParseWarning::UnsupportedValue {
+                value: "sometimes".to_string(),
+                path: "network.policy".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn serialize_network_policy_roundtrip() {
+        for network in [
+            NetworkPolicy::Offline,
+            NetworkPolicy::AllowedHosts(vec!["example.com".to_string()]),
+        ] {
+            let information = BundleInformation {
+                name: "Cool Game!".to_string(),
+                url: Url::parse("file:///game.swf").unwrap(),
+                cursor: None,
+                splash: None,
+                player: Default::default(),
+                network,
+                pages: vec![],
+            };
+            assert_eq!(read(&information.serialize()), Ok((information, vec![])));
+        }
+    }
+
+    #[test]
+    fn pages() {
+        let (information, warnings) = read(
+            r#"
+            [bundle]
+            name = "Cool Site!"
+            url = "file:///home.swf"
+
+            [pages]
+            "index.html" = "home.swf"
+            "https://example.com/news.html" = "file:///news.swf"
+            "contact.html" = 1234
+            "links.html" = "https://example.com/links.swf"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            information.pages,
+            vec![
+                BundlePage {
+                    link: Url::parse("file:///index.html").unwrap(),
+                    swf: Url::parse("file:///home.swf").unwrap(),
+                },
+                BundlePage {
+                    link: Url::parse("https://example.com/news.html").unwrap(),
+                    swf: Url::parse("file:///news.swf").unwrap(),
+                },
+            ]
+        );
+        assert_eq!(
+            warnings,
+            vec![
+                ParseWarning::UnexpectedType {
+                    expected: "string",
+                    actual: "integer",
+                    path: "pages.contact.html".to_string(),
+                },
+                ParseWarning::UnsupportedValue {
+                    value: "https://example.com/links.swf".to_string(),
+                    path: "pages.links.html".to_string(),
+                },
+            ]
+        );
+
+        let page_for = |url| information.page_for(&Url::parse(url).unwrap());
+        assert_eq!(
+            page_for("https://example.com/news.html?day=1#top"),
+            Some(Url::parse("file:///news.swf?day=1").unwrap())
+        );
+        assert_eq!(
+            page_for("file:///index.html"),
+            Some(Url::parse("file:///home.swf").unwrap())
+        );
+        assert_eq!(page_for("https://example.com/index.html"), None);
+    }
+
+    #[test]
+    fn serialize_pages_roundtrip() {
+        let information = BundleInformation {
+            name: "Cool Site!".to_string(),
+            url: Url::parse("file:///home.swf").unwrap(),
+            cursor: None,
+            splash: None,
+            player: Default::default(),
+            network: Default::default(),
+            pages: vec![BundlePage {
+                link: Url::parse("https://example.com/news.html").unwrap(),
+                swf: Url::parse("file:///news.swf").unwrap(),
+            }],
+        };
+        assert_eq!(
+            "[bundle]\nname = \"Cool Site!\"\nurl = \"file:///home.swf\"\n\n[pages]\n\"https://example.com/news.html\" = \"file:///news.swf\"\n",
+            information.serialize()
+        );
+        assert_eq!(read(&information.serialize()), Ok((information, vec![])));
+    }
 }
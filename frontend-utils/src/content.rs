@@ -1,7 +1,7 @@
 use crate::bundle::Bundle;
 use std::fmt::{Debug, Formatter};
 use std::fs::File;
-use std::io::{ErrorKind, Read};
+use std::io::Read;
 use std::path::Path;
 use url::Url;
 
@@ -27,6 +27,14 @@ impl Debug for PlayingContent {
 }
 
 impl PlayingContent {
+    /// The URL that was opened, which is the bundle itself rather than its SWF for bundles.
+    pub fn url(&self) -> &Url {
+        match self {
+            PlayingContent::DirectFile(url) => url,
+            PlayingContent::Bundle(url, _) => url,
+        }
+    }
+
     pub fn initial_swf_url(&self) -> &Url {
         match self {
             PlayingContent::DirectFile(url) => url,
@@ -34,6 +42,21 @@ impl PlayingContent {
         }
     }
 
+    /// Whether the URL refers to a file inside of the bundle being played,
+    /// which can only be read through [`PlayingContent::get_local_file`].
+    pub fn is_bundled(&self, url: &Url) -> bool {
+        matches!(self, PlayingContent::Bundle(..)) && url.scheme() == "file"
+    }
+
+    /// The SWF to play when the content links to `url`, if the bundle being played declares it
+    /// as one of its pages.
+    pub fn page_for(&self, url: &Url) -> Option<Url> {
+        match self {
+            PlayingContent::DirectFile(_) => None,
+            PlayingContent::Bundle(_, bundle) => bundle.information().page_for(url),
+        }
+    }
+
     pub fn name(&self) -> String {
         match self {
             PlayingContent::DirectFile(url) => crate::url_to_readable_name(url).to_string(),
@@ -56,23 +79,7 @@ impl PlayingContent {
                 file.read_to_end(&mut result)?;
                 Ok(result)
             }
-            PlayingContent::Bundle(_, bundle) => {
-                if url.scheme() != "file" {
-                    return Err(ErrorKind::NotFound.into());
-                }
-                let mut path = String::new();
-                if let Some(segments) = url.path_segments() {
-                    for segment in segments {
-                        path.push('/');
-                        path.push_str(
-                            urlencoding::decode(segment)
-                                .map_err(std::io::Error::other)?
-                                .as_ref(),
-                        );
-                    }
-                }
-                bundle.source().read_content(&path)
-            }
+            PlayingContent::Bundle(_, bundle) => bundle.read_content_url(url),
         }
     }
 }
@@ -1,6 +1,7 @@
 pub mod bookmarks;
 pub mod bundle;
 pub mod parse;
+pub mod play_stats;
 pub mod recents;
 pub mod write;
 
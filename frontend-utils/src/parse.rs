@@ -2,7 +2,7 @@ use std::fmt;
 use std::fmt::Formatter;
 use std::ops::Deref;
 use std::str::FromStr;
-use toml_edit::{ArrayOfTables, DocumentMut, Item, Table, TableLike, TomlError};
+use toml_edit::{Array, ArrayOfTables, DocumentMut, Item, Table, TableLike, TomlError};
 
 /// A holder over values that may be read and potentially written back to disk.
 ///
@@ -206,6 +206,27 @@ pub trait ReadExt<'a> {
         result
     }
 
+    fn get_array<R>(
+        &'a self,
+        cx: &mut ParseContext<'a>,
+        key: &'static str,
+        fun: impl FnOnce(&mut ParseContext<'a>, &'a Array) -> R,
+    ) -> Option<R> {
+        let mut result = None;
+        if let Some(item) = self.get_impl(key) {
+            cx.push_key(key);
+
+            if let Some(array) = item.as_array() {
+                result = Some(fun(cx, array));
+            } else {
+                cx.unexpected_type("array", item.type_name());
+            }
+
+            cx.pop_key();
+        }
+        result
+    }
+
     fn parse_from_str<T: FromStr>(&'a self, cx: &mut ParseContext, key: &'static str) -> Option<T> {
         let mut result = None;
 
@@ -0,0 +1,97 @@
+mod read;
+mod write;
+
+pub use read::read_play_stats;
+pub use write::PlayStatsWriter;
+
+use std::time::Duration;
+use url::Url;
+
+/// How much a movie has been played, kept only on this computer for the user's own curiosity.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PlayStat {
+    pub url: Url,
+
+    /// The total time the movie was open, over all of its sessions.
+    pub play_time: Duration,
+
+    /// How many times the movie was opened.
+    pub sessions: u64,
+}
+
+impl PlayStat {
+    pub fn is_invalid(&self) -> bool {
+        self.url.as_str() == crate::INVALID_URL
+    }
+}
+
+/// Play statistics, one entry per movie URL, in the order they were first played.
+pub type PlayStats = Vec<PlayStat>;
+
+pub trait PlayStatsExt {
+    /// The statistics as a JSON array, sorted by most played first, for use by scripts.
+    ///
+    /// Each entry is an object with the fields:
+    /// - `url`: the URL of the movie, as a string
+    /// - `seconds`: the total time the movie was open, as a whole number of seconds
+    /// - `sessions`: how many times the movie was opened, as a number
+    ///
+    /// Fields may be added in future versions, but won't be removed or changed.
+    fn to_json(&self) -> String;
+}
+
+impl PlayStatsExt for PlayStats {
+    fn to_json(&self) -> String {
+        let mut stats: Vec<_> = self.iter().filter(|x| !x.is_invalid()).collect();
+        stats.sort_by(|a, b| b.play_time.cmp(&a.play_time));
+        let entries: Vec<_> = stats
+            .into_iter()
+            .map(|stat| {
+                serde_json::json!({
+                    "url": stat.url.as_str(),
+                    "seconds": stat.play_time.as_secs(),
+                    "sessions": stat.sessions,
+                })
+            })
+            .collect();
+        serde_json::to_string_pretty(&entries).expect("Play stats must be serializable")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stat(url: &str, seconds: u64) -> PlayStat {
+        PlayStat {
+            url: Url::parse(url).unwrap(),
+            play_time: Duration::from_secs(seconds),
+            sessions: 1,
+        }
+    }
+
+    #[test]
+    fn to_json() {
+        let stats = vec![
+            stat("file:///games/a.swf", 60),
+            stat(crate::INVALID_URL, 3600),
+            stat("https://ruffle.rs/demo/logo-anim.swf", 90),
+        ];
+        let json: serde_json::Value = serde_json::from_str(&stats.to_json()).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!([
+                {
+                    "url": "https://ruffle.rs/demo/logo-anim.swf",
+                    "seconds": 90,
+                    "sessions": 1,
+                },
+                {
+                    "url": "file:///games/a.swf",
+                    "seconds": 60,
+                    "sessions": 1,
+                },
+            ])
+        );
+    }
+}
@@ -0,0 +1,99 @@
+use crate::parse::{DocumentHolder, ParseContext, ParseDetails, ParseWarning, ReadExt};
+use crate::play_stats::{PlayStat, PlayStats};
+use std::time::Duration;
+use toml_edit::DocumentMut;
+use url::Url;
+
+pub fn read_play_stats(input: &str) -> ParseDetails<PlayStats> {
+    let document = match input.parse::<DocumentMut>() {
+        Ok(document) => document,
+        Err(e) => {
+            return ParseDetails {
+                result: Default::default(),
+                warnings: vec![ParseWarning::InvalidToml(e)],
+            }
+        }
+    };
+
+    let mut result = Vec::new();
+    let mut cx = ParseContext::default();
+
+    document.get_array_of_tables(&mut cx, "movie", |cx, movies| {
+        for movie in movies.iter() {
+            let url = match movie.parse_from_str(cx, "url") {
+                Some(url) => url,
+                None => Url::parse(crate::INVALID_URL).expect("Url is constant and valid"),
+            };
+
+            let seconds = movie
+                .get_integer(cx, "seconds")
+                .and_then(|x| u64::try_from(x).ok())
+                .unwrap_or_default();
+            let sessions = movie
+                .get_integer(cx, "sessions")
+                .and_then(|x| u64::try_from(x).ok())
+                .unwrap_or_default();
+
+            result.push(PlayStat {
+                url,
+                play_time: Duration::from_secs(seconds),
+                sessions,
+            });
+        }
+    });
+
+    ParseDetails {
+        warnings: cx.warnings,
+        result: DocumentHolder::new(result, document),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty() {
+        let result = read_play_stats("");
+        assert_eq!(&Vec::<PlayStat>::new(), result.values());
+        assert_eq!(Vec::<ParseWarning>::new(), result.warnings);
+    }
+
+    #[test]
+    fn movies() {
+        let result = read_play_stats(
+            r#"
+            [[movie]]
+            url = "file:///games/a.swf"
+            seconds = 125
+            sessions = 3
+
+            [[movie]]
+            url = "invalid"
+            seconds = -1
+            "#,
+        );
+        assert_eq!(
+            &vec![
+                PlayStat {
+                    url: Url::parse("file:///games/a.swf").unwrap(),
+                    play_time: Duration::from_secs(125),
+                    sessions: 3,
+                },
+                PlayStat {
+                    url: Url::parse(crate::INVALID_URL).unwrap(),
+                    play_time: Duration::ZERO,
+                    sessions: 0,
+                },
+            ],
+            result.values()
+        );
+        assert_eq!(
+            vec![ParseWarning::UnsupportedValue {
+                value: "invalid".to_string(),
+                path: "movie.url".to_string(),
+            }],
+            result.warnings
+        );
+    }
+}
@@ -0,0 +1,74 @@
+use crate::parse::DocumentHolder;
+use crate::play_stats::{PlayStat, PlayStats};
+use crate::write::TableExt;
+use std::time::Duration;
+use toml_edit::{value, ArrayOfTables, Table};
+use url::Url;
+
+pub struct PlayStatsWriter<'a>(&'a mut DocumentHolder<PlayStats>);
+
+impl<'a> PlayStatsWriter<'a> {
+    pub fn new(play_stats: &'a mut DocumentHolder<PlayStats>) -> Self {
+        Self(play_stats)
+    }
+
+    fn with_underlying_table(&mut self, fun: impl FnOnce(&mut PlayStats, &mut ArrayOfTables)) {
+        self.0.edit(|values, toml_document| {
+            let table = toml_document.get_or_create_array_of_tables("movie");
+            fun(values, table)
+        })
+    }
+
+    /// Adds a session of the given length to the movie with the given URL,
+    /// starting to keep track of it if it wasn't already.
+    pub fn add_session(&mut self, url: &Url, play_time: Duration) {
+        self.with_underlying_table(|values, array| {
+            let index = match values.iter().position(|x| &x.url == url) {
+                Some(index) => index,
+                None => {
+                    let mut table = Table::new();
+                    table["url"] = value(url.as_str());
+                    array.push(table);
+                    values.push(PlayStat {
+                        url: url.clone(),
+                        play_time: Duration::ZERO,
+                        sessions: 0,
+                    });
+                    values.len() - 1
+                }
+            };
+            let stat = &mut values[index];
+            stat.play_time += play_time;
+            stat.sessions += 1;
+            // Only whole seconds are kept, so that's all that's added up.
+            stat.play_time = Duration::from_secs(stat.play_time.as_secs());
+            if let Some(table) = array.get_mut(index) {
+                table["seconds"] = value(stat.play_time.as_secs() as i64);
+                table["sessions"] = value(stat.sessions as i64);
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::play_stats::read_play_stats;
+
+    crate::define_serialization_test_helpers!(read_play_stats, PlayStats, PlayStatsWriter);
+
+    #[test]
+    fn add_session() {
+        let url = Url::parse("file:///games/a.swf").unwrap();
+        test(
+            "",
+            |writer| writer.add_session(&url, Duration::from_millis(90_500)),
+            "[[movie]]\nurl = \"file:///games/a.swf\"\nseconds = 90\nsessions = 1\n",
+        );
+        test(
+            "[[movie]]\nurl = \"file:///games/a.swf\"\nseconds = 90\nsessions = 1\n",
+            |writer| writer.add_session(&url, Duration::from_secs(30)),
+            "[[movie]]\nurl = \"file:///games/a.swf\"\nseconds = 120\nsessions = 2\n",
+        );
+    }
+}
@@ -49,4 +49,99 @@ impl PlayerOptions {
                 .or(other.dummy_external_interface),
         }
     }
+    /// Names the options that are set in both `self` and `other`, but to different values.
+    /// These are the options where `self.or(other)` discards what `other` asked for.
+    pub fn conflicting_options(&self, other: &Self) -> Vec<&'static str> {
+        fn differs<T: PartialEq>(a: &Option<T>, b: &Option<T>) -> bool {
+            matches!((a, b), (Some(a), Some(b)) if a != b)
+        }
+
+        [
+            (
+                "max_execution_duration",
+                differs(&self.max_execution_duration, &other.max_execution_duration),
+            ),
+            ("base", differs(&self.base, &other.base)),
+            ("quality", differs(&self.quality, &other.quality)),
+            ("align", differs(&self.align, &other.align)),
+            (
+                "force_align",
+                differs(&self.force_align, &other.force_align),
+            ),
+            ("scale", differs(&self.scale, &other.scale)),
+            (
+                "force_scale",
+                differs(&self.force_scale, &other.force_scale),
+            ),
+            (
+                "upgrade_to_https",
+                differs(&self.upgrade_to_https, &other.upgrade_to_https),
+            ),
+            (
+                "load_behavior",
+                differs(&self.load_behavior, &other.load_behavior),
+            ),
+            ("letterbox", differs(&self.letterbox, &other.letterbox)),
+            ("spoof_url", differs(&self.spoof_url, &other.spoof_url)),
+            (
+                "player_version",
+                differs(&self.player_version, &other.player_version),
+            ),
+            (
+                "player_runtime",
+                differs(&self.player_runtime, &other.player_runtime),
+            ),
+            ("frame_rate", differs(&self.frame_rate, &other.frame_rate)),
+            (
+                "dummy_external_interface",
+                differs(
+                    &self.dummy_external_interface,
+                    &other.dummy_external_interface,
+                ),
+            ),
+        ]
+        .into_iter()
+        .filter(|(_, conflicts)| *conflicts)
+        .map(|(name, _)| name)
+        .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::player_options::PlayerOptions;
+    use ruffle_render::quality::StageQuality;
+
+    #[test]
+    fn or_prefers_self() {
+        let user = PlayerOptions {
+            quality: Some(StageQuality::Low),
+            ..Default::default()
+        };
+        let bundle = PlayerOptions {
+            quality: Some(StageQuality::High),
+            frame_rate: Some(30.0),
+            ..Default::default()
+        };
+        let merged = user.or(&bundle);
+        assert_eq!(merged.quality, Some(StageQuality::Low));
+        assert_eq!(merged.frame_rate, Some(30.0));
+    }
+
+    #[test]
+    fn conflicting_options() {
+        let user = PlayerOptions {
+            quality: Some(StageQuality::Low),
+            frame_rate: Some(30.0),
+            force_scale: Some(true),
+            ..Default::default()
+        };
+        let bundle = PlayerOptions {
+            quality: Some(StageQuality::High),
+            frame_rate: Some(30.0),
+            scale: Some(Default::default()),
+            ..Default::default()
+        };
+        assert_eq!(user.conflicting_options(&bundle), vec!["quality"]);
+    }
 }
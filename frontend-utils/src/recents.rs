@@ -4,14 +4,82 @@ mod write;
 pub use read::read_recents;
 pub use write::RecentsWriter;
 
+use ruffle_core::config::Letterbox;
+use ruffle_core::StageScaleMode;
+use ruffle_render::quality::StageQuality;
+use sha2::{Digest, Sha256};
+use std::borrow::Cow;
+use std::fs::File;
+use std::path::Path;
 use url::Url;
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct Recent {
     pub url: Url,
+
+    /// The name of the content when it was opened, such as the name of a bundle.
+    pub name: Option<String>,
+
+    /// Pinned entries are listed first, and are only evicted once no other entries are left.
+    pub pinned: bool,
+
+    /// A [`content_hash`] of the file, used to recognize it after it's moved or renamed.
+    pub content_hash: Option<String>,
+
+    /// When this was last opened, in seconds since the Unix epoch.
+    pub last_opened: Option<u64>,
+
+    /// How the content was last presented, to present it the same way when it's opened again.
+    pub presentation: RecentPresentation,
+}
+
+/// The presentation settings last picked for a recent entry while it was playing.
+///
+/// Settings that were never picked are left to the defaults.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct RecentPresentation {
+    pub quality: Option<StageQuality>,
+    pub scale: Option<StageScaleMode>,
+    pub letterbox: Option<Letterbox>,
+}
+
+impl RecentPresentation {
+    /// Combines both, preferring the settings of `self` and falling back to those of `other`.
+    pub fn or(self, other: Self) -> Self {
+        Self {
+            quality: self.quality.or(other.quality),
+            scale: self.scale.or(other.scale),
+            letterbox: self.letterbox.or(other.letterbox),
+        }
+    }
+}
+
+fn letterbox_name(letterbox: Letterbox) -> &'static str {
+    match letterbox {
+        Letterbox::Off => "off",
+        Letterbox::Fullscreen => "fullscreen",
+        Letterbox::On => "on",
+    }
+}
+
+fn letterbox_from_name(name: &str) -> Option<Letterbox> {
+    match name {
+        "off" => Some(Letterbox::Off),
+        "fullscreen" => Some(Letterbox::Fullscreen),
+        "on" => Some(Letterbox::On),
+        _ => None,
+    }
 }
 
 impl Recent {
+    /// The name to show for this entry, falling back to the file name in its URL.
+    pub fn display_name(&self) -> Cow<'_, str> {
+        match &self.name {
+            Some(name) => Cow::Borrowed(name),
+            None => crate::url_to_readable_name(&self.url),
+        }
+    }
+
     pub fn is_invalid(&self) -> bool {
         self.url.as_str() == crate::INVALID_URL
     }
@@ -33,3 +101,210 @@ impl Recent {
 
 /// Recent entries, stored from oldest to newest.
 pub type Recents = Vec<Recent>;
+
+/// Where a group of recent entries came from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RecentSource {
+    /// A local directory, given as a `file://` URL ending in a slash.
+    Directory(Url),
+
+    /// A remote host.
+    Host(String),
+
+    /// Anything without an obvious parent, such as a `data:` URL.
+    Other,
+}
+
+impl RecentSource {
+    fn of(url: &Url) -> Self {
+        if url.scheme() == "file" {
+            match url.join(".") {
+                Ok(directory) if !url.cannot_be_a_base() => RecentSource::Directory(directory),
+                _ => RecentSource::Other,
+            }
+        } else if let Some(host) = url.host_str() {
+            RecentSource::Host(host.to_owned())
+        } else {
+            RecentSource::Other
+        }
+    }
+}
+
+/// Recent entries that share the same [`RecentSource`].
+#[derive(Debug, PartialEq)]
+pub struct RecentGroup<'a> {
+    pub source: RecentSource,
+
+    /// The entries in this group, from newest to oldest.
+    pub entries: Vec<&'a Recent>,
+}
+
+pub trait RecentsExt {
+    /// Buckets the entries by the directory or host they came from.
+    ///
+    /// Groups are ordered by their newest entry, newest first.
+    /// Invalid entries are skipped.
+    fn grouped_by_directory(&self) -> Vec<RecentGroup<'_>>;
+
+    /// Serializes the entries for use by other programs, such as launchers.
+    ///
+    /// The result is a JSON array of the valid entries, newest first, where each entry is an object with:
+    /// - `url`: the URL of the content, as a string
+    /// - `name`: the name of the content when it was opened, or `null` if unknown
+    /// - `last_opened`: when it was last opened in seconds since the Unix epoch, or `null` if unknown
+    /// - `pinned`: whether the user pinned this entry, as a boolean
+    ///
+    /// Fields may be added in future versions, but won't be removed or changed.
+    fn to_json(&self) -> String;
+}
+
+impl RecentsExt for Recents {
+    fn grouped_by_directory(&self) -> Vec<RecentGroup<'_>> {
+        let mut groups: Vec<RecentGroup> = Vec::new();
+        for recent in self.iter().rev().filter(|x| !x.is_invalid()) {
+            let source = RecentSource::of(&recent.url);
+            match groups.iter_mut().find(|group| group.source == source) {
+                Some(group) => group.entries.push(recent),
+                None => groups.push(RecentGroup {
+                    source,
+                    entries: vec![recent],
+                }),
+            }
+        }
+        groups
+    }
+
+    fn to_json(&self) -> String {
+        let entries: Vec<_> = self
+            .iter()
+            .rev()
+            .filter(|x| !x.is_invalid())
+            .map(|recent| {
+                serde_json::json!({
+                    "url": recent.url.as_str(),
+                    "name": recent.name,
+                    "last_opened": recent.last_opened,
+                    "pinned": recent.pinned,
+                })
+            })
+            .collect();
+        serde_json::to_string_pretty(&entries).expect("Recents must be serializable")
+    }
+}
+
+/// Identifies the content of a file, from a SHA-256 digest of all of its data.
+///
+/// This is stable between runs and versions, so that it can be stored in recents.
+pub fn content_hash(path: &Path) -> std::io::Result<String> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher)?;
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect())
+}
+
+/// A recent entry for the URL, with nothing else known about it.
+#[cfg(test)]
+fn recent(url: &str) -> Recent {
+    Recent {
+        url: Url::parse(url).unwrap(),
+        name: None,
+        pinned: false,
+        content_hash: None,
+        last_opened: None,
+        presentation: Default::default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn to_json() {
+        let mut recents = vec![
+            recent("file:///games/a.swf"),
+            recent(crate::INVALID_URL),
+            recent("https://ruffle.rs/demo/logo-anim.swf"),
+        ];
+        recents[2].name = Some("Logo \"Anim\"".to_string());
+        recents[2].last_opened = Some(1700000000);
+        recents[2].pinned = true;
+        let json: serde_json::Value = serde_json::from_str(&recents.to_json()).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!([
+                {
+                    "url": "https://ruffle.rs/demo/logo-anim.swf",
+                    "name": "Logo \"Anim\"",
+                    "last_opened": 1700000000,
+                    "pinned": true,
+                },
+                {
+                    "url": "file:///games/a.swf",
+                    "name": null,
+                    "last_opened": null,
+                    "pinned": false,
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn grouped_by_directory() {
+        let recents = vec![
+            recent("file:///games/first/a.swf"),
+            recent("https://ruffle.rs/demo/logo-anim.swf"),
+            recent("file:///games/second/b.swf"),
+            recent("data:application/x-shockwave-flash,FWS"),
+            recent(crate::INVALID_URL),
+            recent("file:///games/first/c.swf"),
+        ];
+        assert_eq!(
+            recents.grouped_by_directory(),
+            vec![
+                RecentGroup {
+                    source: RecentSource::Directory(Url::parse("file:///games/first/").unwrap()),
+                    entries: vec![&recents[5], &recents[0]],
+                },
+                RecentGroup {
+                    source: RecentSource::Other,
+                    entries: vec![&recents[3]],
+                },
+                RecentGroup {
+                    source: RecentSource::Directory(Url::parse("file:///games/second/").unwrap()),
+                    entries: vec![&recents[2]],
+                },
+                RecentGroup {
+                    source: RecentSource::Host("ruffle.rs".to_string()),
+                    entries: vec![&recents[1]],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn grouped_by_directory_empty() {
+        assert_eq!(Recents::new().grouped_by_directory(), vec![]);
+    }
+
+    #[test]
+    fn content_hash_covers_the_whole_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut data = vec![0; 100 * 1024];
+        std::fs::write(dir.path().join("a.swf"), &data).unwrap();
+        *data.last_mut().unwrap() = 1;
+        std::fs::write(dir.path().join("b.swf"), &data).unwrap();
+        std::fs::write(dir.path().join("c.swf"), &data).unwrap();
+
+        let a = content_hash(&dir.path().join("a.swf")).unwrap();
+        let b = content_hash(&dir.path().join("b.swf")).unwrap();
+        let c = content_hash(&dir.path().join("c.swf")).unwrap();
+        assert_ne!(a, b);
+        assert_eq!(b, c);
+        assert_eq!(a.len(), 64);
+    }
+}
@@ -1,5 +1,5 @@
 use crate::parse::{DocumentHolder, ParseContext, ParseDetails, ParseWarning, ReadExt};
-use crate::recents::{Recent, Recents};
+use crate::recents::{letterbox_from_name, Recent, RecentPresentation, Recents};
 use toml_edit::DocumentMut;
 use url::Url;
 
@@ -24,7 +24,38 @@ pub fn read_recents(input: &str) -> ParseDetails<Recents> {
                 None => Url::parse(crate::INVALID_URL).expect("Url is constant and valid"),
             };
 
-            result.push(Recent { url });
+            let name = recent.parse_from_str(cx, "name");
+            let pinned = recent.get_bool(cx, "pinned").unwrap_or_default();
+            let content_hash = recent.parse_from_str(cx, "content_hash");
+            let last_opened = recent
+                .get_integer(cx, "last_opened")
+                .and_then(|x| u64::try_from(x).ok());
+            let quality = recent.parse_from_str(cx, "quality");
+            let scale = recent.parse_from_str(cx, "scale");
+            let letterbox: Option<String> = recent.parse_from_str(cx, "letterbox");
+            let letterbox = letterbox.and_then(|letterbox| {
+                let result = letterbox_from_name(&letterbox);
+                if result.is_none() {
+                    cx.push_key("letterbox");
+                    cx.unsupported_value(letterbox);
+                    cx.pop_key();
+                }
+                result
+            });
+            let presentation = RecentPresentation {
+                quality,
+                scale,
+                letterbox,
+            };
+
+            result.push(Recent {
+                url,
+                name,
+                pinned,
+                content_hash,
+                last_opened,
+                presentation,
+            });
         }
     });
 
@@ -37,6 +68,10 @@ pub fn read_recents(input: &str) -> ParseDetails<Recents> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::recents::recent;
+    use ruffle_core::config::Letterbox;
+    use ruffle_core::StageScaleMode;
+    use ruffle_render::quality::StageQuality;
 
     #[test]
     fn empty() {
@@ -62,24 +97,14 @@ mod tests {
     #[test]
     fn empty_entry() {
         let result = read_recents("[[recent]]");
-        assert_eq!(
-            &vec![Recent {
-                url: Url::parse(crate::INVALID_URL).unwrap(),
-            }],
-            result.values()
-        );
+        assert_eq!(&vec![recent(crate::INVALID_URL)], result.values());
         assert_eq!(Vec::<ParseWarning>::new(), result.warnings);
     }
 
     #[test]
     fn invalid_url() {
         let result = read_recents("[[recent]]\nurl = \"invalid\"");
-        assert_eq!(
-            &vec![Recent {
-                url: Url::parse(crate::INVALID_URL).unwrap(),
-            }],
-            result.values()
-        );
+        assert_eq!(&vec![recent(crate::INVALID_URL)], result.values());
         assert_eq!(
             vec![ParseWarning::UnsupportedValue {
                 value: "invalid".to_string(),
@@ -93,9 +118,7 @@ mod tests {
     fn valid_entry() {
         let result = read_recents("[[recent]]\nurl = \"https://ruffle.rs/logo-anim.swf\"\n");
         assert_eq!(
-            &vec![Recent {
-                url: Url::parse("https://ruffle.rs/logo-anim.swf").unwrap(),
-            }],
+            &vec![recent("https://ruffle.rs/logo-anim.swf")],
             result.values()
         );
         assert_eq!(Vec::<ParseWarning>::new(), result.warnings);
@@ -113,14 +136,7 @@ mod tests {
         "#,
         );
         assert_eq!(
-            &vec![
-                Recent {
-                    url: Url::parse("file:///first.swf").unwrap(),
-                },
-                Recent {
-                    url: Url::parse("file:///second.swf").unwrap(),
-                }
-            ],
+            &vec![recent("file:///first.swf"), recent("file:///second.swf")],
             result.values()
         );
         assert_eq!(Vec::<ParseWarning>::new(), result.warnings);
@@ -147,21 +163,11 @@ mod tests {
         );
         assert_eq!(
             &vec![
-                Recent {
-                    url: Url::parse("file:///first.swf").unwrap(),
-                },
-                Recent {
-                    url: Url::parse(crate::INVALID_URL).unwrap(),
-                },
-                Recent {
-                    url: Url::parse(crate::INVALID_URL).unwrap(),
-                },
-                Recent {
-                    url: Url::parse(crate::INVALID_URL).unwrap(),
-                },
-                Recent {
-                    url: Url::parse("file:///second.swf").unwrap(),
-                },
+                recent("file:///first.swf"),
+                recent(crate::INVALID_URL),
+                recent(crate::INVALID_URL),
+                recent(crate::INVALID_URL),
+                recent("file:///second.swf"),
             ],
             result.values()
         );
@@ -180,4 +186,109 @@ mod tests {
             result.warnings
         );
     }
+
+    #[test]
+    fn name_and_pinned() {
+        let result = read_recents(
+            r#"
+            [[recent]]
+            url = "file:///game.ruf"
+            name = "Cool Game!"
+            pinned = true
+
+            [[recent]]
+            url = "file:///second.swf"
+            pinned = "yes"
+        "#,
+        );
+        assert_eq!(
+            &vec![
+                Recent {
+                    name: Some("Cool Game!".to_string()),
+                    pinned: true,
+                    ..recent("file:///game.ruf")
+                },
+                recent("file:///second.swf"),
+            ],
+            result.values()
+        );
+        assert_eq!(
+            vec![ParseWarning::UnexpectedType {
+                expected: "boolean",
+                actual: "string",
+                path: "recent.pinned".to_string()
+            }],
+            result.warnings
+        );
+    }
+
+    #[test]
+    fn last_opened() {
+        let result = read_recents(
+            r#"
+            [[recent]]
+            url = "file:///first.swf"
+            last_opened = 1700000000
+
+            [[recent]]
+            url = "file:///second.swf"
+            last_opened = -1
+        "#,
+        );
+        assert_eq!(
+            vec![Some(1700000000), None],
+            result
+                .values()
+                .iter()
+                .map(|x| x.last_opened)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(Vec::<ParseWarning>::new(), result.warnings);
+    }
+
+    #[test]
+    fn presentation() {
+        let result = read_recents(
+            r#"
+            [[recent]]
+            url = "file:///first.swf"
+            quality = "low"
+            scale = "noScale"
+            letterbox = "fullscreen"
+
+            [[recent]]
+            url = "file:///second.swf"
+            quality = "blurry"
+            letterbox = "sometimes"
+        "#,
+        );
+        assert_eq!(
+            vec![
+                RecentPresentation {
+                    quality: Some(StageQuality::Low),
+                    scale: Some(StageScaleMode::NoScale),
+                    letterbox: Some(Letterbox::Fullscreen),
+                },
+                RecentPresentation::default(),
+            ],
+            result
+                .values()
+                .iter()
+                .map(|x| x.presentation)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![
+                ParseWarning::UnsupportedValue {
+                    value: "blurry".to_string(),
+                    path: "recent.quality".to_string()
+                },
+                ParseWarning::UnsupportedValue {
+                    value: "sometimes".to_string(),
+                    path: "recent.letterbox".to_string()
+                },
+            ],
+            result.warnings
+        );
+    }
 }
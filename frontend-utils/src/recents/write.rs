@@ -1,7 +1,11 @@
 use crate::parse::DocumentHolder;
-use crate::recents::{Recent, Recents};
+use crate::recents::{letterbox_name, Recent, Recents};
 use crate::write::TableExt;
+use ruffle_core::config::Letterbox;
+use ruffle_core::StageScaleMode;
+use ruffle_render::quality::StageQuality;
 use toml_edit::{value, ArrayOfTables, Table};
+use url::Url;
 
 pub struct RecentsWriter<'a>(&'a mut DocumentHolder<Recents>);
 
@@ -24,7 +28,70 @@ impl<'a> RecentsWriter<'a> {
         });
     }
 
+    /// Evicts the oldest entries until there are at most `limit` left.
+    ///
+    /// Pinned entries are only evicted once there are no unpinned entries left.
+    pub fn enforce_limit(&mut self, limit: usize) {
+        self.with_underlying_table(|values, array| evict_down_to(values, array, limit));
+    }
+
+    /// Changes the entry with the given URL, and its table, if there is one.
+    fn edit_entry(&mut self, url: &Url, fun: impl FnOnce(&mut Recent, &mut Table)) {
+        self.with_underlying_table(|values, array| {
+            let Some(index) = values.iter().position(|x| &x.url == url) else {
+                return;
+            };
+            if let Some(table) = array.get_mut(index) {
+                fun(&mut values[index], table);
+            }
+        });
+    }
+
+    /// Remembers the quality picked for the entry with the given URL, if there is one.
+    pub fn set_quality(&mut self, url: &Url, quality: StageQuality) {
+        self.edit_entry(url, |recent, table| {
+            table["quality"] = value(quality.to_string());
+            recent.presentation.quality = Some(quality);
+        });
+    }
+
+    /// Remembers the scale mode picked for the entry with the given URL, if there is one.
+    pub fn set_scale(&mut self, url: &Url, scale: StageScaleMode) {
+        self.edit_entry(url, |recent, table| {
+            table["scale"] = value(scale.to_string());
+            recent.presentation.scale = Some(scale);
+        });
+    }
+
+    /// Remembers the letterbox picked for the entry with the given URL, if there is one.
+    pub fn set_letterbox(&mut self, url: &Url, letterbox: Letterbox) {
+        self.edit_entry(url, |recent, table| {
+            table["letterbox"] = value(letterbox_name(letterbox));
+            recent.presentation.letterbox = Some(letterbox);
+        });
+    }
+
+    /// Pins or unpins the entry with the given URL, if there is one.
+    pub fn set_pinned(&mut self, url: &Url, pinned: bool) {
+        self.with_underlying_table(|values, array| {
+            let Some(index) = values.iter().position(|x| &x.url == url) else {
+                return;
+            };
+            if let Some(table) = array.get_mut(index) {
+                if pinned {
+                    table["pinned"] = value(true);
+                } else {
+                    table.remove("pinned");
+                }
+            }
+            values[index].pinned = pinned;
+        });
+    }
+
     /// Pushes a new recent entry on the entry stack, if same entry already exists, it will get moved to the top.
+    ///
+    /// An entry with the same content hash is also considered the same entry,
+    /// and is moved to the new URL, as long as its file isn't where it used to be anymore.
     pub fn push(&mut self, recent: Recent, limit: usize) {
         if limit == 0 {
             // Do not even bother.
@@ -33,7 +100,13 @@ impl<'a> RecentsWriter<'a> {
 
         self.with_underlying_table(|values, array| {
             // First, lets check if we already have existing entry with the same URL and move it to the top.
-            let existing = values.iter().position(|x| x.url == recent.url);
+            let existing = values.iter().position(|x| x.url == recent.url).or_else(|| {
+                recent.content_hash.as_ref().and_then(|hash| {
+                    values
+                        .iter()
+                        .position(|x| x.content_hash.as_ref() == Some(hash) && !file_exists(&x.url))
+                })
+            });
 
             if let Some(index) = existing {
                 // Existing entry, just move it to the top.
@@ -41,42 +114,76 @@ impl<'a> RecentsWriter<'a> {
                 // Update TOML first, then internal values.
                 // TODO: Unfortunately, ArrayOfTables does not return the removed entry, so we need to recreate it.
                 //       https://github.com/toml-rs/toml/issues/712
+                let existing = values.remove(index);
+                let recent = Recent {
+                    name: recent.name.or(existing.name),
+                    pinned: existing.pinned,
+                    content_hash: recent.content_hash.or(existing.content_hash),
+                    last_opened: recent.last_opened.or(existing.last_opened),
+                    presentation: recent.presentation.or(existing.presentation),
+                    ..recent
+                };
                 array.remove(index);
-                let mut table = Table::new();
-                table["url"] = value(recent.url.as_str());
-                array.push(table);
-
-                let recent = values.remove(index);
+                array.push(recent_to_table(&recent));
                 values.push(recent);
             } else {
                 // New entry.
-                // Evict old entries, if we are at or over the limit.
-                if values.len() >= limit {
-                    // Remove n elements over limit plus 1, since we need to push a new one too.
-                    let elements_to_remove = (values.len() - limit) + 1;
-
-                    // yes, this is inefficient, but this is not hot code :D (usually we only need to remove 1 element, unless the limit changed)
-                    for _ in 0..elements_to_remove {
-                        array.remove(0);
-                        values.remove(0);
-                    }
-                }
+                // Evict old entries, if we are at or over the limit, since we need to push a new one too.
+                evict_down_to(values, array, limit - 1);
 
-                // Create a new table and push it.
-                let mut table = Table::new();
-                table["url"] = value(recent.url.as_str());
-                array.push(table);
+                array.push(recent_to_table(&recent));
                 values.push(recent);
             }
         });
     }
 }
 
+/// Whether the URL is of a local file that still exists.
+fn file_exists(url: &Url) -> bool {
+    url.to_file_path().is_ok_and(|path| path.exists())
+}
+
+fn evict_down_to(values: &mut Recents, array: &mut ArrayOfTables, limit: usize) {
+    // yes, this is inefficient, but this is not hot code :D (usually we only need to remove 1 element, unless the limit changed)
+    while values.len() > limit {
+        // Pinned entries are kept for as long as there's something else to evict.
+        let oldest = values.iter().position(|x| !x.pinned).unwrap_or(0);
+        array.remove(oldest);
+        values.remove(oldest);
+    }
+}
+
+fn recent_to_table(recent: &Recent) -> Table {
+    let mut table = Table::new();
+    table["url"] = value(recent.url.as_str());
+    if let Some(name) = &recent.name {
+        table["name"] = value(name);
+    }
+    if recent.pinned {
+        table["pinned"] = value(true);
+    }
+    if let Some(content_hash) = &recent.content_hash {
+        table["content_hash"] = value(content_hash);
+    }
+    if let Some(last_opened) = recent.last_opened {
+        table["last_opened"] = value(last_opened as i64);
+    }
+    if let Some(quality) = recent.presentation.quality {
+        table["quality"] = value(quality.to_string());
+    }
+    if let Some(scale) = recent.presentation.scale {
+        table["scale"] = value(scale.to_string());
+    }
+    if let Some(letterbox) = recent.presentation.letterbox {
+        table["letterbox"] = value(letterbox_name(letterbox));
+    }
+    table
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::recents::read_recents;
-    use url::Url;
+    use crate::recents::{read_recents, recent};
 
     crate::define_serialization_test_helpers!(read_recents, Recents, RecentsWriter);
 
@@ -84,30 +191,19 @@ mod tests {
     fn simple_push() {
         test(
             "",
-            |writer| {
-                writer.push(
-                    Recent {
-                        url: Url::parse("file:///1.swf").unwrap(),
-                    },
-                    10,
-                )
-            },
+            |writer| writer.push(recent("file:///1.swf"), 10),
             "[[recent]]\nurl = \"file:///1.swf\"\n",
         );
     }
 
     #[test]
     fn test_limit() {
-        test("[[recent]]\nurl = \"file:///1.swf\"\n[[recent]]\nurl = \"file:///2.swf\"\n[[recent]]\nurl = \"file:///3.swf\"\n", |writer| writer.push(Recent {
-            url: Url::parse("file:///very_important_file.swf").unwrap(),
-        }, 2), "[[recent]]\nurl = \"file:///3.swf\"\n\n[[recent]]\nurl = \"file:///very_important_file.swf\"\n");
+        test("[[recent]]\nurl = \"file:///1.swf\"\n[[recent]]\nurl = \"file:///2.swf\"\n[[recent]]\nurl = \"file:///3.swf\"\n", |writer| writer.push(recent("file:///very_important_file.swf"), 2), "[[recent]]\nurl = \"file:///3.swf\"\n\n[[recent]]\nurl = \"file:///very_important_file.swf\"\n");
     }
 
     #[test]
     fn test_move_to_top() {
-        test("[[recent]]\nurl = \"file:///very_important_file.swf\"\n[[recent]]\nurl = \"file:///2.swf\"\n[[recent]]\nurl = \"file:///3.swf\"\n", |writer| writer.push(Recent {
-            url: Url::parse("file:///very_important_file.swf").unwrap(),
-        }, 3), "[[recent]]\nurl = \"file:///2.swf\"\n[[recent]]\nurl = \"file:///3.swf\"\n\n[[recent]]\nurl = \"file:///very_important_file.swf\"\n");
+        test("[[recent]]\nurl = \"file:///very_important_file.swf\"\n[[recent]]\nurl = \"file:///2.swf\"\n[[recent]]\nurl = \"file:///3.swf\"\n", |writer| writer.push(recent("file:///very_important_file.swf"), 3), "[[recent]]\nurl = \"file:///2.swf\"\n[[recent]]\nurl = \"file:///3.swf\"\n\n[[recent]]\nurl = \"file:///very_important_file.swf\"\n");
     }
 
     #[test]
@@ -117,16 +213,130 @@ mod tests {
 
     #[test]
     fn zero_limit() {
+        test(
+            "",
+            |writer| writer.push(recent("file:///no_crash.swf"), 0),
+            "",
+        );
+    }
+
+    #[test]
+    fn push_with_name() {
         test(
             "",
             |writer| {
                 writer.push(
                     Recent {
-                        url: Url::parse("file:///no_crash.swf").unwrap(),
+                        name: Some("Cool Game!".to_string()),
+                        ..recent("file:///game.ruf")
                     },
-                    0,
+                    10,
                 )
             },
+            "[[recent]]\nurl = \"file:///game.ruf\"\nname = \"Cool Game!\"\n",
+        );
+    }
+
+    #[test]
+    fn move_to_top_keeps_pin() {
+        test("[[recent]]\nurl = \"file:///1.swf\"\npinned = true\n[[recent]]\nurl = \"file:///2.swf\"\n", |writer| writer.push(recent("file:///1.swf"), 10), "[[recent]]\nurl = \"file:///2.swf\"\n\n[[recent]]\nurl = \"file:///1.swf\"\npinned = true\n");
+    }
+
+    #[test]
+    fn limit_keeps_pinned() {
+        test("[[recent]]\nurl = \"file:///1.swf\"\npinned = true\n[[recent]]\nurl = \"file:///2.swf\"\n", |writer| writer.push(recent("file:///3.swf"), 2), "[[recent]]\nurl = \"file:///1.swf\"\npinned = true\n\n[[recent]]\nurl = \"file:///3.swf\"\n");
+    }
+
+    #[test]
+    fn pin_and_unpin() {
+        test(
+            "[[recent]]\nurl = \"file:///1.swf\"\n",
+            |writer| writer.set_pinned(&Url::parse("file:///1.swf").unwrap(), true),
+            "[[recent]]\nurl = \"file:///1.swf\"\npinned = true\n",
+        );
+        test(
+            "[[recent]]\nurl = \"file:///1.swf\"\npinned = true\n",
+            |writer| writer.set_pinned(&Url::parse("file:///1.swf").unwrap(), false),
+            "[[recent]]\nurl = \"file:///1.swf\"\n",
+        );
+    }
+
+    #[test]
+    fn set_presentation() {
+        let url = Url::parse("file:///1.swf").unwrap();
+        test(
+            "[[recent]]\nurl = \"file:///1.swf\"\n",
+            |writer| {
+                writer.set_quality(&url, StageQuality::Low);
+                writer.set_scale(&url, StageScaleMode::NoScale);
+                writer.set_letterbox(&url, Letterbox::Off);
+            },
+            "[[recent]]\nurl = \"file:///1.swf\"\nquality = \"low\"\nscale = \"noScale\"\nletterbox = \"off\"\n",
+        );
+        test(
+            "[[recent]]\nurl = \"file:///2.swf\"\n",
+            |writer| writer.set_quality(&url, StageQuality::Low),
+            "[[recent]]\nurl = \"file:///2.swf\"\n",
+        );
+    }
+
+    #[test]
+    fn move_to_top_keeps_presentation() {
+        test("[[recent]]\nurl = \"file:///1.swf\"\nquality = \"low\"\n[[recent]]\nurl = \"file:///2.swf\"\n", |writer| writer.push(recent("file:///1.swf"), 10), "[[recent]]\nurl = \"file:///2.swf\"\n\n[[recent]]\nurl = \"file:///1.swf\"\nquality = \"low\"\n");
+    }
+
+    #[test]
+    fn move_by_content_hash() {
+        test("[[recent]]\nurl = \"file:///old/game.swf\"\ncontent_hash = \"0123456789abcdef\"\n[[recent]]\nurl = \"file:///2.swf\"\n", |writer| writer.push(Recent {
+            content_hash: Some("0123456789abcdef".to_string()),
+            ..recent("file:///new/game.swf")
+        }, 10), "[[recent]]\nurl = \"file:///2.swf\"\n\n[[recent]]\nurl = \"file:///new/game.swf\"\ncontent_hash = \"0123456789abcdef\"\n");
+    }
+
+    #[test]
+    fn copy_with_same_content_hash() {
+        // The old file is still there, so the new one is a copy rather than where it moved to.
+        let old = tempfile::NamedTempFile::new().unwrap();
+        let old_url = Url::from_file_path(old.path()).unwrap();
+        test(&format!("[[recent]]\nurl = \"{old_url}\"\ncontent_hash = \"0123456789abcdef\"\n"), |writer| writer.push(Recent {
+            content_hash: Some("0123456789abcdef".to_string()),
+            ..recent("file:///new/game.swf")
+        }, 10), &format!("[[recent]]\nurl = \"{old_url}\"\ncontent_hash = \"0123456789abcdef\"\n\n[[recent]]\nurl = \"file:///new/game.swf\"\ncontent_hash = \"0123456789abcdef\"\n"));
+    }
+
+    #[test]
+    fn different_content_hash() {
+        test("[[recent]]\nurl = \"file:///1.swf\"\ncontent_hash = \"0123456789abcdef\"\n", |writer| writer.push(Recent {
+            content_hash: Some("fedcba9876543210".to_string()),
+            ..recent("file:///2.swf")
+        }, 10), "[[recent]]\nurl = \"file:///1.swf\"\ncontent_hash = \"0123456789abcdef\"\n\n[[recent]]\nurl = \"file:///2.swf\"\ncontent_hash = \"fedcba9876543210\"\n");
+    }
+
+    #[test]
+    fn enforce_lower_limit() {
+        test("[[recent]]\nurl = \"file:///1.swf\"\n[[recent]]\nurl = \"file:///2.swf\"\n[[recent]]\nurl = \"file:///3.swf\"\n[[recent]]\nurl = \"file:///4.swf\"\n", |writer| writer.enforce_limit(2), "[[recent]]\nurl = \"file:///3.swf\"\n[[recent]]\nurl = \"file:///4.swf\"\n");
+    }
+
+    #[test]
+    fn enforce_limit_keeps_pinned() {
+        test("[[recent]]\nurl = \"file:///1.swf\"\npinned = true\n[[recent]]\nurl = \"file:///2.swf\"\n[[recent]]\nurl = \"file:///3.swf\"\n[[recent]]\nurl = \"file:///4.swf\"\npinned = true\n", |writer| writer.enforce_limit(3), "[[recent]]\nurl = \"file:///1.swf\"\npinned = true\n[[recent]]\nurl = \"file:///3.swf\"\n[[recent]]\nurl = \"file:///4.swf\"\npinned = true\n");
+        test("[[recent]]\nurl = \"file:///1.swf\"\npinned = true\n[[recent]]\nurl = \"file:///2.swf\"\n[[recent]]\nurl = \"file:///3.swf\"\npinned = true\n", |writer| writer.enforce_limit(1), "[[recent]]\nurl = \"file:///3.swf\"\npinned = true\n");
+    }
+
+    #[test]
+    fn enforce_higher_limit() {
+        test(
+            "[[recent]]\nurl = \"file:///1.swf\"\n[[recent]]\nurl = \"file:///2.swf\"\n",
+            |writer| writer.enforce_limit(10),
+            "[[recent]]\nurl = \"file:///1.swf\"\n[[recent]]\nurl = \"file:///2.swf\"\n",
+        );
+    }
+
+    #[test]
+    fn enforce_zero_limit() {
+        test(
+            "[[recent]]\nurl = \"file:///1.swf\"\npinned = true\n",
+            |writer| writer.enforce_limit(0),
             "",
         );
     }
@@ -21,7 +21,7 @@ use ruffle_render::backend::{RenderBackend, ShapeHandle, ViewportDimensions};
 use ruffle_render::bitmap::{
     Bitmap, BitmapFormat, BitmapHandle, BitmapSource, PixelRegion, RgbaBufRead, SyncHandle,
 };
-use ruffle_render::commands::CommandList;
+use ruffle_render::commands::{Command, CommandList};
 use ruffle_render::error::Error as BitmapError;
 use ruffle_render::filters::Filter;
 use ruffle_render::pixel_bender::{
@@ -53,6 +53,8 @@ pub struct WgpuRenderBackend<T: RenderTarget> {
     pub(crate) offscreen_buffer_pool: Arc<BufferPool<wgpu::Buffer, BufferDimensions>>,
     dynamic_transforms: DynamicTransforms,
     active_frame: ActiveFrame,
+    /// Whether bitmaps are always or never smoothed, regardless of what the movie asked for.
+    bitmap_smoothing: Option<bool>,
 }
 
 impl WgpuRenderBackend<SwapChainTarget> {
@@ -229,6 +231,7 @@ impl<T: RenderTarget> WgpuRenderBackend<T> {
             offscreen_buffer_pool: Arc::new(offscreen_buffer_pool),
             dynamic_transforms: transforms,
             active_frame,
+            bitmap_smoothing: None,
         })
     }
 
@@ -326,6 +329,18 @@ impl<T: RenderTarget> WgpuRenderBackend<T> {
         }
     }
 
+    /// Whether bitmaps are forced to be smoothed (`Some(true)`) or not (`Some(false)`),
+    /// or follow the smoothing of each bitmap in the movie (`None`).
+    pub fn bitmap_smoothing(&self) -> Option<bool> {
+        self.bitmap_smoothing
+    }
+
+    /// Forces bitmaps to be smoothed or not when they're drawn, or leaves it to the movie with `None`.
+    /// This doesn't apply to bitmap fills of shapes.
+    pub fn set_bitmap_smoothing(&mut self, smoothing: Option<bool>) {
+        self.bitmap_smoothing = smoothing;
+    }
+
     pub fn descriptors(&self) -> &Arc<Descriptors> {
         &self.descriptors
     }
@@ -491,9 +506,16 @@ impl<T: RenderTarget + 'static> RenderBackend for WgpuRenderBackend<T> {
     fn submit_frame(
         &mut self,
         clear: Color,
-        commands: CommandList,
-        cache_entries: Vec<BitmapCacheEntry>,
+        mut commands: CommandList,
+        mut cache_entries: Vec<BitmapCacheEntry>,
     ) {
+        if let Some(smoothing) = self.bitmap_smoothing {
+            override_bitmap_smoothing(&mut commands, smoothing);
+            for entry in &mut cache_entries {
+                override_bitmap_smoothing(&mut entry.commands, smoothing);
+            }
+        }
+
         let frame_output = match self.target.get_next_texture() {
             Ok(frame) => frame,
             Err(e) => {
@@ -1116,6 +1138,40 @@ pub async fn request_adapter_and_device(
     Ok((adapter, device, queue))
 }
 
+/// Makes every bitmap drawn by `commands` smoothed or not, including those drawn within blends.
+fn override_bitmap_smoothing(commands: &mut CommandList, smoothing: bool) {
+    for command in &mut commands.commands {
+        match command {
+            Command::RenderBitmap {
+                smoothing: bitmap_smoothing,
+                ..
+            } => *bitmap_smoothing = smoothing,
+            Command::Blend(commands, _) => override_bitmap_smoothing(commands, smoothing),
+            _ => {}
+        }
+    }
+}
+
+/// Like [`request_adapter_and_device`], but only accepts the fallback adapter of the system,
+/// which is usually a software renderer. It's slow, but avoids problems with graphics drivers.
+pub async fn request_fallback_adapter_and_device(
+    instance: &wgpu::Instance,
+    surface: Option<&wgpu::Surface<'static>>,
+    trace_path: Option<&Path>,
+) -> Result<(wgpu::Adapter, wgpu::Device, wgpu::Queue), Error> {
+    let adapter = instance
+        .request_adapter(&wgpu::RequestAdapterOptions {
+            power_preference: wgpu::PowerPreference::LowPower,
+            compatible_surface: surface,
+            force_fallback_adapter: true,
+        })
+        .await
+        .ok_or_else(|| "No fallback graphics device was found".to_string())?;
+
+    let (device, queue) = request_device(&adapter, trace_path).await?;
+    Ok((adapter, device, queue))
+}
+
 // We try to request the highest limits we can get away with
 async fn request_device(
     adapter: &wgpu::Adapter,
@@ -400,6 +400,17 @@ impl BlendMode {
     }
 }
 
+impl Display for Compression {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let s = match *self {
+            Compression::None => "uncompressed",
+            Compression::Zlib => "zlib",
+            Compression::Lzma => "LZMA",
+        };
+        f.write_str(s)
+    }
+}
+
 impl Display for BlendMode {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         let s = match *self {
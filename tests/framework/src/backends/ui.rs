@@ -2,7 +2,8 @@ use crate::test::Font;
 use chrono::{DateTime, Utc};
 use ruffle_core::backend::ui::{
     DialogLoaderError, DialogResultFuture, FileDialogResult, FileFilter, FontDefinition,
-    FullscreenError, LanguageIdentifier, MouseCursor, UiBackend, US_ENGLISH,
+    FullscreenError, LanguageIdentifier, MouseCursor, ScriptTimeoutAction, UiBackend,
+    UnsupportedVideoReason, US_ENGLISH,
 };
 use url::Url;
 
@@ -76,16 +77,19 @@ impl FileDialogResult for TestFileDialogResult {
 /// * Attempting to display a file save dialog with a file name hint of "debug-success.txt" will simulate successfully selecting a destination
 ///   otherwise a user cancellation will be simulated
 /// * Simulated in-memory clipboard
+/// * Scripts that run for too long get the `script_timeout` answer from the test options
 pub struct TestUiBackend {
     fonts: Vec<Font>,
     clipboard: String,
+    script_timeout: ScriptTimeoutAction,
 }
 
 impl TestUiBackend {
-    pub fn new(fonts: Vec<Font>) -> Self {
+    pub fn new(fonts: Vec<Font>, script_timeout: ScriptTimeoutAction) -> Self {
         Self {
             fonts,
             clipboard: "".to_string(),
+            script_timeout,
         }
     }
 }
@@ -99,6 +103,8 @@ impl UiBackend for TestUiBackend {
 
     fn set_mouse_cursor(&mut self, _cursor: MouseCursor) {}
 
+    fn set_custom_cursor_active(&mut self, _active: bool) {}
+
     fn clipboard_content(&mut self) -> String {
         self.clipboard.clone()
     }
@@ -123,6 +129,19 @@ impl UiBackend for TestUiBackend {
 
     fn display_unsupported_video(&self, _url: Url) {}
 
+    fn display_unsupported_compression(&self, _compression: ruffle_core::swf::Compression) {}
+
+    fn display_unsupported_video_codec(
+        &self,
+        _codec: ruffle_core::swf::VideoCodec,
+        _reason: UnsupportedVideoReason,
+    ) {
+    }
+
+    fn script_timeout(&mut self) -> ScriptTimeoutAction {
+        self.script_timeout
+    }
+
     fn load_device_font(
         &self,
         name: &str,
@@ -6,6 +6,9 @@ use anyhow::{anyhow, Result};
 use approx::relative_eq;
 use image::ImageFormat;
 use regex::Regex;
+use ruffle_core::backend::ui::ScriptTimeoutAction;
+use ruffle_core::config::{Letterbox, LetterboxPattern, LetterboxStyle, LoopMode};
+use ruffle_core::swf::Color;
 use ruffle_core::tag_utils::SwfMovie;
 use ruffle_core::{PlayerBuilder, PlayerRuntime, ViewportDimensions};
 use ruffle_render::backend::RenderBackend;
@@ -141,6 +144,24 @@ impl RequiredFeatures {
     }
 }
 
+/// How the bars around the movie look, when it's always letterboxed.
+#[derive(Clone, Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct LetterboxOptions {
+    color: u32,
+    pattern: LetterboxPattern,
+}
+
+/// What the test UI answers when a script runs for longer than the maximum execution duration.
+#[derive(Clone, Copy, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ScriptTimeout {
+    #[default]
+    Halt,
+    Abort,
+    Continue,
+}
+
 #[derive(Clone, Deserialize, Default)]
 #[serde(default, deny_unknown_fields)]
 pub struct PlayerOptions {
@@ -150,6 +171,9 @@ pub struct PlayerOptions {
     with_audio: bool,
     with_video: bool,
     runtime: PlayerRuntime,
+    loop_mode: LoopMode,
+    script_timeout: ScriptTimeout,
+    letterbox: Option<LetterboxOptions>,
 }
 
 impl PlayerOptions {
@@ -172,7 +196,18 @@ impl PlayerOptions {
             player_builder = player_builder.with_audio(TestAudioBackend::default());
         }
 
-        player_builder = player_builder.with_player_runtime(self.runtime);
+        player_builder = player_builder
+            .with_player_runtime(self.runtime)
+            .with_loop_mode(self.loop_mode);
+
+        if let Some(letterbox) = &self.letterbox {
+            player_builder = player_builder
+                .with_letterbox(Letterbox::On)
+                .with_letterbox_style(LetterboxStyle {
+                    color: Color::from_rgb(letterbox.color, 255),
+                    pattern: letterbox.pattern,
+                });
+        }
 
         #[cfg(feature = "ruffle_video_software")]
         if self.with_video {
@@ -183,6 +218,14 @@ impl PlayerOptions {
         Ok(player_builder)
     }
 
+    pub fn script_timeout(&self) -> ScriptTimeoutAction {
+        match self.script_timeout {
+            ScriptTimeout::Halt => ScriptTimeoutAction::Halt,
+            ScriptTimeout::Abort => ScriptTimeoutAction::Abort,
+            ScriptTimeout::Continue => ScriptTimeoutAction::Continue,
+        }
+    }
+
     pub fn can_run(&self, check_renderer: bool, environment: &impl Environment) -> bool {
         if let Some(render) = &self.with_renderer {
             // If we don't actually want to check the renderer (ie we're just listing potential tests),
@@ -0,0 +1,9 @@
+package {
+	trace("start");
+	var i:int = 0;
+	while (i < 1000000) {
+		i++;
+	}
+	trace(i);
+	trace("done");
+}
@@ -0,0 +1,4 @@
+package {
+	trace("frame 1");
+	while (true) {}
+}
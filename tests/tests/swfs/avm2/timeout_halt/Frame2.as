@@ -0,0 +1,3 @@
+package {
+	trace("frame 2");
+}
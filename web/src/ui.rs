@@ -4,7 +4,8 @@ use ruffle_core::backend::ui::{
     DialogLoaderError, DialogResultFuture, FileDialogResult, FileFilter,
 };
 use ruffle_core::backend::ui::{
-    FontDefinition, FullscreenError, LanguageIdentifier, MouseCursor, UiBackend, US_ENGLISH,
+    FontDefinition, FullscreenError, LanguageIdentifier, MouseCursor, ScriptTimeoutAction,
+    UiBackend, UnsupportedVideoReason, US_ENGLISH,
 };
 use ruffle_web_common::JsResult;
 use std::borrow::Cow;
@@ -211,6 +212,8 @@ impl UiBackend for WebUiBackend {
         self.update_mouse_cursor();
     }
 
+    fn set_custom_cursor_active(&mut self, _active: bool) {}
+
     fn set_mouse_cursor(&mut self, cursor: MouseCursor) {
         self.cursor = cursor;
         self.update_mouse_cursor();
@@ -294,6 +297,23 @@ impl UiBackend for WebUiBackend {
         self.js_player.display_unsupported_video(url.as_str());
     }
 
+    fn display_unsupported_compression(&self, _compression: ruffle_core::swf::Compression) {
+        self.js_player
+            .display_root_movie_download_failed_message(true)
+    }
+
+    fn display_unsupported_video_codec(
+        &self,
+        _codec: ruffle_core::swf::VideoCodec,
+        _reason: UnsupportedVideoReason,
+    ) {
+        // The core already logs the error, and there's no message to show for it yet.
+    }
+
+    fn script_timeout(&mut self) -> ScriptTimeoutAction {
+        ScriptTimeoutAction::Halt
+    }
+
     fn load_device_font(
         &self,
         _name: &str,